@@ -1,44 +1,145 @@
 use num_enum::TryFromPrimitive;
 use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::Deserialize;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
+use std::time::Duration;
 
 use crate::board::Board;
 use crate::board::Cell;
+use crate::board::Move;
 use crate::board::Player;
 use crate::common::CellList;
+use crate::platform::Instant;
 use crate::referee::Referee;
 
-type Move = (usize, usize);
-
 // Message-passing types
+/// the work an agent is asked to do, see `platform::AgentRunner`
+pub enum AgentRequest {
+    Move(MoveRequest),
+    Analysis(AnalysisRequest),
+    /// restarts the random choices of the agent from the seed, see `Agent::reseed`
+    Seed(u64),
+    /// kills the thread it is sent to, to exercise the recovery of `platform::AgentRunner`
+    #[cfg(test)]
+    Panic,
+}
+
+/// asks for a move of `player` on `board`, see `Agent::answer_move_request`
+#[derive(Clone)]
 pub struct MoveRequest {
     pub board: Board,
     pub player: Player,
-    pub pace_response: bool,
     pub algorithm_choice: AiType,
+    /// how many plies to search, from 1 to `MAX_RECURSION_DEPTH`
     pub recursion_depth: usize,
+    /// when set, the search deepens iteratively and stops at the deepest depth finished in time
+    pub time_budget: Option<Duration>,
 }
 
+/// whether the disk on each square is one of a kind, by row and column
+pub type DiskSet = [[bool; Board::SIZE]; Board::SIZE];
+
+// a direction along each of the four lines through a square, the other one being its opposite
+const LINES: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// the answer to a `MoveRequest`
 pub struct MoveResult {
     pub board: Board,
     pub player: Player,
+    /// `(Board::SIZE, Board::SIZE)` if the player had no valid move
     pub next_move: Move,
+    /// the search's evaluation from the perspective of `player`, if it did any searching
+    pub score: Option<f32>,
+}
+
+/// scores every valid move of each position, reporting back one position at a time
+pub struct AnalysisRequest {
+    pub positions: Vec<AnalysisPosition>,
+    pub recursion_depth: usize,
+    /// stops the analysis before the next position once set
+    pub cancel: Arc<AtomicBool>,
+    pub result_sender: mpsc::Sender<AnalysisResult>,
+}
+
+/// a position of a game to analyze, `ply` tells it apart in the results
+pub struct AnalysisPosition {
+    pub ply: usize,
+    pub board: Board,
+    pub player: Player,
+}
+
+/// the scores of every valid move of the position at `ply`
+pub struct AnalysisResult {
+    pub ply: usize,
+    pub move_scores: Vec<(Move, f32)>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, TryFromPrimitive)]
+/// how the agent picks its moves
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    TryFromPrimitive,
+    Serialize,
+    Deserialize,
+)]
 #[repr(usize)]
 pub enum AiType {
     Random,
     Minimax,
     AlphaBeta,
+    // alpha-beta, but weighing the squares the way strong players do: corners are gold, the
+    // squares next to them give corners away
+    Positional,
+}
+
+impl AiType {
+    /// e.g. "AlphaBeta lvl 4", as the player shows up in statistics and reports
+    ///
+    /// ```
+    /// use fia_othello::ai::AiType;
+    ///
+    /// assert_eq!(AiType::AlphaBeta.describe(4), "AlphaBeta lvl 4");
+    /// assert_eq!(AiType::Random.describe(4), "Random");
+    /// ```
+    pub fn describe(&self, recursion_depth: usize) -> String {
+        match self {
+            AiType::Random => "Random".to_string(),
+            AiType::Minimax => format!("Minimax lvl {recursion_depth}"),
+            AiType::AlphaBeta => format!("AlphaBeta lvl {recursion_depth}"),
+            AiType::Positional => format!("Positional lvl {recursion_depth}"),
+        }
+    }
 }
 
+/// the deepest search offered, deeper ones take too long to be useful
+pub const MAX_RECURSION_DEPTH: usize = 10;
+
+/// searches for moves, picking at random among equally good ones
+///
+/// not thread-safe, every thread needs its own Agent
 pub struct Agent {
-    rng: rand::prelude::ThreadRng,
-    move_request_receiver: mpsc::Receiver<MoveRequest>,
-    move_result_sender: mpsc::Sender<MoveResult>,
+    rng: StdRng,
+    /// prints the scores of the candidate moves of every depth 2 search
+    pub log_searches: bool,
     valid_moves: CellList,
     referee: Referee,
+    // searches past this instant are abandoned
+    deadline: Option<Instant>,
+    search_aborted: bool,
+    // the square weights of the current search, depending on its AI type
+    weights: &'static [[i32; 8]; 8],
 }
 
 const OTHELLO_WEIGHTS: [[i32; 8]; 8] = [
@@ -52,48 +153,242 @@ const OTHELLO_WEIGHTS: [[i32; 8]; 8] = [
     [7, 2, 5, 4, 4, 5, 2, 7],
 ];
 
+/// the score of a finished game per disk of the final difference, outweighing any sum of square
+/// weights
+pub const SCORE_PER_FINAL_DISK: f32 = 1000.0;
+
+const POSITIONAL_WEIGHTS: [[i32; 8]; 8] = [
+    [100, -20, 10, 5, 5, 10, -20, 100],
+    [-20, -50, -2, -2, -2, -2, -50, -20],
+    [10, -2, -1, -1, -1, -1, -2, 10],
+    [5, -2, -1, -1, -1, -1, -2, 5],
+    [5, -2, -1, -1, -1, -1, -2, 5],
+    [10, -2, -1, -1, -1, -1, -2, 10],
+    [-20, -50, -2, -2, -2, -2, -50, -20],
+    [100, -20, 10, 5, 5, 10, -20, 100],
+];
+
+impl Default for Agent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Agent {
-    pub fn new(
-        move_request_receiver: mpsc::Receiver<MoveRequest>,
-        move_result_sender: mpsc::Sender<MoveResult>,
-    ) -> Self {
+    /// seeded by the OS, so the moves picked among equally good ones differ from run to run
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_os_rng())
+    }
+
+    /// the same seed and the same requests give the same moves
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// from here on, the agent picks among equally good moves as `Agent::with_seed` would
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::ai::MoveRequest;
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    ///
+    /// let request = || MoveRequest {
+    ///     board: Board::default(),
+    ///     player: Player::Black,
+    ///     algorithm_choice: AiType::Random,
+    ///     recursion_depth: 1,
+    ///     time_budget: None,
+    /// };
+    /// let mut agent = Agent::new();
+    /// agent.reseed(3);
+    /// let first = agent.answer_move_request(request()).next_move;
+    /// assert_eq!(Agent::with_seed(3).answer_move_request(request()).next_move, first);
+    /// ```
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
         Agent {
-            rng: rand::rng(),
-            move_request_receiver,
-            move_result_sender,
+            rng,
+            log_searches: true,
             valid_moves: CellList::default(),
             referee: Referee::default(),
+            deadline: None,
+            search_aborted: false,
+            weights: &OTHELLO_WEIGHTS,
+        }
+    }
+
+    /// blocks until the move is found, pacing the response is up to the caller
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::ai::MoveRequest;
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Referee;
+    ///
+    /// let mut agent = Agent::with_seed(1);
+    /// let move_result = agent.answer_move_request(MoveRequest {
+    ///     board: Board::default(),
+    ///     player: Player::Black,
+    ///     algorithm_choice: AiType::Minimax,
+    ///     recursion_depth: 2,
+    ///     time_budget: None,
+    /// });
+    /// assert!(Referee::default().validate_move(
+    ///     &Board::default(),
+    ///     Player::Black,
+    ///     move_result.next_move
+    /// ));
+    /// ```
+    pub fn answer_move_request(&mut self, move_request: MoveRequest) -> MoveResult {
+        let (next_move, score) = self.find_best_move_within_budget(&move_request);
+        // scores of positions without moves are infinite and tell nothing about the balance
+        let score = score.filter(|score| score.is_finite());
+
+        MoveResult {
+            board: move_request.board,
+            player: move_request.player,
+            next_move,
+            score,
+        }
+    }
+
+    /// answers on the request's channel, position by position, until done or cancelled
+    pub fn analyze(&mut self, request: AnalysisRequest) {
+        for position in request.positions.iter() {
+            if request.cancel.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let move_scores =
+                self.score_moves(&position.board, position.player, request.recursion_depth);
+            let result = AnalysisResult {
+                ply: position.ply,
+                move_scores,
+            };
+            if request.result_sender.send(result).is_err() {
+                // nobody is waiting for the analysis anymore
+                break;
+            }
         }
     }
 
-    pub fn run(&mut self) {
-        while let Ok(move_request) = self.move_request_receiver.recv() {
-            let next_move = match move_request.algorithm_choice {
-                AiType::Random => self.find_random_move(&move_request.board, move_request.player),
-                AiType::Minimax => self.find_best_move_using_minimax(
-                    &move_request.board,
-                    move_request.player,
-                    move_request.recursion_depth,
-                ),
-                AiType::AlphaBeta => self.find_best_move_using_alpha_beta(
-                    &move_request.board,
-                    move_request.player,
-                    move_request.recursion_depth,
-                ),
+    /// returns every valid move with the score a full-window search gives it
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    ///
+    /// let move_scores = Agent::new().score_moves(&Board::default(), Player::Black, 1);
+    /// assert_eq!(move_scores.len(), 4);
+    /// ```
+    pub fn score_moves(
+        &mut self,
+        board: &Board,
+        player: Player,
+        recursion_depth: usize,
+    ) -> Vec<(Move, f32)> {
+        self.weights = &OTHELLO_WEIGHTS;
+        let mut valid_moves = CellList::default();
+        self.referee
+            .find_all_valid_moves(board, player, &mut valid_moves);
+
+        let mut move_scores = Vec::with_capacity(valid_moves.count);
+        for next_move in valid_moves.iter() {
+            let mut new_board = board.clone();
+            let mut flip_cells = CellList::default();
+            self.referee
+                .find_flip_cells_for_move(board, player, next_move, &mut flip_cells);
+            Referee::apply_move(&mut new_board, player, next_move, &flip_cells);
+
+            let score = if recursion_depth <= 1 {
+                Self::weighted_balance(&new_board, player, self.weights)
+            } else {
+                let mut allocation_count = 0;
+                let (_opponent_move, opponent_score) = self.find_best_move_recursive_alpha_beta(
+                    &new_board,
+                    player.opponent(),
+                    recursion_depth - 1,
+                    &mut allocation_count,
+                    f32::NEG_INFINITY,
+                    f32::INFINITY,
+                );
+                -opponent_score
             };
+            move_scores.push((next_move, score));
+        }
+
+        move_scores
+    }
+
+    fn search(
+        &mut self,
+        algorithm_choice: AiType,
+        board: &Board,
+        player: Player,
+        recursion_depth: usize,
+    ) -> (Move, Option<f32>) {
+        self.weights = match algorithm_choice {
+            AiType::Positional => &POSITIONAL_WEIGHTS,
+            _ => &OTHELLO_WEIGHTS,
+        };
+        match algorithm_choice {
+            AiType::Random => (self.find_random_move(board, player), None),
+            AiType::Minimax => self.find_best_move_using_minimax(board, player, recursion_depth),
+            AiType::AlphaBeta | AiType::Positional => {
+                self.find_best_move_using_alpha_beta(board, player, recursion_depth)
+            }
+        }
+    }
 
-            if move_request.pace_response {
-                std::thread::sleep(std::time::Duration::from_secs(1));
+    fn find_best_move_within_budget(&mut self, move_request: &MoveRequest) -> (Move, Option<f32>) {
+        let board = &move_request.board;
+        let player = move_request.player;
+        let algorithm_choice = move_request.algorithm_choice;
+
+        let Some(time_budget) = move_request
+            .time_budget
+            .filter(|_| algorithm_choice != AiType::Random)
+        else {
+            return self.search(
+                algorithm_choice,
+                board,
+                player,
+                move_request.recursion_depth,
+            );
+        };
+
+        // the shallowest search always finishes, so there is a move even with no time left
+        let mut best = self.search(algorithm_choice, board, player, 1);
+        self.deadline = Some(Instant::now() + time_budget);
+        for recursion_depth in 2..=move_request.recursion_depth {
+            let result = self.search(algorithm_choice, board, player, recursion_depth);
+            if self.search_aborted {
+                break;
             }
+            best = result;
+        }
+        self.deadline = None;
+        self.search_aborted = false;
 
-            self.move_result_sender
-                .send(MoveResult {
-                    board: move_request.board,
-                    player: move_request.player,
-                    next_move,
-                })
-                .unwrap();
+        best
+    }
+
+    // once out of time, every search node returns immediately
+    fn is_out_of_time(&mut self) -> bool {
+        if !self.search_aborted
+            && let Some(deadline) = self.deadline
+        {
+            self.search_aborted = Instant::now() >= deadline;
         }
+        self.search_aborted
     }
 
     // returns a random valid move
@@ -114,11 +409,11 @@ impl Agent {
         board: &Board,
         player: Player,
         recursion_depth: usize,
-    ) -> Move {
+    ) -> (Move, Option<f32>) {
         let mut allocation_count = 0;
-        let (optimal_move, _optimal_score) =
+        let (optimal_move, optimal_score) =
             self.find_best_move_recursive(board, player, recursion_depth, &mut allocation_count);
-        optimal_move
+        (optimal_move, Some(optimal_score))
     }
 
     // uses negamax with alpha-beta pruning for faster, deeper search
@@ -127,9 +422,9 @@ impl Agent {
         board: &Board,
         player: Player,
         recursion_depth: usize,
-    ) -> Move {
+    ) -> (Move, Option<f32>) {
         let mut allocation_count = 0;
-        let (optimal_move, _optimal_score) = self.find_best_move_recursive_alpha_beta(
+        let (optimal_move, optimal_score) = self.find_best_move_recursive_alpha_beta(
             board,
             player,
             recursion_depth,
@@ -137,7 +432,7 @@ impl Agent {
             f32::NEG_INFINITY,
             f32::INFINITY,
         );
-        optimal_move
+        (optimal_move, Some(optimal_score))
     }
 
     // returns (the optimal move, evaluation score given to that move)
@@ -150,6 +445,10 @@ impl Agent {
         recursion_depth: usize,
         allocation_count: &mut i32,
     ) -> (Move, f32) {
+        if self.is_out_of_time() {
+            return ((Board::SIZE, Board::SIZE), 0.0);
+        }
+
         let mut optimal_move = (Board::SIZE, Board::SIZE);
         let mut optimal_score = f32::NEG_INFINITY;
         let mut selection_count = 0; // Track number of equally good moves found
@@ -175,7 +474,7 @@ impl Agent {
                     if recursion_depth == 1 {
 
                         // ...either how good it would make the board for us now...
-                        self.evaluate_board(&new_board, player)
+                        Self::weighted_balance(&new_board, player, self.weights)
 
                     } else {
 
@@ -213,30 +512,276 @@ impl Agent {
                     selection_count = 1;
                 }
             }
+        } else if let Some(score) = self.score_without_moves(board, player) {
+            optimal_score = score;
+        } else if recursion_depth > 1 {
+            // the player has to pass, and the opponent moves again
+            let (_opponent_move, opponent_score) = self.find_best_move_recursive(
+                board,
+                player.opponent(),
+                recursion_depth - 1,
+                allocation_count,
+            );
+            optimal_score = -opponent_score;
+        } else {
+            optimal_score = Self::weighted_balance(board, player, self.weights);
         }
 
-        if recursion_depth == 2 && !depth2.is_empty() {
+        if self.log_searches && recursion_depth == 2 && !depth2.is_empty() {
             println!("depth 2 : {}", depth2.join(", "));
         }
 
         (optimal_move, optimal_score)
     }
 
-    // for now, the evaluation is only based on the number of pieces
-    // TODO: add end-of-game awareness
-    fn evaluate_board(&self, board: &Board, player: Player) -> f32 {
+    /// the summed square weights of the player's disks minus the opponent's, corners weigh
+    /// the most
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    ///
+    /// // the start position is symmetric
+    /// assert_eq!(Agent::evaluate_board(&Board::default(), Player::Black), 0.0);
+    /// ```
+    pub fn evaluate_board(board: &Board, player: Player) -> f32 {
+        Self::weighted_balance(board, player, &OTHELLO_WEIGHTS)
+    }
+
+    // the player's weighted disks minus the opponent's, so that negamax can negate it
+    fn weighted_balance(board: &Board, player: Player, weights: &[[i32; 8]; 8]) -> f32 {
         let mut sum = 0;
-        for (i, row_weights) in OTHELLO_WEIGHTS.iter().enumerate().take(Board::SIZE) {
+        for (i, row_weights) in weights.iter().enumerate().take(Board::SIZE) {
             for (j, weight) in row_weights.iter().enumerate().take(Board::SIZE) {
                 match board.grid[i][j] {
                     Cell::Taken(p) if p == player => sum += *weight,
-                    _ => {}
+                    Cell::Taken(_) => sum -= *weight,
+                    Cell::Empty => {}
                 }
             }
         }
         sum as f32
     }
 
+    /// the final disk difference every valid move leads to when both sides play perfectly
+    ///
+    /// searches to the end of the game, meant for endgames of a dozen empty squares or less
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::notation;
+    ///
+    /// // white fills the last square, flipping the rest of the first row, and loses 8 to 56
+    /// let position = format!("OXXXXXX-{} O", "X".repeat(56));
+    /// let (board, player) = notation::parse_position(&position).unwrap();
+    /// assert_eq!(Agent::new().solve_endgame(&board, player), [((0, 7), -48)]);
+    /// ```
+    pub fn solve_endgame(&mut self, board: &Board, player: Player) -> Vec<(Move, i32)> {
+        let mut valid_moves = CellList::default();
+        self.referee
+            .find_all_valid_moves(board, player, &mut valid_moves);
+
+        valid_moves
+            .iter()
+            .map(|next_move| {
+                let new_board = self.board_after(board, player, next_move);
+                let score = -self.solve_recursive(&new_board, player.opponent(), -64, 64, false);
+                (next_move, score)
+            })
+            .collect()
+    }
+
+    // negamax with alpha-beta pruning down to the end of the game
+    fn solve_recursive(
+        &mut self,
+        board: &Board,
+        player: Player,
+        mut alpha: i32,
+        beta: i32,
+        opponent_passed: bool,
+    ) -> i32 {
+        let mut valid_moves = CellList::default();
+        if !self
+            .referee
+            .find_all_valid_moves(board, player, &mut valid_moves)
+        {
+            if opponent_passed {
+                // neither side can move, the game is over
+                let (player_count, opponent_count) = Referee::count_disks(board, player);
+                return player_count - opponent_count;
+            }
+            return -self.solve_recursive(board, player.opponent(), -beta, -alpha, true);
+        }
+
+        let mut best_score = -64;
+        for next_move in valid_moves.iter() {
+            let new_board = self.board_after(board, player, next_move);
+            let score = -self.solve_recursive(&new_board, player.opponent(), -beta, -alpha, false);
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_score
+    }
+
+    fn board_after(&mut self, board: &Board, player: Player, next_move: Move) -> Board {
+        let mut new_board = board.clone();
+        let mut flip_cells = CellList::default();
+        self.referee
+            .find_flip_cells_for_move(board, player, next_move, &mut flip_cells);
+        Referee::apply_move(&mut new_board, player, next_move, &flip_cells);
+        new_board
+    }
+
+    /// the disks that can never be flipped again, or a safe part of them: along each of the four
+    /// lines through it, a stable disk has the edge or a stable disk of its own color beside it,
+    /// or no empty square left on the line
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::board::Board;
+    /// use fia_othello::notation;
+    ///
+    /// let none = |stable: [[bool; 8]; 8]| stable.iter().flatten().all(|stable| !stable);
+    /// assert!(none(Agent::stable_disks(&Board::default())));
+    ///
+    /// // black's corner holds the disk beside it, white's disk next to them can still turn
+    /// let (board, _) = notation::parse_position(&format!("XXO{} O", "-".repeat(61))).unwrap();
+    /// let stable = Agent::stable_disks(&board);
+    /// assert_eq!(stable[0][..3], [true, true, false]);
+    /// ```
+    pub fn stable_disks(board: &Board) -> DiskSet {
+        let mut stable = [[false; Board::SIZE]; Board::SIZE];
+        // every disk found stable can hold its neighbors, until no more are found
+        loop {
+            let mut found = false;
+            for row in 0..Board::SIZE {
+                for col in 0..Board::SIZE {
+                    if !stable[row][col]
+                        && let Cell::Taken(player) = board.grid[row][col]
+                        && LINES.iter().all(|&direction| {
+                            Self::is_held_along(board, &stable, player, (row, col), direction)
+                        })
+                    {
+                        stable[row][col] = true;
+                        found = true;
+                    }
+                }
+            }
+            if !found {
+                return stable;
+            }
+        }
+    }
+
+    // whether no move can flip the player's disk on the square along the line in the direction
+    fn is_held_along(
+        board: &Board,
+        stable: &DiskSet,
+        player: Player,
+        square: Move,
+        (row_step, col_step): (isize, isize),
+    ) -> bool {
+        let step = |(row, col): Move, sign: isize| {
+            let row = row.checked_add_signed(row_step * sign)?;
+            let col = col.checked_add_signed(col_step * sign)?;
+            (row < Board::SIZE && col < Board::SIZE).then_some((row, col))
+        };
+        let is_held_by = |sign| match step(square, sign) {
+            None => true,
+            Some((row, col)) => stable[row][col] && board.grid[row][col] == Cell::Taken(player),
+        };
+        // a full line has no square left to play a move along it from
+        let is_full = [1, -1].into_iter().all(|sign| {
+            std::iter::successors(step(square, sign), |&next| step(next, sign))
+                .all(|next| board.cell(next) != Cell::Empty)
+        });
+        is_held_by(1) || is_held_by(-1) || is_full
+    }
+
+    /// the disks with an empty square among their eight neighbors, which give the opponent
+    /// moves to play
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::board::Board;
+    ///
+    /// let frontier = Agent::frontier_disks(&Board::default());
+    /// assert_eq!(frontier.iter().flatten().filter(|&&frontier| frontier).count(), 4);
+    /// ```
+    pub fn frontier_disks(board: &Board) -> DiskSet {
+        let mut frontier = [[false; Board::SIZE]; Board::SIZE];
+        for (row, row_frontier) in frontier.iter_mut().enumerate() {
+            for (col, is_frontier) in row_frontier.iter_mut().enumerate() {
+                *is_frontier = board.cell((row, col)) != Cell::Empty
+                    && (row.saturating_sub(1)..=(row + 1).min(Board::SIZE - 1)).any(|row| {
+                        (col.saturating_sub(1)..=(col + 1).min(Board::SIZE - 1))
+                            .any(|col| board.cell((row, col)) == Cell::Empty)
+                    });
+            }
+        }
+        frontier
+    }
+
+    /// the opponent's frontier disks minus the player's, a term for evaluations that prefer
+    /// few disks of one's own next to the empty squares
+    ///
+    /// ```
+    /// use fia_othello::ai::Agent;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::notation;
+    ///
+    /// // white's corner and black's two disks beside it all touch the empty second row
+    /// let (board, _) = notation::parse_position(&format!("OXX{} X", "-".repeat(61))).unwrap();
+    /// assert_eq!(Agent::frontier_balance(&board, Player::Black), -1.0);
+    /// // the last empty square borders black's two disks and white's one
+    /// let (board, _) = notation::parse_position(&format!("{}O- X", "X".repeat(62))).unwrap();
+    /// assert_eq!(Agent::frontier_balance(&board, Player::Black), -1.0);
+    /// assert_eq!(Agent::frontier_balance(&board, Player::White), 1.0);
+    /// ```
+    pub fn frontier_balance(board: &Board, player: Player) -> f32 {
+        let frontier = Self::frontier_disks(board);
+        let mut balance = 0;
+        for (row, row_frontier) in frontier.iter().enumerate() {
+            for (col, _) in row_frontier
+                .iter()
+                .enumerate()
+                .filter(|(_, frontier)| **frontier)
+            {
+                match board.cell((row, col)) {
+                    Cell::Taken(p) if p == player => balance -= 1,
+                    Cell::Taken(_) => balance += 1,
+                    Cell::Empty => {}
+                }
+            }
+        }
+        balance as f32
+    }
+
+    /// a shallow, symmetric evaluation from black's perspective, no search involved
+    pub fn positional_balance(board: &Board) -> f32 {
+        Self::evaluate_board(board, Player::Black)
+    }
+
+    // called when the player has no valid move: the final score if the opponent has none
+    // either, None if the player merely passes
+    // a won game beats any position, and winning by more beats winning by less
+    fn score_without_moves(&mut self, board: &Board, player: Player) -> Option<f32> {
+        let mut opponent_moves = CellList::default();
+        if self
+            .referee
+            .find_all_valid_moves(board, player.opponent(), &mut opponent_moves)
+        {
+            return None;
+        }
+
+        let (player_count, opponent_count) = Referee::count_disks(board, player);
+        Some((player_count - opponent_count) as f32 * SCORE_PER_FINAL_DISK)
+    }
+
     // returns (the optimal move, evaluation score given to that move)
     // Implements negamax with alpha-beta pruning
     fn find_best_move_recursive_alpha_beta(
@@ -248,6 +793,10 @@ impl Agent {
         mut alpha: f32,
         beta: f32,
     ) -> (Move, f32) {
+        if self.is_out_of_time() {
+            return ((Board::SIZE, Board::SIZE), 0.0);
+        }
+
         let mut optimal_move = (Board::SIZE, Board::SIZE);
         let mut optimal_score = f32::NEG_INFINITY;
         let mut selection_count = 0; // Track number of equally good moves found
@@ -272,7 +821,7 @@ impl Agent {
                     // ...(depending on how far we want to think into the future)...
                     if recursion_depth == 1 {
                         // ...either how good it would make the board for us now...
-                        self.evaluate_board(&new_board, player)
+                        Self::weighted_balance(&new_board, player, self.weights)
                     } else {
                         // ...or how good the board will become if the opponent makes their best move next...
                         let (_opponent_move, opponent_score) = self.find_best_move_recursive_alpha_beta(
@@ -329,12 +878,126 @@ impl Agent {
                     break; // Beta cutoff - prune remaining moves
                 }
             }
+        } else if let Some(score) = self.score_without_moves(board, player) {
+            optimal_score = score;
+        } else if recursion_depth > 1 {
+            // the player has to pass, and the opponent moves again
+            let (_opponent_move, opponent_score) = self.find_best_move_recursive_alpha_beta(
+                board,
+                player.opponent(),
+                recursion_depth - 1,
+                allocation_count,
+                -beta,
+                -alpha,
+            );
+            optimal_score = -opponent_score;
+        } else {
+            optimal_score = Self::weighted_balance(board, player, self.weights);
         }
 
-        if recursion_depth == 2 && !depth2.is_empty() {
+        if self.log_searches && recursion_depth == 2 && !depth2.is_empty() {
             println!("depth 2 : {}", depth2.join(", "));
         }
 
         (optimal_move, optimal_score)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation;
+
+    fn position(rows: [&str; 8], player: &str) -> (Board, Player) {
+        notation::parse_position(&format!("{} {player}", rows.concat())).unwrap()
+    }
+
+    fn quiet_agent() -> Agent {
+        let mut agent = Agent::with_seed(0);
+        agent.log_searches = false;
+        agent
+    }
+
+    // black could take the corner a1, flanking b1 with c1
+    const CORNER_ON_OFFER: [&str; 8] = [
+        "-OX-----", "--------", "--------", "---OX---", "---XO---", "--------", "--------",
+        "--------",
+    ];
+
+    #[test]
+    fn the_evaluation_of_one_player_is_the_negation_of_the_other() {
+        let (board, _) = position(CORNER_ON_OFFER, "X");
+
+        let black = Agent::evaluate_board(&board, Player::Black);
+        assert_ne!(black, 0.0);
+        assert_eq!(Agent::evaluate_board(&board, Player::White), -black);
+        assert_eq!(Agent::positional_balance(&board), black);
+    }
+
+    #[test]
+    fn a_finished_game_is_scored_by_its_disk_difference() {
+        let position = format!("{}{}", "X".repeat(48), "O".repeat(16));
+        let (board, _) = notation::parse_position(&position).unwrap();
+
+        let mut agent = quiet_agent();
+        assert_eq!(
+            agent.score_without_moves(&board, Player::Black),
+            Some(32.0 * SCORE_PER_FINAL_DISK)
+        );
+        assert_eq!(
+            agent.score_without_moves(&board, Player::White),
+            Some(-32.0 * SCORE_PER_FINAL_DISK)
+        );
+    }
+
+    #[test]
+    fn a_player_who_has_to_pass_is_scored_by_the_opponent_moving_again() {
+        // black cannot flank a1, white can flank b1 from c1
+        let rows = [
+            "OX------", "--------", "--------", "--------", "--------", "--------", "--------",
+            "--------",
+        ];
+        let (board, player) = position(rows, "X");
+        let mut agent = quiet_agent();
+        assert_eq!(agent.score_without_moves(&board, player), None);
+
+        let (after_white, _) = position(
+            [
+                "OOO-----", "--------", "--------", "--------", "--------", "--------", "--------",
+                "--------",
+            ],
+            "X",
+        );
+        let expected = Agent::evaluate_board(&after_white, Player::Black);
+        let (_, minimax) = agent.find_best_move_recursive(&board, player, 2, &mut 0);
+        let (_, alpha_beta) = agent.find_best_move_recursive_alpha_beta(
+            &board,
+            player,
+            2,
+            &mut 0,
+            f32::NEG_INFINITY,
+            f32::INFINITY,
+        );
+        assert_eq!(minimax, expected);
+        assert_eq!(alpha_beta, expected);
+    }
+
+    #[test]
+    fn positional_takes_the_corner_the_plain_weights_pass_over() {
+        let (board, player) = position(CORNER_ON_OFFER, "X");
+        let best_move = |algorithm_choice| {
+            quiet_agent()
+                .answer_move_request(MoveRequest {
+                    board: board.clone(),
+                    player,
+                    algorithm_choice,
+                    recursion_depth: 1,
+                    time_budget: None,
+                })
+                .next_move
+        };
+
+        assert_eq!(best_move(AiType::Positional), (0, 0));
+        assert_ne!(best_move(AiType::AlphaBeta), (0, 0));
+    }
+}
@@ -0,0 +1,482 @@
+use crate::ai::SCORE_PER_FINAL_DISK;
+use crate::board::Move;
+use crate::board::Player;
+use crate::history::Ply;
+use crate::locale::Strings;
+use crate::notation;
+
+// the engine's opinion of one position of the game, from the perspective of the player to move
+pub struct PositionAnalysis {
+    pub player: Player,
+    pub played_move: Move,
+    pub move_scores: Vec<(Move, f32)>,
+}
+
+impl PositionAnalysis {
+    pub fn best(&self) -> Option<(Move, f32)> {
+        self.move_scores
+            .iter()
+            .copied()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    pub fn played_score(&self) -> Option<f32> {
+        self.move_scores
+            .iter()
+            .find(|(next_move, _)| *next_move == self.played_move)
+            .map(|(_, score)| *score)
+    }
+
+    // how much the played move gave away compared to the best one, never negative
+    pub fn swing(&self) -> f32 {
+        match (self.best(), self.played_score()) {
+            // equal infinities would otherwise subtract to NaN
+            (Some((_, best)), Some(played)) if best != played => best - played,
+            _ => 0.0,
+        }
+    }
+}
+
+// the results of analyzing every position of a game, one entry per ply
+pub struct Analysis {
+    pub recursion_depth: usize,
+    // the analyzed game, to tell whether the analysis still belongs to the current one
+    pub plies: Vec<Ply>,
+    // None for passes, and for positions that have not been (or will never be) analyzed
+    pub positions: Vec<Option<PositionAnalysis>>,
+}
+
+impl Analysis {
+    pub fn new(plies: Vec<Ply>, recursion_depth: usize) -> Self {
+        let positions = plies.iter().map(|_| None).collect();
+        Analysis {
+            recursion_depth,
+            plies,
+            positions,
+        }
+    }
+
+    pub fn add_result(&mut self, ply: usize, move_scores: Vec<(Move, f32)>) {
+        if let Some(&Ply::Move { player, next_move }) = self.plies.get(ply) {
+            self.positions[ply] = Some(PositionAnalysis {
+                player,
+                played_move: next_move,
+                move_scores,
+            });
+        }
+    }
+}
+
+// minimum swings for a move to count as an inaccuracy, mistake or blunder
+#[derive(Clone, Copy)]
+pub struct SwingThresholds {
+    pub inaccuracy: f32,
+    pub mistake: f32,
+    pub blunder: f32,
+}
+
+impl Default for SwingThresholds {
+    fn default() -> Self {
+        SwingThresholds {
+            inaccuracy: 3.0,
+            mistake: 6.0,
+            blunder: 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl SwingThresholds {
+    pub fn classify(&self, swing: f32) -> Option<Severity> {
+        if swing >= self.blunder {
+            Some(Severity::Blunder)
+        } else if swing >= self.mistake {
+            Some(Severity::Mistake)
+        } else if swing >= self.inaccuracy {
+            Some(Severity::Inaccuracy)
+        } else {
+            None
+        }
+    }
+}
+
+impl Severity {
+    // the usual annotation symbols
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Severity::Inaccuracy => "?!",
+            Severity::Mistake => "?",
+            Severity::Blunder => "??",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PlayerSummary {
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+}
+
+pub struct CostlyMove {
+    pub ply: usize,
+    pub player: Player,
+    pub played_move: Move,
+    pub best_move: Move,
+    pub swing: f32,
+}
+
+pub struct Summary {
+    // indexed by player
+    pub players: [PlayerSummary; 2],
+    // the worst moves of the game, worst first
+    pub worst_moves: Vec<CostlyMove>,
+}
+
+const WORST_MOVE_COUNT: usize = 3;
+
+// passes are never analyzed, so they never count against a player
+pub fn summarize(analysis: &Analysis, thresholds: &SwingThresholds) -> Summary {
+    let mut players = [PlayerSummary::default(), PlayerSummary::default()];
+    let mut worst_moves = Vec::new();
+
+    for (ply, position) in analysis.positions.iter().enumerate() {
+        let Some(position) = position else {
+            continue;
+        };
+        let swing = position.swing();
+
+        let player_summary = &mut players[position.player as usize];
+        match thresholds.classify(swing) {
+            Some(Severity::Inaccuracy) => player_summary.inaccuracies += 1,
+            Some(Severity::Mistake) => player_summary.mistakes += 1,
+            Some(Severity::Blunder) => player_summary.blunders += 1,
+            None => {}
+        }
+
+        if swing > 0.0
+            && let Some((best_move, _)) = position.best()
+        {
+            worst_moves.push(CostlyMove {
+                ply,
+                player: position.player,
+                played_move: position.played_move,
+                best_move,
+                swing,
+            });
+        }
+    }
+
+    worst_moves.sort_by(|a, b| b.swing.total_cmp(&a.swing));
+    worst_moves.truncate(WORST_MOVE_COUNT);
+
+    Summary {
+        players,
+        worst_moves,
+    }
+}
+
+pub fn summary_text(summary: &Summary, strings: &Strings) -> String {
+    let mut lines = Vec::new();
+    for player in [Player::Black, Player::White] {
+        let player_summary = &summary.players[player as usize];
+        lines.push(strings.tr_args(
+            "summary-player",
+            &[
+                ("player", &strings.player_name(player)),
+                ("inaccuracies", &player_summary.inaccuracies),
+                ("mistakes", &player_summary.mistakes),
+                ("blunders", &player_summary.blunders),
+            ],
+        ));
+    }
+    for costly_move in summary.worst_moves.iter() {
+        lines.push(strings.tr_args(
+            "summary-costly-move",
+            &[
+                ("ply", &(costly_move.ply + 1)),
+                ("player", &strings.player_name(costly_move.player)),
+                ("played", &notation::format_move(costly_move.played_move)),
+                ("best", &notation::format_move(costly_move.best_move)),
+                ("swing", &format_score(-costly_move.swing)),
+            ],
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn format_score(score: f32) -> String {
+    if score == f32::INFINITY {
+        "+∞".to_string()
+    } else if score == f32::NEG_INFINITY {
+        "−∞".to_string()
+    } else {
+        format!("{score:+.0}")
+    }
+}
+
+// short enough for the corner of a square: +6, −12, or a win and a loss once the search saw the
+// end of the game
+pub fn compact_score(score: f32, strings: &Strings) -> String {
+    if score >= SCORE_PER_FINAL_DISK {
+        strings.tr("annotation-win").to_string()
+    } else if score <= -SCORE_PER_FINAL_DISK {
+        strings.tr("annotation-loss").to_string()
+    } else {
+        let rounded = score.round();
+        if rounded == 0.0 {
+            "0".to_string()
+        } else if rounded > 0.0 {
+            format!("+{rounded}")
+        } else {
+            format!("−{}", -rounded)
+        }
+    }
+}
+
+// one line per ply with the played move, the engine's preference and the swing
+pub fn annotated_text(
+    analysis: &Analysis,
+    thresholds: &SwingThresholds,
+    strings: &Strings,
+) -> String {
+    let mut text = strings.tr_args("annotated-depth", &[("depth", &analysis.recursion_depth)]);
+    text.push('\n');
+
+    for (i, (ply, position)) in analysis
+        .plies
+        .iter()
+        .zip(analysis.positions.iter())
+        .enumerate()
+    {
+        let line = match (ply, position) {
+            (Ply::Pass { player }, _) => strings.tr_args(
+                "annotated-pass",
+                &[
+                    ("index", &(i + 1)),
+                    ("player", &strings.player_name(*player)),
+                ],
+            ),
+            (Ply::Move { player, next_move }, None) => strings.tr_args(
+                "annotated-not-analyzed",
+                &[
+                    ("index", &(i + 1)),
+                    ("player", &strings.player_name(*player)),
+                    ("move", &notation::format_move(*next_move)),
+                ],
+            ),
+            (Ply::Move { player, next_move }, Some(position)) => {
+                let (best_move, best_score) = position
+                    .best()
+                    .unwrap_or((*next_move, position.played_score().unwrap_or(0.0)));
+                let symbol = thresholds
+                    .classify(position.swing())
+                    .map_or("", |severity| severity.symbol());
+                // every valid move of the position is scored, so they count its mobility
+                strings.tr_args(
+                    "annotated-move",
+                    &[
+                        ("index", &(i + 1)),
+                        ("player", &strings.player_name(*player)),
+                        ("move", &notation::format_move(*next_move)),
+                        ("symbol", &symbol),
+                        (
+                            "score",
+                            &format_score(position.played_score().unwrap_or(best_score)),
+                        ),
+                        ("best", &notation::format_move(best_move)),
+                        ("best_score", &format_score(best_score)),
+                        ("swing", &format_score(-position.swing())),
+                        ("count", &position.move_scores.len()),
+                    ],
+                )
+            }
+        };
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    text.push('\n');
+    text.push_str(&summary_text(&summarize(analysis, thresholds), strings));
+    text.push('\n');
+
+    text
+}
+
+// how each move ranks among the moves of its position, from 0 for the worst to 1 for the best
+// by rank rather than by score, so that one far-off score does not squeeze the others together,
+// and equal scores share their level
+pub fn heat_levels(move_scores: &[(Move, f32)]) -> Vec<(Move, f32)> {
+    let others = move_scores.len().saturating_sub(1);
+    move_scores
+        .iter()
+        .map(|&(next_move, score)| {
+            if others == 0 {
+                return (next_move, 1.0);
+            }
+            let beaten = move_scores
+                .iter()
+                .filter(|(_, other)| *other < score)
+                .count();
+            let tied = move_scores
+                .iter()
+                .filter(|(_, other)| *other == score)
+                .count()
+                - 1;
+            (
+                next_move,
+                (beaten as f32 + tied as f32 / 2.0) / others as f32,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::Language;
+
+    #[test]
+    fn a_corner_given_away_is_a_blunder_of_the_player_who_gave_it() {
+        let ply = |player, next_move| Ply::Move { player, next_move };
+        let plies = vec![
+            ply(Player::Black, (1, 1)),
+            ply(Player::White, (0, 0)),
+            Ply::Pass {
+                player: Player::Black,
+            },
+            ply(Player::White, (7, 6)),
+            ply(Player::Black, (6, 7)),
+        ];
+        let mut analysis = Analysis::new(plies, 4);
+        // each from the side of the player to move: b2 hands a1 over instead of a safe d3
+        analysis.add_result(0, vec![((1, 1), -14.0), ((2, 3), 4.0), ((5, 2), 1.0)]);
+        // white's best move is a bad one for black, and no swing at all
+        analysis.add_result(1, vec![((0, 0), 22.0), ((2, 2), 6.0)]);
+        // a pass has nothing to score
+        analysis.add_result(2, vec![((3, 3), 50.0), ((4, 4), -50.0)]);
+        // g8 instead of the safe f1 is a smaller slip of white's own
+        analysis.add_result(3, vec![((7, 6), 17.0), ((0, 5), 24.0)]);
+        analysis.add_result(4, vec![((6, 7), -30.0), ((7, 7), -27.0)]);
+        assert!(analysis.positions[2].is_none());
+
+        let summary = summarize(&analysis, &SwingThresholds::default());
+        let black = &summary.players[Player::Black as usize];
+        assert_eq!(
+            (black.inaccuracies, black.mistakes, black.blunders),
+            (1, 0, 1)
+        );
+        let white = &summary.players[Player::White as usize];
+        assert_eq!(
+            (white.inaccuracies, white.mistakes, white.blunders),
+            (0, 1, 0)
+        );
+
+        let worst: Vec<_> = summary
+            .worst_moves
+            .iter()
+            .map(|costly| (costly.ply, costly.player, costly.best_move, costly.swing))
+            .collect();
+        assert_eq!(
+            worst,
+            [
+                (0, Player::Black, (2, 3), 18.0),
+                (3, Player::White, (0, 5), 7.0),
+                (4, Player::Black, (7, 7), 3.0),
+            ]
+        );
+        let text = summary_text(&summary, &Strings::new(Language::English));
+        assert!(
+            text.contains("1. Black played b2 instead of d3, swing -18"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn compact_scores_fit_in_a_corner() {
+        let strings = Strings::new(Language::English);
+        assert_eq!(compact_score(6.4, &strings), "+6");
+        assert_eq!(compact_score(-12.0, &strings), "−12");
+        assert_eq!(compact_score(-0.4, &strings), "0");
+        assert_eq!(compact_score(3.0 * SCORE_PER_FINAL_DISK, &strings), "win");
+        assert_eq!(compact_score(-SCORE_PER_FINAL_DISK, &strings), "loss");
+        assert_eq!(compact_score(f32::NEG_INFINITY, &strings), "loss");
+
+        let french = Strings::new(Language::French);
+        assert_eq!(compact_score(SCORE_PER_FINAL_DISK, &french), "gain");
+        assert_eq!(compact_score(-SCORE_PER_FINAL_DISK, &french), "perte");
+        assert_eq!(compact_score(6.4, &french), "+6");
+    }
+
+    #[test]
+    fn heat_levels_go_by_rank_and_share_ties() {
+        let levels = heat_levels(&[
+            ((2, 3), 4.0),
+            ((3, 2), -60.0),
+            ((4, 5), 2.0),
+            ((5, 4), 2.0),
+            ((2, 2), f32::INFINITY),
+        ]);
+        assert_eq!(
+            levels,
+            [
+                ((2, 3), 0.75),
+                // far below the others, yet the ones above it keep their spacing
+                ((3, 2), 0.0),
+                ((4, 5), 0.375),
+                ((5, 4), 0.375),
+                ((2, 2), 1.0),
+            ]
+        );
+        assert_eq!(heat_levels(&[((2, 3), -5.0)]), [((2, 3), 1.0)]);
+        assert!(heat_levels(&[]).is_empty());
+    }
+
+    #[test]
+    fn the_annotated_game_counts_the_legal_moves_of_every_analyzed_position() {
+        let plies = vec![
+            Ply::Move {
+                player: Player::Black,
+                next_move: (2, 3),
+            },
+            Ply::Pass {
+                player: Player::White,
+            },
+        ];
+        let mut analysis = Analysis::new(plies, 2);
+        analysis.positions[0] = Some(PositionAnalysis {
+            player: Player::Black,
+            played_move: (2, 3),
+            move_scores: vec![((2, 3), 1.0), ((3, 2), 1.0), ((4, 5), 1.0), ((5, 4), 1.0)],
+        });
+
+        let text = annotated_text(
+            &analysis,
+            &SwingThresholds::default(),
+            &Strings::new(Language::English),
+        );
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Analysis at depth 2");
+        assert!(lines[1].starts_with("1. Black d3"), "{}", lines[1]);
+        assert!(lines[1].ends_with("legal moves 4"), "{}", lines[1]);
+        assert_eq!(lines[2], "2. White passes");
+
+        // and in the language of the interface
+        let text = annotated_text(
+            &analysis,
+            &SwingThresholds::default(),
+            &Strings::new(Language::French),
+        );
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Analyse à la profondeur 2");
+        assert!(lines[1].ends_with("coups légaux 4"), "{}", lines[1]);
+        assert_eq!(lines[2], "2. Blanc passe");
+        assert!(text.contains("Noir : "), "{text}");
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use crate::board::Move;
+use crate::platform::Instant;
+
+// the share of a disc's turn the next disc along its ray waits before turning
+const STAGGER: f32 = 0.5;
+
+// the discs of a move turning over one after the other outward along each ray
+pub struct FlipAnimation {
+    // the flipped discs and their distance from the placed one along their ray
+    flipped: Vec<(Move, usize)>,
+    started_at: Instant,
+    duration: Duration,
+}
+
+impl FlipAnimation {
+    pub fn new(
+        next_move: Move,
+        flipped: impl IntoIterator<Item = Move>,
+        duration: Duration,
+    ) -> Self {
+        let (row, col) = next_move;
+        let flipped = flipped
+            .into_iter()
+            .map(|square| (square, square.0.abs_diff(row).max(square.1.abs_diff(col))))
+            .collect();
+        FlipAnimation {
+            flipped,
+            started_at: Instant::now(),
+            duration,
+        }
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) >= self.duration
+    }
+
+    // how far the flipped disc is through its turn from the opponent's color to the player's,
+    // from 0 to 1, None for a square that was not flipped
+    pub fn turn(&self, square: Move, now: Instant) -> Option<f32> {
+        let elapsed = now.saturating_duration_since(self.started_at);
+        self.flipped
+            .iter()
+            .find(|(flipped, _)| *flipped == square)
+            .map(|&(_, distance)| self.progress(distance, elapsed))
+    }
+
+    // the disc at `distance` starts once the one before it is half way through its turn, the
+    // placed disc counting as the first
+    fn progress(&self, distance: usize, elapsed: Duration) -> f32 {
+        let farthest = self.flipped.iter().map(|&(_, distance)| distance).max();
+        let turns = 1.0 + farthest.unwrap_or(0) as f32 * STAGGER;
+        let turn_seconds = self.duration.as_secs_f32() / turns;
+        if turn_seconds <= 0.0 {
+            return 1.0;
+        }
+        let start = distance as f32 * STAGGER * turn_seconds;
+        ((elapsed.as_secs_f32() - start) / turn_seconds).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_flips_follow_each_other_outward_along_every_ray() {
+        // d3 flips d4 and d5 below it, and e4 diagonally
+        let animation =
+            FlipAnimation::new((2, 3), [(3, 3), (4, 3), (3, 4)], Duration::from_secs(2));
+        let at = |seconds: f32| animation.started_at + Duration::from_secs_f32(seconds);
+
+        // one turn takes a second, each distance starting half a turn later
+        assert_eq!(animation.turn((3, 3), at(0.5)), Some(0.0));
+        assert_eq!(animation.turn((3, 4), at(1.0)), Some(0.5));
+        assert_eq!(animation.turn((3, 3), at(1.0)), Some(0.5));
+        assert_eq!(animation.turn((4, 3), at(1.0)), Some(0.0));
+        assert_eq!(animation.turn((4, 3), at(1.5)), Some(0.5));
+        // the placed disc does not turn
+        assert_eq!(animation.turn((2, 3), at(1.0)), None);
+        assert_eq!(animation.turn((0, 0), at(1.0)), None);
+
+        assert!(!animation.is_finished(at(1.9)));
+        assert!(animation.is_finished(at(2.0)));
+        assert_eq!(animation.turn((4, 3), at(2.0)), Some(1.0));
+    }
+
+    #[test]
+    fn an_animation_without_a_duration_is_over_at_once() {
+        let animation = FlipAnimation::new((2, 3), [(3, 3)], Duration::ZERO);
+        assert!(animation.is_finished(animation.started_at));
+        assert_eq!(animation.turn((3, 3), animation.started_at), Some(1.0));
+    }
+}
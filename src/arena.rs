@@ -0,0 +1,398 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::ai::AiType;
+use crate::board::Player;
+use crate::headless;
+use crate::headless::EngineConfig;
+use crate::headless::MoveTime;
+use crate::platform::Instant;
+use crate::referee::Outcome;
+use crate::referee::Referee;
+use crate::tournament::MatchTally;
+
+pub const MAX_CONTESTANTS: usize = 8;
+
+// one AI configuration of the arena
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Contestant {
+    pub ai_type: AiType,
+    pub recursion_depth: usize,
+}
+
+impl Contestant {
+    pub fn name(&self) -> String {
+        self.ai_type.describe(self.recursion_depth)
+    }
+
+    fn engine(&self) -> EngineConfig {
+        EngineConfig {
+            ai_type: self.ai_type,
+            recursion_depth: self.recursion_depth,
+            seed: None,
+        }
+    }
+}
+
+pub fn default_contestants() -> Vec<Contestant> {
+    vec![
+        Contestant {
+            ai_type: AiType::Random,
+            recursion_depth: 1,
+        },
+        Contestant {
+            ai_type: AiType::Minimax,
+            recursion_depth: 2,
+        },
+        Contestant {
+            ai_type: AiType::AlphaBeta,
+            recursion_depth: 3,
+        },
+        Contestant {
+            ai_type: AiType::Positional,
+            recursion_depth: 3,
+        },
+    ]
+}
+
+// the games between two contestants, by index, the first playing black in the first game
+pub struct Pairing {
+    pub first: usize,
+    pub second: usize,
+    pub tally: MatchTally,
+}
+
+// sent by the thread playing the pairing after every game
+enum GameReport {
+    Played {
+        pairing: usize,
+        outcome: Outcome,
+        black_count: usize,
+        white_count: usize,
+        plies: usize,
+    },
+    Failed(String),
+}
+
+// a round-robin of AI configurations, every pair playing a match with colors alternating
+// the pairings are played on background threads through the headless match code
+pub struct Arena {
+    pub contestants: Vec<Contestant>,
+    pub pairings: Vec<Pairing>,
+    // the first failure stops the arena
+    pub error: Option<String>,
+    pub started_at: Instant,
+    running: bool,
+    cancel: Arc<AtomicBool>,
+    report_receiver: mpsc::Receiver<GameReport>,
+}
+
+impl Arena {
+    pub fn start(contestants: Vec<Contestant>, games_per_pairing: usize) -> Self {
+        let mut pairings = Vec::new();
+        for first in 0..contestants.len() {
+            for second in first + 1..contestants.len() {
+                pairings.push(Pairing {
+                    first,
+                    second,
+                    tally: MatchTally::new(games_per_pairing),
+                });
+            }
+        }
+
+        let engines: Arc<Vec<[EngineConfig; 2]>> = Arc::new(
+            pairings
+                .iter()
+                .map(|pairing| {
+                    [
+                        contestants[pairing.first].engine(),
+                        contestants[pairing.second].engine(),
+                    ]
+                })
+                .collect(),
+        );
+        let next_pairing = Arc::new(AtomicUsize::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (report_sender, report_receiver) = mpsc::channel();
+        let thread_count = thread::available_parallelism()
+            .map_or(1, |count| count.get())
+            .min(pairings.len());
+        for _ in 0..thread_count {
+            let engines = Arc::clone(&engines);
+            let next_pairing = Arc::clone(&next_pairing);
+            let cancel = Arc::clone(&cancel);
+            let report_sender = report_sender.clone();
+            thread::spawn(move || {
+                loop {
+                    let pairing = next_pairing.fetch_add(1, Ordering::Relaxed);
+                    let Some(&engines) = engines.get(pairing) else {
+                        return;
+                    };
+                    if !play_pairing(pairing, engines, games_per_pairing, &cancel, &report_sender) {
+                        return;
+                    }
+                }
+            });
+        }
+
+        Arena {
+            contestants,
+            pairings,
+            error: None,
+            started_at: Instant::now(),
+            // without pairings no thread ever reports, the first poll ends the arena
+            running: true,
+            cancel,
+            report_receiver,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    // the games under way are left unfinished, the tallies keep what was played before
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.running = false;
+    }
+
+    // call this from the UI thread
+    // returns the pairings whose last game came in
+    pub fn poll(&mut self) -> Vec<usize> {
+        let mut finished = Vec::new();
+        while self.running {
+            match self.report_receiver.try_recv() {
+                Ok(GameReport::Played {
+                    pairing,
+                    outcome,
+                    black_count,
+                    white_count,
+                    plies,
+                }) => {
+                    let tally = &mut self.pairings[pairing].tally;
+                    tally.record(&outcome, black_count, white_count, plies);
+                    if tally.is_finished() {
+                        finished.push(pairing);
+                    }
+                }
+                Ok(GameReport::Failed(error)) => {
+                    self.error = Some(error);
+                    self.cancel();
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                // every thread ran out of pairings
+                Err(mpsc::TryRecvError::Disconnected) => self.running = false,
+            }
+        }
+        finished
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.pairings
+            .iter()
+            .map(|pairing| pairing.tally.games_played)
+            .sum()
+    }
+
+    pub fn total_games(&self) -> usize {
+        self.pairings
+            .iter()
+            .map(|pairing| pairing.tally.total_games)
+            .sum()
+    }
+
+    // the points of each contestant, by row, against each other one, by column, a win counting
+    // 1 and a draw 1/2, None against itself
+    pub fn crosstable(&self) -> Vec<Vec<Option<f64>>> {
+        let count = self.contestants.len();
+        let mut table: Vec<Vec<Option<f64>>> = (0..count)
+            .map(|row| (0..count).map(|col| (row != col).then_some(0.0)).collect())
+            .collect();
+        for pairing in self.pairings.iter() {
+            let tally = &pairing.tally;
+            let draws = tally.draws as f64 / 2.0;
+            table[pairing.first][pairing.second] = Some(tally.first_wins as f64 + draws);
+            table[pairing.second][pairing.first] = Some(tally.second_wins as f64 + draws);
+        }
+        table
+    }
+
+    // the games each contestant played so far
+    pub fn games_by_contestant(&self) -> Vec<usize> {
+        let mut games = vec![0; self.contestants.len()];
+        for pairing in self.pairings.iter() {
+            games[pairing.first] += pairing.tally.games_played;
+            games[pairing.second] += pairing.tally.games_played;
+        }
+        games
+    }
+
+    // one row per contestant, with its total points last
+    pub fn to_csv(&self) -> String {
+        let names: Vec<String> = self
+            .contestants
+            .iter()
+            .map(|contestant| csv_field(&contestant.name()))
+            .collect();
+        let mut csv = format!(",{},Total\n", names.join(","));
+        for (name, row) in names.iter().zip(self.crosstable()) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|points| points.map_or(String::new(), |points| points.to_string()))
+                .collect();
+            let total: f64 = row.iter().flatten().sum();
+            csv.push_str(&format!("{name},{},{total}\n", cells.join(",")));
+        }
+        csv
+    }
+}
+
+// plays the games of one pairing, returns whether the arena goes on
+fn play_pairing(
+    pairing: usize,
+    engines: [EngineConfig; 2],
+    games: usize,
+    cancel: &AtomicBool,
+    report_sender: &mpsc::Sender<GameReport>,
+) -> bool {
+    let [mut first_agent, mut second_agent] = engines.map(|engine| engine.agent());
+    let mut move_times = [MoveTime::default(); 2];
+    let mut tally = MatchTally::new(games);
+
+    while !tally.is_finished() {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let [first_time, second_time] = &mut move_times;
+        let (agents, colors, times) = match tally.first_color() {
+            Player::Black => (
+                [&mut first_agent, &mut second_agent],
+                engines,
+                [first_time, second_time],
+            ),
+            Player::White => (
+                [&mut second_agent, &mut first_agent],
+                [engines[1], engines[0]],
+                [second_time, first_time],
+            ),
+        };
+        let report = match headless::play_game(agents, colors, times) {
+            Ok(game) => {
+                let (black_count, white_count) = Referee::count_disks(&game.board, Player::Black);
+                let (black_count, white_count) = (black_count as usize, white_count as usize);
+                let plies = game.history.plies.len();
+                tally.record(&game.outcome, black_count, white_count, plies);
+                GameReport::Played {
+                    pairing,
+                    outcome: game.outcome,
+                    black_count,
+                    white_count,
+                    plies,
+                }
+            }
+            Err(e) => GameReport::Failed(e),
+        };
+        let failed = matches!(report, GameReport::Failed(_));
+        if report_sender.send(report).is_err() || failed {
+            return false;
+        }
+    }
+    true
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn random_contestants(count: usize) -> Vec<Contestant> {
+        vec![
+            Contestant {
+                ai_type: AiType::Random,
+                recursion_depth: 1,
+            };
+            count
+        ]
+    }
+
+    fn run_to_end(arena: &mut Arena) -> Vec<usize> {
+        let mut finished = Vec::new();
+        while arena.is_running() {
+            finished.extend(arena.poll());
+            thread::sleep(Duration::from_millis(1));
+        }
+        finished
+    }
+
+    #[test]
+    fn every_pair_plays_its_games() {
+        let mut arena = Arena::start(random_contestants(3), 4);
+        let mut finished = run_to_end(&mut arena);
+
+        finished.sort();
+        assert_eq!(finished, [0, 1, 2]);
+        assert_eq!(arena.error, None);
+        assert_eq!(arena.games_played(), 12);
+        assert_eq!(arena.games_played(), arena.total_games());
+
+        // every game hands out one point between the two
+        let table = arena.crosstable();
+        for pairing in arena.pairings.iter() {
+            let (first, second) = (pairing.first, pairing.second);
+            assert_eq!(
+                table[first][second].unwrap() + table[second][first].unwrap(),
+                4.0
+            );
+        }
+        assert_eq!(table[1][1], None);
+        assert_eq!(arena.games_by_contestant(), [8, 8, 8]);
+    }
+
+    #[test]
+    fn the_crosstable_is_exported_row_by_row() {
+        let mut arena = Arena::start(random_contestants(2), 0);
+        run_to_end(&mut arena);
+        arena.pairings[0].tally.first_wins = 2;
+        arena.pairings[0].tally.draws = 1;
+        arena.pairings[0].tally.second_wins = 1;
+
+        assert_eq!(
+            arena.to_csv(),
+            ",Random,Random,Total\nRandom,,2.5,2.5\nRandom,1.5,,1.5\n"
+        );
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn a_cancelled_arena_keeps_its_results() {
+        let mut arena = Arena::start(random_contestants(2), 1000);
+        while arena.games_played() == 0 {
+            arena.poll();
+            thread::sleep(Duration::from_millis(1));
+        }
+        arena.cancel();
+        let games_played = arena.games_played();
+
+        assert!(!arena.is_running());
+        assert!(arena.poll().is_empty());
+        assert_eq!(arena.games_played(), games_played);
+    }
+}
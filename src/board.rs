@@ -1,10 +1,21 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::Deserialize;
+use serde::Serialize;
+
+/// one of the two sides, black moves first
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Player {
     Black,
     White,
 }
 
 impl Player {
+    /// the other side
+    ///
+    /// ```
+    /// use fia_othello::board::Player;
+    ///
+    /// assert_eq!(Player::Black.opponent(), Player::White);
+    /// ```
     pub fn opponent(&self) -> Self {
         match self {
             Player::Black => Player::White,
@@ -13,27 +24,97 @@ impl Player {
     }
 }
 
+/// a square of the board, empty or holding a disk
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Cell {
     Empty,
     Taken(Player),
 }
 
+/// the disks on the board, indexed by row from the top and column from the left
+///
+/// the default board holds the four disks of the start position
+///
+/// ```
+/// use fia_othello::board::Board;
+/// use fia_othello::board::Cell;
+/// use fia_othello::board::Player;
+///
+/// let board = Board::default();
+/// assert_eq!(board.cell((3, 3)), Cell::Taken(Player::White));
+/// assert_eq!(board.cell((3, 4)), Cell::Taken(Player::Black));
+/// assert_eq!(board.cell((0, 0)), Cell::Empty);
+/// ```
 #[derive(Clone, Debug)]
 pub struct Board {
     pub grid: [[Cell; Board::SIZE]; Board::SIZE],
 }
 
-type Move = (usize, usize);
+/// a square as (row, column), both counted from 0
+///
+/// `(Board::SIZE, Board::SIZE)` stands for no move at all
+pub type Move = (usize, usize);
 
 impl Board {
+    /// the number of rows and of columns
     pub const SIZE: usize = 8;
 
+    /// the cell at the square, which must be on the board
+    ///
+    /// ```
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Cell;
+    ///
+    /// assert_eq!(Board::default().cell((7, 7)), Cell::Empty);
+    /// ```
     pub fn cell(&self, (row, col): Move) -> Cell {
         self.grid[row][col]
     }
 }
 
+/// a reflection or rotation of the board that leaves the start position as it is
+///
+/// each one is its own inverse
+///
+/// ```
+/// use fia_othello::board::Symmetry;
+///
+/// // F5, D3, C4 and E6 are the same first move
+/// let f5 = (4, 5);
+/// let first_moves: Vec<_> = Symmetry::ALL.iter().map(|symmetry| symmetry.apply(f5)).collect();
+/// assert_eq!(first_moves, [(4, 5), (5, 4), (2, 3), (3, 2)]);
+/// assert_eq!(Symmetry::Diagonal.apply(Symmetry::Diagonal.apply(f5)), f5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    Identity,
+    /// the reflection in the diagonal from a1 to h8
+    Diagonal,
+    /// the reflection in the diagonal from h1 to a8
+    AntiDiagonal,
+    HalfTurn,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 4] = [
+        Symmetry::Identity,
+        Symmetry::Diagonal,
+        Symmetry::AntiDiagonal,
+        Symmetry::HalfTurn,
+    ];
+
+    /// the square the move lands on, which must be on the board
+    pub fn apply(self, (row, col): Move) -> Move {
+        let last = Board::SIZE - 1;
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::Diagonal => (col, row),
+            Symmetry::AntiDiagonal => (last - col, last - row),
+            Symmetry::HalfTurn => (last - row, last - col),
+        }
+    }
+}
+
 impl Default for Board {
     fn default() -> Self {
         let mut grid = [[Cell::Empty; Board::SIZE]; Board::SIZE];
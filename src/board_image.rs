@@ -0,0 +1,1344 @@
+use crate::board::Board;
+use crate::board::Cell;
+use crate::board::Move;
+use crate::board::Player;
+use crate::history::MoveNumbers;
+use crate::marks::MARK_LETTERS;
+use crate::marks::SquareMark;
+use crate::orientation::Line;
+use crate::orientation::Orientation;
+
+// a picture's width and height in pixels, whatever the size of the window
+pub const IMAGE_SIZE: u32 = 1024;
+
+// what goes on a picture of the board besides the discs
+#[derive(Clone, Default)]
+pub struct DisplayOptions {
+    // a-h above and below the board, 1-8 left and right of it
+    pub coordinates: bool,
+    pub last_move: Option<Move>,
+    // marked with small dots
+    pub valid_moves: Vec<Move>,
+    // from the center of one square to the center of another
+    pub arrows: Vec<(Move, Move)>,
+    // on the discs, in the color of the other side
+    pub move_numbers: Option<MoveNumbers>,
+    // the end of the game over a dimmed board
+    pub result: Option<ResultOverlay>,
+    // empty squares tinted by how their move ranks, from 0 for the worst to 1 for the best,
+    // with the move's score
+    pub heatmap: Vec<(Move, f32, f32)>,
+    // the analyzed score of each legal move in the corner of its square, as
+    // `analysis::compact_score` writes it, and whether it is the best one, which stands out
+    pub annotations: Vec<(Move, String, bool)>,
+    // the marks left on the squares, over the discs and under the result
+    pub marks: Vec<(Move, SquareMark)>,
+    // the coordinates are written by the lines they end up beside
+    pub orientation: Orientation,
+}
+
+// the winner's disc, or one of each color for a tie, above the final score
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ResultOverlay {
+    pub winner: Option<Player>,
+    pub black_count: usize,
+    pub white_count: usize,
+}
+
+impl ResultOverlay {
+    // the score as it is written, black's count first
+    fn score(&self) -> String {
+        format!("{}-{}", self.black_count, self.white_count)
+    }
+}
+
+type Rgb = [u8; 3];
+
+const FRAME_COLOR: Rgb = [40, 40, 40];
+const LABEL_COLOR: Rgb = [210, 210, 210];
+const SQUARE_COLOR: Rgb = [0, 100, 0];
+const LINE_COLOR: Rgb = [0, 0, 0];
+const LAST_MOVE_COLOR: Rgb = [220, 40, 40];
+const VALID_MOVE_COLOR: Rgb = [0, 60, 0];
+const ARROW_COLOR: Rgb = [255, 190, 0];
+const ANNOTATION_COLOR: Rgb = [230, 230, 230];
+const BEST_ANNOTATION_COLOR: Rgb = [80, 160, 255];
+const MARK_COLOR: Rgb = [255, 80, 160];
+// the radius of a circle and the half height of a letter, over the size of a square
+const MARK_REACH: f32 = 0.3;
+// the width of the strokes of a circle and a cross, over the size of a square
+const MARK_WIDTH: f32 = 0.07;
+const BLACK_DISC_COLOR: Rgb = [0, 0, 0];
+const WHITE_DISC_COLOR: Rgb = [255, 255, 255];
+const SCRIM_COLOR: Rgb = [0, 0, 0];
+const SCRIM_OPACITY: f32 = 0.6;
+const HEATMAP_OPACITY: f32 = 0.75;
+// from dark purple for the worst move through blue and green to yellow for the best, evenly
+// spaced in lightness
+const HEATMAP_COLORS: [Rgb; 5] = [
+    [68, 1, 84],
+    [59, 82, 139],
+    [33, 145, 140],
+    [94, 201, 98],
+    [253, 231, 37],
+];
+
+// 5 pixels wide and 7 high, the high bit of each row being the leftmost pixel
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+#[rustfmt::skip]
+const LETTER_GLYPHS: [[u8; GLYPH_HEIGHT]; Board::SIZE] = [
+    [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111],
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110],
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+    [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111],
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110],
+    [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000],
+    [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+];
+#[rustfmt::skip]
+const DIGIT_GLYPHS: [[u8; GLYPH_HEIGHT]; 10] = [
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+];
+const PLUS_GLYPH: [u8; GLYPH_HEIGHT] = [
+    0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000,
+];
+const DASH_GLYPH: [u8; GLYPH_HEIGHT] = [
+    0b00000, 0b00000, 0b00000, 0b01110, 0b00000, 0b00000, 0b00000,
+];
+#[rustfmt::skip]
+const CAPITAL_GLYPHS: [[u8; GLYPH_HEIGHT]; MARK_LETTERS.len()] = [
+    [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+    [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+    [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+    [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+    [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+];
+// the letters of the built-in words for a win and a loss past the column letters
+#[rustfmt::skip]
+const WORD_GLYPHS: [(char, [u8; GLYPH_HEIGHT]); 9] = [
+    ('i', [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('l', [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('n', [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001]),
+    ('o', [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('p', [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000]),
+    ('r', [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000]),
+    ('s', [0b00000, 0b00000, 0b01111, 0b10000, 0b01110, 0b00001, 0b11110]),
+    ('t', [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110]),
+    ('w', [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010]),
+];
+
+// where the board sits in a picture of `size` pixels
+pub struct Layout {
+    // the frame around the board, holding the coordinates
+    pub margin: f32,
+    pub square_size: f32,
+    orientation: Orientation,
+}
+
+impl Layout {
+    pub fn new(size: u32, options: &DisplayOptions) -> Self {
+        let size = size as f32;
+        let margin = if options.coordinates {
+            size / 16.0
+        } else {
+            0.0
+        };
+        Layout {
+            margin,
+            square_size: (size - 2.0 * margin) / Board::SIZE as f32,
+            orientation: options.orientation,
+        }
+    }
+
+    pub fn square_center(&self, square: Move) -> (f32, f32) {
+        self.view_center(self.orientation.to_view(square))
+    }
+
+    // of a square by its row and column in the picture
+    fn view_center(&self, (row, col): Move) -> (f32, f32) {
+        (
+            self.margin + (col as f32 + 0.5) * self.square_size,
+            self.margin + (row as f32 + 0.5) * self.square_size,
+        )
+    }
+
+    fn line_width(&self) -> f32 {
+        (self.square_size * 0.02).max(1.0)
+    }
+
+    fn disc_radius(&self) -> f32 {
+        self.square_size / 2.0 * 0.93
+    }
+
+    fn valid_move_radius(&self) -> f32 {
+        self.square_size * 0.08
+    }
+
+    fn last_move_radius(&self) -> f32 {
+        self.square_size * 0.1
+    }
+
+    fn move_number_height(&self) -> f32 {
+        self.square_size * 0.3
+    }
+
+    fn heat_score_height(&self) -> f32 {
+        self.square_size * 0.25
+    }
+
+    fn annotation_height(&self, best: bool) -> f32 {
+        self.square_size * if best { 0.22 } else { 0.16 }
+    }
+
+    // the center of an annotation of `columns` glyph pixels across, in the top right corner of
+    // the square as it is shown
+    fn annotation_center(&self, square: Move, columns: usize, height: f32) -> (f32, f32) {
+        let (center_x, center_y) = self.square_center(square);
+        let width = columns as f32 * height / GLYPH_HEIGHT as f32;
+        let padding = self.square_size * 0.06;
+        (
+            center_x + self.square_size / 2.0 - padding - width / 2.0,
+            center_y - self.square_size / 2.0 + padding + height / 2.0,
+        )
+    }
+
+    fn board_center(&self) -> (f32, f32) {
+        let center = self.margin + self.square_size * Board::SIZE as f32 / 2.0;
+        (center, center)
+    }
+
+    // the centers of the discs of the result, one for a winner and two side by side for a tie,
+    // and their radius
+    fn result_discs(&self, result: &ResultOverlay) -> Vec<((f32, f32), f32, Player)> {
+        let (center_x, center_y) = self.board_center();
+        let disc_y = center_y - self.square_size;
+        match result.winner {
+            Some(winner) => vec![((center_x, disc_y), self.square_size * 0.8, winner)],
+            None => [Player::Black, Player::White]
+                .into_iter()
+                .zip([-0.6, 0.6])
+                .map(|(player, offset)| {
+                    let x = center_x + offset * self.square_size;
+                    ((x, disc_y), self.square_size * 0.5, player)
+                })
+                .collect(),
+        }
+    }
+
+    fn result_score_center(&self) -> (f32, f32) {
+        let (center_x, center_y) = self.board_center();
+        (center_x, center_y + self.square_size)
+    }
+
+    fn result_score_height(&self) -> f32 {
+        self.square_size
+    }
+
+    // the shaft from the start to the base of the head, and the head as its tip and two corners
+    fn arrow(&self, (from, to): (Move, Move)) -> Arrow {
+        let start = self.square_center(from);
+        let tip = self.square_center(to);
+        let length = (tip.0 - start.0).hypot(tip.1 - start.1).max(1.0);
+        let direction = ((tip.0 - start.0) / length, (tip.1 - start.1) / length);
+        let head_length = (self.square_size * 0.3).min(length);
+        let base = (
+            tip.0 - direction.0 * head_length,
+            tip.1 - direction.1 * head_length,
+        );
+        let half_width = self.square_size * 0.15;
+        Arrow {
+            start,
+            base,
+            shaft_width: self.square_size * 0.08,
+            head: [
+                tip,
+                (
+                    base.0 - direction.1 * half_width,
+                    base.1 + direction.0 * half_width,
+                ),
+                (
+                    base.0 + direction.1 * half_width,
+                    base.1 - direction.0 * half_width,
+                ),
+            ],
+        }
+    }
+}
+
+struct Arrow {
+    start: (f32, f32),
+    base: (f32, f32),
+    shaft_width: f32,
+    head: [(f32, f32); 3],
+}
+
+impl Arrow {
+    // how much of the pixel at `point` the arrow covers
+    fn coverage(&self, point: (f32, f32)) -> f32 {
+        let cross = |a: (f32, f32), b: (f32, f32)| {
+            (b.0 - a.0) * (point.1 - a.1) - (b.1 - a.1) * (point.0 - a.0)
+        };
+        let [tip, left, right] = self.head;
+        let sides = [cross(tip, left), cross(left, right), cross(right, tip)];
+        if sides.iter().all(|side| *side >= 0.0) || sides.iter().all(|side| *side <= 0.0) {
+            return 1.0;
+        }
+
+        let distance = segment_distance(point, self.start, self.base);
+        (self.shaft_width / 2.0 - distance + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+// from the point to the nearest point of the segment from `a` to `b`
+fn segment_distance(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let segment = (b.0 - a.0, b.1 - a.1);
+    let along = ((point.0 - a.0) * segment.0 + (point.1 - a.1) * segment.1)
+        / (segment.0 * segment.0 + segment.1 * segment.1).max(1.0);
+    let nearest = (
+        a.0 + segment.0 * along.clamp(0.0, 1.0),
+        a.1 + segment.1 * along.clamp(0.0, 1.0),
+    );
+    (point.0 - nearest.0).hypot(point.1 - nearest.1)
+}
+
+// the two strokes of a cross on the square, each from corner to corner of the circle's square
+fn cross_strokes(layout: &Layout, square: Move) -> [((f32, f32), (f32, f32)); 2] {
+    let (x, y) = layout.square_center(square);
+    let reach = layout.square_size * MARK_REACH * 0.8;
+    [
+        ((x - reach, y - reach), (x + reach, y + reach)),
+        ((x - reach, y + reach), (x + reach, y - reach)),
+    ]
+}
+
+// how much of the pixel at `point` the mark on the square covers
+fn mark_coverage(layout: &Layout, square: Move, mark: SquareMark, point: (f32, f32)) -> f32 {
+    let center = layout.square_center(square);
+    let reach = layout.square_size * MARK_REACH;
+    let distance = match mark {
+        SquareMark::Circle => ((point.0 - center.0).hypot(point.1 - center.1) - reach).abs(),
+        SquareMark::Cross => cross_strokes(layout, square)
+            .iter()
+            .map(|&(a, b)| segment_distance(point, a, b))
+            .fold(f32::INFINITY, f32::min),
+        SquareMark::Letter(letter) => {
+            let scale = 2.0 * reach / GLYPH_HEIGHT as f32;
+            let on = on_glyphs(&[capital_glyph(letter)], center, scale, point);
+            return if on { 1.0 } else { 0.0 };
+        }
+    };
+    (layout.square_size * MARK_WIDTH / 2.0 - distance + 0.5).clamp(0.0, 1.0)
+}
+
+fn capital_glyph(letter: char) -> [u8; GLYPH_HEIGHT] {
+    MARK_LETTERS
+        .iter()
+        .position(|&mark_letter| mark_letter == letter)
+        .map_or([0; GLYPH_HEIGHT], |index| CAPITAL_GLYPHS[index])
+}
+
+// the glyphs of an annotation, their center, the size of a glyph pixel and their color
+type AnnotationGlyphs = (Vec<[u8; GLYPH_HEIGHT]>, (f32, f32), f32, Rgb);
+
+// the picture as RGBA rows from the top, `size` pixels wide and high
+pub fn render(board: &Board, options: &DisplayOptions, size: u32) -> Vec<u8> {
+    let layout = Layout::new(size, options);
+    let board_size = layout.square_size * Board::SIZE as f32;
+    let arrows: Vec<Arrow> = options
+        .arrows
+        .iter()
+        .map(|arrow| layout.arrow(*arrow))
+        .collect();
+    let annotations: Vec<AnnotationGlyphs> = options
+        .annotations
+        .iter()
+        .map(|(square, text, best)| {
+            let (square, best) = (*square, *best);
+            let glyphs: Vec<_> = text.chars().map(annotation_glyph).collect();
+            let height = layout.annotation_height(best);
+            let columns = glyphs.len() * (GLYPH_WIDTH + 1) - 1;
+            let center = layout.annotation_center(square, columns, height);
+            let color = if best {
+                BEST_ANNOTATION_COLOR
+            } else {
+                ANNOTATION_COLOR
+            };
+            (glyphs, center, height / GLYPH_HEIGHT as f32, color)
+        })
+        .collect();
+
+    let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+    for y in 0..size {
+        for x in 0..size {
+            // the center of the pixel, relative to the board
+            let board_x = x as f32 + 0.5 - layout.margin;
+            let board_y = y as f32 + 0.5 - layout.margin;
+            let color =
+                if (0.0..board_size).contains(&board_x) && (0.0..board_size).contains(&board_y) {
+                    let point = (x as f32 + 0.5, y as f32 + 0.5);
+                    let color = annotations.iter().fold(
+                        square_pixel(board, options, &layout, board_x, board_y),
+                        |color, (glyphs, center, scale, annotation_color)| {
+                            if on_glyphs(glyphs, *center, *scale, point) {
+                                *annotation_color
+                            } else {
+                                color
+                            }
+                        },
+                    );
+                    let color = arrows.iter().fold(color, |color, arrow| {
+                        blend(color, ARROW_COLOR, arrow.coverage(point))
+                    });
+                    let color = options.marks.iter().fold(color, |color, &(square, mark)| {
+                        blend(
+                            color,
+                            MARK_COLOR,
+                            mark_coverage(&layout, square, mark, point),
+                        )
+                    });
+                    match &options.result {
+                        Some(result) => result_pixel(&layout, result, color, point),
+                        None => color,
+                    }
+                } else if label_pixel(&layout, size, x as f32 + 0.5, y as f32 + 0.5) {
+                    LABEL_COLOR
+                } else {
+                    FRAME_COLOR
+                };
+            pixels.extend_from_slice(&[color[0], color[1], color[2], 255]);
+        }
+    }
+    pixels
+}
+
+fn square_pixel(
+    board: &Board,
+    options: &DisplayOptions,
+    layout: &Layout,
+    board_x: f32,
+    board_y: f32,
+) -> Rgb {
+    let square = layout.orientation.to_board((
+        (board_y / layout.square_size) as usize,
+        (board_x / layout.square_size) as usize,
+    ));
+    let (center_x, center_y) = layout.square_center(square);
+    let distance = (board_x + layout.margin - center_x).hypot(board_y + layout.margin - center_y);
+    // how far inside a circle of the radius the pixel is, smoothed over one pixel
+    let coverage = |radius: f32| (radius - distance + 0.5).clamp(0.0, 1.0);
+
+    let to_line = (board_x % layout.square_size)
+        .min(layout.square_size - board_x % layout.square_size)
+        .min(board_y % layout.square_size)
+        .min(layout.square_size - board_y % layout.square_size);
+    let on_line = to_line < layout.line_width() / 2.0;
+    let mut color = if on_line { LINE_COLOR } else { SQUARE_COLOR };
+
+    if let Some(&(_, level, _)) = heat_square(options, square)
+        && !on_line
+    {
+        color = blend(color, heat_color(level), HEATMAP_OPACITY);
+    }
+
+    if let Cell::Taken(player) = board.cell(square) {
+        color = blend(color, disc_color(player), coverage(layout.disc_radius()));
+        if let Some(number) = move_number(options, square) {
+            let glyphs: Vec<_> = number
+                .bytes()
+                .map(|digit| DIGIT_GLYPHS[(digit - b'0') as usize])
+                .collect();
+            let scale = layout.move_number_height() / GLYPH_HEIGHT as f32;
+            let point = (board_x + layout.margin, board_y + layout.margin);
+            if on_glyphs(&glyphs, (center_x, center_y), scale, point) {
+                color = disc_color(player.opponent());
+            }
+        }
+    } else if options.valid_moves.contains(&square) {
+        color = blend(
+            color,
+            VALID_MOVE_COLOR,
+            coverage(layout.valid_move_radius()),
+        );
+    }
+    if options.last_move == Some(square) {
+        color = blend(color, LAST_MOVE_COLOR, coverage(layout.last_move_radius()));
+    }
+    if let Some(&(_, level, score)) = heat_square(options, square)
+        && let Some(text) = heat_score_text(score)
+    {
+        let glyphs: Vec<_> = text.bytes().map(sign_or_digit_glyph).collect();
+        let scale = layout.heat_score_height() / GLYPH_HEIGHT as f32;
+        let point = (board_x + layout.margin, board_y + layout.margin);
+        if on_glyphs(&glyphs, (center_x, center_y), scale, point) {
+            color = heat_text_color(level);
+        }
+    }
+    color
+}
+
+fn heat_square(options: &DisplayOptions, square: Move) -> Option<&(Move, f32, f32)> {
+    options
+        .heatmap
+        .iter()
+        .find(|(heat_square, _, _)| *heat_square == square)
+}
+
+// the color of a heatmap level from 0 to 1
+pub fn heat_color(level: f32) -> [u8; 3] {
+    let position = level.clamp(0.0, 1.0) * (HEATMAP_COLORS.len() - 1) as f32;
+    let index = (position as usize).min(HEATMAP_COLORS.len() - 2);
+    blend(
+        HEATMAP_COLORS[index],
+        HEATMAP_COLORS[index + 1],
+        position - index as f32,
+    )
+}
+
+// dark on the light end of the heatmap, light on the dark end
+pub fn heat_text_color(level: f32) -> [u8; 3] {
+    if level > 0.6 {
+        BLACK_DISC_COLOR
+    } else {
+        WHITE_DISC_COLOR
+    }
+}
+
+// e.g. +12 or -3, nothing for a won or lost position, which the glyphs cannot write
+fn heat_score_text(score: f32) -> Option<String> {
+    score.is_finite().then(|| format!("{score:+.0}"))
+}
+
+fn annotation_glyph(letter: char) -> [u8; GLYPH_HEIGHT] {
+    match letter {
+        '−' => DASH_GLYPH,
+        'a'..='h' => LETTER_GLYPHS[letter as usize - 'a' as usize],
+        _ => WORD_GLYPHS
+            .iter()
+            .find(|(word_letter, _)| *word_letter == letter)
+            .map_or_else(
+                || u8::try_from(letter).map_or([0; GLYPH_HEIGHT], sign_or_digit_glyph),
+                |(_, glyph)| *glyph,
+            ),
+    }
+}
+
+fn sign_or_digit_glyph(byte: u8) -> [u8; GLYPH_HEIGHT] {
+    match byte {
+        b'+' => PLUS_GLYPH,
+        b'-' => DASH_GLYPH,
+        digit @ b'0'..=b'9' => DIGIT_GLYPHS[(digit - b'0') as usize],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+// the board dimmed, with the discs and the score of the result on top
+fn result_pixel(layout: &Layout, result: &ResultOverlay, below: Rgb, point: (f32, f32)) -> Rgb {
+    let mut color = blend(below, SCRIM_COLOR, SCRIM_OPACITY);
+    for (center, radius, player) in layout.result_discs(result) {
+        let distance = (point.0 - center.0).hypot(point.1 - center.1);
+        color = blend(
+            color,
+            disc_color(player),
+            (radius - distance + 0.5).clamp(0.0, 1.0),
+        );
+    }
+    let glyphs: Vec<_> = result.score().bytes().map(sign_or_digit_glyph).collect();
+    let scale = layout.result_score_height() / GLYPH_HEIGHT as f32;
+    if on_glyphs(&glyphs, layout.result_score_center(), scale, point) {
+        color = LABEL_COLOR;
+    }
+    color
+}
+
+// the number to write on the disc of the square, if any
+fn move_number(options: &DisplayOptions, (row, col): Move) -> Option<String> {
+    options.move_numbers?[row][col].map(|number| number.to_string())
+}
+
+fn disc_color(player: Player) -> Rgb {
+    match player {
+        Player::Black => BLACK_DISC_COLOR,
+        Player::White => WHITE_DISC_COLOR,
+    }
+}
+
+fn blend(below: Rgb, above: Rgb, coverage: f32) -> Rgb {
+    let mix = |b: u8, a: u8| (b as f32 + (a as f32 - b as f32) * coverage).round() as u8;
+    [
+        mix(below[0], above[0]),
+        mix(below[1], above[1]),
+        mix(below[2], above[2]),
+    ]
+}
+
+// whether the pixel belongs to one of the coordinates in the frame
+fn label_pixel(layout: &Layout, size: u32, x: f32, y: f32) -> bool {
+    if layout.margin == 0.0 {
+        return false;
+    }
+    let scale = layout.margin / 2.0 / GLYPH_HEIGHT as f32;
+    let far_margin = size as f32 - layout.margin;
+
+    for index in 0..Board::SIZE {
+        let (column_center, _) = layout.view_center((0, index));
+        let (_, row_center) = layout.view_center((index, 0));
+        // above and below the columns, left and right of the rows
+        let column_spots =
+            [layout.margin / 2.0, far_margin + layout.margin / 2.0].map(|center_y| {
+                let glyph = line_glyph(layout.orientation.view_column(index));
+                (glyph, column_center, center_y)
+            });
+        let row_spots = [layout.margin / 2.0, far_margin + layout.margin / 2.0].map(|center_x| {
+            let glyph = line_glyph(layout.orientation.view_row(index));
+            (glyph, center_x, row_center)
+        });
+        for (glyph, center_x, center_y) in column_spots.into_iter().chain(row_spots) {
+            if on_glyphs(&[glyph], (center_x, center_y), scale, (x, y)) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// a column's letter or a row's number
+fn line_glyph(line: Line) -> [u8; GLYPH_HEIGHT] {
+    match line {
+        Line::Column(col) => LETTER_GLYPHS[col],
+        Line::Row(row) => DIGIT_GLYPHS[row + 1],
+    }
+}
+
+// whether the point is on a lit pixel of the glyphs, set side by side around the center with a
+// pixel between them, `scale` being the size of a glyph pixel
+fn on_glyphs(
+    glyphs: &[[u8; GLYPH_HEIGHT]],
+    (center_x, center_y): (f32, f32),
+    scale: f32,
+    (x, y): (f32, f32),
+) -> bool {
+    let columns = glyphs.len() * (GLYPH_WIDTH + 1) - 1;
+    let width = columns as f32 * scale;
+    let height = GLYPH_HEIGHT as f32 * scale;
+    let left = center_x - width / 2.0;
+    let top = center_y - height / 2.0;
+    if !(left..left + width).contains(&x) || !(top..top + height).contains(&y) {
+        return false;
+    }
+
+    let column = (((x - left) / scale) as usize).min(columns - 1);
+    let row = (((y - top) / scale) as usize).min(GLYPH_HEIGHT - 1);
+    let (glyph, column) = (column / (GLYPH_WIDTH + 1), column % (GLYPH_WIDTH + 1));
+    column < GLYPH_WIDTH && (glyphs[glyph][row] >> (GLYPH_WIDTH - 1 - column)) & 1 == 1
+}
+
+// a PNG of the board at `IMAGE_SIZE`
+pub fn encode_png(board: &Board, options: &DisplayOptions) -> Result<Vec<u8>, String> {
+    let pixels = render(board, options, IMAGE_SIZE);
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, IMAGE_SIZE, IMAGE_SIZE);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(png)
+}
+
+// an SVG document of the same picture as `render` at `IMAGE_SIZE`
+// the same position and options always give the same markup, element for element
+pub fn svg(board: &Board, options: &DisplayOptions) -> String {
+    let layout = Layout::new(IMAGE_SIZE, options);
+    let board_size = layout.square_size * Board::SIZE as f32;
+    let far_edge = layout.margin + board_size;
+    let mut lines = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{IMAGE_SIZE}" height="{IMAGE_SIZE}" viewBox="0 0 {IMAGE_SIZE} {IMAGE_SIZE}">"#
+        ),
+        format!(
+            r#"<rect width="{IMAGE_SIZE}" height="{IMAGE_SIZE}" fill="{}"/>"#,
+            hex(FRAME_COLOR)
+        ),
+        format!(
+            r#"<rect x="{margin}" y="{margin}" width="{size}" height="{size}" fill="{}"/>"#,
+            hex(SQUARE_COLOR),
+            margin = number(layout.margin),
+            size = number(board_size)
+        ),
+    ];
+    for &(square, level, _) in options.heatmap.iter() {
+        let (x, y) = layout.square_center(square);
+        let half = layout.square_size / 2.0;
+        lines.push(format!(
+            r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="{}" fill-opacity="{}"/>"#,
+            number(x - half),
+            number(y - half),
+            hex(heat_color(level)),
+            number(HEATMAP_OPACITY),
+            size = number(layout.square_size)
+        ));
+    }
+    lines.push(format!(
+        r#"<g stroke="{}" stroke-width="{}">"#,
+        hex(LINE_COLOR),
+        number(layout.line_width())
+    ));
+    for index in 0..=Board::SIZE {
+        let offset = number(layout.margin + index as f32 * layout.square_size);
+        let (near, far) = (number(layout.margin), number(far_edge));
+        lines.push(format!(
+            r#"<line x1="{offset}" y1="{near}" x2="{offset}" y2="{far}"/>"#
+        ));
+        lines.push(format!(
+            r#"<line x1="{near}" y1="{offset}" x2="{far}" y2="{offset}"/>"#
+        ));
+    }
+    lines.push("</g>".to_string());
+
+    if options.coordinates {
+        lines.push(format!(
+            r#"<g font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">"#,
+            number(layout.margin / 2.0),
+            hex(LABEL_COLOR)
+        ));
+        let (near, far) = (
+            number(layout.margin / 2.0),
+            number(far_edge + layout.margin / 2.0),
+        );
+        for index in 0..Board::SIZE {
+            let (column_center, _) = layout.view_center((0, index));
+            let (_, row_center) = layout.view_center((index, 0));
+            let column_label = layout.orientation.view_column(index).label();
+            let row_label = layout.orientation.view_row(index).label();
+            for y in [&near, &far] {
+                lines.push(format!(
+                    r#"<text x="{}" y="{y}">{column_label}</text>"#,
+                    number(column_center)
+                ));
+            }
+            for x in [&near, &far] {
+                lines.push(format!(
+                    r#"<text x="{x}" y="{}">{row_label}</text>"#,
+                    number(row_center)
+                ));
+            }
+        }
+        lines.push("</g>".to_string());
+    }
+
+    let circle = |square: Move, radius: f32, color: Rgb| {
+        let (x, y) = layout.square_center(square);
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            number(x),
+            number(y),
+            number(radius),
+            hex(color)
+        )
+    };
+    for row in 0..Board::SIZE {
+        for col in 0..Board::SIZE {
+            match board.cell((row, col)) {
+                Cell::Taken(player) => {
+                    lines.push(circle((row, col), layout.disc_radius(), disc_color(player)))
+                }
+                Cell::Empty if options.valid_moves.contains(&(row, col)) => lines.push(circle(
+                    (row, col),
+                    layout.valid_move_radius(),
+                    VALID_MOVE_COLOR,
+                )),
+                Cell::Empty => {}
+            }
+        }
+    }
+    if options.move_numbers.is_some() {
+        lines.push(format!(
+            r#"<g font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+            number(layout.move_number_height())
+        ));
+        for row in 0..Board::SIZE {
+            for col in 0..Board::SIZE {
+                if let (Cell::Taken(player), Some(move_number)) =
+                    (board.cell((row, col)), move_number(options, (row, col)))
+                {
+                    let (x, y) = layout.square_center((row, col));
+                    lines.push(format!(
+                        r#"<text x="{}" y="{}" fill="{}">{move_number}</text>"#,
+                        number(x),
+                        number(y),
+                        hex(disc_color(player.opponent()))
+                    ));
+                }
+            }
+        }
+        lines.push("</g>".to_string());
+    }
+    if let Some(last_move) = options.last_move {
+        lines.push(circle(
+            last_move,
+            layout.last_move_radius(),
+            LAST_MOVE_COLOR,
+        ));
+    }
+    for &(square, level, score) in options.heatmap.iter() {
+        if let Some(text) = heat_score_text(score) {
+            let (x, y) = layout.square_center(square);
+            lines.push(format!(
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{text}</text>"#,
+                number(x),
+                number(y),
+                number(layout.heat_score_height()),
+                hex(heat_text_color(level))
+            ));
+        }
+    }
+
+    for (square, text, best) in options.annotations.iter() {
+        let (square, best) = (*square, *best);
+        let height = layout.annotation_height(best);
+        // as wide as the glyphs of the picture, so that it lands on the same spot
+        let columns = text.chars().count() * (GLYPH_WIDTH + 1) - 1;
+        let (x, y) = layout.annotation_center(square, columns, height);
+        let (color, weight) = if best {
+            (BEST_ANNOTATION_COLOR, "bold")
+        } else {
+            (ANNOTATION_COLOR, "normal")
+        };
+        lines.push(format!(
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" font-weight="{weight}" fill="{}" text-anchor="middle" dominant-baseline="central">{text}</text>"#,
+            number(x),
+            number(y),
+            number(height),
+            hex(color)
+        ));
+    }
+
+    for arrow in options.arrows.iter() {
+        let arrow = layout.arrow(*arrow);
+        lines.push(format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+            number(arrow.start.0),
+            number(arrow.start.1),
+            number(arrow.base.0),
+            number(arrow.base.1),
+            hex(ARROW_COLOR),
+            number(arrow.shaft_width)
+        ));
+        let points: Vec<String> = arrow
+            .head
+            .iter()
+            .map(|(x, y)| format!("{},{}", number(*x), number(*y)))
+            .collect();
+        lines.push(format!(
+            r#"<polygon points="{}" fill="{}"/>"#,
+            points.join(" "),
+            hex(ARROW_COLOR)
+        ));
+    }
+
+    let stroke_width = number(layout.square_size * MARK_WIDTH);
+    for &(square, mark) in options.marks.iter() {
+        let (x, y) = layout.square_center(square);
+        let reach = layout.square_size * MARK_REACH;
+        lines.push(match mark {
+            SquareMark::Circle => format!(
+                r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{stroke_width}"/>"#,
+                number(x),
+                number(y),
+                number(reach),
+                hex(MARK_COLOR)
+            ),
+            SquareMark::Cross => {
+                let strokes: Vec<String> = cross_strokes(&layout, square)
+                    .iter()
+                    .map(|(a, b)| {
+                        format!(
+                            "M{},{} L{},{}",
+                            number(a.0),
+                            number(a.1),
+                            number(b.0),
+                            number(b.1)
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#"<path d="{}" stroke="{}" stroke-width="{stroke_width}" stroke-linecap="round"/>"#,
+                    strokes.join(" "),
+                    hex(MARK_COLOR)
+                )
+            }
+            SquareMark::Letter(letter) => format!(
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" font-weight="bold" fill="{}" text-anchor="middle" dominant-baseline="central">{letter}</text>"#,
+                number(x),
+                number(y),
+                number(2.0 * reach),
+                hex(MARK_COLOR)
+            ),
+        });
+    }
+
+    if let Some(result) = &options.result {
+        lines.push(format!(
+            r#"<rect x="{margin}" y="{margin}" width="{size}" height="{size}" fill="{}" fill-opacity="{}"/>"#,
+            hex(SCRIM_COLOR),
+            number(SCRIM_OPACITY),
+            margin = number(layout.margin),
+            size = number(board_size)
+        ));
+        for ((x, y), radius, player) in layout.result_discs(result) {
+            lines.push(format!(
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                number(x),
+                number(y),
+                number(radius),
+                hex(disc_color(player))
+            ));
+        }
+        let (x, y) = layout.result_score_center();
+        lines.push(format!(
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            number(x),
+            number(y),
+            number(layout.result_score_height()),
+            hex(LABEL_COLOR),
+            result.score()
+        ));
+    }
+
+    lines.push("</svg>".to_string());
+    lines.join("\n") + "\n"
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+// at most two decimals and no trailing zeros, e.g. 64, 2.24 or 0.5
+fn number(value: f32) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis;
+    use crate::locale::Language;
+    use crate::locale::Strings;
+    use crate::orientation::Rotation;
+
+    fn pixel(pixels: &[u8], size: u32, (x, y): (f32, f32)) -> Rgb {
+        let index = (y as usize * size as usize + x as usize) * 4;
+        [pixels[index], pixels[index + 1], pixels[index + 2]]
+    }
+
+    // FNV-1a, stable across platforms and compiler versions unlike the std hasher
+    fn hash(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    #[test]
+    fn the_discs_and_markers_are_drawn_on_their_squares() {
+        let options = DisplayOptions {
+            coordinates: true,
+            last_move: Some((3, 4)),
+            valid_moves: vec![(2, 3)],
+            arrows: vec![((7, 0), (7, 2))],
+            move_numbers: None,
+            result: None,
+            heatmap: Vec::new(),
+            annotations: Vec::new(),
+            marks: Vec::new(),
+            orientation: Orientation::default(),
+        };
+        let pixels = render(&Board::default(), &options, 256);
+        let layout = Layout::new(256, &options);
+
+        assert_eq!(pixel(&pixels, 256, layout.square_center((3, 3))), [255; 3]);
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((3, 4))),
+            LAST_MOVE_COLOR
+        );
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((2, 3))),
+            VALID_MOVE_COLOR
+        );
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((0, 0))),
+            SQUARE_COLOR
+        );
+        assert_eq!(pixel(&pixels, 256, (1.0, 1.0)), FRAME_COLOR);
+        // the shaft passes through the square between the ends
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((7, 1))),
+            ARROW_COLOR
+        );
+    }
+
+    #[test]
+    fn the_picture_depends_on_the_position_alone() {
+        let options = DisplayOptions {
+            coordinates: true,
+            last_move: Some((3, 4)),
+            valid_moves: vec![(2, 3), (3, 2), (4, 5), (5, 4)],
+            arrows: Vec::new(),
+            move_numbers: None,
+            result: None,
+            heatmap: Vec::new(),
+            annotations: Vec::new(),
+            marks: Vec::new(),
+            orientation: Orientation::default(),
+        };
+        let pixels = render(&Board::default(), &options, IMAGE_SIZE);
+        assert_eq!(pixels.len(), (IMAGE_SIZE * IMAGE_SIZE * 4) as usize);
+        assert_eq!(hash(&pixels), 3726712869213619573);
+    }
+
+    #[test]
+    fn the_svg_markup_is_stable() {
+        let mut board = Board {
+            grid: [[Cell::Empty; Board::SIZE]; Board::SIZE],
+        };
+        board.grid[0][0] = Cell::Taken(Player::Black);
+        board.grid[7][7] = Cell::Taken(Player::White);
+        let options = DisplayOptions {
+            coordinates: false,
+            last_move: Some((0, 0)),
+            valid_moves: vec![(1, 1)],
+            arrows: vec![((7, 0), (5, 2))],
+            move_numbers: None,
+            result: None,
+            heatmap: Vec::new(),
+            annotations: Vec::new(),
+            marks: Vec::new(),
+            orientation: Orientation::default(),
+        };
+
+        let grid: String = (0..=Board::SIZE)
+            .map(|index| {
+                let offset = index * 128;
+                format!(
+                    "<line x1=\"{offset}\" y1=\"0\" x2=\"{offset}\" y2=\"1024\"/>\n\
+                     <line x1=\"0\" y1=\"{offset}\" x2=\"1024\" y2=\"{offset}\"/>\n"
+                )
+            })
+            .collect();
+        assert_eq!(
+            svg(&board, &options),
+            format!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="1024" height="1024" viewBox="0 0 1024 1024">
+<rect width="1024" height="1024" fill="#282828"/>
+<rect x="0" y="0" width="1024" height="1024" fill="#006400"/>
+<g stroke="#000000" stroke-width="2.56">
+{grid}</g>
+<circle cx="64" cy="64" r="59.52" fill="#000000"/>
+<circle cx="192" cy="192" r="10.24" fill="#003c00"/>
+<circle cx="960" cy="960" r="59.52" fill="#ffffff"/>
+<circle cx="64" cy="64" r="12.8" fill="#dc2828"/>
+<line x1="64" y1="960" x2="292.85" y2="731.15" stroke="#ffbe00" stroke-width="10.24" stroke-linecap="round"/>
+<polygon points="320,704 306.42,744.73 279.27,717.58" fill="#ffbe00"/>
+</svg>
+"##
+            )
+        );
+    }
+
+    #[test]
+    fn the_svg_has_every_coordinate_twice() {
+        let options = DisplayOptions {
+            coordinates: true,
+            ..Default::default()
+        };
+        let svg = svg(&Board::default(), &options);
+
+        assert_eq!(svg.matches("<text ").count(), 4 * Board::SIZE);
+        assert_eq!(svg.matches(">a</text>").count(), 2);
+        assert_eq!(svg.matches(">8</text>").count(), 2);
+        assert_eq!(svg.matches("<circle ").count(), 4);
+    }
+
+    #[test]
+    fn a_turned_picture_moves_the_squares_and_their_names_along() {
+        let mut board = Board::default();
+        board.grid[0][0] = Cell::Taken(Player::Black);
+        let options = DisplayOptions {
+            coordinates: true,
+            last_move: Some((0, 0)),
+            orientation: Orientation {
+                rotation: Rotation::Quarter,
+                mirrored: false,
+            },
+            ..Default::default()
+        };
+        let pixels = render(&board, &options, 256);
+        let layout = Layout::new(256, &options);
+
+        // a1 in the top right corner, with the last move marker on it
+        assert_eq!(layout.square_center((0, 0)), layout.view_center((0, 7)));
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((0, 0))),
+            LAST_MOVE_COLOR
+        );
+        let (x, y) = layout.view_center((0, 0));
+        assert_eq!(pixel(&pixels, 256, (x, y)), SQUARE_COLOR);
+
+        // the rows are named across the top, 8 first, and the columns down the sides
+        let svg = svg(&board, &options);
+        let first_label = svg.find("<text ").unwrap();
+        assert!(svg[first_label..].starts_with(&format!(
+            r#"<text x="{}" y="{}">8</text>"#,
+            number(Layout::new(IMAGE_SIZE, &options).view_center((0, 0)).0),
+            number(Layout::new(IMAGE_SIZE, &options).margin / 2.0)
+        )));
+        assert_eq!(svg.matches(">a</text>").count(), 2);
+        assert_eq!(svg.matches(">8</text>").count(), 2);
+    }
+
+    #[test]
+    fn annotations_sit_in_the_corner_of_the_square_as_it_is_shown() {
+        let board = Board::default();
+        let options = DisplayOptions {
+            annotations: vec![
+                ((0, 0), "+3".to_string(), true),
+                ((7, 7), "loss".to_string(), false),
+            ],
+            orientation: Orientation {
+                rotation: Rotation::Half,
+                mirrored: false,
+            },
+            ..Default::default()
+        };
+        let pixels = render(&board, &options, 512);
+        let layout = Layout::new(512, &options);
+
+        // a1 turned to the bottom right, its score's glyphs in the top right corner of it
+        let best_height = layout.annotation_height(true);
+        let (x, y) = layout.annotation_center((0, 0), 2 * (GLYPH_WIDTH + 1) - 1, best_height);
+        let (center_x, center_y) = layout.view_center((7, 7));
+        assert!(x > center_x && y < center_y);
+        let scale = best_height / GLYPH_HEIGHT as f32;
+        let glyphs = [PLUS_GLYPH, DIGIT_GLYPHS[3]];
+        let (left, top) = ((x - best_height) as u32, (y - best_height) as u32);
+        let lit: Vec<(f32, f32)> = (top..top + 2 * best_height as u32)
+            .flat_map(|row| (left..left + 2 * best_height as u32).map(move |column| (column, row)))
+            .map(|(column, row)| (column as f32 + 0.5, row as f32 + 0.5))
+            .filter(|&point| on_glyphs(&glyphs, (x, y), scale, point))
+            .collect();
+        assert!(lit.len() > 20);
+        for point in lit {
+            assert_eq!(pixel(&pixels, 512, point), BEST_ANNOTATION_COLOR);
+        }
+
+        let svg = svg(&board, &options);
+        assert!(svg.contains(r##"font-weight="bold" fill="#50a0ff""##));
+        assert!(svg.contains(">+3</text>"));
+        assert!(svg.contains(">loss</text>"));
+    }
+
+    #[test]
+    fn marks_go_over_the_discs_and_under_the_result() {
+        let board = Board::default();
+        let mut options = DisplayOptions {
+            marks: vec![
+                ((3, 3), SquareMark::Circle),
+                ((3, 4), SquareMark::Cross),
+                ((0, 0), SquareMark::Letter('A')),
+            ],
+            ..Default::default()
+        };
+        let pixels = render(&board, &options, 256);
+        let layout = Layout::new(256, &options);
+
+        // the ring of the circle crosses the white disc of d4, the cross the middle of e4's
+        let (x, y) = layout.square_center((3, 3));
+        let ring = (x + layout.square_size * MARK_REACH, y);
+        assert_eq!(pixel(&pixels, 256, ring), MARK_COLOR);
+        assert_eq!(pixel(&pixels, 256, (x, y)), WHITE_DISC_COLOR);
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((3, 4))),
+            MARK_COLOR
+        );
+        let (x, y) = layout.square_center((0, 0));
+        let scale = 2.0 * layout.square_size * MARK_REACH / GLYPH_HEIGHT as f32;
+        // the top left pixel of an A is off, the one under it on
+        assert_eq!(
+            pixel(&pixels, 256, (x - 2.0 * scale, y - 3.0 * scale)),
+            SQUARE_COLOR
+        );
+        assert_eq!(
+            pixel(&pixels, 256, (x - 2.0 * scale, y - 2.0 * scale)),
+            MARK_COLOR
+        );
+
+        options.result = Some(ResultOverlay {
+            winner: None,
+            black_count: 2,
+            white_count: 2,
+        });
+        let pixels = render(&board, &options, 256);
+        assert_eq!(
+            pixel(&pixels, 256, ring),
+            blend(MARK_COLOR, SCRIM_COLOR, SCRIM_OPACITY)
+        );
+
+        let svg = svg(&board, &options);
+        let circle = svg.find(r##"fill="none" stroke="#ff50a0""##).unwrap();
+        assert!(svg.find("stroke-linecap=\"round\"/>").unwrap() > circle);
+        assert!(svg.find(">A</text>").unwrap() < svg.find("fill-opacity").unwrap());
+    }
+
+    #[test]
+    fn every_compact_score_has_glyphs() {
+        for language in Language::ALL {
+            let strings = Strings::new(language);
+            for score in [-2000.0, -12.0, 0.0, 7.0, 2000.0] {
+                for letter in analysis::compact_score(score, &strings).chars() {
+                    assert_ne!(annotation_glyph(letter), [0; GLYPH_HEIGHT], "{letter}");
+                }
+            }
+        }
+        // a loaded translation may use letters the picture has no glyph for, left blank
+        assert_eq!(annotation_glyph('ж'), [0; GLYPH_HEIGHT]);
+        assert_eq!(annotation_glyph('z'), [0; GLYPH_HEIGHT]);
+    }
+
+    #[test]
+    fn move_numbers_are_written_in_the_other_color() {
+        let mut move_numbers = [[None; Board::SIZE]; Board::SIZE];
+        move_numbers[3][3] = Some(1);
+        move_numbers[3][4] = Some(10);
+        let options = DisplayOptions {
+            move_numbers: Some(move_numbers),
+            ..Default::default()
+        };
+        let pixels = render(&Board::default(), &options, 512);
+        let layout = Layout::new(512, &options);
+        let scale = layout.move_number_height() / GLYPH_HEIGHT as f32;
+
+        // the stroke of the one, black on the white disc
+        let (x, y) = layout.square_center((3, 3));
+        assert_eq!(pixel(&pixels, 512, (x, y)), disc_color(Player::Black));
+        // the left side and the hollow of the zero of ten, on the second row of the glyph
+        let (x, y) = layout.square_center((3, 4));
+        let second_row = y - 2.0 * scale;
+        assert_eq!(
+            pixel(&pixels, 512, (x + scale, second_row)),
+            disc_color(Player::White)
+        );
+        assert_eq!(
+            pixel(&pixels, 512, (x + 2.0 * scale, second_row)),
+            disc_color(Player::Black)
+        );
+        // the disc of an unnumbered square stays plain
+        let (x, y) = layout.square_center((4, 3));
+        assert_eq!(pixel(&pixels, 512, (x, y)), disc_color(Player::Black));
+
+        let svg = svg(&Board::default(), &options);
+        assert!(svg.contains(r##"fill="#000000">1</text>"##));
+        assert!(svg.contains(r##"fill="#ffffff">10</text>"##));
+    }
+
+    #[test]
+    fn the_result_is_drawn_over_a_dimmed_board() {
+        let options = DisplayOptions {
+            result: Some(ResultOverlay {
+                winner: Some(Player::White),
+                black_count: 24,
+                white_count: 40,
+            }),
+            ..Default::default()
+        };
+        let pixels = render(&Board::default(), &options, 256);
+        let layout = Layout::new(256, &options);
+
+        let [(disc_center, _, _)] = layout.result_discs(options.result.as_ref().unwrap())[..]
+        else {
+            panic!("a winner has one disc");
+        };
+        assert_eq!(pixel(&pixels, 256, disc_center), WHITE_DISC_COLOR);
+        assert_eq!(
+            pixel(&pixels, 256, layout.square_center((0, 0))),
+            blend(SQUARE_COLOR, SCRIM_COLOR, SCRIM_OPACITY)
+        );
+        // the middle row of the dash between the counts
+        assert_eq!(
+            pixel(&pixels, 256, layout.result_score_center()),
+            LABEL_COLOR
+        );
+
+        let svg = svg(&Board::default(), &options);
+        assert!(svg.contains(r#"fill-opacity="0.6""#));
+        assert!(svg.contains(">24-40</text>"));
+
+        let tie = ResultOverlay {
+            winner: None,
+            black_count: 32,
+            white_count: 32,
+        };
+        assert_eq!(layout.result_discs(&tie).len(), 2);
+    }
+
+    #[test]
+    fn the_heatmap_tints_its_squares_and_writes_the_scores() {
+        let options = DisplayOptions {
+            heatmap: vec![((2, 3), 1.0, 4.0), ((3, 2), 0.0, f32::NEG_INFINITY)],
+            ..Default::default()
+        };
+        let pixels = render(&Board::default(), &options, 512);
+        let layout = Layout::new(512, &options);
+        let tint = |level| blend(SQUARE_COLOR, heat_color(level), HEATMAP_OPACITY);
+
+        // beside the text in the corner of the square
+        let corner =
+            |(x, y): (f32, f32)| (x - layout.square_size * 0.4, y - layout.square_size * 0.4);
+        assert_eq!(
+            pixel(&pixels, 512, corner(layout.square_center((2, 3)))),
+            tint(1.0)
+        );
+        assert_eq!(
+            pixel(&pixels, 512, corner(layout.square_center((3, 2)))),
+            tint(0.0)
+        );
+        // the crossing of the plus of +4, dark on the bright end
+        let (x, y) = layout.square_center((2, 3));
+        let scale = layout.heat_score_height() / GLYPH_HEIGHT as f32;
+        let plus_center = (x - 3.0 * scale, y);
+        assert_eq!(pixel(&pixels, 512, plus_center), heat_text_color(1.0));
+        assert_eq!(heat_color(0.0), HEATMAP_COLORS[0]);
+        assert_eq!(heat_color(1.0), HEATMAP_COLORS[4]);
+
+        let svg = svg(&Board::default(), &options);
+        assert_eq!(svg.matches(r#"fill-opacity="0.75""#).count(), 2);
+        // a lost position has no score to write
+        assert!(svg.contains(">+4</text>"));
+        assert_eq!(svg.matches("</text>").count(), 1);
+    }
+
+    #[test]
+    fn the_png_is_a_png() {
+        let png = encode_png(&Board::default(), &DisplayOptions::default()).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::ai::AiType;
+use crate::ai::MAX_RECURSION_DEPTH;
+use crate::board::Board;
+use crate::board::Player;
+use crate::common::CellList;
+use crate::notation;
+use crate::referee::Referee;
+use crate::referee::Replay;
+
+pub const USAGE: &str = "\
+usage: FIA-Othello [OPTIONS]
+       FIA-Othello --headless [HEADLESS OPTIONS]
+       FIA-Othello --engine
+
+Options:
+  --black-ai <PLAYER>        who plays black: human, random, minimax, alphabeta or
+                             positional
+  --black-depth <DEPTH>      search depth of the black AI, 1 to 10
+  --white-ai <PLAYER>        who plays white: human, random, minimax, alphabeta or
+                             positional
+  --white-depth <DEPTH>      search depth of the white AI, 1 to 10
+  --auto-restart             start a new game as soon as one ends
+  --show-valid-moves         mark the valid moves of the player to move
+  --show-effects-of-moves    show which disks a move would flip
+  --no-pace-ai               let the AI move as fast as it can (playback speed instant)
+  --position <POSITION>      start from a position, 64 cells from a1 to h8 (X black,
+                             O white, - empty), optionally followed by X or O to move
+  --transcript <MOVES>       start after the moves of a transcript such as F5D6C3
+  --seed <SEED>              let the AIs pick among equally good moves the same way in
+                             every game, for reproducible games
+  --headless                 play an engine match without a window, see
+                             `FIA-Othello --headless --help`
+  --engine                   speak a GTP-like protocol on stdin and stdout, for
+                             tournament managers
+  -h, --help                 print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayerKind {
+    Human,
+    Ai(AiType),
+}
+
+// None leaves the setting as it is by default
+#[derive(Default)]
+pub struct PlayerArgs {
+    pub kind: Option<PlayerKind>,
+    pub recursion_depth: Option<usize>,
+}
+
+// the flags only ever change a setting away from its default
+#[derive(Default)]
+pub struct StartupArgs {
+    // indexed by player
+    pub players: [PlayerArgs; 2],
+    pub auto_restart: bool,
+    pub show_valid_moves: bool,
+    pub show_effects_of_moves: bool,
+    pub no_pace_ai: bool,
+    pub position: Option<(Board, Player)>,
+    pub transcript: Option<Replay>,
+    pub seed: Option<u64>,
+}
+
+pub enum Command {
+    Gui(Box<StartupArgs>),
+    Headless,
+    Engine,
+    Help,
+}
+
+fn parse_player_kind(value: &str) -> Result<PlayerKind, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "human" => Ok(PlayerKind::Human),
+        "random" => Ok(PlayerKind::Ai(AiType::Random)),
+        "minimax" => Ok(PlayerKind::Ai(AiType::Minimax)),
+        "alphabeta" => Ok(PlayerKind::Ai(AiType::AlphaBeta)),
+        "positional" => Ok(PlayerKind::Ai(AiType::Positional)),
+        _ => Err(format!(
+            "unknown player \"{value}\", expected human, random, minimax, alphabeta or positional"
+        )),
+    }
+}
+
+fn parse_depth(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(depth) if (1..=MAX_RECURSION_DEPTH).contains(&depth) => Ok(depth),
+        _ => Err(format!(
+            "invalid depth \"{value}\", expected a number from 1 to {MAX_RECURSION_DEPTH}"
+        )),
+    }
+}
+
+// the position must be playable, otherwise the game would wait for a move that cannot come
+fn parse_position(value: &str) -> Result<(Board, Player), String> {
+    let (board, player) =
+        notation::parse_position(value).map_err(|e| format!("invalid position: {e}"))?;
+
+    let mut referee = Referee::default();
+    let mut valid_moves = CellList::default();
+    if !referee.find_all_valid_moves(&board, player, &mut valid_moves) {
+        return Err(format!(
+            "invalid position: {player:?} is to move but has no valid moves"
+        ));
+    }
+
+    Ok((board, player))
+}
+
+fn parse_transcript(value: &str) -> Result<Replay, String> {
+    let moves =
+        notation::parse_transcript(value).map_err(|e| format!("invalid transcript: {e}"))?;
+    Referee::default()
+        .replay(&Board::default(), Player::Black, &moves)
+        .map_err(|e| format!("invalid transcript: {e}"))
+}
+
+// expects the arguments without the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    // the headless mode has options of its own
+    if args.iter().any(|arg| arg == "--headless") {
+        return Ok(Command::Headless);
+    }
+    if args.iter().any(|arg| arg == "--engine") {
+        return Ok(Command::Engine);
+    }
+
+    let mut startup = StartupArgs::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--black-ai" => {
+                startup.players[Player::Black as usize].kind = Some(parse_player_kind(value()?)?)
+            }
+            "--white-ai" => {
+                startup.players[Player::White as usize].kind = Some(parse_player_kind(value()?)?)
+            }
+            "--black-depth" => {
+                startup.players[Player::Black as usize].recursion_depth =
+                    Some(parse_depth(value()?)?)
+            }
+            "--white-depth" => {
+                startup.players[Player::White as usize].recursion_depth =
+                    Some(parse_depth(value()?)?)
+            }
+            "--auto-restart" => startup.auto_restart = true,
+            "--show-valid-moves" => startup.show_valid_moves = true,
+            "--show-effects-of-moves" => startup.show_effects_of_moves = true,
+            "--no-pace-ai" => startup.no_pace_ai = true,
+            "--position" => startup.position = Some(parse_position(value()?)?),
+            "--transcript" => startup.transcript = Some(parse_transcript(value()?)?),
+            "--seed" => {
+                let value = value()?;
+                startup.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed \"{value}\""))?,
+                );
+            }
+            _ => return Err(format!("unknown argument \"{arg}\"")),
+        }
+    }
+
+    // transcripts are always played from the start position
+    if startup.position.is_some() && startup.transcript.is_some() {
+        return Err("--position and --transcript cannot be combined".to_string());
+    }
+    for player in [Player::Black, Player::White] {
+        let player_args = &startup.players[player as usize];
+        if player_args.kind == Some(PlayerKind::Human) && player_args.recursion_depth.is_some() {
+            let name = format!("{player:?}").to_ascii_lowercase();
+            return Err(format!(
+                "--{name}-depth only applies to an AI, but --{name}-ai is human"
+            ));
+        }
+    }
+
+    Ok(Command::Gui(Box::new(startup)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_gui(args: &[&str]) -> Result<StartupArgs, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        match parse(&args)? {
+            Command::Gui(startup) => Ok(*startup),
+            _ => panic!("expected the GUI to start"),
+        }
+    }
+
+    #[test]
+    fn players_are_configured_per_color() {
+        let startup = parse_gui(&[
+            "--black-ai",
+            "minimax",
+            "--black-depth",
+            "7",
+            "--white-ai",
+            "random",
+        ])
+        .unwrap();
+
+        let black = &startup.players[Player::Black as usize];
+        assert_eq!(black.kind, Some(PlayerKind::Ai(AiType::Minimax)));
+        assert_eq!(black.recursion_depth, Some(7));
+        let white = &startup.players[Player::White as usize];
+        assert_eq!(white.kind, Some(PlayerKind::Ai(AiType::Random)));
+        assert_eq!(white.recursion_depth, None);
+    }
+
+    #[test]
+    fn flags_are_off_unless_given() {
+        let startup = parse_gui(&["--auto-restart", "--show-valid-moves"]).unwrap();
+        assert!(startup.auto_restart);
+        assert!(startup.show_valid_moves);
+        assert!(!startup.show_effects_of_moves);
+        assert!(!startup.no_pace_ai);
+        assert!(startup.position.is_none());
+        assert!(startup.seed.is_none());
+    }
+
+    #[test]
+    fn transcript_is_replayed() {
+        let startup = parse_gui(&["--transcript", "f5d6c3"]).unwrap();
+        let replay = startup.transcript.unwrap();
+        assert_eq!(replay.history.moves().len(), 3);
+        assert_eq!(replay.next_player, Some(Player::White));
+    }
+
+    const START_POSITION: &str = "---------------------------OX------XO---------------------------";
+
+    #[test]
+    fn position_sets_the_player_to_move() {
+        let position = format!("{START_POSITION} O");
+        let (board, player) = parse_gui(&["--position", &position])
+            .unwrap()
+            .position
+            .unwrap();
+        assert_eq!(player, Player::White);
+        assert_eq!(board.grid, Board::default().grid);
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(parse_gui(&["--black-ai", "deepblue"]).is_err());
+        assert!(parse_gui(&["--white-depth", "0"]).is_err());
+        assert!(parse_gui(&["--white-depth"]).is_err());
+        assert!(parse_gui(&["--position", "XO-"]).is_err());
+        assert!(parse_gui(&["--transcript", "f5f5"]).is_err());
+        assert!(parse_gui(&["--black-ai", "human", "--black-depth", "3"]).is_err());
+        assert!(parse_gui(&["--position", &"-".repeat(64)]).is_err());
+        assert!(parse_gui(&["--transcript", "f5", "--position", START_POSITION]).is_err());
+        assert!(parse_gui(&["--seed", "-1"]).is_err());
+        assert!(parse_gui(&["--fullscreen"]).is_err());
+    }
+}
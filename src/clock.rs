@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use crate::board::Player;
+use crate::platform::Instant;
+
+// below this, a clock is shown as running low
+pub const LOW_TIME: Duration = Duration::from_secs(10);
+
+// a chess clock for both players, driven by whatever time source the caller uses
+pub struct GameClock {
+    remaining: [Duration; 2],
+    // added to a player's clock after each of their moves (Fischer increment)
+    increment: Duration,
+    last_update: Option<Instant>,
+    low_time_warned: [bool; 2],
+}
+
+impl GameClock {
+    pub fn new(time_per_player: Duration, increment: Duration) -> Self {
+        GameClock {
+            remaining: [time_per_player; 2],
+            increment,
+            last_update: None,
+            low_time_warned: [false; 2],
+        }
+    }
+
+    pub fn remaining(&self, player: Player) -> Duration {
+        self.remaining[player as usize]
+    }
+
+    pub fn has_flagged(&self, player: Player) -> bool {
+        self.remaining(player).is_zero()
+    }
+
+    pub fn is_low(&self, player: Player) -> bool {
+        self.remaining(player) < LOW_TIME
+    }
+
+    // true only the first time the player's clock runs low
+    pub fn take_low_time_warning(&mut self, player: Player) -> bool {
+        let warned = &mut self.low_time_warned[player as usize];
+        if self.remaining[player as usize] < LOW_TIME && !*warned {
+            *warned = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    // call this after the player completed a move, never after they flagged
+    pub fn add_increment(&mut self, player: Player) {
+        if !self.has_flagged(player) {
+            self.remaining[player as usize] += self.increment;
+        }
+    }
+
+    // charges the time since the last update to the running player, None pauses the clock
+    pub fn update(&mut self, running: Option<Player>, now: Instant) {
+        if let (Some(player), Some(last_update)) = (running, self.last_update) {
+            let remaining = &mut self.remaining[player as usize];
+            *remaining = remaining.saturating_sub(now.saturating_duration_since(last_update));
+        }
+        self.last_update = running.map(|_| now);
+    }
+}
+
+// formats as m:ss, or with tenths of a second once time gets short
+// the tenths are cut rather than rounded, so that 9.97 s never shows as 10.0
+pub fn format_remaining(remaining: Duration) -> String {
+    let tenths = remaining.as_millis() / 100;
+    if tenths < 100 {
+        format!("0:0{}.{}", tenths / 10, tenths % 10)
+    } else {
+        let seconds = remaining.as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn short_times_show_tenths_that_never_round_up() {
+        let millis = Duration::from_millis;
+        assert_eq!(format_remaining(millis(9_950)), "0:09.9");
+        assert_eq!(format_remaining(millis(9_999)), "0:09.9");
+        assert_eq!(format_remaining(millis(10_000)), "0:10");
+        assert_eq!(format_remaining(millis(3_460)), "0:03.4");
+        assert_eq!(format_remaining(millis(99)), "0:00.0");
+        assert_eq!(format_remaining(Duration::ZERO), "0:00.0");
+        assert_eq!(format_remaining(millis(59_999)), "0:59");
+        assert_eq!(format_remaining(millis(61_000)), "1:01");
+        assert_eq!(format_remaining(MINUTE * 15), "15:00");
+    }
+
+    #[test]
+    fn a_clock_that_runs_out_flags_and_stops_at_zero() {
+        let mut clock = GameClock::new(Duration::from_secs(3), Duration::from_secs(2));
+        let start = Instant::now();
+        clock.update(Some(Player::White), start);
+        clock.update(Some(Player::White), start + Duration::from_millis(2_999));
+        assert!(!clock.has_flagged(Player::White));
+        clock.update(Some(Player::White), start + Duration::from_secs(5));
+        assert!(clock.has_flagged(Player::White));
+        assert_eq!(clock.remaining(Player::White), Duration::ZERO);
+        assert!(!clock.has_flagged(Player::Black));
+
+        // no increment brings a flagged clock back
+        clock.add_increment(Player::White);
+        assert!(clock.has_flagged(Player::White));
+    }
+
+    #[test]
+    fn every_move_adds_the_increment_to_the_mover() {
+        let mut clock = GameClock::new(MINUTE, Duration::from_secs(3));
+        let start = Instant::now();
+        clock.update(Some(Player::Black), start);
+        clock.update(Some(Player::Black), start + Duration::from_secs(10));
+        clock.add_increment(Player::Black);
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(53));
+        assert_eq!(clock.remaining(Player::White), MINUTE);
+        // above the starting time too
+        clock.add_increment(Player::White);
+        assert_eq!(clock.remaining(Player::White), Duration::from_secs(63));
+    }
+
+    #[test]
+    fn the_low_time_warning_comes_once_per_player() {
+        let mut clock = GameClock::new(Duration::from_secs(12), Duration::from_secs(5));
+        let start = Instant::now();
+        clock.update(Some(Player::Black), start);
+        clock.update(Some(Player::Black), start + Duration::from_secs(2));
+        assert!(!clock.is_low(Player::Black));
+        assert!(!clock.take_low_time_warning(Player::Black));
+
+        clock.update(Some(Player::Black), start + Duration::from_millis(2_001));
+        assert!(clock.is_low(Player::Black));
+        assert!(clock.take_low_time_warning(Player::Black));
+        assert!(!clock.take_low_time_warning(Player::Black));
+        // back above the limit with the increment, and low again, without a second warning
+        clock.add_increment(Player::Black);
+        assert!(!clock.is_low(Player::Black));
+        clock.update(Some(Player::Black), start + Duration::from_secs(10));
+        assert!(clock.is_low(Player::Black));
+        assert!(!clock.take_low_time_warning(Player::Black));
+        assert!(!clock.take_low_time_warning(Player::White));
+    }
+
+    #[test]
+    fn only_the_running_player_is_charged() {
+        let mut clock = GameClock::new(MINUTE, Duration::ZERO);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        // the first update only starts the clock
+        clock.update(Some(Player::Black), at(0));
+        assert_eq!(clock.remaining(Player::Black), MINUTE);
+        clock.update(Some(Player::Black), at(5));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(55));
+        assert_eq!(clock.remaining(Player::White), MINUTE);
+
+        // each update charges the time since the one before to the player running now, which the
+        // game keeps small by updating every frame
+        clock.update(Some(Player::White), at(7));
+        clock.update(Some(Player::White), at(10));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(55));
+        assert_eq!(clock.remaining(Player::White), Duration::from_secs(55));
+    }
+
+    #[test]
+    fn a_paused_clock_charges_nobody_for_the_wait() {
+        let mut clock = GameClock::new(MINUTE, Duration::ZERO);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        clock.update(Some(Player::Black), at(0));
+        clock.update(Some(Player::Black), at(4));
+        // a replay, a pass notice or the editor holds the game for a while
+        clock.update(None, at(5));
+        clock.update(None, at(30));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(56));
+        // running again starts from the end of the wait
+        clock.update(Some(Player::Black), at(100));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(56));
+        clock.update(Some(Player::Black), at(102));
+        assert_eq!(clock.remaining(Player::Black), Duration::from_secs(54));
+        assert_eq!(clock.remaining(Player::White), MINUTE);
+    }
+}
@@ -1,11 +1,23 @@
 use crate::board::Board;
-
-type Move = (usize, usize);
+use crate::board::Move;
 
 // Common utility types
-// a cache for re-use to avoid unnecesary memory allocations
+/// a cache for re-use to avoid unnecesary memory allocations
+///
+/// holds up to 64 squares, the referee fills it with valid moves or disks to flip
+///
+/// ```
+/// use fia_othello::common::CellList;
+///
+/// let mut cells = CellList::default();
+/// cells.push_back((2, 3));
+/// cells.push_back((3, 2));
+/// assert_eq!(cells.iter().collect::<Vec<_>>(), [(2, 3), (3, 2)]);
+/// ```
+#[derive(Clone, Copy)]
 pub struct CellList {
     pub list: [Move; 64],
+    /// how many squares of `list` are in use
     pub count: usize,
 }
 
@@ -19,11 +31,13 @@ impl Default for CellList {
 }
 
 impl CellList {
+    /// adds a square, panics once all 64 are in use
     pub fn push_back(&mut self, cell: Move) {
         self.list[self.count] = cell;
         self.count += 1;
     }
 
+    /// the squares in use, in the order they were added
     pub fn iter(&self) -> impl Iterator<Item = Move> {
         self.list[..self.count].iter().copied()
     }
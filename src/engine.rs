@@ -0,0 +1,213 @@
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::process::ExitCode;
+
+use crate::ai::Agent;
+use crate::ai::AiType;
+use crate::ai::MAX_RECURSION_DEPTH;
+use crate::ai::MoveRequest;
+use crate::board::Board;
+use crate::board::Cell;
+use crate::board::Player;
+use crate::common::CellList;
+use crate::notation;
+use crate::referee::Referee;
+
+const COMMANDS: [&str; 12] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "set_depth",
+    "showboard",
+    "quit",
+];
+
+// the engine side of a GTP-like session, the controller decides whose turn it is
+pub struct Engine {
+    referee: Referee,
+    agent: Agent,
+    board: Board,
+    recursion_depth: usize,
+    flip_cells: CellList,
+    valid_moves: CellList,
+}
+
+fn parse_player(text: &str) -> Result<Player, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "b" | "black" => Ok(Player::Black),
+        "w" | "white" => Ok(Player::White),
+        _ => Err(format!("invalid color {text}")),
+    }
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let mut agent = Agent::new();
+        // stdout belongs to the protocol
+        agent.log_searches = false;
+
+        Engine {
+            referee: Referee::default(),
+            agent,
+            board: Board::default(),
+            recursion_depth: 4,
+            flip_cells: CellList::default(),
+            valid_moves: CellList::default(),
+        }
+    }
+
+    // returns the text of a successful reply, or the error message
+    pub fn execute(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("protocol_version", []) => Ok("2".to_string()),
+            ("name", []) => Ok("FIA-Othello".to_string()),
+            ("version", []) => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            ("known_command", [name]) => Ok(COMMANDS.contains(name).to_string()),
+            ("list_commands", []) => Ok(COMMANDS.join("\n")),
+            ("boardsize", [size]) => match size.parse::<usize>() {
+                Ok(Board::SIZE) => Ok(String::new()),
+                _ => Err("unacceptable size".to_string()),
+            },
+            ("clear_board", []) => {
+                self.board = Board::default();
+                Ok(String::new())
+            }
+            ("play", [player, vertex]) => {
+                self.play(parse_player(player)?, vertex)?;
+                Ok(String::new())
+            }
+            ("genmove", [player]) => Ok(self.genmove(parse_player(player)?)),
+            ("set_depth", [depth]) => match depth.parse() {
+                Ok(depth) if (1..=MAX_RECURSION_DEPTH).contains(&depth) => {
+                    self.recursion_depth = depth;
+                    Ok(String::new())
+                }
+                _ => Err(format!("depth must be from 1 to {MAX_RECURSION_DEPTH}")),
+            },
+            ("showboard", []) => Ok(self.board_text()),
+            ("quit", []) => Ok(String::new()),
+            _ if COMMANDS.contains(&command) => Err("wrong number of arguments".to_string()),
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    fn play(&mut self, player: Player, vertex: &str) -> Result<(), String> {
+        let has_moves =
+            self.referee
+                .find_all_valid_moves(&self.board, player, &mut self.valid_moves);
+
+        if vertex.eq_ignore_ascii_case("pass") {
+            // passing is only allowed without a valid move
+            return if has_moves {
+                Err("illegal move".to_string())
+            } else {
+                Ok(())
+            };
+        }
+
+        let next_move = notation::parse_move(vertex).ok_or("invalid coordinate")?;
+        if !self.referee.find_flip_cells_for_move(
+            &self.board,
+            player,
+            next_move,
+            &mut self.flip_cells,
+        ) {
+            return Err("illegal move".to_string());
+        }
+        Referee::apply_move(&mut self.board, player, next_move, &self.flip_cells);
+
+        Ok(())
+    }
+
+    // finds, plays and returns the move, or "pass" if there is none
+    fn genmove(&mut self, player: Player) -> String {
+        if !self
+            .referee
+            .find_all_valid_moves(&self.board, player, &mut self.valid_moves)
+        {
+            return "pass".to_string();
+        }
+
+        let move_result = self.agent.answer_move_request(MoveRequest {
+            board: self.board.clone(),
+            player,
+            algorithm_choice: AiType::AlphaBeta,
+            recursion_depth: self.recursion_depth,
+            time_budget: None,
+        });
+        let vertex = notation::format_move(move_result.next_move);
+        // the agent only ever comes up with valid moves
+        let _ = self.play(player, &vertex);
+
+        vertex
+    }
+
+    // rows top to bottom, X for black and O for white
+    fn board_text(&self) -> String {
+        let mut text = "  a b c d e f g h".to_string();
+        for (row, cells) in self.board.grid.iter().enumerate() {
+            text.push_str(&format!("\n{}", row + 1));
+            for cell in cells {
+                text.push_str(match cell {
+                    Cell::Empty => " -",
+                    Cell::Taken(Player::Black) => " X",
+                    Cell::Taken(Player::White) => " O",
+                });
+            }
+        }
+        text
+    }
+}
+
+// answers one command per line until "quit" or the end of the input
+pub fn run(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut engine = Engine::new();
+
+    for line in input.lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default();
+        let mut words = line.split_whitespace();
+        let Some(first) = words.next() else {
+            continue;
+        };
+
+        // commands can be numbered, replies then carry the same number
+        let (id, command) = match first.parse::<u32>() {
+            Ok(_) => (first, words.next().unwrap_or_default()),
+            Err(_) => ("", first),
+        };
+        let args: Vec<&str> = words.collect();
+
+        let reply = match engine.execute(command, &args) {
+            Ok(text) => format!("={id} {text}"),
+            Err(e) => format!("?{id} {e}"),
+        };
+        // every reply ends with an empty line
+        writeln!(output, "{}\n", reply.trim_end())?;
+        output.flush()?;
+
+        if command == "quit" {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// the entry point of `--engine`
+pub fn main() -> ExitCode {
+    match run(io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Engine session failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
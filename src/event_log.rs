@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+// the oldest entries make room for new ones past this
+pub const CAPACITY: usize = 500;
+
+// what happened during the session, passes, AI failures and setting changes included
+pub struct EventLog {
+    started_at: Instant,
+    // with the time since the start of the session
+    entries: VecDeque<(Duration, String)>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        EventLog {
+            started_at: Instant::now(),
+            entries: VecDeque::with_capacity(CAPACITY),
+        }
+    }
+
+    pub fn push(&mut self, message: String) {
+        self.push_at(self.started_at.elapsed(), message);
+    }
+
+    fn push_at(&mut self, elapsed: Duration, message: String) {
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((elapsed, message));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // oldest first, each as "[h:mm:ss] message"
+    pub fn lines(&self) -> impl Iterator<Item = String> {
+        self.entries
+            .iter()
+            .map(|(elapsed, message)| format!("[{}] {message}", format_elapsed(*elapsed)))
+    }
+
+    // one line per entry
+    pub fn to_text(&self) -> String {
+        self.lines().map(|line| line + "\n").collect()
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_timestamped_in_order() {
+        let mut log = EventLog::new();
+        log.push_at(Duration::from_secs(5), "Black plays d3".to_string());
+        log.push_at(Duration::from_secs(3725), "White passes".to_string());
+
+        assert_eq!(
+            log.to_text(),
+            "[0:00:05] Black plays d3\n[1:02:05] White passes\n"
+        );
+    }
+
+    #[test]
+    fn the_oldest_entries_make_room() {
+        let mut log = EventLog::new();
+        for i in 0..CAPACITY + 2 {
+            log.push_at(Duration::ZERO, i.to_string());
+        }
+
+        let lines: Vec<String> = log.lines().collect();
+        assert_eq!(lines.len(), CAPACITY);
+        assert_eq!(lines[0], "[0:00:00] 2");
+        assert_eq!(lines[CAPACITY - 1], format!("[0:00:00] {}", CAPACITY + 1));
+
+        log.clear();
+        assert!(log.is_empty());
+    }
+}
@@ -1,61 +1,649 @@
-use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
-use std::thread;
 use std::time::Duration;
-use std::time::Instant;
 
 use eframe::egui;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::ai::Agent;
+use crate::ai::AgentRequest;
 use crate::ai::AiType;
+use crate::ai::AnalysisPosition;
+use crate::ai::AnalysisRequest;
+use crate::ai::AnalysisResult;
+use crate::ai::DiskSet;
+use crate::ai::MAX_RECURSION_DEPTH;
 use crate::ai::MoveRequest;
 use crate::ai::MoveResult;
+use crate::analysis;
+use crate::analysis::Analysis;
+use crate::analysis::SwingThresholds;
+use crate::analysis::heat_levels;
+use crate::animation::FlipAnimation;
+use crate::arena;
+use crate::arena::Arena;
+use crate::arena::Contestant;
 use crate::board::Board;
 use crate::board::Cell;
+use crate::board::Move;
 use crate::board::Player;
+use crate::board_image;
+use crate::board_image::DisplayOptions;
+use crate::board_image::ResultOverlay;
+use crate::cli::PlayerArgs;
+use crate::cli::PlayerKind;
+use crate::cli::StartupArgs;
+use crate::clock;
+use crate::clock::GameClock;
 use crate::common::CellList;
+use crate::event_log::EventLog;
+use crate::history::History;
+use crate::history::MoveNumbers;
+use crate::history::Ply;
+use crate::locale::Language;
+use crate::locale::Strings;
+use crate::marks::SquareMark;
+use crate::marks::SquareMarks;
+use crate::multi_board;
+use crate::multi_board::BoardGame;
+use crate::multi_board::MultiBoard;
+use crate::multi_board::Seat;
+use crate::network;
+use crate::network::Connection;
+use crate::network::Message;
+use crate::network::NetworkEvent;
+use crate::notation;
+use crate::orientation::Orientation;
+use crate::orientation::Rotation;
+use crate::panel::OpenSections;
+use crate::panel::PanelSection;
+use crate::platform;
+use crate::platform::AgentRunner;
+use crate::platform::Instant;
+use crate::platform::RequestId;
+use crate::puzzle::PUZZLES;
+use crate::referee::Advance;
 use crate::referee::Outcome;
 use crate::referee::Referee;
+use crate::referee::Replay;
+use crate::referee::WinReason;
+use crate::save;
+use crate::save::GameRecord;
+use crate::save::Preset;
+use crate::save::SavedGame;
+use crate::save::Settings;
+use crate::save::StatisticsFile;
+use crate::sound::Sound;
+use crate::sound::Sounds;
+use crate::statistics::DepthGap;
+use crate::statistics::DepthPoint;
+use crate::statistics::GameResult;
+use crate::statistics::HeadToHead;
+use crate::statistics::Matchup;
+use crate::statistics::OpeningTree;
+use crate::statistics::Participant;
+use crate::statistics::Rating;
+use crate::statistics::Statistic;
 use crate::statistics::Statistics;
+use crate::statistics::StatisticsColumn;
+use crate::statistics::StatisticsSort;
+use crate::statistics::ThinkingTime;
+use crate::statistics::canonical_opening;
+use crate::statistics::score_summary;
+use crate::theme::Appearance;
+use crate::theme::BoardTheme;
+use crate::theme::BoardThemeName;
+use crate::tournament;
+use crate::tournament::MatchTally;
+use crate::wthor;
+use crate::wthor::WthorDatabase;
 
-type Move = (usize, usize);
+const EVALUATION_BAR_WIDTH: f32 = 24.0;
+const EVALUATION_BAR_GAP: f32 = 8.0;
+const SCORE_BAR_HEIGHT: f32 = 18.0;
+// a move under the pointer shows through, a selected one stands out more
+const HOVER_GHOST_OPACITY: f32 = 0.45;
+const PENDING_GHOST_OPACITY: f32 = 0.8;
+// the frame holding the coordinates around the board, in squares
+const COORDINATE_MARGIN: f32 = 0.5;
+// the side panel can be dragged between these widths, and to at most this share of the window
+const SIDE_PANEL_WIDTH_RANGE: std::ops::RangeInclusive<f32> = 240.0..=720.0;
+const SIDE_PANEL_MAX_SHARE: f32 = 0.6;
+// of a move list row, which stand side by side in a wide panel
+const MOVE_LIST_COLUMN_WIDTH: f32 = 140.0;
+// the UI scale on top of the display's
+const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.0;
+// evaluations of this size fill roughly three quarters of the bar
+const EVALUATION_BAR_SCALE: f32 = 40.0;
+const SCORE_GRAPH_HEIGHT: f32 = 80.0;
+// the plies on the x axis, the longest game without passes
+const SCORE_GRAPH_PLIES: usize = 60;
+const DEPTH_PLOT_HEIGHT: f32 = 100.0;
+// the share of wins the deeper search is asked to reach in the depth gap table
+const DEPTH_GAP_WIN_RATIO: f64 = 0.75;
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const PLACED_DISC_SCALE_IN: Duration = Duration::from_millis(120);
+const VALID_MOVES_FADE_IN: Duration = Duration::from_millis(200);
+const GAME_OVER_FADE_IN: Duration = Duration::from_millis(400);
+// how often the agent threads and the connection are looked at while an answer is due
+const AI_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// a clock shows tenths under 10 s and whole seconds above
+const LOW_CLOCK_REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+const CLOCK_REPAINT_INTERVAL: Duration = Duration::from_secs(1);
+// a running arena writes its statistics at most this often, however fast its games
+const ARENA_STATISTICS_INTERVAL: Duration = Duration::from_secs(1);
+// hints are for humans, a quick search is enough
+const HINT_DEPTH: usize = 4;
+// below each board of the grid, for its score
+const MINI_BOARD_LABEL_HEIGHT: f32 = 20.0;
+const MINI_BOARD_GAP: f32 = 8.0;
+// the AIs by the names they go by in every language
+const AI_TYPE_NAMES: [(AiType, &str); 4] = [
+    (AiType::Random, "Random"),
+    (AiType::Minimax, "Minimax"),
+    (AiType::AlphaBeta, "AlphaBeta"),
+    (AiType::Positional, "Positional"),
+];
+
+// everything the keyboard can do
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Action {
+    Restart,
+    Undo,
+    Redo,
+    TakeBack,
+    Hint,
+    Step,
+    ToggleValidMoves,
+    ToggleEffects,
+    ToggleCompactMode,
+    Cancel,
+}
+
+const fn shortcut(modifiers: egui::Modifiers, key: egui::Key) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers, key)
+}
+
+// the one place shortcuts are registered, listed in the side panel in this order with the
+// translation of their description
+const SHORTCUTS: [(egui::KeyboardShortcut, Action, &str); 11] = [
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::R),
+        Action::Restart,
+        "shortcut-restart",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::U),
+        Action::Undo,
+        "shortcut-undo",
+    ),
+    (
+        shortcut(egui::Modifiers::COMMAND, egui::Key::Z),
+        Action::Undo,
+        "shortcut-undo",
+    ),
+    (
+        shortcut(egui::Modifiers::COMMAND, egui::Key::Y),
+        Action::Redo,
+        "shortcut-redo",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::T),
+        Action::TakeBack,
+        "shortcut-take-back",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::H),
+        Action::Hint,
+        "shortcut-hint",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::Space),
+        Action::Step,
+        "shortcut-step",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::V),
+        Action::ToggleValidMoves,
+        "shortcut-valid-moves",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::E),
+        Action::ToggleEffects,
+        "shortcut-effects",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::Tab),
+        Action::ToggleCompactMode,
+        "shortcut-compact-mode",
+    ),
+    (
+        shortcut(egui::Modifiers::NONE, egui::Key::Escape),
+        Action::Cancel,
+        "shortcut-cancel",
+    ),
+];
 
 #[derive(Clone, Copy)]
 enum Phase {
     Turn(Player),
-    Win(Player),
+    Win(Player, WinReason),
     Tie,
 }
 
+impl From<Outcome> for Phase {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Won(player, reason) => Phase::Win(player, reason),
+            Outcome::Tie => Phase::Tie,
+        }
+    }
+}
+
+// a read-only view of an earlier position of the current game
+struct ReplayView {
+    // number of plies of the history that have been played on `board`
+    index: usize,
+    board: Board,
+}
+
+// the most recent move on the board and the discs it flipped
+struct LastMove {
+    next_move: Move,
+    flipped: Vec<Move>,
+}
+
+// what eases in on a state change
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum AnimationKey {
+    PlacedDisc,
+    ValidMoves,
+    GameOver,
+}
+
+// cosmetic transitions, queried while painting, nothing in the game waits for them
+#[derive(Default)]
+struct Animations {
+    // when each started and how long it takes
+    running: HashMap<AnimationKey, (Instant, Duration)>,
+}
+
+impl Animations {
+    fn start(&mut self, key: AnimationKey, duration: Duration) {
+        self.running.insert(key, (Instant::now(), duration));
+    }
+
+    // from 0 when the animation starts to 1 once it is over or if it never started
+    fn progress(&self, key: AnimationKey, now: Instant) -> f32 {
+        let Some(&(started_at, duration)) = self.running.get(&key) else {
+            return 1.0;
+        };
+        if duration.is_zero() {
+            return 1.0;
+        }
+        // a gentle ease out
+        let linear = (now.saturating_duration_since(started_at).as_secs_f32()
+            / duration.as_secs_f32())
+        .min(1.0);
+        1.0 - (1.0 - linear) * (1.0 - linear)
+    }
+
+    fn is_running(&self, now: Instant) -> bool {
+        self.running
+            .values()
+            .any(|&(started_at, duration)| now.saturating_duration_since(started_at) < duration)
+    }
+
+    fn clear(&mut self) {
+        self.running.clear();
+    }
+}
+
+// the rendered rows of the move list, rebuilt whenever the history changes
+#[derive(Default)]
+struct MoveList {
+    // the position the rows were built from, None before the first build
+    start_board: Option<Board>,
+    plies: Vec<Ply>,
+    // how a pass was written, the rows are rebuilt in another language
+    pass: String,
+    rows: Vec<String>,
+    // the (black, white) disc counts before the first ply and after each, for the score graph
+    counts: Vec<(usize, usize)>,
+}
+
+// an analysis in progress on the agent thread
+struct AnalysisRun {
+    cancel: Arc<AtomicBool>,
+    result_receiver: mpsc::Receiver<AnalysisResult>,
+    total: usize,
+    received: usize,
+}
+
+// the scores of every valid move of one position, asked of the analysis agent for the heatmap
+struct PositionScores {
+    board: Board,
+    cancel: Arc<AtomicBool>,
+    result_receiver: mpsc::Receiver<AnalysisResult>,
+    // None until the agent answered
+    move_scores: Option<Vec<(Move, f32)>>,
+}
+
+// the number of valid moves of both colors in the position on display, worked out again only
+// when the position changes
+struct MoveCounts {
+    board: Board,
+    // the side to move
+    player: Player,
+    counts: [usize; 2],
+}
+
+// what the square under the pointer would flip, worked out again only when the pointer moves to
+// another square or the position changes
+struct HoverFlips {
+    board: Board,
+    player: Player,
+    square: Move,
+    // None for a square that is no valid move
+    flip_cells: Option<CellList>,
+}
+
+// the discs of the position on display picked out for an overlay, worked out again only when the
+// position changes
+struct DiscMarks {
+    board: Board,
+    discs: DiskSet,
+}
+
+impl DiscMarks {
+    // the marks of the board, None while they are not shown, and whether they were worked out
+    fn refresh(
+        marks: &mut Option<DiscMarks>,
+        shown: bool,
+        board: &Board,
+        find: fn(&Board) -> DiskSet,
+    ) -> bool {
+        if !shown {
+            *marks = None;
+            return false;
+        }
+        if marks
+            .as_ref()
+            .is_some_and(|marks| marks.board.grid == board.grid)
+        {
+            return false;
+        }
+        *marks = Some(DiscMarks {
+            board: board.clone(),
+            discs: find(board),
+        });
+        true
+    }
+
+    // of the player's color
+    fn count(&self, player: Player) -> usize {
+        self.discs
+            .iter()
+            .flatten()
+            .zip(self.board.grid.iter().flatten())
+            .filter(|&(&marked, &cell)| marked && cell == Cell::Taken(player))
+            .count()
+    }
+}
+
+// shown when a player had to pass, until it is dismissed or times out
+struct PassNotice {
+    player: Player,
+    // only AI-vs-AI games dismiss the notice on their own
+    dismiss_at: Option<Instant>,
+}
+
+// why the game on the board does not count in the statistics
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StatisticsBlock {
+    // once it ended
+    Counted,
+    // loaded, set up or taken back to rather than played
+    PositionSet,
+    // after the first move
+    SettingChanged(SettingChange),
+    SwappedSides,
+    // the AI took over the human seats
+    AutoFinished,
+}
+
+// how far the game on the board has gone, counted in moves with the passes left out
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum GameProgress {
+    // the number of the move to play is unknown in a position set up in the editor
+    Turn {
+        move_number: Option<usize>,
+        empties: usize,
+    },
+    Over {
+        moves: usize,
+    },
+}
+
+// a change of the player options, for telling why a game no longer counts
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SettingChange {
+    AiEnabled(Player),
+    AiType(Player),
+    AiDepth(Player),
+    AiFailed(Player),
+    Seats,
+    Preset,
+    Reset,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+// options missing from an older settings file keep their defaults
+#[serde(default)]
 pub struct GameOptions {
     show_effects_of_moves: bool,
+    // the number of discs a legal move would flip, on the hovered or selected square
+    show_flip_counts: bool,
+    // a see-through disc on a legal square under the pointer
+    show_move_preview: bool,
+    // and on the discs the move would flip, in the mover's color
+    preview_flips: bool,
     show_valid_moves: bool,
+    // the number of valid moves of the side that does not move next, besides the other
+    show_opponent_move_count: bool,
+    // the side panel hidden, the status over a corner of the board
+    compact_mode: bool,
+    side_panel_width: f32,
+    open_sections: OpenSections,
     auto_restart: bool,
-    pace_ai: bool,
-    pause_at_win: bool,
+    // the player options swap seats on every auto-restart
+    alternate_colors: bool,
+    // a first click only selects a move, a second one on the same square plays it
+    confirm_moves: bool,
+    // the minimum time an AI move takes, so that a game against the AI can be followed
+    // 0 plays instantly
+    ai_move_seconds: f32,
+    // AI moves and auto-restarts wait for Next Move
+    step_mode: bool,
+    // how long the discs of a move take to turn over, 0 for none
+    flip_animation_seconds: f32,
+    // no animations at all, the flips included
+    reduce_motion: bool,
+    // on placing a disc, once the discs turned over and at the end of the game
+    play_sounds: bool,
+    // from 0 to 1, also for the low time warning
+    sound_volume: f32,
+    // how long a finished game stays on the board before an auto-restart
+    pause_at_win_seconds: u64,
     should_take_statistics: bool,
+    // statistics of fewer games are grayed out
+    min_statistics_games: usize,
+    // the column of the statistics table whose header was clicked last
+    statistics_sort: StatisticsSort,
+    // the length of the opening lines counted from the game records
+    opening_plies: usize,
+    // the score of every valid move that continues a recorded opening, on the board
+    show_opening_scores: bool,
+    // the score of every valid move on its square, colored by its rank among them
+    show_heatmap: bool,
+    // the heatmap of every position an AI move leaves to a human, not only after a hint
+    live_heatmap: bool,
+    // the analyzed score of every valid move in the corner of its square in a replay
+    annotate_analyzed_moves: bool,
+    mark_passes_in_transcript: bool,
+    show_last_move: bool,
+    // the number of the move that placed each disc, when the whole game is known
+    show_move_numbers: bool,
+    // a lock on the discs that can no longer be flipped, of both colors
+    show_stable_discs: bool,
+    // an outline around the discs next to an empty square, with their count per color
+    show_frontier_discs: bool,
+    show_evaluation_bar: bool,
+    // letters and numbers of the squares in a frame around the board
+    show_coordinates: bool,
+    // of the board on the screen and in exported pictures
+    orientation: Orientation,
+    // the board turned upside down on top of the orientation while the human plays white
+    auto_orient: bool,
+    board_theme: BoardThemeName,
+    appearance: Appearance,
+    // of the interface, a loaded translation file is kept for the session only
+    language: Language,
+    // on top of the scale factor of the display
+    ui_scale: f32,
+    // the valid moves as crosses instead of a tinted square
+    valid_move_shapes: bool,
+    // the last move as a square and the hint as a dashed ring, not by their color alone
+    marker_shapes: bool,
+    // a ring on white's discs, so that the color is not the only difference
+    disc_pattern: bool,
+    use_clocks: bool,
+    clock_minutes: u64,
+    clock_increment_seconds: u64,
+    beep_on_low_time: bool,
+    ai_uses_clock: bool,
+    // a rule variant, black moves first in standard games
+    first_player: Player,
+    clear_log_on_reset: bool,
+    // the marks left on the squares go with the old game
+    clear_marks_on_new_game: bool,
 }
 
 impl Default for GameOptions {
     fn default() -> Self {
         GameOptions {
             show_effects_of_moves: false,
+            show_flip_counts: false,
+            show_move_preview: false,
+            preview_flips: false,
             show_valid_moves: false,
+            show_opponent_move_count: false,
+            compact_mode: false,
+            side_panel_width: 280.0,
+            open_sections: OpenSections::default(),
             auto_restart: false,
-            pace_ai: true,
-            pause_at_win: true,
+            alternate_colors: false,
+            confirm_moves: false,
+            ai_move_seconds: 1.0,
+            step_mode: false,
+            flip_animation_seconds: 0.4,
+            reduce_motion: false,
+            play_sounds: false,
+            sound_volume: 0.5,
+            pause_at_win_seconds: 1,
             should_take_statistics: true,
+            min_statistics_games: 10,
+            statistics_sort: StatisticsSort::default(),
+            opening_plies: 6,
+            show_opening_scores: false,
+            show_heatmap: false,
+            live_heatmap: false,
+            annotate_analyzed_moves: true,
+            mark_passes_in_transcript: false,
+            show_last_move: true,
+            show_move_numbers: false,
+            show_stable_discs: false,
+            show_frontier_discs: false,
+            show_evaluation_bar: true,
+            show_coordinates: true,
+            orientation: Orientation::default(),
+            auto_orient: false,
+            board_theme: BoardThemeName::default(),
+            appearance: Appearance::default(),
+            language: Language::default(),
+            ui_scale: 1.0,
+            valid_move_shapes: false,
+            marker_shapes: false,
+            disc_pattern: false,
+            use_clocks: false,
+            clock_minutes: 5,
+            clock_increment_seconds: 0,
+            beep_on_low_time: false,
+            ai_uses_clock: true,
+            first_player: Player::Black,
+            clear_log_on_reset: false,
+            clear_marks_on_new_game: true,
         }
     }
 }
 
-#[derive(Clone, Copy)]
+impl GameOptions {
+    fn apply_startup_args(&mut self, startup: &StartupArgs) {
+        self.auto_restart |= startup.auto_restart;
+        self.show_valid_moves |= startup.show_valid_moves;
+        self.show_effects_of_moves |= startup.show_effects_of_moves;
+        if startup.no_pace_ai {
+            self.ai_move_seconds = 0.0;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PlayerOptions {
     ai_enabled: bool,
     ai_type: AiType,
     ai_recursion_depth: usize,
 }
 
+impl PlayerOptions {
+    fn apply_startup_args(&mut self, player_args: &PlayerArgs) {
+        match player_args.kind {
+            Some(PlayerKind::Human) => self.ai_enabled = false,
+            Some(PlayerKind::Ai(ai_type)) => {
+                self.ai_enabled = true;
+                self.ai_type = ai_type;
+            }
+            None => {}
+        }
+        if let Some(recursion_depth) = player_args.recursion_depth {
+            self.ai_recursion_depth = recursion_depth;
+        }
+    }
+
+    fn participant(&self) -> Participant {
+        if self.ai_enabled {
+            Participant::ai(self.ai_type, self.ai_recursion_depth)
+        } else {
+            Participant::Human
+        }
+    }
+
+    // how the player shows up in the statistics
+    fn name(&self) -> String {
+        if self.ai_enabled {
+            self.ai_type.describe(self.ai_recursion_depth)
+        } else {
+            "Human".to_string()
+        }
+    }
+}
+
 impl Default for PlayerOptions {
     fn default() -> Self {
         PlayerOptions {
@@ -66,538 +654,9577 @@ impl Default for PlayerOptions {
     }
 }
 
+// the move the AI suggests to the human to move
+#[derive(Clone, Copy)]
+enum Hint {
+    // asked of the hint agent
+    Pending(RequestId),
+    Ready(Move),
+}
+
+// a move asked of a seat's agent, until it is played or dropped
+struct AiRequest {
+    id: RequestId,
+    requested_at: Instant,
+    // how long the agent took for `move_result`
+    thinking_seconds: f64,
+    // an answer that came in before the minimum time per move was up
+    move_result: Option<MoveResult>,
+}
+
+// why a move was not played
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MoveError {
+    IllegalMove(Move),
+    // it is the other player's turn
+    WrongTurn(Player),
+    GameOver,
+}
+
+impl MoveError {
+    fn text(self, strings: &Strings) -> String {
+        match self {
+            MoveError::IllegalMove(next_move) if next_move.0 < Board::SIZE => strings.tr_args(
+                "move-error-illegal",
+                &[("move", &notation::format_move(next_move))],
+            ),
+            MoveError::IllegalMove(_) => strings.tr("move-error-no-move").to_string(),
+            MoveError::WrongTurn(player) => strings.tr_args(
+                "move-error-wrong-turn",
+                &[(
+                    "player",
+                    &strings.tr(match player {
+                        Player::Black => "player-black",
+                        Player::White => "player-white",
+                    }),
+                )],
+            ),
+            MoveError::GameOver => strings.tr("move-error-game-over").to_string(),
+        }
+    }
+}
+
+// the seats the human plays, the AI plays the others
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PlayAs {
+    Black,
+    White,
+    Both,
+    Neither,
+}
+
+impl PlayAs {
+    fn from_player_options(player_options: &[PlayerOptions; 2]) -> Self {
+        match player_options.map(|options| options.ai_enabled) {
+            [false, true] => PlayAs::Black,
+            [true, false] => PlayAs::White,
+            [false, false] => PlayAs::Both,
+            [true, true] => PlayAs::Neither,
+        }
+    }
+
+    fn is_human(&self, player: Player) -> bool {
+        match self {
+            PlayAs::Black => player == Player::Black,
+            PlayAs::White => player == Player::White,
+            PlayAs::Both => true,
+            PlayAs::Neither => false,
+        }
+    }
+}
+
+// a change of the seat setup, which waits for confirmation while a game is in progress
+#[derive(Clone, Copy)]
+enum SeatChange {
+    PlayAs(PlayAs),
+    FirstPlayer(Player),
+}
+
+// a game against another instance of the app, one seat is played over there
+struct NetworkGame {
+    connection: Connection,
+    // None until connected
+    local_player: Option<Player>,
+    // once the connection is lost, the game stays frozen until the network game is left
+    error: Option<String>,
+}
+
+// the puzzle on the board, which takes exactly one move
+struct PuzzleRun {
+    // into PUZZLES
+    index: usize,
+    solutions: Vec<Move>,
+    // None until the move is made
+    solved: Option<bool>,
+    // a revealed puzzle no longer counts as solved
+    revealed: bool,
+}
+
+// a position being set up by hand, the game is suspended until it is done or cancelled
+struct PositionEditor {
+    board: Board,
+    player: Player,
+    // the boards before each edit
+    undo_stack: Vec<Board>,
+    error: Option<String>,
+}
+
+// what Export Image puts on the picture and where it goes
+struct ImageExport {
+    path: String,
+    coordinates: bool,
+    last_move: bool,
+    valid_moves: bool,
+    move_numbers: bool,
+    heatmap: bool,
+    arrows: bool,
+    annotations: bool,
+    marks: bool,
+    // where the last picture went, or why it could not
+    result: Option<Result<String, String>>,
+}
+
+impl Default for ImageExport {
+    fn default() -> Self {
+        ImageExport {
+            path: "board.png".to_string(),
+            coordinates: true,
+            last_move: true,
+            valid_moves: false,
+            move_numbers: false,
+            heatmap: true,
+            arrows: true,
+            annotations: true,
+            marks: true,
+            result: None,
+        }
+    }
+}
+
+// a series of AI games played back-to-back, until the tally is complete or the match is stopped
+struct MatchRun {
+    tally: MatchTally,
+    // the player options in the order of the tally, the first one playing black in the first game
+    configurations: [PlayerOptions; 2],
+    running: bool,
+    // of its statistics entry, which follows the match game by game
+    name: String,
+    started_at: Instant,
+}
+
 pub struct Game {
     board: Board,
     current_phase: Phase,
     options: GameOptions,
     player_options: [PlayerOptions; 2],
-    ai_thread: Option<thread::JoinHandle<()>>,
-    awaiting_ai_move: bool,
-    move_request_sender: Option<mpsc::Sender<MoveRequest>>,
-    move_result_receiver: mpsc::Receiver<MoveResult>,
+    // the move each seat's agent is thinking about, by color
+    ai_requests: [Option<AiRequest>; 2],
+    // of the AI moves of the current game, by color
+    thinking_times: [ThinkingTime; 2],
+    // one agent per seat, so a seat's search never waits behind the other's, indexed by player
+    agent_runners: [AgentRunner; 2],
+    // hints have an agent of their own, so that a hint is never taken for a seat's move
+    hint_runner: AgentRunner,
+    // game analysis runs apart from both seats
+    analysis_runner: AgentRunner,
     referee: Referee,
     valid_moves: CellList,
     flip_cells: CellList,
-    scheduled_restart: Instant,
+    // the pause before an auto-restart counts from here, None once the restart is held
+    game_over_at: Option<Instant>,
     is_board_untouched: bool,
-    can_take_statistics: bool,
-    statistics: Statistics,
+    // None while the game counts in the statistics when it ends
+    statistics_block: Option<StatisticsBlock>,
+    // loaded at startup and written whenever it changes
+    statistics_file: StatisticsFile,
+    // the results since the app started or the session was reset, never saved
+    session_statistics: Statistics,
+    // the panel shows the session instead of all time
+    show_session_statistics: bool,
+    // the statistics table only shows the entries whose name contains it
+    statistics_filter: String,
+    // computed from the statistics shown whenever they change
+    ratings: Vec<Rating>,
+    head_to_head: HeadToHead,
+    // the games behind the statistics, oldest first
+    game_records: Vec<GameRecord>,
+    // the matchup the game history is narrowed down to
+    game_history_filter: Option<Matchup>,
+    // opens the game history in the next frame
+    open_game_history: bool,
+    // the opening lines of the game records, rebuilt whenever `opening_plies` changes
+    openings: OpeningTree,
+    // the AI whose depths are plotted against each other
+    depth_plot_ai_type: AiType,
+    // awaiting the confirmation to clear all statistics
+    clearing_statistics: bool,
+    // the participant of the statistics to rename, and the name to give it
+    rename_from: Option<Participant>,
+    rename_to: String,
+    history: History,
+    // an unfinished game found in the autosave at startup, until the user decides
+    resume_offer: Option<(SavedGame, Replay)>,
+    transcript_input: String,
+    transcript_error: Option<String>,
+    // while set, the board shows an earlier position and the game is suspended
+    replay: Option<ReplayView>,
+    pass_notice: Option<PassNotice>,
+    hover_flips: Option<HoverFlips>,
+    // the width the side panel is being dragged to, kept once the drag is over so that the
+    // settings are not written on every frame of it
+    dragged_side_panel_width: Option<f32>,
+    // how often the hover flips were worked out, to tell that it is not on every frame
+    #[cfg(debug_assertions)]
+    hover_flip_computations: usize,
+    // only while they are shown
+    stable_discs: Option<DiscMarks>,
+    frontier_discs: Option<DiscMarks>,
+    // left on the board with right clicks, whatever the game does
+    square_marks: SquareMarks,
+    // how often the stable or the frontier discs were worked out, to tell that it is not on every frame
+    #[cfg(debug_assertions)]
+    disc_mark_computations: usize,
+    // None once the game is over
+    move_counts: Option<MoveCounts>,
+    // the result over the board was closed, until the next game ends
+    result_overlay_closed: bool,
+    move_list: MoveList,
+    last_move: Option<LastMove>,
+    // the discs of the last move still turning over, the next move waits for them
+    flip_animation: Option<FlipAnimation>,
+    animations: Animations,
+    sounds: Sounds,
+    // the strings of the interface, replaced when the language option changes
+    strings: Strings,
+    translation_path: String,
+    translation_result: Option<Result<String, String>>,
+    // the appearance given to egui, set again when the option changes
+    applied_appearance: Option<Appearance>,
+    // the zoom factor given to egui, for telling a zoom with the keyboard apart
+    applied_ui_scale: Option<f32>,
+    // whether the visuals were dark last frame, to notice the system preference changing
+    dark_mode: Option<bool>,
+    clock: Option<GameClock>,
+    // the evaluation of the last AI search, from black's perspective
+    last_search_score: Option<f32>,
+    analysis: Option<Analysis>,
+    analysis_run: Option<AnalysisRun>,
+    // of the position on the board, shown while it stays there
+    position_scores: Option<PositionScores>,
+    analysis_depth: usize,
+    swing_thresholds: SwingThresholds,
+    wthor_path: String,
+    wthor_database: Option<WthorDatabase>,
+    wthor_error: Option<String>,
+    match_games: usize,
+    match_run: Option<MatchRun>,
+    // AI games on a grid of boards, in place of the game on the main board
+    multi_board: Option<MultiBoard>,
+    multi_board_count: usize,
+    arena_contestants: Vec<Contestant>,
+    // each pairing plays this many games with either color
+    arena_games_per_color: usize,
+    arena: Option<Arena>,
+    // numbers the arena entries in the statistics
+    arenas_run: usize,
+    // the games of the arena in the statistics so far, and when they were put there
+    arena_games_recorded: usize,
+    arena_recorded_at: Instant,
+    arena_csv_path: String,
+    // where the last crosstable went, or why it could not
+    arena_csv_result: Option<Result<String, String>>,
+    // numbers the aggregated match entries in the statistics
+    matches_played: usize,
+    network: Option<NetworkGame>,
+    network_port: u16,
+    network_address: String,
+    puzzle: Option<PuzzleRun>,
+    next_puzzle: usize,
+    editor: Option<PositionEditor>,
+    image_export: ImageExport,
+    // takes over the human seats to finish a game
+    auto_finish_ai: PlayerOptions,
+    // while finishing, the player options to restore once the game is over
+    auto_finish: Option<[PlayerOptions; 2]>,
+    // awaiting the confirmation to restart
+    seat_change: Option<SeatChange>,
+    // in step mode, the AI may think about one move
+    step_requested: bool,
+    // as last loaded or written
+    saved_settings: Settings,
+    // a move typed as a coordinate, played on Enter
+    move_input: String,
+    move_input_error: Option<String>,
+    // the selected move awaiting confirmation, with confirm_moves
+    pending_move: Option<Move>,
+    hint: Option<Hint>,
+    // a short message about something that went wrong, until it times out
+    toast: Option<(String, Instant)>,
+    // why an AI was turned off, shown until the next game
+    ai_failure: Option<String>,
+    // in place of the console
+    event_log: EventLog,
+    // the AIs pick among equally good moves with this in the current game
+    seed: u64,
+    // used by every new game instead of a fresh seed, so the games can be reproduced
+    pinned_seed: Option<u64>,
+    presets: Vec<Preset>,
+    // into presets
+    selected_preset: Option<usize>,
+    preset_name: String,
+    // a preset to import, pasted as JSON
+    preset_json: String,
+    preset_error: Option<String>,
 }
 
 impl Default for Game {
     fn default() -> Self {
-        let (move_request_sender, move_request_receiver) = mpsc::channel::<MoveRequest>();
-        let (move_result_sender, move_result_receiver) = mpsc::channel::<MoveResult>();
-
-        let ai_thread = thread::spawn(move || {
-            let mut agent = Agent::new(move_request_receiver, move_result_sender);
-            agent.run();
-        });
+        Self::new(StartupArgs::default())
+    }
+}
 
+impl Game {
+    // the command-line arguments are applied before the first frame
+    pub fn new(startup: StartupArgs) -> Self {
         let mut game = Game {
             board: Board::default(),
             current_phase: Phase::Turn(Player::Black),
             options: GameOptions::default(),
             player_options: [PlayerOptions::default(); 2],
-            ai_thread: Some(ai_thread),
-            awaiting_ai_move: false,
-            move_request_sender: Some(move_request_sender),
-            move_result_receiver,
+            ai_requests: [None, None],
+            thinking_times: [ThinkingTime::default(); 2],
+            agent_runners: [AgentRunner::new(), AgentRunner::new()],
+            hint_runner: AgentRunner::new(),
+            analysis_runner: AgentRunner::new(),
             referee: Referee::default(),
             valid_moves: CellList::default(),
             flip_cells: CellList::default(),
-            scheduled_restart: Instant::now(),
+            game_over_at: None,
             is_board_untouched: false,
-            can_take_statistics: false,
-            statistics: Statistics::default(),
+            statistics_block: None,
+            statistics_file: StatisticsFile::default(),
+            session_statistics: Statistics::default(),
+            show_session_statistics: false,
+            statistics_filter: String::new(),
+            ratings: Vec::new(),
+            head_to_head: HeadToHead::default(),
+            game_records: Vec::new(),
+            openings: OpeningTree::default(),
+            game_history_filter: None,
+            open_game_history: false,
+            depth_plot_ai_type: AiType::Minimax,
+            clearing_statistics: false,
+            rename_from: None,
+            rename_to: String::new(),
+            history: History::default(),
+            resume_offer: None,
+            transcript_input: String::new(),
+            transcript_error: None,
+            replay: None,
+            pass_notice: None,
+            hover_flips: None,
+            dragged_side_panel_width: None,
+            #[cfg(debug_assertions)]
+            hover_flip_computations: 0,
+            stable_discs: None,
+            square_marks: SquareMarks::default(),
+            frontier_discs: None,
+            #[cfg(debug_assertions)]
+            disc_mark_computations: 0,
+            move_counts: None,
+            result_overlay_closed: false,
+            move_list: MoveList::default(),
+            last_move: None,
+            flip_animation: None,
+            animations: Animations::default(),
+            sounds: Sounds::default(),
+            strings: Strings::new(Language::default()),
+            translation_path: String::new(),
+            translation_result: None,
+            applied_appearance: None,
+            applied_ui_scale: None,
+            dark_mode: None,
+            clock: None,
+            last_search_score: None,
+            analysis: None,
+            analysis_run: None,
+            position_scores: None,
+            analysis_depth: 4,
+            swing_thresholds: SwingThresholds::default(),
+            wthor_path: String::new(),
+            wthor_database: None,
+            wthor_error: None,
+            match_games: 10,
+            multi_board: None,
+            multi_board_count: 4,
+            match_run: None,
+            arena_contestants: arena::default_contestants(),
+            arena_games_per_color: 2,
+            arena: None,
+            arenas_run: 0,
+            arena_games_recorded: 0,
+            arena_recorded_at: Instant::now(),
+            arena_csv_path: "arena.csv".to_string(),
+            arena_csv_result: None,
+            matches_played: 0,
+            network: None,
+            network_port: 7777,
+            network_address: "127.0.0.1:7777".to_string(),
+            puzzle: None,
+            next_puzzle: 0,
+            editor: None,
+            image_export: ImageExport::default(),
+            auto_finish_ai: PlayerOptions {
+                ai_enabled: true,
+                ai_type: AiType::Positional,
+                ai_recursion_depth: 3,
+            },
+            auto_finish: None,
+            seat_change: None,
+            step_requested: false,
+            saved_settings: save::load_settings(),
+            move_input: String::new(),
+            move_input_error: None,
+            pending_move: None,
+            hint: None,
+            toast: None,
+            ai_failure: None,
+            event_log: EventLog::new(),
+            seed: 0,
+            pinned_seed: startup.seed,
+            presets: Vec::new(),
+            selected_preset: None,
+            preset_name: String::new(),
+            preset_json: String::new(),
+            preset_error: None,
         };
+        match save::load_statistics() {
+            Ok(statistics_file) => game.statistics_file = statistics_file,
+            Err(e) => game.log(e),
+        }
+        game.refresh_statistics_views();
+        let (game_records, newer) = save::load_game_records();
+        game.game_records = game_records;
+        if newer > 0 {
+            game.log(
+                game.strings
+                    .tr_args("log-newer-records", &[("count", &newer)]),
+            );
+        }
+        game.arenas_run = game
+            .statistics_file
+            .statistics
+            .data
+            .keys()
+            .filter_map(|name| name.strip_prefix("Arena ")?.split(':').next()?.parse().ok())
+            .max()
+            .unwrap_or(0);
+        // matches of earlier sessions keep their numbers
+        game.matches_played = game
+            .statistics_file
+            .statistics
+            .data
+            .keys()
+            .filter(|name| name.starts_with("Match "))
+            .count();
+
+        game.options = game.saved_settings.options;
+        game.player_options = game.saved_settings.player_options;
+        game.presets = game.saved_settings.presets.clone();
+        if !game.saved_settings.arena_contestants.is_empty() {
+            game.arena_contestants = game.saved_settings.arena_contestants.clone();
+        }
+        game.options.apply_startup_args(&startup);
+        game.apply_language();
+        for (player_options, player_args) in game.player_options.iter_mut().zip(&startup.players) {
+            player_options.apply_startup_args(player_args);
+        }
+        // the command line only applies to this session, unless changed further in the UI
+        game.saved_settings = game.settings();
 
         game.reset();
+        if let Some(replay) = startup.transcript {
+            game.load_replay(replay);
+        } else if let Some((board, player)) = startup.position {
+            game.start_from_position(board, player);
+        } else {
+            // an explicitly requested game takes precedence over resuming the last one
+            game.resume_offer = save::load_unfinished_autosave(&mut game.referee);
+        }
 
         game
     }
 }
 
-impl Drop for Game {
-    fn drop(&mut self) {
-        println!("Game is being dropped. Cleaning up AI thread...");
-
-        // Drop the sender so AI thread exits
-        self.move_request_sender = None;
-
-        // Wait for AI thread to exit
-        if let Some(ai_thread) = self.ai_thread.take() {
-            let _ = ai_thread.join();
-            println!("...and joined AI thread");
-        }
-    }
-}
-
 impl Game {
     // call this from the UI thread
     fn reset(&mut self) {
+        if !self.history.plies.is_empty() {
+            // the previous game was abandoned or finished, there is nothing to resume anymore
+            save::clear_autosave();
+        }
+        // the rule variant is local, both sides of a network game start with black
+        let first_player = match self.network {
+            Some(_) => Player::Black,
+            None => self.options.first_player,
+        };
+        self.history = History {
+            start_player: first_player,
+            ..Default::default()
+        };
+        self.replay = None;
+        self.pass_notice = None;
+        self.puzzle = None;
+        self.editor = None;
+        self.stop_auto_finish();
+        self.step_requested = false;
+        self.pending_move = None;
+        self.hover_flips = None;
+        self.hint = None;
+        self.ai_failure = None;
+        if self.options.clear_log_on_reset {
+            self.event_log.clear();
+        }
+        if self.options.clear_marks_on_new_game {
+            self.square_marks.clear();
+        }
+        self.reseed_agents();
+        self.last_move = None;
+        self.flip_animation = None;
+        self.animations.clear();
+        self.animate(AnimationKey::ValidMoves, VALID_MOVES_FADE_IN);
+        self.last_search_score = None;
+        self.cancel_analysis();
+        self.cancel_position_scores();
+        self.reset_clock();
         self.board = Board::default();
-        self.current_phase = Phase::Turn(Player::Black);
+        self.current_phase = Phase::Turn(first_player);
         self.referee
-            .find_all_valid_moves(&self.board, Player::Black, &mut self.valid_moves);
+            .find_all_valid_moves(&self.board, first_player, &mut self.valid_moves);
         self.is_board_untouched = true;
-        self.can_take_statistics = true;
+        self.statistics_block = None;
+        self.thinking_times = [ThinkingTime::default(); 2];
     }
 
-    fn ai_setting_changed(&mut self) {
-        // statistics are deemed invalid if the ai settings are changed after the game has started
-        if !self.is_board_untouched {
-            self.can_take_statistics = false;
+    // call this from the UI thread
+    fn load_replay(&mut self, replay: Replay) {
+        if self.network.is_some() {
+            self.log(self.strings.tr("log-load-during-network").to_string());
+            return;
         }
+        self.history = replay.history;
+        self.set_position(replay.board, replay.next_player);
     }
 
     // call this from the UI thread
-    fn tick_ai(&mut self, player: Player) {
-        // either poll for ai response, non-blocking
-        if self.awaiting_ai_move {
-            if let Ok(move_result) = self.move_result_receiver.try_recv() {
-                let (row, col) = move_result.next_move;
-                if row < Board::SIZE && col < Board::SIZE {
-                    if move_result.board.grid == self.board.grid && move_result.player == player {
-                        assert!(self.make_move(move_result.next_move, player));
-                    }
-                } else {
-                    // unable to come up with a valid move, it seems
-                    self.player_options[player as usize].ai_enabled = false;
-                }
-                self.awaiting_ai_move = false;
+    fn set_position(&mut self, board: Board, next_player: Option<Player>) {
+        self.replay = None;
+        self.pending_move = None;
+        self.hint = None;
+        self.pass_notice = None;
+        self.puzzle = None;
+        self.editor = None;
+        self.board = board;
+        self.last_search_score = None;
+        self.refresh_last_move();
+        self.flip_animation = None;
+        self.animations.clear();
+        self.animate(AnimationKey::ValidMoves, VALID_MOVES_FADE_IN);
+        self.current_phase = match next_player {
+            Some(player) => {
+                self.referee
+                    .find_all_valid_moves(&self.board, player, &mut self.valid_moves);
+                Phase::Turn(player)
             }
-        } else {
-            // or ask ai to start thinking about the next move
+            None => Phase::from(Referee::check_outcome(&self.board)),
+        };
 
-            if let Some(tx) = &self.move_request_sender {
-                self.awaiting_ai_move = true;
-                let _ = tx.send(MoveRequest {
-                    board: self.board.clone(),
-                    player,
-                    pace_response: self.options.pace_ai,
-                    algorithm_choice: self.player_options[player as usize].ai_type,
-                    recursion_depth: self.player_options[player as usize].ai_recursion_depth,
-                });
-            }
-        }
+        // nobody watched this game being played with the current settings
+        self.is_board_untouched = false;
+        self.statistics_block = Some(StatisticsBlock::PositionSet);
     }
 
     // call this from the UI thread
-    fn make_move(&mut self, next_move: Move, player: Player) -> bool {
-        // Validate and collect flip cells for ai move
-        if self.referee.find_flip_cells_for_move(
-            &self.board,
-            player,
-            next_move,
-            &mut self.flip_cells,
-        ) {
-            Referee::apply_move(&mut self.board, player, next_move, &self.flip_cells);
+    // a new game from the position, which undo goes back to instead of the standard start
+    fn start_from_position(&mut self, board: Board, player: Player) {
+        self.reset();
+        self.history = History {
+            start_board: board.clone(),
+            start_player: player,
+            ..Default::default()
+        };
+        self.set_position(board, Some(player));
+    }
 
-            let (black_count, white_count) = count_pieces(&self.board);
-            println!(
-                "Turn score - Black: {}, White: {}",
-                black_count, white_count
-            );
+    // finds the last move of the history and the discs it flipped
+    fn refresh_last_move(&mut self) {
+        self.last_move = None;
 
-            let opponent = player.opponent();
+        let Some(index) = self
+            .history
+            .plies
+            .iter()
+            .rposition(|ply| matches!(ply, Ply::Move { .. }))
+        else {
+            return;
+        };
+        let Ply::Move { player, next_move } = self.history.plies[index] else {
+            return;
+        };
 
-            if self
-                .referee
-                .find_all_valid_moves(&self.board, opponent, &mut self.valid_moves)
-            {
-                // switch players if the other player has valid moves
-                self.current_phase = Phase::Turn(opponent);
-            } else if !self
-                .referee
-                .find_all_valid_moves(&self.board, player, &mut self.valid_moves)
-            {
-                // no player has any valid moves, game ends
-                let outcome = Referee::check_outcome(&self.board);
-                self.current_phase = match outcome {
-                    Outcome::Won(player) => Phase::Win(player),
-                    Outcome::Tie => Phase::Tie,
-                };
+        if let Ok(replay) = self.referee.replay(
+            &self.history.start_board,
+            self.history.start_player,
+            &self.history.moves_until(index),
+        ) {
+            let mut flip_cells = CellList::default();
+            self.referee.find_flip_cells_for_move(
+                &replay.board,
+                player,
+                next_move,
+                &mut flip_cells,
+            );
+            self.last_move = Some(LastMove {
+                next_move,
+                flipped: turned_discs(&replay.board, player, &flip_cells)
+                    .iter()
+                    .collect(),
+            });
+        }
+    }
 
-                self.take_statistics(outcome);
+    fn can_undo(&self) -> bool {
+        // the other side would not follow
+        !self.is_match_running()
+            && self.network.is_none()
+            && self.puzzle.is_none()
+            && self.editor.is_none()
+            && !self.history.plies.is_empty()
+    }
 
-                // only used if auto_restart is enabled
-                self.scheduled_restart = Instant::now();
-                if self.options.pause_at_win {
-                    self.scheduled_restart += Duration::from_secs(1);
-                }
-            }
+    fn can_redo(&self) -> bool {
+        !self.is_match_running()
+            && self.network.is_none()
+            && self.puzzle.is_none()
+            && self.editor.is_none()
+            && self.history.next_redo().is_some()
+    }
 
-            if self.is_board_untouched {
-                // you can mess with the settings before the first move and still take statistics
-                self.can_take_statistics = true;
-                self.is_board_untouched = false;
-            }
+    fn undo(&mut self) {
+        if !self.can_undo() || self.history.undo().is_none() {
+            return;
+        }
 
-            true
-        } else {
-            false
+        match self.referee.replay(
+            &self.history.start_board,
+            self.history.start_player,
+            &self.history.moves(),
+        ) {
+            Ok(replay) => {
+                self.set_position(replay.board, replay.next_player);
+                self.autosave();
+            }
+            Err(e) => self.log(self.strings.tr_args("log-undo-failed", &[("error", &e)])),
         }
     }
 
-    fn take_statistics(&mut self, outcome: Outcome) {
-        if self.can_take_statistics {
-            let mut names: [String; 2] = [String::new(), String::new()];
-            for (i, name) in names.iter_mut().enumerate() {
-                *name = if self.player_options[i].ai_enabled {
-                    match self.player_options[i].ai_type {
-                        AiType::Random => "Random".to_string(),
-                        AiType::Minimax => {
-                            format!("Minimax lvl {}", self.player_options[i].ai_recursion_depth)
-                        }
-                        AiType::AlphaBeta => {
-                            format!("AlphaBeta lvl {}", self.player_options[i].ai_recursion_depth)
-                        }
-                    }
-                } else {
-                    "Human".to_string()
-                };
-            }
+    // the human facing the AI, who may take back their last move and the reply to it
+    fn take_back_player(&self) -> Option<Player> {
+        if self.network.is_some()
+            || self.puzzle.is_some()
+            || self.editor.is_some()
+            || self.replay.is_some()
+            || self.is_match_running()
+        {
+            return None;
+        }
 
-            // sort so that another player color doesn't render another entry
-            let first_player = if names[0] < names[1] {
-                Player::Black
-            } else {
-                Player::White
-            };
+        let human = match self.player_options.map(|options| options.ai_enabled) {
+            [false, true] => Player::Black,
+            [true, false] => Player::White,
+            _ => return None,
+        };
+        self.history
+            .plies
+            .iter()
+            .any(|ply| matches!(*ply, Ply::Move { player, .. } if player == human))
+            .then_some(human)
+    }
 
-            self.statistics.add_datum(
-                format!(
-                    "{} vs {}",
-                    names[first_player as usize],
-                    names[(first_player as usize + 1) % 2]
-                ),
-                first_player,
-                &outcome,
-            );
+    // call this from the UI thread
+    fn take_back(&mut self) {
+        let Some(human) = self.take_back_player() else {
+            return;
+        };
+
+        // a reply still on its way is for the position being taken back, it is dropped on arrival
+        self.ai_requests = [None, None];
+        let Some(taken_back) = self.history.take_back(human) else {
+            return;
+        };
+        self.log(self.strings.tr_args(
+            "log-take-back",
+            &[("player", &self.player_name(human)), ("count", &taken_back)],
+        ));
 
-            self.can_take_statistics = false;
+        match self.referee.replay(
+            &self.history.start_board,
+            self.history.start_player,
+            &self.history.moves(),
+        ) {
+            Ok(replay) => {
+                // this also rules out statistics for the rest of the game
+                self.set_position(replay.board, replay.next_player);
+                self.autosave();
+            }
+            Err(e) => self.log(
+                self.strings
+                    .tr_args("log-take-back-failed", &[("error", &e)]),
+            ),
         }
     }
 
-    fn update_player_options_controls(&mut self, ui: &mut egui::Ui, player: Player) {
-        // Define the maximum depth for the minimax algorithm
-        let max_depth = 10;
-
-        ui.label(format!("{:?} Player Options", player));
-        if ui
-            .checkbox(
-                &mut self.player_options[player as usize].ai_enabled,
-                "Enable AI",
-            )
-            .changed()
-        {
-            self.ai_setting_changed();
-        }
-        ui.label("AI Type");
-        self.player_options[player as usize].ai_type = self.update_ai_type_radio_buttons(
-            ui,
-            self.player_options[player as usize].ai_type,
-            player,
-        );
-        // a slider for the minimax algorithm recursion depth
-        ui.label("AI Recursion Depth");
-        if ui
-            .add(
-                egui::Slider::new(
-                    &mut self.player_options[player as usize].ai_recursion_depth,
-                    1..=max_depth,
-                )
-                .text(""),
-            )
-            .changed()
-            && self.player_options[player as usize].ai_enabled
-            && (self.player_options[player as usize].ai_type == AiType::Minimax
-                || self.player_options[player as usize].ai_type == AiType::AlphaBeta)
+    fn redo(&mut self) {
+        if self.can_redo()
+            && let Some(Ply::Move { player, next_move }) = self.history.next_redo()
+            && matches!(self.current_phase, Phase::Turn(current_player) if current_player == player)
+            && let Err(e) = self.make_move(next_move, player)
         {
-            self.ai_setting_changed();
+            self.report_move_error(player, e);
         }
     }
 
-    // closure that handles the dynamic depth options
-    fn update_ai_type_radio_buttons(
-        &mut self,
-        ui: &mut egui::Ui,
-        ai_type: AiType,
-        player: Player,
-    ) -> AiType {
-        let options = ["Random".to_string(), "Minimax".to_string(), "AlphaBeta".to_string()];
-
-        let mut result = ai_type;
+    fn show_replay_position(&mut self, index: usize) {
+        let index = index.min(self.history.plies.len());
+        match self.referee.replay(
+            &self.history.start_board,
+            self.history.start_player,
+            &self.history.moves_until(index),
+        ) {
+            Ok(replay) => {
+                self.flip_animation = None;
+                self.replay = Some(ReplayView {
+                    index,
+                    board: replay.board,
+                });
+            }
+            Err(e) => self.log(self.strings.tr_args("log-replay-failed", &[("error", &e)])),
+        }
+    }
 
-        // Display dynamic depth options in a loop
-        for (i, option) in options.iter().enumerate() {
-            if ui.radio(ai_type as usize == i, option).clicked() {
-                result = match AiType::try_from(i) {
-                    Ok(agent_type) => agent_type,
-                    Err(e) => {
-                        panic!("AI type conversion failed: {e}")
-                    }
-                };
+    // drops the rest of the history and resumes playing from the replayed position
+    fn continue_from_replay(&mut self) {
+        let Some(index) = self.replay.as_ref().map(|replay| replay.index) else {
+            return;
+        };
 
-                if self.player_options[player as usize].ai_enabled {
-                    self.ai_setting_changed();
-                }
+        match self.referee.replay(
+            &self.history.start_board,
+            self.history.start_player,
+            &self.history.moves_until(index),
+        ) {
+            Ok(replay) => {
+                self.load_replay(replay);
+                self.autosave();
             }
+            Err(e) => self.log(
+                self.strings
+                    .tr_args("log-continue-failed", &[("error", &e)]),
+            ),
         }
-
-        result
     }
-}
+
+    fn refresh_move_list(&mut self) {
+        if self.move_list.plies == self.history.plies
+            && self.move_list.pass == self.strings.tr("move-list-pass")
+            && self
+                .move_list
+                .start_board
+                .as_ref()
+                .is_some_and(|board| board.grid == self.history.start_board.grid)
+        {
+            return;
+        }
+
+        let mut board = self.history.start_board.clone();
+        let mut flip_cells = CellList::default();
+        self.move_list.rows.clear();
+        self.move_list.counts.clear();
+        self.move_list.counts.push(count_pieces(&board));
+
+        for (i, ply) in self.history.plies.iter().enumerate() {
+            let (player, notation) = match *ply {
+                Ply::Move { player, next_move } => {
+                    self.referee.find_flip_cells_for_move(
+                        &board,
+                        player,
+                        next_move,
+                        &mut flip_cells,
+                    );
+                    Referee::apply_move(&mut board, player, next_move, &flip_cells);
+                    (player, notation::format_move(next_move))
+                }
+                Ply::Pass { player } => (player, self.strings.tr("move-list-pass").to_string()),
+            };
+            let symbol = match player {
+                Player::Black => '●',
+                Player::White => '○',
+            };
+            let (black_count, white_count) = count_pieces(&board);
+            self.move_list.rows.push(format!(
+                "{}. {symbol} {notation}  ({black_count}–{white_count})",
+                i + 1
+            ));
+            self.move_list.counts.push((black_count, white_count));
+        }
+
+        self.move_list.start_board = Some(self.history.start_board.clone());
+        self.move_list.plies = self.history.plies.clone();
+        self.move_list.pass = self.strings.tr("move-list-pass").to_string();
+    }
+
+    fn update_move_list(&mut self, ui: &mut egui::Ui) {
+        self.refresh_move_list();
+
+        let replay_index = self.replay.as_ref().map(|replay| replay.index);
+        let mut selected_ply = None;
+        // as many rows side by side as the width of the panel has room for, read across
+        let columns = ((ui.available_width() / MOVE_LIST_COLUMN_WIDTH) as usize).max(1);
+        egui::ScrollArea::vertical()
+            .id_salt("move_list")
+            .max_height(150.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                egui::Grid::new("move_list_rows")
+                    .num_columns(columns)
+                    .min_col_width(MOVE_LIST_COLUMN_WIDTH - ui.spacing().item_spacing.x)
+                    .show(ui, |ui| {
+                        for (i, row) in self.move_list.rows.iter().enumerate() {
+                            // the replay index counts the plies already played on the shown board
+                            let is_shown = replay_index == Some(i + 1);
+                            if ui
+                                .selectable_label(is_shown, row)
+                                .on_hover_text(self.strings.tr("move-list-row-tooltip"))
+                                .clicked()
+                            {
+                                selected_ply = Some(i + 1);
+                            }
+                            if (i + 1) % columns == 0 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+
+        if let Some(ply_count) = selected_ply {
+            self.show_replay_position(ply_count);
+        }
+    }
+
+    // the disc counts over the plies, with a cursor on the replayed ply
+    // clicking a ply shows it in the replay
+    fn update_score_graph(&mut self, ui: &mut egui::Ui) {
+        self.refresh_move_list();
+
+        ui.label(self.strings.tr("score-graph"));
+
+        let counts = &self.move_list.counts;
+        // a whole game fits without rescaling, only passes can make it longer
+        let ply_range = (counts.len() - 1).max(SCORE_GRAPH_PLIES) as f32;
+        let disc_range = (Board::SIZE * Board::SIZE) as f32;
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), SCORE_GRAPH_HEIGHT),
+            egui::Sense::click(),
+        );
+        let to_pos = |ply: usize, count: usize| {
+            egui::pos2(
+                rect.left() + ply as f32 / ply_range * rect.width(),
+                rect.bottom() - count as f32 / disc_range * rect.height(),
+            )
+        };
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, egui::Color32::DARK_GREEN);
+        // the line of an even score
+        painter.hline(
+            rect.x_range(),
+            rect.center().y,
+            egui::Stroke::new(1.0, egui::Color32::from_black_alpha(60)),
+        );
+        if let Some(replay) = &self.replay {
+            let x = to_pos(replay.index, 0).x;
+            painter.vline(
+                x,
+                rect.y_range(),
+                egui::Stroke::new(1.5, egui::Color32::GOLD),
+            );
+        }
+        for (player, color) in [
+            (Player::Black, egui::Color32::BLACK),
+            (Player::White, egui::Color32::WHITE),
+        ] {
+            let points = counts
+                .iter()
+                .enumerate()
+                .map(|(ply, &(black_count, white_count))| match player {
+                    Player::Black => to_pos(ply, black_count),
+                    Player::White => to_pos(ply, white_count),
+                })
+                .collect();
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+        }
+
+        let Some(position) = response.hover_pos() else {
+            return;
+        };
+        let ply = (((position.x - rect.left()) / rect.width() * ply_range).round() as usize)
+            .min(counts.len() - 1);
+        let (black_count, white_count) = counts[ply];
+        for (count, color) in [
+            (black_count, egui::Color32::BLACK),
+            (white_count, egui::Color32::WHITE),
+        ] {
+            painter.circle_filled(to_pos(ply, count), 3.0, color);
+        }
+        let clicked = response.clicked();
+        response.on_hover_text_at_pointer(self.strings.tr_args(
+            "score-graph-ply",
+            &[
+                ("ply", &ply),
+                ("black", &self.player_name(Player::Black)),
+                ("black_count", &black_count),
+                ("white", &self.player_name(Player::White)),
+                ("white_count", &white_count),
+            ],
+        ));
+        if clicked {
+            self.show_replay_position(ply);
+        }
+    }
+
+    // the analysis of the current game, if there is one
+    fn current_analysis(&self) -> Option<&Analysis> {
+        self.analysis
+            .as_ref()
+            .filter(|analysis| analysis.plies == self.history.plies)
+    }
+
+    fn start_analysis(&mut self) {
+        if self.current_analysis().is_some_and(|analysis| {
+            analysis.recursion_depth == self.analysis_depth
+                && analysis
+                    .positions
+                    .iter()
+                    .zip(self.history.plies.iter())
+                    .all(|(position, ply)| position.is_some() || matches!(ply, Ply::Pass { .. }))
+        }) {
+            // cached from an earlier run
+            self.show_replay_position(0);
+            return;
+        }
+
+        self.cancel_analysis();
+
+        let mut positions = Vec::new();
+        let mut board = self.history.start_board.clone();
+        let mut flip_cells = CellList::default();
+        for (ply, entry) in self.history.plies.iter().enumerate() {
+            if let Ply::Move { player, next_move } = *entry {
+                positions.push(AnalysisPosition {
+                    ply,
+                    board: board.clone(),
+                    player,
+                });
+                self.referee
+                    .find_flip_cells_for_move(&board, player, next_move, &mut flip_cells);
+                Referee::apply_move(&mut board, player, next_move, &flip_cells);
+            }
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (result_sender, result_receiver) = mpsc::channel::<AnalysisResult>();
+        let total = positions.len();
+        self.analysis_runner
+            .send(AgentRequest::Analysis(AnalysisRequest {
+                positions,
+                recursion_depth: self.analysis_depth,
+                cancel: cancel.clone(),
+                result_sender,
+            }));
+
+        self.analysis = Some(Analysis::new(
+            self.history.plies.clone(),
+            self.analysis_depth,
+        ));
+        self.analysis_run = Some(AnalysisRun {
+            cancel,
+            result_receiver,
+            total,
+            received: 0,
+        });
+    }
+
+    // keeps the results gathered so far
+    fn cancel_analysis(&mut self) {
+        if let Some(run) = self.analysis_run.take() {
+            run.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // call this from the UI thread
+    fn poll_analysis(&mut self) {
+        let Some(run) = &mut self.analysis_run else {
+            return;
+        };
+
+        while let Ok(result) = run.result_receiver.try_recv() {
+            run.received += 1;
+            if let Some(analysis) = &mut self.analysis {
+                analysis.add_result(result.ply, result.move_scores);
+            }
+        }
+
+        if run.received >= run.total {
+            self.analysis_run = None;
+            self.show_replay_position(0);
+        }
+    }
+
+    fn update_analysis_controls(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.analysis_depth, 1..=8).text(self.strings.tr("depth")))
+            .on_hover_text(self.strings.tr("analysis-depth-tooltip"));
+
+        if let Some(run) = &self.analysis_run {
+            ui.label(self.strings.tr_args(
+                "analyzing-ply",
+                &[("index", &(run.received + 1)), ("count", &run.total)],
+            ));
+            if ui
+                .button(self.strings.tr("cancel-analysis"))
+                .on_hover_text(self.strings.tr("cancel-analysis-tooltip"))
+                .clicked()
+            {
+                self.cancel_analysis();
+            }
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.history.plies.is_empty(),
+                    egui::Button::new(self.strings.tr("analyze-game")),
+                )
+                .on_hover_text(self.strings.tr("analyze-game-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-no-moves"))
+                .clicked()
+            {
+                self.start_analysis();
+            }
+            if let Some(analysis) = self.current_analysis()
+                && ui
+                    .button(self.strings.tr("copy-annotated-game"))
+                    .on_hover_text(self.strings.tr("copy-annotated-game-tooltip"))
+                    .clicked()
+            {
+                ui.ctx().copy_text(analysis::annotated_text(
+                    analysis,
+                    &self.swing_thresholds,
+                    &self.strings,
+                ));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("swings"))
+                .on_hover_text(self.strings.tr("swings-tooltip"));
+            let thresholds = &mut self.swing_thresholds;
+            ui.add(egui::DragValue::new(&mut thresholds.inaccuracy).prefix("?! "))
+                .on_hover_text(self.strings.tr("swing-inaccuracy-tooltip"));
+            ui.add(egui::DragValue::new(&mut thresholds.mistake).prefix("? "))
+                .on_hover_text(self.strings.tr("swing-mistake-tooltip"));
+            ui.add(egui::DragValue::new(&mut thresholds.blunder).prefix("?? "))
+                .on_hover_text(self.strings.tr("swing-blunder-tooltip"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.options.show_heatmap,
+                self.strings.tr("score-heatmap"),
+            )
+            .on_hover_text(self.strings.tr("score-heatmap-tooltip"));
+            ui.add_enabled(
+                self.options.show_heatmap,
+                egui::Checkbox::new(
+                    &mut self.options.live_heatmap,
+                    self.strings.tr("live-heatmap"),
+                ),
+            )
+            .on_hover_text(self.strings.tr("live-heatmap-tooltip"))
+            .on_disabled_hover_text(self.strings.tr("disabled-needs-heatmap"));
+        });
+
+        ui.checkbox(
+            &mut self.options.annotate_analyzed_moves,
+            self.strings.tr("annotate-moves"),
+        )
+        .on_hover_text(self.strings.tr("annotate-moves-tooltip"));
+
+        if let Some(analysis) = self.current_analysis() {
+            let summary = analysis::summarize(analysis, &self.swing_thresholds);
+            ui.label(analysis::summary_text(&summary, &self.strings));
+        }
+    }
+
+    // asks the analysis agent to score every valid move of the position on the board
+    fn request_position_scores(&mut self) {
+        let Some(player) = self.human_to_move() else {
+            return;
+        };
+        if self
+            .position_scores
+            .as_ref()
+            .is_some_and(|scores| scores.board.grid == self.board.grid)
+        {
+            return;
+        }
+        self.cancel_position_scores();
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (result_sender, result_receiver) = mpsc::channel::<AnalysisResult>();
+        self.analysis_runner
+            .send(AgentRequest::Analysis(AnalysisRequest {
+                positions: vec![AnalysisPosition {
+                    ply: 0,
+                    board: self.board.clone(),
+                    player,
+                }],
+                recursion_depth: self.analysis_depth,
+                cancel: cancel.clone(),
+                result_sender,
+            }));
+        self.position_scores = Some(PositionScores {
+            board: self.board.clone(),
+            cancel,
+            result_receiver,
+            move_scores: None,
+        });
+    }
+
+    fn cancel_position_scores(&mut self) {
+        if let Some(scores) = self.position_scores.take() {
+            scores.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // call this from the UI thread
+    fn poll_position_scores(&mut self) {
+        if self.options.show_heatmap && self.options.live_heatmap {
+            self.request_position_scores();
+        }
+        if let Some(scores) = &mut self.position_scores
+            && scores.move_scores.is_none()
+            && let Ok(result) = scores.result_receiver.try_recv()
+        {
+            scores.move_scores = Some(result.move_scores);
+        }
+    }
+
+    // the arrows on the board and the color of their marker: onto the hinted move, and in an
+    // analyzed replay onto the move the engine preferred, each from the disc closing the longest
+    // line the move flips
+    fn arrows(&self) -> Vec<(Move, Move, egui::Color32)> {
+        let theme = self.options.board_theme.theme();
+        let mut arrows = Vec::new();
+        if let (None, None, Some(Hint::Ready(square)), Phase::Turn(player)) =
+            (&self.editor, &self.replay, self.hint, self.current_phase)
+            && let Some((from, to)) = capture_arrow(&self.board, player, square)
+        {
+            arrows.push((from, to, theme.hint));
+        }
+        if let Some(replay) = &self.replay
+            && let Some(analysis) = self.current_analysis()
+            && let Some(Some(position)) = analysis.positions.get(replay.index)
+            && let Some((best_move, _)) = position.best()
+            && let Some((from, to)) = capture_arrow(&replay.board, position.player, best_move)
+        {
+            arrows.push((from, to, theme.best_move));
+        }
+        arrows
+    }
+
+    // the score of every valid move of the replayed position, if it was analyzed, and whether it
+    // is the best one
+    fn annotations(&self) -> Vec<(Move, String, bool)> {
+        if !self.options.annotate_analyzed_moves {
+            return Vec::new();
+        }
+        let Some(replay) = &self.replay else {
+            return Vec::new();
+        };
+        let Some(Some(position)) = self
+            .current_analysis()
+            .and_then(|analysis| analysis.positions.get(replay.index))
+        else {
+            return Vec::new();
+        };
+        let Some((_, best_score)) = position.best() else {
+            return Vec::new();
+        };
+        position
+            .move_scores
+            .iter()
+            .map(|&(square, score)| {
+                let text = analysis::compact_score(score, &self.strings);
+                (square, text, score == best_score)
+            })
+            .collect()
+    }
+
+    // the squares of the heatmap of the position on display with their level and score, from the
+    // analysis in a replay and from the scores asked for the board otherwise
+    fn heatmap(&self) -> Vec<(Move, f32, f32)> {
+        if !self.options.show_heatmap || self.editor.is_some() {
+            return Vec::new();
+        }
+        let move_scores = match &self.replay {
+            Some(replay) => self
+                .current_analysis()
+                .and_then(|analysis| analysis.positions.get(replay.index))
+                .and_then(|position| position.as_ref())
+                .map(|position| &position.move_scores),
+            None => self
+                .position_scores
+                .as_ref()
+                .filter(|scores| scores.board.grid == self.board.grid)
+                .and_then(|scores| scores.move_scores.as_ref()),
+        };
+        let Some(move_scores) = move_scores else {
+            return Vec::new();
+        };
+        heat_levels(move_scores)
+            .into_iter()
+            .zip(move_scores.iter())
+            .map(|((square, level), &(_, score))| (square, level, score))
+            .collect()
+    }
+
+    fn update_replay_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(index) = self.replay.as_ref().map(|replay| replay.index) else {
+            if ui
+                .add_enabled(
+                    !self.history.plies.is_empty(),
+                    egui::Button::new(self.strings.tr("enter-replay")),
+                )
+                .on_hover_text(self.strings.tr("enter-replay-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-no-moves-to-replay"))
+                .clicked()
+            {
+                self.show_replay_position(self.history.plies.len());
+            }
+            return;
+        };
+
+        let last_index = self.history.plies.len();
+        let mut new_index = index;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    index > 0,
+                    egui::Button::new(self.strings.tr("replay-first")),
+                )
+                .on_hover_text(self.strings.tr("replay-first-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-replay-at-start"))
+                .clicked()
+            {
+                new_index = 0;
+            }
+            if ui
+                .add_enabled(
+                    index > 0,
+                    egui::Button::new(self.strings.tr("replay-previous")),
+                )
+                .on_hover_text(self.strings.tr("replay-previous-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-replay-at-start"))
+                .clicked()
+            {
+                new_index = index - 1;
+            }
+            if ui
+                .add_enabled(
+                    index < last_index,
+                    egui::Button::new(self.strings.tr("replay-next")),
+                )
+                .on_hover_text(self.strings.tr("replay-next-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-replay-at-end"))
+                .clicked()
+            {
+                new_index = index + 1;
+            }
+            if ui
+                .add_enabled(
+                    index < last_index,
+                    egui::Button::new(self.strings.tr("replay-last")),
+                )
+                .on_hover_text(self.strings.tr("replay-last-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-replay-at-end"))
+                .clicked()
+            {
+                new_index = last_index;
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut new_index, 0..=last_index).text(self.strings.tr("replay-ply")),
+        )
+        .on_hover_text(self.strings.tr("replay-ply-tooltip"));
+        if new_index != index {
+            self.show_replay_position(new_index);
+        }
+
+        if let Some(analysis) = self.current_analysis()
+            && let Some(Some(position)) = analysis.positions.get(index)
+        {
+            if let Some((best_move, best_score)) = position.best() {
+                ui.label(self.strings.tr_args(
+                    "replay-evaluation",
+                    &[
+                        ("player", &self.player_name(position.player)),
+                        ("score", &analysis::format_score(best_score)),
+                    ],
+                ));
+                ui.label(self.strings.tr_args(
+                    "replay-engine-prefers",
+                    &[("move", &notation::format_move(best_move))],
+                ));
+            }
+            ui.label(self.strings.tr_args(
+                "replay-played",
+                &[
+                    ("move", &notation::format_move(position.played_move)),
+                    ("swing", &analysis::format_score(-position.swing())),
+                ],
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(self.strings.tr("continue-from-here"))
+                .on_hover_text(self.strings.tr("continue-from-here-tooltip"))
+                .clicked()
+            {
+                self.continue_from_replay();
+            }
+            if ui
+                .button(self.strings.tr("exit-replay"))
+                .on_hover_text(self.strings.tr("exit-replay-tooltip"))
+                .clicked()
+            {
+                self.replay = None;
+            }
+        });
+    }
+
+    fn load_wthor_game(&mut self, game: usize) {
+        let Some(database) = &self.wthor_database else {
+            return;
+        };
+
+        match self.referee.replay(
+            &Board::default(),
+            Player::Black,
+            &database.games[game].moves,
+        ) {
+            Ok(replay) => {
+                self.load_replay(replay);
+                self.show_replay_position(0);
+                self.wthor_error = None;
+            }
+            Err(e) => {
+                self.wthor_error = Some(
+                    self.strings
+                        .tr_args("wthor-game-error", &[("game", &(game + 1)), ("error", &e)]),
+                );
+            }
+        }
+    }
+
+    // the move numbers of the board on display, None in the editor or when the history does not
+    // start from the standard position
+    fn move_numbers(&self) -> Option<MoveNumbers> {
+        match (&self.editor, &self.replay) {
+            (Some(_), _) => None,
+            (None, Some(replay)) => self.history.move_numbers(replay.index),
+            (None, None) => self.history.move_numbers(self.history.plies.len()),
+        }
+    }
+
+    // the board on display, with the markers asked for, which only the live game has
+    fn display_options(&self) -> (Board, DisplayOptions) {
+        let board = match (&self.editor, &self.replay) {
+            (Some(editor), _) => return (editor.board.clone(), self.static_display_options()),
+            (None, Some(replay)) => return (replay.board.clone(), self.static_display_options()),
+            (None, None) => self.board.clone(),
+        };
+        let options = DisplayOptions {
+            last_move: self
+                .last_move
+                .as_ref()
+                .filter(|_| self.image_export.last_move)
+                .map(|last_move| last_move.next_move),
+            valid_moves: match self.current_phase {
+                Phase::Turn(_) if self.image_export.valid_moves => {
+                    self.valid_moves.iter().collect()
+                }
+                _ => Vec::new(),
+            },
+            result: self
+                .shows_result_overlay()
+                .then(|| self.result_overlay())
+                .flatten(),
+            ..self.static_display_options()
+        };
+        (board, options)
+    }
+
+    fn static_display_options(&self) -> DisplayOptions {
+        DisplayOptions {
+            coordinates: self.image_export.coordinates,
+            move_numbers: self
+                .move_numbers()
+                .filter(|_| self.image_export.move_numbers),
+            heatmap: if self.image_export.heatmap {
+                self.heatmap()
+            } else {
+                Vec::new()
+            },
+            arrows: if self.image_export.arrows {
+                self.arrows()
+                    .into_iter()
+                    .map(|(from, to, _)| (from, to))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            annotations: if self.image_export.annotations {
+                self.annotations()
+            } else {
+                Vec::new()
+            },
+            marks: if self.image_export.marks {
+                self.square_marks.iter().collect()
+            } else {
+                Vec::new()
+            },
+            orientation: self.orientation(),
+            ..Default::default()
+        }
+    }
+
+    // renders on the UI thread, whatever the AI is doing
+    // the extension of the file name is replaced with the format's
+    fn export_image(&mut self, extension: &str) {
+        let path = std::path::Path::new(self.image_export.path.trim()).with_extension(extension);
+        let (board, options) = self.display_options();
+        let contents = match extension {
+            "svg" => Ok(board_image::svg(&board, &options).into_bytes()),
+            _ => board_image::encode_png(&board, &options),
+        };
+        let result = if path.file_stem().is_none() {
+            Err(self.strings.tr("file-name-missing").to_string())
+        } else {
+            contents.and_then(|contents| self.write_file(&path, contents))
+        };
+        match &result {
+            Ok(message) | Err(message) => self.log(message.clone()),
+        }
+        self.image_export.result = Some(result);
+    }
+
+    // what writing the file did, in the language of the interface
+    fn write_file(
+        &self,
+        path: &std::path::Path,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<String, String> {
+        let path_text = path.display();
+        std::fs::write(path, contents)
+            .map(|_| {
+                self.strings
+                    .tr_args("file-written", &[("path", &path_text)])
+            })
+            .map_err(|e| {
+                self.strings
+                    .tr_args("file-write-failed", &[("path", &path_text), ("error", &e)])
+            })
+    }
+
+    fn update_image_export_controls(&mut self, ui: &mut egui::Ui) {
+        if platform::HAS_FILE_SYSTEM {
+            ui.text_edit_singleline(&mut self.image_export.path)
+                .on_hover_text(self.strings.tr("export-path-tooltip"));
+        }
+        ui.horizontal(|ui| {
+            if platform::HAS_FILE_SYSTEM {
+                if ui
+                    .button(self.strings.tr("export-save-as"))
+                    .on_hover_text(self.strings.tr("export-save-as-tooltip"))
+                    .clicked()
+                    && let Some(path) = platform::pick_file_to_save(
+                        self.strings.tr("export-file-filter"),
+                        &["png", "svg"],
+                        self.image_export.path.trim(),
+                    )
+                {
+                    let extension = match path.extension().and_then(|e| e.to_str()) {
+                        Some("svg") => "svg",
+                        _ => "png",
+                    };
+                    self.image_export.path = path.display().to_string();
+                    self.export_image(extension);
+                }
+                if ui
+                    .button(self.strings.tr("export-png"))
+                    .on_hover_text(self.strings.tr("export-png-tooltip"))
+                    .clicked()
+                {
+                    self.export_image("png");
+                }
+                if ui
+                    .button(self.strings.tr("export-svg"))
+                    .on_hover_text(self.strings.tr("export-svg-tooltip"))
+                    .clicked()
+                {
+                    self.export_image("svg");
+                }
+            }
+            if ui
+                .button(self.strings.tr("copy-svg"))
+                .on_hover_text(self.strings.tr("copy-svg-tooltip"))
+                .clicked()
+            {
+                let (board, options) = self.display_options();
+                ui.ctx().copy_text(board_image::svg(&board, &options));
+            }
+        });
+        ui.horizontal(|ui| {
+            let strings = &self.strings;
+            let export = &mut self.image_export;
+            ui.checkbox(&mut export.coordinates, strings.tr("export-coordinates"))
+                .on_hover_text(strings.tr("export-coordinates-tooltip"));
+            ui.checkbox(&mut export.last_move, strings.tr("export-last-move"))
+                .on_hover_text(strings.tr("export-last-move-tooltip"));
+            ui.checkbox(&mut export.valid_moves, strings.tr("export-valid-moves"))
+                .on_hover_text(strings.tr("export-valid-moves-tooltip"));
+            ui.checkbox(&mut export.move_numbers, strings.tr("export-move-numbers"))
+                .on_hover_text(strings.tr("export-move-numbers-tooltip"));
+            ui.checkbox(&mut export.heatmap, strings.tr("export-heatmap"))
+                .on_hover_text(strings.tr("export-heatmap-tooltip"));
+            ui.checkbox(&mut export.arrows, strings.tr("export-arrows"))
+                .on_hover_text(strings.tr("export-arrows-tooltip"));
+            ui.checkbox(&mut export.annotations, strings.tr("export-annotations"))
+                .on_hover_text(strings.tr("export-annotations-tooltip"));
+            ui.checkbox(&mut export.marks, strings.tr("export-marks"))
+                .on_hover_text(strings.tr("export-marks-tooltip"));
+        });
+        match &self.image_export.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
+        }
+    }
+
+    fn import_wthor(&mut self) {
+        match wthor::read(std::path::Path::new(self.wthor_path.trim())) {
+            Ok(database) => {
+                self.wthor_database = Some(database);
+                self.wthor_error = None;
+            }
+            Err(e) => {
+                self.wthor_database = None;
+                self.wthor_error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn update_wthor_controls(&mut self, ui: &mut egui::Ui) {
+        ui.label(self.strings.tr("wthor-database"));
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.wthor_path)
+                    .hint_text(self.strings.tr("wthor-path-hint")),
+            )
+            .on_hover_text(self.strings.tr("wthor-path-tooltip"));
+            if ui
+                .button(self.strings.tr("wthor-browse"))
+                .on_hover_text(self.strings.tr("wthor-browse-tooltip"))
+                .clicked()
+                && let Some(path) =
+                    platform::pick_file_to_open(self.strings.tr("wthor-file-filter"), &["wtb"])
+            {
+                self.wthor_path = path.display().to_string();
+                self.import_wthor();
+            }
+            if ui
+                .button(self.strings.tr("wthor-import"))
+                .on_hover_text(self.strings.tr("wthor-import-tooltip"))
+                .clicked()
+            {
+                self.import_wthor();
+            }
+        });
+
+        if let Some(error) = &self.wthor_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        let mut selected_game = None;
+        if let Some(database) = &self.wthor_database {
+            ui.label(self.strings.tr_args(
+                "wthor-summary",
+                &[
+                    ("count", &database.games.len()),
+                    ("year", &database.year),
+                    ("depth", &database.depth),
+                ],
+            ));
+            egui::ScrollArea::vertical()
+                .id_salt("wthor_games")
+                .max_height(150.0)
+                .show_rows(ui, 18.0, database.games.len(), |ui, rows| {
+                    for i in rows {
+                        let game = &database.games[i];
+                        let label = self.strings.tr_args(
+                            "wthor-game",
+                            &[
+                                ("index", &(i + 1)),
+                                ("tournament", &game.tournament),
+                                ("black", &game.black_player),
+                                ("white", &game.white_player),
+                                ("black_score", &game.black_score),
+                                (
+                                    "white_score",
+                                    &(Board::SIZE * Board::SIZE - game.black_score as usize),
+                                ),
+                                ("theory", &game.theoretical_score),
+                            ],
+                        );
+                        if ui
+                            .selectable_label(false, label)
+                            .on_hover_text(self.strings.tr("wthor-game-tooltip"))
+                            .clicked()
+                        {
+                            selected_game = Some(i);
+                        }
+                    }
+                });
+        }
+
+        if let Some(game) = selected_game {
+            self.load_wthor_game(game);
+        }
+    }
+
+    fn import_transcript(&mut self) {
+        let result = notation::parse_transcript(&self.transcript_input)
+            .map_err(|e| e.to_string())
+            .and_then(|moves| {
+                self.referee
+                    .replay(&Board::default(), Player::Black, &moves)
+                    .map_err(|e| e.to_string())
+            });
+
+        match result {
+            Ok(replay) => {
+                self.load_replay(replay);
+                self.autosave();
+                self.transcript_error = None;
+            }
+            Err(e) => self.transcript_error = Some(e),
+        }
+    }
+
+    fn autosave(&self) {
+        // puzzles are not games worth resuming
+        if self.puzzle.is_some() {
+            return;
+        }
+        // a resumed game is played by whoever played it before auto-finishing
+        let player_options = self.auto_finish.unwrap_or(self.player_options);
+        save::write_autosave(&SavedGame::new(&self.history, player_options, self.seed));
+    }
+
+    fn is_game_in_progress(&self) -> bool {
+        matches!(self.current_phase, Phase::Turn(_)) && !self.history.plies.is_empty()
+    }
+
+    fn apply_seat_change(&mut self, seat_change: SeatChange) {
+        match seat_change {
+            SeatChange::PlayAs(play_as) => {
+                for player in [Player::Black, Player::White] {
+                    self.player_options[player as usize].ai_enabled = !play_as.is_human(player);
+                }
+                self.ai_setting_changed(SettingChange::Seats);
+            }
+            SeatChange::FirstPlayer(player) => self.options.first_player = player,
+        }
+    }
+
+    // call this from the UI thread
+    // a game in progress would not be the game it was set up as, so it is restarted after asking
+    fn request_seat_change(&mut self, seat_change: SeatChange) {
+        if self.is_game_in_progress() {
+            self.seat_change = Some(seat_change);
+            return;
+        }
+
+        self.apply_seat_change(seat_change);
+        if self.is_board_untouched {
+            // nothing was played yet, so the new first player can start right away
+            self.reset();
+        }
+    }
+
+    fn show_clear_statistics_prompt(&mut self, ctx: &egui::Context) {
+        if !self.clearing_statistics {
+            return;
+        }
+
+        let mut clear = false;
+        let mut cancel = false;
+        egui::Window::new(self.strings.tr("clear-statistics-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(self.strings.tr("clear-statistics-prompt"));
+                ui.horizontal(|ui| {
+                    clear = ui.button(self.strings.tr("clear")).clicked();
+                    cancel = ui.button(self.strings.tr("cancel")).clicked();
+                });
+            });
+
+        if clear {
+            self.clearing_statistics = false;
+            // a match or arena may have started since the button was clicked
+            if !self.is_match_running() && !self.is_arena_running() {
+                self.clear_statistics();
+            }
+        } else if cancel {
+            self.clearing_statistics = false;
+        }
+    }
+
+    fn show_seat_change_prompt(&mut self, ctx: &egui::Context) {
+        if self.seat_change.is_none() {
+            return;
+        }
+
+        let mut restart = false;
+        let mut keep_playing = false;
+        egui::Window::new(self.strings.tr("seat-change-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(self.strings.tr("seat-change-prompt"));
+                ui.horizontal(|ui| {
+                    restart = ui.button(self.strings.tr("restart")).clicked();
+                    keep_playing = ui.button(self.strings.tr("keep-playing")).clicked();
+                });
+            });
+
+        if restart {
+            if let Some(seat_change) = self.seat_change.take() {
+                self.apply_seat_change(seat_change);
+                self.reset();
+            }
+        } else if keep_playing {
+            self.seat_change = None;
+        }
+    }
+
+    fn update_seat_controls(&mut self, ui: &mut egui::Ui) {
+        let mut play_as = PlayAs::from_player_options(&self.player_options);
+        let mut first_player = self.options.first_player;
+
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("play-as"))
+                .on_hover_text(self.strings.tr("play-as-tooltip"));
+            for (option, text) in [
+                (PlayAs::Black, self.strings.tr("player-black")),
+                (PlayAs::White, self.strings.tr("player-white")),
+                (PlayAs::Both, self.strings.tr("play-as-both")),
+                (PlayAs::Neither, self.strings.tr("play-as-neither")),
+            ] {
+                ui.radio_value(&mut play_as, option, text);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("first-move"))
+                .on_hover_text(self.strings.tr("first-move-tooltip"));
+            ui.radio_value(
+                &mut first_player,
+                Player::Black,
+                self.strings.tr("player-black"),
+            );
+            ui.radio_value(
+                &mut first_player,
+                Player::White,
+                self.strings.tr("player-white"),
+            );
+        });
+
+        if play_as != PlayAs::from_player_options(&self.player_options) {
+            self.request_seat_change(SeatChange::PlayAs(play_as));
+        }
+        if first_player != self.options.first_player {
+            self.request_seat_change(SeatChange::FirstPlayer(first_player));
+        }
+    }
+
+    // the options as the user set them, not as a match or auto-finish changed them for a while
+    fn settings(&self) -> Settings {
+        let player_options = match (&self.match_run, self.auto_finish) {
+            (Some(match_run), _) if match_run.running => match_run.configurations,
+            (_, Some(player_options)) => player_options,
+            _ => self.player_options,
+        };
+        Settings {
+            options: self.options,
+            player_options,
+            presets: self.presets.clone(),
+            arena_contestants: self.arena_contestants.clone(),
+        }
+    }
+
+    // the same conditions as for changing the player options by hand
+    fn can_change_player_options(&self) -> bool {
+        !self.is_match_running()
+            && self.puzzle.is_none()
+            && self.auto_finish.is_none()
+            && self.network.is_none()
+    }
+
+    // saves the current options under the name, replacing a preset of the same name
+    fn save_preset(&mut self, preset: Preset) {
+        self.preset_error = None;
+        match self
+            .presets
+            .iter()
+            .position(|other| other.name == preset.name)
+        {
+            Some(index) => {
+                self.presets[index] = preset;
+                self.selected_preset = Some(index);
+            }
+            None => {
+                self.presets.push(preset);
+                self.selected_preset = Some(self.presets.len() - 1);
+            }
+        }
+    }
+
+    // call this from the UI thread
+    fn apply_preset(&mut self, index: usize) {
+        let Some(preset) = self.presets.get(index).cloned() else {
+            return;
+        };
+        if !self.can_change_player_options() {
+            return;
+        }
+
+        // as if each setting had been changed by hand
+        let use_clocks_changed = preset.options.use_clocks != self.options.use_clocks;
+        let player_options_changed = preset.player_options != self.player_options;
+        self.options = preset.options;
+        self.player_options = preset.player_options;
+        if !preset.arena_contestants.is_empty() && self.arena.is_none() {
+            self.arena_contestants = preset.arena_contestants;
+        }
+        if use_clocks_changed {
+            self.reset_clock();
+        }
+        if player_options_changed {
+            self.ai_setting_changed(SettingChange::Preset);
+        }
+        self.log(
+            self.strings
+                .tr_args("log-preset-applied", &[("name", &preset.name)]),
+        );
+    }
+
+    fn update_preset_controls(&mut self, ui: &mut egui::Ui) {
+        section_header(ui, self.strings.tr("presets"));
+        let selected_name = self
+            .selected_preset
+            .and_then(|index| self.presets.get(index))
+            .map_or(self.strings.tr("none-selected"), |preset| {
+                preset.name.as_str()
+            });
+        egui::ComboBox::from_id_salt("presets")
+            .selected_text(selected_name.to_string())
+            .show_ui(ui, |ui| {
+                for (index, preset) in self.presets.iter().enumerate() {
+                    ui.selectable_value(&mut self.selected_preset, Some(index), &preset.name);
+                }
+            });
+
+        let selected = self
+            .selected_preset
+            .filter(|&index| index < self.presets.len());
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    selected.is_some() && self.can_change_player_options(),
+                    egui::Button::new(self.strings.tr("apply-preset")),
+                )
+                .clicked()
+                && let Some(index) = selected
+            {
+                self.apply_preset(index);
+            }
+            if ui
+                .add_enabled(
+                    selected.is_some(),
+                    egui::Button::new(self.strings.tr("delete-preset")),
+                )
+                .clicked()
+                && let Some(index) = selected
+            {
+                self.presets.remove(index);
+                self.selected_preset = None;
+            }
+            if ui
+                .add_enabled(
+                    selected.is_some(),
+                    egui::Button::new(self.strings.tr("copy-preset")),
+                )
+                .clicked()
+                && let Some(index) = selected
+            {
+                ui.ctx().copy_text(self.presets[index].to_json());
+            }
+        });
+
+        let name = self.preset_name.trim().to_string();
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.preset_name);
+            if ui
+                .add_enabled(
+                    !name.is_empty(),
+                    egui::Button::new(self.strings.tr("save-preset")),
+                )
+                .clicked()
+            {
+                let preset = Preset {
+                    name: name.clone(),
+                    options: self.options,
+                    player_options: self.settings().player_options,
+                    arena_contestants: self.arena_contestants.clone(),
+                };
+                self.save_preset(preset);
+            }
+            if ui
+                .add_enabled(
+                    !name.is_empty() && selected.is_some(),
+                    egui::Button::new(self.strings.tr("rename-preset")),
+                )
+                .clicked()
+                && let Some(index) = selected
+            {
+                if self
+                    .presets
+                    .iter()
+                    .enumerate()
+                    .any(|(other, preset)| other != index && preset.name == name)
+                {
+                    self.preset_error = Some(
+                        self.strings
+                            .tr_args("preset-name-taken", &[("name", &name)]),
+                    );
+                } else {
+                    self.presets[index].name = name.clone();
+                    self.preset_error = None;
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.preset_json);
+            if ui.button(self.strings.tr("import-preset")).clicked() {
+                match Preset::from_json(&self.preset_json) {
+                    Ok(preset) => {
+                        self.save_preset(preset);
+                        self.preset_json.clear();
+                    }
+                    Err(e) => {
+                        self.preset_error =
+                            Some(self.strings.tr_args("preset-invalid", &[("error", &e)]));
+                    }
+                }
+            }
+        });
+        if let Some(error) = &self.preset_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn save_settings_if_changed(&mut self) {
+        let settings = self.settings();
+        if settings != self.saved_settings {
+            save::write_settings(&settings);
+            self.saved_settings = settings;
+        }
+    }
+
+    // call this from the UI thread
+    fn reset_settings(&mut self) {
+        if self.is_match_running() || self.auto_finish.is_some() {
+            return;
+        }
+
+        self.options = GameOptions::default();
+        self.player_options = [PlayerOptions::default(); 2];
+        self.ai_setting_changed(SettingChange::Reset);
+        // written even if nothing changed, to replace a file that could not be read
+        self.saved_settings = self.settings();
+        save::write_settings(&self.saved_settings);
+    }
+
+    fn show_resume_offer(&mut self, ctx: &egui::Context) {
+        let Some((saved_game, _)) = &self.resume_offer else {
+            return;
+        };
+
+        let mut resume = false;
+        let mut discard = false;
+        egui::Window::new(self.strings.tr("resume-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    self.strings
+                        .tr_args("resume-prompt", &[("count", &saved_game.moves.len())]),
+                );
+                ui.horizontal(|ui| {
+                    resume = ui.button(self.strings.tr("resume")).clicked();
+                    discard = ui.button(self.strings.tr("discard")).clicked();
+                });
+            });
+
+        if resume {
+            if let Some((saved_game, replay)) = self.resume_offer.take() {
+                self.player_options = saved_game.player_options;
+                self.load_replay(replay);
+            }
+        } else if discard {
+            self.resume_offer = None;
+            save::clear_autosave();
+        }
+    }
+
+    // the position on display and the side to move in it, None once the game is over; a player
+    // who has to pass is the side to move until the pass is done
+    fn shown_position_to_move(&self) -> Option<(&Board, Player)> {
+        if let Some(editor) = &self.editor {
+            return Some((&editor.board, editor.player));
+        }
+        let to_move = match &self.pass_notice {
+            Some(notice) => Some(notice.player),
+            None => match self.current_phase {
+                Phase::Turn(player) => Some(player),
+                Phase::Win(..) | Phase::Tie => None,
+            },
+        };
+        match &self.replay {
+            Some(replay) => match self.history.plies.get(replay.index) {
+                Some(Ply::Move { player, .. } | Ply::Pass { player }) => {
+                    Some((&replay.board, *player))
+                }
+                None => to_move.map(|player| (&replay.board, player)),
+            },
+            None => to_move.map(|player| (&self.board, player)),
+        }
+    }
+
+    // the discs a move on the hovered square would flip, None if it is no valid move
+    fn hover_flips(&mut self, player: Player, square: Move) -> Option<CellList> {
+        let is_cached = self.hover_flips.as_ref().is_some_and(|hover| {
+            hover.square == square && hover.player == player && hover.board.grid == self.board.grid
+        });
+        if !is_cached {
+            #[cfg(debug_assertions)]
+            {
+                self.hover_flip_computations += 1;
+            }
+            let mut flip_cells = CellList::default();
+            let is_valid_move =
+                self.referee
+                    .find_flip_cells_for_move(&self.board, player, square, &mut flip_cells);
+            self.hover_flips = Some(HoverFlips {
+                board: self.board.clone(),
+                player,
+                square,
+                flip_cells: is_valid_move.then(|| turned_discs(&self.board, player, &flip_cells)),
+            });
+        }
+        self.hover_flips.as_ref().and_then(|hover| hover.flip_cells)
+    }
+
+    // for the position on display, in the editor and in replays too
+    fn refresh_disc_marks(&mut self) {
+        let board = match (&self.editor, &self.replay) {
+            (Some(editor), _) => &editor.board,
+            (None, Some(replay)) => &replay.board,
+            (None, None) => &self.board,
+        };
+        let _computations = [
+            DiscMarks::refresh(
+                &mut self.stable_discs,
+                self.options.show_stable_discs,
+                board,
+                Agent::stable_disks,
+            ),
+            DiscMarks::refresh(
+                &mut self.frontier_discs,
+                self.options.show_frontier_discs,
+                board,
+                Agent::frontier_disks,
+            ),
+        ];
+        #[cfg(debug_assertions)]
+        {
+            self.disc_mark_computations += _computations.iter().filter(|&&done| done).count();
+        }
+    }
+
+    // e.g. "Frontier discs: Black 5, White 7"
+    fn frontier_count_text(&self) -> Option<String> {
+        let frontier = self.frontier_discs.as_ref()?;
+        Some(self.strings.tr_args(
+            "frontier-counts",
+            &[
+                ("black", &self.player_name(Player::Black)),
+                ("black_count", &frontier.count(Player::Black)),
+                ("white", &self.player_name(Player::White)),
+                ("white_count", &frontier.count(Player::White)),
+            ],
+        ))
+    }
+
+    fn update_move_counts(&mut self) {
+        let Some((board, player)) = self.shown_position_to_move() else {
+            self.move_counts = None;
+            return;
+        };
+        if self
+            .move_counts
+            .as_ref()
+            .is_some_and(|counts| counts.player == player && counts.board.grid == board.grid)
+        {
+            return;
+        }
+        let board = board.clone();
+        let mut valid_moves = CellList::default();
+        let mut counts = [0; 2];
+        for color in [Player::Black, Player::White] {
+            self.referee
+                .find_all_valid_moves(&board, color, &mut valid_moves);
+            counts[color as usize] = valid_moves.count;
+        }
+        self.move_counts = Some(MoveCounts {
+            board,
+            player,
+            counts,
+        });
+    }
+
+    // e.g. "Legal moves: 7", with the opponent's if asked for
+    fn move_count_text(&self) -> Option<String> {
+        let move_counts = self.move_counts.as_ref()?;
+        let player = move_counts.player;
+        let describe = |player: Player| match move_counts.counts[player as usize] {
+            0 => self
+                .strings
+                .tr_args("legal-moves-none", &[("player", &self.player_name(player))]),
+            count => count.to_string(),
+        };
+        Some(if self.options.show_opponent_move_count {
+            self.strings.tr_args(
+                "legal-moves-both",
+                &[
+                    ("count", &describe(player)),
+                    ("opponent", &self.player_name(player.opponent())),
+                    ("opponent_count", &describe(player.opponent())),
+                ],
+            )
+        } else {
+            self.strings
+                .tr_args("legal-moves", &[("count", &describe(player))])
+        })
+    }
+
+    fn show_pass_notice(&mut self, ctx: &egui::Context) {
+        let Some(notice) = &self.pass_notice else {
+            return;
+        };
+        if notice
+            .dismiss_at
+            .is_some_and(|dismiss_at| Instant::now() >= dismiss_at)
+        {
+            self.pass_notice = None;
+            return;
+        }
+
+        let player = notice.player;
+        let can_dismiss = notice.dismiss_at.is_none();
+        let mut dismiss = false;
+        egui::Window::new(self.strings.tr("pass-title"))
+            .id(egui::Id::new("pass_notice"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(self.strings.tr_args(
+                    "pass-notice",
+                    &[
+                        ("player", &self.player_name(player)),
+                        ("opponent", &self.player_name(player.opponent())),
+                    ],
+                ));
+                if can_dismiss {
+                    dismiss = ui.button(self.strings.tr("ok")).clicked();
+                }
+            });
+
+        if dismiss {
+            self.pass_notice = None;
+        }
+    }
+
+    fn ai_setting_changed(&mut self, change: SettingChange) {
+        self.log(self.strings.tr("log-ai-settings-changed").to_string());
+        // statistics are deemed invalid if the ai settings are changed after the game has started
+        if !self.is_board_untouched {
+            self.block_statistics(StatisticsBlock::SettingChanged(change));
+        }
+    }
+
+    fn can_take_statistics(&self) -> bool {
+        self.statistics_block.is_none()
+    }
+
+    // the first reason a game stops counting is the one kept
+    fn block_statistics(&mut self, block: StatisticsBlock) {
+        if self.statistics_block.is_none() {
+            self.statistics_block = Some(block);
+            let reason = self.statistics_block_reason(block);
+            self.log(
+                self.strings
+                    .tr_args("statistics-blocked", &[("reason", &reason)]),
+            );
+        }
+    }
+
+    fn statistics_block_reason(&self, block: StatisticsBlock) -> String {
+        match block {
+            StatisticsBlock::Counted => self.strings.tr("statistics-block-counted").to_string(),
+            StatisticsBlock::PositionSet => {
+                self.strings.tr("statistics-block-position").to_string()
+            }
+            StatisticsBlock::SwappedSides => {
+                self.strings.tr("statistics-block-swapped").to_string()
+            }
+            StatisticsBlock::AutoFinished => self
+                .strings
+                .tr("statistics-block-auto-finished")
+                .to_string(),
+            StatisticsBlock::SettingChanged(change) => match change {
+                SettingChange::AiEnabled(player) => self.strings.tr_args(
+                    "statistics-block-ai-enabled",
+                    &[("player", &self.player_name(player))],
+                ),
+                SettingChange::AiType(player) => self.strings.tr_args(
+                    "statistics-block-ai-type",
+                    &[("player", &self.player_name(player))],
+                ),
+                SettingChange::AiDepth(player) => self.strings.tr_args(
+                    "statistics-block-ai-depth",
+                    &[("player", &self.player_name(player))],
+                ),
+                SettingChange::AiFailed(player) => self.strings.tr_args(
+                    "statistics-block-ai-failed",
+                    &[("player", &self.player_name(player))],
+                ),
+                SettingChange::Seats => self.strings.tr("statistics-block-seats").to_string(),
+                SettingChange::Preset => self.strings.tr("statistics-block-preset").to_string(),
+                SettingChange::Reset => self.strings.tr("statistics-block-reset").to_string(),
+            },
+        }
+    }
+
+    // every seat starts the game with an RNG of its own derived from the game's seed
+    fn reseed_agents(&mut self) {
+        self.seed = self.pinned_seed.unwrap_or_else(rand::random);
+        for player in [Player::Black, Player::White] {
+            let seat_seed = self.seed.wrapping_mul(2).wrapping_add(player as u64);
+            self.agent_runners[player as usize].send(AgentRequest::Seed(seat_seed));
+        }
+        self.log(
+            self.strings
+                .tr_args("log-new-game", &[("seed", &self.seed)]),
+        );
+    }
+
+    fn log(&mut self, message: String) {
+        self.event_log.push(message);
+    }
+
+    // call this from the UI thread
+    fn tick_ai(&mut self, player: Player) {
+        // either poll for ai response, non-blocking
+        if let Some(mut request) = self.ai_requests[player as usize].take() {
+            if request.move_result.is_none() {
+                match self.recv_ai(player, request.id) {
+                    Ok(move_result) => {
+                        // the pacing that follows is not part of the thinking
+                        if move_result.is_some() {
+                            request.thinking_seconds = request.requested_at.elapsed().as_secs_f64();
+                        }
+                        request.move_result = move_result;
+                    }
+                    Err(e) => {
+                        self.turn_off_failed_ai(player, e);
+                        return;
+                    }
+                }
+            }
+            // the minimum time applies as it is set now, so a changed speed takes effect at once
+            let move_result = match request.move_result.take() {
+                Some(move_result)
+                    if Instant::now() >= request.requested_at + self.ai_move_time() =>
+                {
+                    move_result
+                }
+                move_result => {
+                    request.move_result = move_result;
+                    self.ai_requests[player as usize] = Some(request);
+                    return;
+                }
+            };
+            // an answer for another position is dropped, the next tick asks again
+            if move_result.board.grid == self.board.grid && move_result.player == player {
+                // timed before the move, which may end the game and take the statistics
+                let thinking_times = self.thinking_times;
+                self.thinking_times[player as usize].add(request.thinking_seconds);
+                match self.make_move(move_result.next_move, player) {
+                    Ok(()) => {
+                        self.last_search_score = move_result.score.map(|score| match player {
+                            Player::Black => score,
+                            Player::White => -score,
+                        });
+                    }
+                    Err(e) => {
+                        self.thinking_times = thinking_times;
+                        self.report_move_error(player, e);
+                    }
+                }
+            }
+        } else {
+            // or ask ai to start thinking about the next move
+
+            self.log(self.strings.tr_args(
+                "log-asking-ai",
+                &[
+                    ("player", &self.player_name(player)),
+                    ("ai", &self.player_options[player as usize].name()),
+                ],
+            ));
+            let id = self.agent_runners[player as usize].send(AgentRequest::Move(MoveRequest {
+                board: self.board.clone(),
+                player,
+                algorithm_choice: self.player_options[player as usize].ai_type,
+                recursion_depth: self.player_options[player as usize].ai_recursion_depth,
+                time_budget: self.ai_time_budget(player),
+            }));
+            self.ai_requests[player as usize] = Some(AiRequest {
+                id,
+                requested_at: Instant::now(),
+                thinking_seconds: 0.0,
+                move_result: None,
+            });
+        }
+    }
+
+    // the seat's answer to the request `id` if it is in, the answers to requests dropped before
+    // it are skipped and a restart of the agent thread is logged
+    fn recv_ai(&mut self, player: Player, id: RequestId) -> Result<Option<MoveResult>, String> {
+        let received = loop {
+            match self.agent_runners[player as usize].try_recv() {
+                Ok(Some((answered, _))) if answered != id => {}
+                received => {
+                    break received.map(|answer| answer.map(|(_, move_result)| move_result));
+                }
+            }
+        };
+        if self.agent_runners[player as usize].take_restart() {
+            self.log(
+                self.strings
+                    .tr_args("log-ai-restarted", &[("player", &self.player_name(player))]),
+            );
+        }
+        received
+    }
+
+    // whether the seat's agent was asked for a move that is not played yet
+    fn is_ai_thinking(&self, player: Player) -> bool {
+        self.ai_requests[player as usize].is_some()
+    }
+
+    // the agent thread died twice on the player's move, so the human takes over the seat
+    fn turn_off_failed_ai(&mut self, player: Player, error: String) {
+        let message = self.strings.tr_args(
+            "log-ai-turned-off",
+            &[("player", &self.player_name(player)), ("error", &error)],
+        );
+        self.log(message.clone());
+        self.stop_auto_finish();
+        self.player_options[player as usize].ai_enabled = false;
+        self.ai_setting_changed(SettingChange::AiFailed(player));
+        self.ai_requests[player as usize] = None;
+        self.ai_failure = Some(message);
+    }
+
+    // call this from the UI thread
+    fn make_move(&mut self, next_move: Move, player: Player) -> Result<(), MoveError> {
+        // e.g. the player already ran out of time
+        match self.current_phase {
+            Phase::Win(..) | Phase::Tie => return Err(MoveError::GameOver),
+            _ if self.is_puzzle_answered() => return Err(MoveError::GameOver),
+            Phase::Turn(current_player) if current_player != player => {
+                return Err(MoveError::WrongTurn(player));
+            }
+            Phase::Turn(_) => {}
+        }
+        let ply = self.history.plies.len();
+        let board_before = self.board.clone();
+        // Validate, apply and record the move, the same way every game loop does
+        let Some(advance) = self.referee.play_move(
+            &mut self.board,
+            &mut self.history,
+            player,
+            next_move,
+            &mut self.flip_cells,
+            &mut self.valid_moves,
+        ) else {
+            return Err(MoveError::IllegalMove(next_move));
+        };
+        // whatever was selected or hinted was for the position before
+        self.pending_move = None;
+        self.hint = None;
+        self.flip_cells = turned_discs(&board_before, player, &self.flip_cells);
+
+        if let Some(clock) = &mut self.clock {
+            clock.add_increment(player);
+        }
+        if let Some(network) = &self.network
+            && network.local_player == Some(player)
+        {
+            network.connection.send(Message::Move { ply, next_move });
+        }
+        self.last_search_score = None;
+        self.last_move = Some(LastMove {
+            next_move,
+            flipped: self.flip_cells.iter().collect(),
+        });
+        self.flip_animation = self
+            .flip_animation_time()
+            .map(|duration| FlipAnimation::new(next_move, self.flip_cells.iter(), duration));
+        self.animate(AnimationKey::PlacedDisc, PLACED_DISC_SCALE_IN);
+        self.animate(AnimationKey::ValidMoves, VALID_MOVES_FADE_IN);
+        self.play_sound(Sound::Place);
+        if self.puzzle.is_some() {
+            self.answer_puzzle(next_move);
+        }
+
+        let (black_count, white_count) = count_pieces(&self.board);
+        self.log(self.strings.tr_args(
+            "log-move",
+            &[
+                ("player", &self.player_name(player)),
+                ("move", &notation::format_move(next_move)),
+                ("black", &black_count),
+                ("white", &white_count),
+            ],
+        ));
+
+        match advance {
+            Advance::Turn(next_player) => self.current_phase = Phase::Turn(next_player),
+            Advance::Pass { passing_player } => {
+                // the opponent has to pass, the same player moves again
+                self.log(
+                    self.strings
+                        .tr_args("log-pass", &[("player", &self.player_name(passing_player))]),
+                );
+
+                let both_ai = self.player_options.iter().all(|options| options.ai_enabled);
+                let delay = self.ai_move_time();
+                self.pass_notice = Some(PassNotice {
+                    player: passing_player,
+                    dismiss_at: both_ai.then(|| Instant::now() + delay),
+                });
+            }
+            // no player has any valid moves, game ends
+            Advance::Over(outcome) => self.end_game(outcome),
+        }
+
+        if let Phase::Turn(_) = self.current_phase {
+            self.autosave();
+        }
+
+        if self.is_board_untouched {
+            // you can mess with the settings before the first move and still take statistics
+            self.statistics_block = None;
+            self.is_board_untouched = false;
+        }
+
+        Ok(())
+    }
+
+    fn report_move_error(&mut self, player: Player, error: MoveError) {
+        let message = self.strings.tr_args(
+            "log-move-rejected",
+            &[
+                ("player", &self.player_name(player)),
+                ("error", &error.text(&self.strings)),
+            ],
+        );
+        self.log(message.clone());
+        self.toast = Some((message, Instant::now() + TOAST_DURATION));
+    }
+
+    // from black's perspective, infinite once the game is decided
+    fn evaluation(&self) -> f32 {
+        if let Some(replay) = &self.replay {
+            return Agent::positional_balance(&replay.board);
+        }
+
+        match self.current_phase {
+            Phase::Win(Player::Black, _) => f32::INFINITY,
+            Phase::Win(Player::White, _) => f32::NEG_INFINITY,
+            Phase::Tie => 0.0,
+            Phase::Turn(_) => self
+                .last_search_score
+                .unwrap_or_else(|| Agent::positional_balance(&self.board)),
+        }
+    }
+
+    // the disc balance, black's share from the left, with the winner's share outlined once the game
+    // is over
+    fn draw_score_bar(&self, ui: &mut egui::Ui, black_count: usize, white_count: usize) {
+        let theme = self.options.board_theme.theme();
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), SCORE_BAR_HEIGHT),
+            egui::Sense::hover(),
+        );
+        let total = black_count + white_count;
+        let target_black_share = if total == 0 {
+            0.5
+        } else {
+            black_count as f32 / total as f32
+        };
+        let animation_seconds = if self.options.reduce_motion { 0.0 } else { 0.3 };
+        let black_share = ui.ctx().animate_value_with_time(
+            egui::Id::new("score_bar"),
+            target_black_share,
+            animation_seconds,
+        );
+
+        let split_x = rect.left() + rect.width() * black_share;
+        let black_rect = egui::Rect::from_min_max(rect.min, egui::pos2(split_x, rect.bottom()));
+        let white_rect = egui::Rect::from_min_max(egui::pos2(split_x, rect.top()), rect.max);
+        let painter = ui.painter();
+        painter.rect_filled(black_rect, 0.0, theme.disc(Player::Black));
+        painter.rect_filled(white_rect, 0.0, theme.disc(Player::White));
+        painter.rect_stroke(
+            rect,
+            0.0,
+            egui::Stroke::new(1.0, theme.grid),
+            egui::StrokeKind::Outside,
+        );
+        // a replay or the editor shows another position than the one the game ended in
+        if let Phase::Win(winner, _) = self.current_phase
+            && self.replay.is_none()
+            && self.editor.is_none()
+        {
+            let winner_rect = match winner {
+                Player::Black => black_rect,
+                Player::White => white_rect,
+            };
+            painter.rect_stroke(
+                winner_rect,
+                0.0,
+                egui::Stroke::new(3.0, theme.winner),
+                egui::StrokeKind::Inside,
+            );
+        }
+
+        let font = egui::FontId::proportional(SCORE_BAR_HEIGHT * 0.7);
+        let padding = egui::vec2(6.0, 0.0);
+        painter.text(
+            rect.left_center() + padding,
+            egui::Align2::LEFT_CENTER,
+            black_count.to_string(),
+            font.clone(),
+            theme.disc(Player::White),
+        );
+        painter.text(
+            rect.right_center() - padding,
+            egui::Align2::RIGHT_CENTER,
+            white_count.to_string(),
+            font,
+            theme.disc(Player::Black),
+        );
+        response.on_hover_text(self.strings.tr_args(
+            "evaluation-bar-counts",
+            &[("black", &black_count), ("white", &white_count)],
+        ));
+    }
+
+    fn draw_evaluation_bar(&self, ui: &egui::Ui, bar_rect: egui::Rect) {
+        let evaluation = self.evaluation();
+        let target_black_share = if evaluation.is_infinite() {
+            if evaluation > 0.0 { 1.0 } else { 0.0 }
+        } else {
+            0.5 + 0.5 * (evaluation / EVALUATION_BAR_SCALE).tanh()
+        };
+        let black_share = ui.ctx().animate_value_with_time(
+            egui::Id::new("evaluation_bar"),
+            target_black_share,
+            0.3,
+        );
+
+        // white fills from the top, black from the bottom
+        let split_y = bar_rect.bottom() - bar_rect.height() * black_share;
+        let theme = self.options.board_theme.theme();
+        let painter = ui.painter();
+        painter.rect_filled(
+            egui::Rect::from_min_max(bar_rect.min, egui::pos2(bar_rect.right(), split_y)),
+            0.0,
+            theme.disc(Player::White),
+        );
+        painter.rect_filled(
+            egui::Rect::from_min_max(egui::pos2(bar_rect.left(), split_y), bar_rect.max),
+            0.0,
+            theme.disc(Player::Black),
+        );
+        painter.rect_stroke(
+            bar_rect,
+            0.0,
+            egui::Stroke::new(1.0, egui::Color32::GRAY),
+            egui::StrokeKind::Outside,
+        );
+
+        let label = if evaluation.is_finite() {
+            format!("{evaluation:+.0}")
+        } else {
+            // a decided game shows the final disc difference
+            let (black_count, white_count) = count_pieces(&self.board);
+            format!("{:+}", black_count as i32 - white_count as i32)
+        };
+        painter.text(
+            bar_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            label,
+            egui::FontId::proportional(10.0),
+            egui::Color32::from_rgb(128, 128, 128),
+        );
+    }
+
+    fn reset_clock(&mut self) {
+        self.clock = self.options.use_clocks.then(|| {
+            GameClock::new(
+                Duration::from_secs(self.options.clock_minutes * 60),
+                Duration::from_secs(self.options.clock_increment_seconds),
+            )
+        });
+    }
+
+    // call this from the UI thread
+    // the clock stands still whenever the game waits for something other than a move
+    fn clock_running_for(&self) -> Option<Player> {
+        match self.current_phase {
+            Phase::Turn(player)
+                if self.clock.is_some()
+                    && self.replay.is_none()
+                    && self.pass_notice.is_none()
+                    && self.resume_offer.is_none()
+                    && self.editor.is_none()
+                    && !self.is_network_frozen() =>
+            {
+                Some(player)
+            }
+            _ => None,
+        }
+    }
+
+    fn update_clock(&mut self, now: Instant) {
+        let running = self.clock_running_for();
+        let Some(clock) = &mut self.clock else {
+            return;
+        };
+        clock.update(running, now);
+
+        let Some(player) = running else {
+            return;
+        };
+        if clock.take_low_time_warning(player) && self.options.beep_on_low_time {
+            self.sounds
+                .play(Sound::LowTime, self.options.sound_volume, now);
+        }
+
+        // this runs before any move is handled in the frame, so running out of time always wins
+        // over an AI result or a click arriving in the same frame
+        if clock.has_flagged(player) {
+            self.end_game(Outcome::Won(player.opponent(), WinReason::Time));
+        }
+    }
+
+    // a share of the remaining time, so that the AI does not run out before the game ends
+    fn ai_time_budget(&self, player: Player) -> Option<Duration> {
+        let clock = self.clock.as_ref().filter(|_| self.options.ai_uses_clock)?;
+
+        let (black_count, white_count) = count_pieces(&self.board);
+        let empties = Board::SIZE * Board::SIZE - black_count - white_count;
+        let moves_left = empties.div_ceil(2) as u32 + 1;
+
+        // the minimum time per move passes while the AI thinks, so it takes nothing from the search
+        Some(clock.remaining(player) / moves_left)
+    }
+
+    // at startup and whenever the option or the system preference changes
+    fn apply_appearance(&mut self, ctx: &egui::Context) {
+        if self.applied_appearance != Some(self.options.appearance) {
+            ctx.set_theme(self.options.appearance.preference());
+            self.applied_appearance = Some(self.options.appearance);
+        }
+        let dark_mode = ctx.style().visuals.dark_mode;
+        if let Some(was_dark_mode) = self.dark_mode
+            && was_dark_mode != dark_mode
+        {
+            self.options.board_theme = self
+                .options
+                .board_theme
+                .follow_visuals(was_dark_mode, dark_mode);
+        }
+        self.dark_mode = Some(dark_mode);
+
+        // zooming with the keyboard moves the option along
+        let zoom_factor = ctx.zoom_factor();
+        if self
+            .applied_ui_scale
+            .is_some_and(|applied| applied != zoom_factor)
+        {
+            self.options.ui_scale =
+                zoom_factor.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+        }
+        // the slider would jump under the pointer if the panel were rescaled while dragging it
+        if self.options.ui_scale != zoom_factor && ctx.dragged_id().is_none() {
+            // in effect from the next frame on
+            ctx.set_zoom_factor(self.options.ui_scale);
+            self.applied_ui_scale = Some(self.options.ui_scale);
+        } else {
+            self.applied_ui_scale = Some(zoom_factor);
+        }
+    }
+
+    // a language picked through the settings, a preset or a reset replaces a loaded file
+    fn apply_language(&mut self) {
+        if self.strings.language() != self.options.language {
+            self.strings = Strings::new(self.options.language);
+        }
+    }
+
+    fn load_translation(&mut self) {
+        let path = self.translation_path.trim().to_string();
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| self.strings.load(path.clone(), &text));
+        let result = match result {
+            Ok(count) => Ok(self
+                .strings
+                .tr_args("translation-loaded", &[("count", &count), ("path", &path)])),
+            Err(error) => Err(self
+                .strings
+                .tr_args("translation-failed", &[("path", &path), ("error", &error)])),
+        };
+        match &result {
+            Ok(message) | Err(message) => self.log(message.clone()),
+        }
+        self.translation_result = Some(result);
+    }
+
+    fn player_name(&self, player: Player) -> &str {
+        self.strings.player_name(player)
+    }
+
+    fn update_clock_controls(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .checkbox(&mut self.options.use_clocks, self.strings.tr("use-clocks"))
+            .on_hover_text(self.strings.tr("use-clocks-tooltip"))
+            .changed()
+        {
+            self.reset_clock();
+        }
+        ui.add(
+            egui::Slider::new(&mut self.options.clock_minutes, 1..=60)
+                .text(self.strings.tr("clock-minutes")),
+        )
+        .on_hover_text(self.strings.tr("clock-minutes-tooltip"));
+        ui.checkbox(
+            &mut self.options.ai_uses_clock,
+            self.strings.tr("ai-manages-its-time"),
+        )
+        .on_hover_text(self.strings.tr("ai-manages-its-time-tooltip"));
+        ui.checkbox(
+            &mut self.options.beep_on_low_time,
+            self.strings.tr("warn-on-low-time"),
+        )
+        .on_hover_text(self.strings.tr("warn-on-low-time-tooltip"));
+    }
+
+    // the one color played at this screen, None when both or neither are
+    fn own_color(&self) -> Option<Player> {
+        if let Some(remote) = self.remote_player() {
+            return Some(remote.opponent());
+        }
+        match PlayAs::from_player_options(&self.player_options) {
+            PlayAs::Black => Some(Player::Black),
+            PlayAs::White => Some(Player::White),
+            PlayAs::Both | PlayAs::Neither => None,
+        }
+    }
+
+    // the board is shown from white's side while the human plays white alone, if asked to
+    fn orientation(&self) -> Orientation {
+        if self.options.auto_orient && self.own_color() == Some(Player::White) {
+            self.options.orientation.half_turned()
+        } else {
+            self.options.orientation
+        }
+    }
+
+    // the seat played by the other instance of a network game
+    fn remote_player(&self) -> Option<Player> {
+        self.network
+            .as_ref()
+            .and_then(|network| network.local_player)
+            .map(|player| player.opponent())
+    }
+
+    // before connecting and after disconnecting, no one may move
+    fn is_network_frozen(&self) -> bool {
+        self.network
+            .as_ref()
+            .is_some_and(|network| network.local_player.is_none() || network.error.is_some())
+    }
+
+    fn host_network_game(&mut self) {
+        match network::host(&format!("0.0.0.0:{}", self.network_port)) {
+            Ok(connection) => self.start_network_game(connection),
+            Err(e) => {
+                self.network = None;
+                self.log(self.strings.tr_args(
+                    "log-host-failed",
+                    &[("port", &self.network_port), ("error", &e)],
+                ));
+            }
+        }
+    }
+
+    fn start_network_game(&mut self, connection: Connection) {
+        self.network = Some(NetworkGame {
+            connection,
+            local_player: None,
+            error: None,
+        });
+    }
+
+    // freezes the game, it can only be left from here on
+    fn network_failed(&mut self, error: String) {
+        self.log(
+            self.strings
+                .tr_args("log-network-failed", &[("error", &error)]),
+        );
+        if let Some(network) = &mut self.network
+            && network.error.is_none()
+        {
+            network.error = Some(error);
+        }
+    }
+
+    // call this from the UI thread
+    fn poll_network(&mut self) {
+        while let Some(event) = self
+            .network
+            .as_ref()
+            .and_then(|network| network.connection.poll())
+        {
+            if self
+                .network
+                .as_ref()
+                .is_some_and(|network| network.error.is_some())
+            {
+                // nothing received after a failure can be trusted
+                break;
+            }
+
+            match event {
+                NetworkEvent::Connected { local_player } => {
+                    if let Some(network) = &mut self.network {
+                        network.local_player = Some(local_player);
+                    }
+                    self.player_options[local_player.opponent() as usize].ai_enabled = false;
+                    self.log(self.strings.tr_args(
+                        "log-connected",
+                        &[("player", &self.player_name(local_player))],
+                    ));
+                    self.reset();
+                }
+                NetworkEvent::Received(Message::Move { ply, next_move }) => {
+                    let expected_ply = self.history.plies.len();
+                    let accepted = match self.remote_player() {
+                        Some(player) if ply == expected_ply => self.make_move(next_move, player),
+                        _ => Err(MoveError::IllegalMove(next_move)),
+                    };
+                    if let Err(e) = accepted {
+                        self.network_failed(self.strings.tr_args(
+                            "network-invalid-move",
+                            &[("ply", &(ply + 1)), ("error", &e.text(&self.strings))],
+                        ));
+                    }
+                }
+                NetworkEvent::Received(Message::Resign) => {
+                    if let (Phase::Turn(_), Some(player)) =
+                        (self.current_phase, self.remote_player())
+                    {
+                        self.end_game(Outcome::Won(player.opponent(), WinReason::Resignation));
+                    }
+                }
+                NetworkEvent::Received(Message::Restart) => self.reset(),
+                // answered by the connection itself
+                NetworkEvent::Received(Message::Ping) => {}
+                NetworkEvent::Received(Message::Hello { .. }) => {
+                    self.network_failed(self.strings.tr("network-second-greeting").to_string());
+                }
+                NetworkEvent::Disconnected(error) => self.network_failed(error),
+            }
+        }
+    }
+
+    fn update_network_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(network) = &self.network else {
+            ui.add_enabled_ui(!self.is_match_running(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.network_port)
+                            .prefix(format!("{} ", self.strings.tr("network-port"))),
+                    )
+                    .on_hover_text(self.strings.tr("network-port-tooltip"))
+                    .on_disabled_hover_text(self.strings.tr("disabled-match"));
+                    if ui
+                        .button(self.strings.tr("network-host"))
+                        .on_hover_text(self.strings.tr("network-host-tooltip"))
+                        .on_disabled_hover_text(self.strings.tr("disabled-match"))
+                        .clicked()
+                    {
+                        self.host_network_game();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.network_address)
+                        .on_hover_text(self.strings.tr("network-address-tooltip"))
+                        .on_disabled_hover_text(self.strings.tr("disabled-match"));
+                    if ui
+                        .button(self.strings.tr("network-join"))
+                        .on_hover_text(self.strings.tr("network-join-tooltip"))
+                        .on_disabled_hover_text(self.strings.tr("disabled-match"))
+                        .clicked()
+                    {
+                        self.start_network_game(network::join(self.network_address.trim()));
+                    }
+                });
+            });
+            return;
+        };
+
+        let mut resign = false;
+        match (&network.error, network.local_player) {
+            (Some(error), _) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    self.strings
+                        .tr_args("network-disconnected", &[("error", error)]),
+                );
+            }
+            (None, Some(local_player)) => {
+                ui.label(self.strings.tr_args(
+                    "network-connected",
+                    &[("player", &self.player_name(local_player))],
+                ));
+                resign = ui
+                    .add_enabled(
+                        matches!(self.current_phase, Phase::Turn(_)),
+                        egui::Button::new(self.strings.tr("resign")),
+                    )
+                    .on_hover_text(self.strings.tr("resign-tooltip"))
+                    .on_disabled_hover_text(self.strings.tr("disabled-game-over"))
+                    .clicked();
+            }
+            (None, None) => {
+                ui.label(match network.connection.local_address {
+                    Some(address) => self
+                        .strings
+                        .tr_args("network-waiting", &[("port", &address.port())]),
+                    None => self.strings.tr("network-connecting").to_string(),
+                });
+            }
+        }
+
+        if resign && let Some(player) = self.remote_player() {
+            network.connection.send(Message::Resign);
+            self.end_game(Outcome::Won(player, WinReason::Resignation));
+        }
+        if ui
+            .button(self.strings.tr("network-leave"))
+            .on_hover_text(self.strings.tr("network-leave-tooltip"))
+            .clicked()
+        {
+            self.network = None;
+        }
+    }
+
+    // how long the seat's AI has been thinking on the move it was asked for, held at its answer
+    // while the minimum move time runs out
+    fn ai_thinking_time(&self, player: Player) -> Option<f64> {
+        let request = self.ai_requests[player as usize].as_ref()?;
+        if !matches!(self.current_phase, Phase::Turn(to_move) if to_move == player)
+            || !self.player_options[player as usize].ai_enabled
+        {
+            return None;
+        }
+        Some(if request.move_result.is_some() {
+            request.thinking_seconds
+        } else {
+            request.requested_at.elapsed().as_secs_f64()
+        })
+    }
+
+    // a spinner that keeps turning with the time so far, so that a long search does not look
+    // like a hang
+    fn show_thinking_indicator(&self, ui: &mut egui::Ui, player: Player, text: &str) {
+        if let Some(seconds) = self.ai_thinking_time(player) {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.label(self.strings.tr_args(
+                    "thinking-time",
+                    &[("text", &text), ("seconds", &format!("{seconds:.1}"))],
+                ));
+            });
+        }
+    }
+
+    // the edited or the replayed position, or else the game's
+    fn shown_board(&self) -> &Board {
+        match (&self.editor, &self.replay) {
+            (Some(editor), _) => &editor.board,
+            (None, Some(replay)) => &replay.board,
+            (None, None) => &self.board,
+        }
+    }
+
+    // e.g. "Your turn (Black)" or "White won on time"
+    fn status_message(&self) -> String {
+        status_line(&self.strings, self.phase_message(), self.game_progress())
+    }
+
+    // none while editing or viewing a replay, which say where they are themselves
+    fn game_progress(&self) -> Option<GameProgress> {
+        if self.editor.is_some() || self.replay.is_some() {
+            return None;
+        }
+        let moves = self.history.moves().len();
+        Some(match self.current_phase {
+            Phase::Turn(_) => {
+                let (black_count, white_count) = count_pieces(&self.board);
+                GameProgress::Turn {
+                    move_number: self
+                        .history
+                        .starts_from_standard_position()
+                        .then_some(moves + 1),
+                    empties: Board::SIZE * Board::SIZE - black_count - white_count,
+                }
+            }
+            Phase::Win(..) | Phase::Tie => GameProgress::Over { moves },
+        })
+    }
+
+    fn phase_message(&self) -> String {
+        match self.current_phase {
+            _ if self.editor.is_some() => self.strings.tr("status-editing").to_string(),
+            _ if self.replay.is_some() => self.strings.tr_args(
+                "status-viewing",
+                &[
+                    (
+                        "index",
+                        &self
+                            .replay
+                            .as_ref()
+                            .map_or(0, |replay| self.history.moves_until(replay.index).len()),
+                    ),
+                    ("count", &self.history.moves().len()),
+                ],
+            ),
+            Phase::Turn(player) => {
+                let ai_enabled = self.player_options[player as usize].ai_enabled;
+                let name = self.player_name(player);
+                if self.is_ai_thinking(player) && ai_enabled {
+                    self.strings
+                        .tr_args("status-thinking", &[("player", &name)])
+                } else if self.remote_player() == Some(player) {
+                    self.strings
+                        .tr_args("status-waiting-remote", &[("player", &name)])
+                } else if self.is_stepping() && ai_enabled {
+                    self.strings
+                        .tr_args("status-next-move", &[("player", &name)])
+                } else if self.player_options.iter().any(|options| options.ai_enabled)
+                    && !ai_enabled
+                {
+                    self.strings
+                        .tr_args("status-your-turn", &[("player", &name)])
+                } else {
+                    self.strings.tr_args("status-turn", &[("player", &name)])
+                }
+            }
+            Phase::Win(player, reason) => {
+                let name = self.player_name(player);
+                match reason {
+                    WinReason::Disks => self.strings.tr_args("status-won", &[("player", &name)]),
+                    WinReason::Time => self
+                        .strings
+                        .tr_args("status-won-on-time", &[("player", &name)]),
+                    WinReason::Resignation => self
+                        .strings
+                        .tr_args("status-won-by-resignation", &[("player", &name)]),
+                }
+            }
+            Phase::Tie => self.strings.tr("status-tie").to_string(),
+        }
+    }
+
+    // the status message with the thinking indicator, the latest error and a failed AI; in compact
+    // mode the indicator stands in for the player sections too
+    fn show_status(&mut self, ui: &mut egui::Ui, compact: bool) {
+        let message = self.status_message();
+        let fade_in = match self.current_phase {
+            Phase::Win(..) | Phase::Tie => self
+                .animations
+                .progress(AnimationKey::GameOver, Instant::now()),
+            Phase::Turn(_) => 1.0,
+        };
+        // with two AIs each thinks in its own section, unless the sections are hidden
+        let thinking_player = match self.current_phase {
+            Phase::Turn(player)
+                if self.editor.is_none()
+                    && self.replay.is_none()
+                    && (compact
+                        || !self.player_options.iter().all(|options| options.ai_enabled)) =>
+            {
+                self.ai_thinking_time(player).map(|_| player)
+            }
+            _ => None,
+        };
+        if let Some(player) = thinking_player {
+            self.show_thinking_indicator(ui, player, &message);
+        } else {
+            ui.label(
+                egui::RichText::new(message)
+                    .color(ui.visuals().text_color().gamma_multiply(fade_in)),
+            );
+        }
+        if let Some((toast, until)) = &self.toast {
+            if Instant::now() < *until {
+                ui.colored_label(ui.visuals().error_fg_color, toast);
+            } else {
+                self.toast = None;
+            }
+        }
+        if let Some(ai_failure) = &self.ai_failure {
+            ui.colored_label(ui.visuals().error_fg_color, ai_failure);
+        }
+    }
+
+    // the widths the side panel can have in a window of this width
+    fn side_panel_widths(screen_width: f32) -> std::ops::RangeInclusive<f32> {
+        let (min, max) = SIDE_PANEL_WIDTH_RANGE.into_inner();
+        min..=max.min(screen_width * SIDE_PANEL_MAX_SHARE).max(min)
+    }
+
+    // at the width kept with the settings, which a drag on its edge changes once it is over; the
+    // board is not hovered or clicked during the drag, as egui hovers nothing else then
+    fn show_side_panel(&mut self, ctx: &egui::Context) {
+        let dragging = ctx.dragged_id().is_some();
+        if !dragging && let Some(width) = self.dragged_side_panel_width.take() {
+            self.options.side_panel_width = width;
+        }
+        let widths = Self::side_panel_widths(ctx.screen_rect().width());
+        let width = self
+            .options
+            .side_panel_width
+            .clamp(*widths.start(), *widths.end());
+        let mut panel = egui::SidePanel::right("right_panel")
+            .resizable(true)
+            .default_width(width)
+            .width_range(widths);
+        // the width egui remembers gives way to the setting, unless it is being dragged
+        if !dragging {
+            panel = panel.exact_width(width);
+        }
+        let shown_width = panel
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(self.strings.tr("compact-mode"))
+                        .on_hover_text(self.strings.tr("compact-mode-tooltip"))
+                        .clicked()
+                    {
+                        self.perform(Action::ToggleCompactMode);
+                    }
+                });
+                ui.separator();
+
+                self.show_status(ui, false);
+                self.update_restart_controls(ui);
+
+                let (black_score, white_score) = count_pieces(self.shown_board());
+                self.draw_score_bar(ui, black_score, white_score);
+                self.update_move_counts();
+                if let Some(text) = self.move_count_text() {
+                    ui.label(text);
+                }
+                self.refresh_disc_marks();
+                if let Some(text) = self.frontier_count_text() {
+                    ui.label(text);
+                }
+                self.update_move_input_controls(ui);
+
+                ui.separator();
+
+                // everything else in sections of their own, scrolling when they do not fit
+                egui::ScrollArea::vertical()
+                    .id_salt("side_panel")
+                    .show(ui, |ui| {
+                        for section in PanelSection::ALL {
+                            self.show_panel_section(ui, section);
+                        }
+                    });
+            })
+            .response
+            .rect
+            .width();
+        if dragging
+            && (self.dragged_side_panel_width.is_some() || (shown_width - width).abs() >= 1.0)
+        {
+            self.dragged_side_panel_width = Some(shown_width.round());
+        }
+    }
+
+    // the board alone, with the status in a corner over it, for streaming and small screens
+    fn show_compact_status(&mut self, ctx: &egui::Context) {
+        egui::Area::new(egui::Id::new("compact_status"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(220.0);
+                    self.show_status(ui, true);
+                    let (black_score, white_score) = count_pieces(self.shown_board());
+                    ui.label(self.strings.tr_args(
+                        "compact-score",
+                        &[("black", &black_score), ("white", &white_score)],
+                    ));
+                    if let Some(clock) = &self.clock {
+                        ui.label(self.strings.tr_args(
+                            "compact-clocks",
+                            &[
+                                (
+                                    "black",
+                                    &clock::format_remaining(clock.remaining(Player::Black)),
+                                ),
+                                (
+                                    "white",
+                                    &clock::format_remaining(clock.remaining(Player::White)),
+                                ),
+                            ],
+                        ));
+                    }
+                    if ui
+                        .button(self.strings.tr("show-panel"))
+                        .on_hover_text("Tab")
+                        .clicked()
+                    {
+                        self.perform(Action::ToggleCompactMode);
+                    }
+                });
+            });
+    }
+
+    fn ai_move_time(&self) -> Duration {
+        // matches are played at full speed
+        if self.is_match_running() {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f32(self.options.ai_move_seconds)
+        }
+    }
+
+    // the low time warning goes by its own option
+    fn play_sound(&mut self, sound: Sound) {
+        if self.options.play_sounds {
+            self.sounds
+                .play(sound, self.options.sound_volume, Instant::now());
+        }
+    }
+
+    fn animate(&mut self, key: AnimationKey, duration: Duration) {
+        if !self.options.reduce_motion {
+            self.animations.start(key, duration);
+        }
+    }
+
+    // none when the AI plays at full speed, where the animation would only hold it up
+    fn flip_animation_time(&self) -> Option<Duration> {
+        (self.ai_move_time() > Duration::ZERO
+            && self.options.flip_animation_seconds > 0.0
+            && !self.options.reduce_motion
+            && self.auto_finish.is_none())
+        .then(|| Duration::from_secs_f32(self.options.flip_animation_seconds))
+    }
+
+    // matches are played through regardless
+    fn is_stepping(&self) -> bool {
+        self.options.step_mode && !self.is_match_running()
+    }
+
+    fn can_step(&self) -> bool {
+        if !self.is_stepping() || self.replay.is_some() || self.editor.is_some() {
+            return false;
+        }
+        match self.current_phase {
+            Phase::Turn(player) => {
+                self.player_options[player as usize].ai_enabled
+                    && !self.is_ai_thinking(player)
+                    && !self.step_requested
+                    && self.pass_notice.is_none()
+                    && !self.is_network_frozen()
+                    && self.remote_player() != Some(player)
+            }
+            Phase::Win(..) | Phase::Tie => self.options.auto_restart && self.network.is_none(),
+        }
+    }
+
+    // call this from the UI thread
+    // one AI move, or the restart a finished game waits for
+    fn step(&mut self) {
+        if !self.can_step() {
+            return;
+        }
+        match self.current_phase {
+            Phase::Turn(_) => self.step_requested = true,
+            Phase::Win(..) | Phase::Tie => self.restart_automatically(),
+        }
+    }
+
+    // once the flips of the last move are over, for the game on the board and not a replay
+    // matches go on to their next game at once
+    fn shows_result_overlay(&self) -> bool {
+        matches!(self.current_phase, Phase::Win(..) | Phase::Tie)
+            && !self.result_overlay_closed
+            && self.flip_animation.is_none()
+            && self.replay.is_none()
+            && self.editor.is_none()
+            && !self.is_match_running()
+    }
+
+    fn result_overlay(&self) -> Option<ResultOverlay> {
+        let winner = match self.current_phase {
+            Phase::Win(winner, _) => Some(winner),
+            Phase::Tie => None,
+            Phase::Turn(_) => return None,
+        };
+        let (black_count, white_count) = count_pieces(&self.board);
+        Some(ResultOverlay {
+            winner,
+            black_count,
+            white_count,
+        })
+    }
+
+    // e.g. "Black wins 40–24", the winner's count first
+    fn result_text(&self) -> Option<String> {
+        let (black_count, white_count) = count_pieces(&self.board);
+        let counts = |player| match player {
+            Player::Black => (black_count, white_count),
+            Player::White => (white_count, black_count),
+        };
+        Some(match self.current_phase {
+            Phase::Win(player, reason) => {
+                let (own, other) = counts(player);
+                let args: [(&str, &dyn std::fmt::Display); 3] = [
+                    ("player", &self.player_name(player)),
+                    ("own", &own),
+                    ("other", &other),
+                ];
+                match reason {
+                    WinReason::Disks => self.strings.tr_args("result-won", &args),
+                    WinReason::Time => self.strings.tr_args("result-won-on-time", &args),
+                    WinReason::Resignation => {
+                        self.strings.tr_args("result-won-by-resignation", &args)
+                    }
+                }
+            }
+            Phase::Tie => self.strings.tr_args(
+                "result-tie",
+                &[("black", &black_count), ("white", &white_count)],
+            ),
+            Phase::Turn(_) => return None,
+        })
+    }
+
+    // the result over a dimmed board, with the countdown of an auto-restart
+    fn show_result_overlay(&mut self, ui: &mut egui::Ui, board_rect: egui::Rect) {
+        let Some(text) = self.result_text() else {
+            return;
+        };
+        let fade_in = self
+            .animations
+            .progress(AnimationKey::GameOver, Instant::now());
+        let text_color = egui::Color32::WHITE.gamma_multiply(fade_in);
+        ui.painter().rect_filled(
+            board_rect,
+            0.0,
+            egui::Color32::from_black_alpha(150).gamma_multiply(fade_in),
+        );
+
+        let square_size = board_rect.width() / Board::SIZE as f32;
+        let content = egui::UiBuilder::new()
+            .max_rect(board_rect.shrink(square_size / 2.0))
+            .layout(egui::Layout::top_down(egui::Align::Center));
+        ui.scope_builder(content, |ui| {
+            ui.add_space(board_rect.height() / 2.0 - square_size * 2.0);
+            ui.label(
+                egui::RichText::new(text)
+                    .size(square_size * 0.6)
+                    .strong()
+                    .color(text_color),
+            );
+            if let Some(remaining) = self.restart_countdown() {
+                ui.label(
+                    egui::RichText::new(self.strings.tr_args(
+                        "restarting-in",
+                        &[("seconds", &remaining.as_secs_f32().ceil())],
+                    ))
+                    .color(text_color),
+                );
+            }
+            ui.add_space(square_size * 0.3);
+            if ui
+                .button(self.strings.tr("rematch"))
+                .on_hover_text(self.strings.tr("rematch-tooltip"))
+                .clicked()
+            {
+                self.restart();
+            }
+            if ui
+                .add_enabled(
+                    !self.history.plies.is_empty(),
+                    egui::Button::new(self.strings.tr("analyze")),
+                )
+                .on_hover_text(self.strings.tr("analyze-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-no-moves"))
+                .clicked()
+            {
+                self.result_overlay_closed = true;
+                self.start_analysis();
+            }
+            if ui
+                .button(self.strings.tr("close"))
+                .on_hover_text(shortcut_text(ui.ctx(), Action::Cancel))
+                .clicked()
+            {
+                self.result_overlay_closed = true;
+            }
+        });
+    }
+
+    fn will_restart_automatically(&self) -> bool {
+        matches!(self.current_phase, Phase::Win(..) | Phase::Tie)
+            && self.options.auto_restart
+            && self.network.is_none()
+            && !self.is_stepping()
+            && !self.is_match_running()
+    }
+
+    // the time left until the restart, worked out from the deadline each frame so it does not
+    // drift while the window is not drawn, and a longer pause set meanwhile extends it
+    fn restart_countdown(&self) -> Option<Duration> {
+        let deadline = self.game_over_at? + Duration::from_secs(self.options.pause_at_win_seconds);
+        self.will_restart_automatically()
+            .then(|| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    fn update_restart_controls(&mut self, ui: &mut egui::Ui) {
+        if !self.will_restart_automatically() {
+            return;
+        }
+
+        match self.restart_countdown() {
+            Some(remaining) => {
+                ui.label(self.strings.tr_args(
+                    "restarting-in",
+                    &[("seconds", &remaining.as_secs_f32().ceil())],
+                ));
+            }
+            None => {
+                ui.label(self.strings.tr("restart-held"));
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button(self.strings.tr("restart-now"))
+                .on_hover_text(self.strings.tr("restart-now-tooltip"))
+                .clicked()
+            {
+                self.restart_automatically();
+            }
+            if ui
+                .add_enabled(
+                    self.game_over_at.is_some(),
+                    egui::Button::new(self.strings.tr("hold")),
+                )
+                .on_hover_text(self.strings.tr("hold-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-restart-held"))
+                .clicked()
+            {
+                self.game_over_at = None;
+            }
+        });
+    }
+
+    fn restart_automatically(&mut self) {
+        if self.options.alternate_colors {
+            // the statistics follow the configurations, so this is no setting change
+            self.player_options.swap(0, 1);
+        }
+        self.reset();
+    }
+
+    // how soon the next frame is due when there is no input, None to wait for input; nothing that
+    // runs on its own may be left out, or it stalls until the pointer moves
+    fn repaint_delay(&self) -> Option<Duration> {
+        let now = Instant::now();
+        if self.flip_animation.is_some() || self.animations.is_running(now) {
+            return Some(Duration::ZERO);
+        }
+
+        let mut delays = Vec::new();
+        if let Some(player) = self.ai_to_tick() {
+            delays.push(match &self.ai_requests[player as usize] {
+                None => Duration::ZERO,
+                // only the minimum move time is left
+                Some(request) if request.move_result.is_some() => {
+                    (request.requested_at + self.ai_move_time()).saturating_duration_since(now)
+                }
+                Some(_) => AI_POLL_INTERVAL,
+            });
+        }
+        if matches!(self.hint, Some(Hint::Pending(_)))
+            || self
+                .position_scores
+                .as_ref()
+                .is_some_and(|scores| scores.move_scores.is_none())
+        {
+            delays.push(AI_POLL_INTERVAL);
+        }
+        if self.analysis_run.is_some()
+            || self.network.is_some()
+            || self.arena.as_ref().is_some_and(|arena| arena.is_running())
+        {
+            delays.push(BACKGROUND_POLL_INTERVAL);
+        }
+        if let (Some(player), Some(clock)) = (self.clock_running_for(), &self.clock) {
+            delays.push(if clock.remaining(player) < Duration::from_secs(10) {
+                LOW_CLOCK_REPAINT_INTERVAL
+            } else {
+                CLOCK_REPAINT_INTERVAL
+            });
+        }
+        // the countdown shows whole seconds
+        if let Some(remaining) = self.restart_countdown() {
+            delays.push(remaining.min(Duration::from_secs(1)));
+        }
+        if let Some(dismiss_at) = self
+            .pass_notice
+            .as_ref()
+            .and_then(|notice| notice.dismiss_at)
+        {
+            delays.push(dismiss_at.saturating_duration_since(now));
+        }
+        if let Some((_, until)) = &self.toast {
+            delays.push(until.saturating_duration_since(now));
+        }
+        delays.into_iter().min()
+    }
+
+    // the seat whose AI is asked for or waited for in the next frame
+    fn ai_to_tick(&self) -> Option<Player> {
+        match self.current_phase {
+            Phase::Turn(player)
+                if self.editor.is_none()
+                    && self.replay.is_none()
+                    && self.pass_notice.is_none()
+                    && self.flip_animation.is_none()
+                    && !self.is_puzzle_answered()
+                    && !self.is_network_frozen()
+                    && self.remote_player() != Some(player)
+                    && self.player_options[player as usize].ai_enabled
+                    && (!self.is_stepping()
+                        || self.is_ai_thinking(player)
+                        || self.step_requested) =>
+            {
+                Some(player)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_match_running(&self) -> bool {
+        self.match_run
+            .as_ref()
+            .is_some_and(|match_run| match_run.running)
+    }
+
+    // call this from the UI thread
+    fn start_match(&mut self) {
+        if self.network.is_some() || !self.player_options.iter().all(|options| options.ai_enabled) {
+            return;
+        }
+
+        self.matches_played += 1;
+        self.match_run = Some(MatchRun {
+            tally: MatchTally::new(self.match_games),
+            configurations: self.player_options,
+            running: true,
+            name: format!(
+                "Match {}: {} vs {}",
+                self.matches_played,
+                self.player_options[0].name(),
+                self.player_options[1].name()
+            ),
+            started_at: Instant::now(),
+        });
+        self.log(self.strings.tr_args(
+            "log-match-started",
+            &[
+                ("count", &self.match_games),
+                ("black", &self.player_options[0].name()),
+                ("white", &self.player_options[1].name()),
+            ],
+        ));
+        self.reset();
+    }
+
+    // call this from the UI thread, after the current game of the match has ended
+    fn start_next_match_game(&mut self) {
+        let Some(match_run) = &self.match_run else {
+            return;
+        };
+
+        // an AI that failed to come up with a move is disabled, which ends the match as well
+        if match_run.tally.is_finished()
+            || !self.player_options.iter().all(|options| options.ai_enabled)
+        {
+            self.finish_match();
+            return;
+        }
+
+        self.player_options = match match_run.tally.first_color() {
+            Player::Black => match_run.configurations,
+            Player::White => [match_run.configurations[1], match_run.configurations[0]],
+        };
+        self.reset();
+    }
+
+    // call this from the UI thread, the tally stays on display until the next match
+    fn finish_match(&mut self) {
+        let Some(match_run) = &mut self.match_run else {
+            return;
+        };
+        if !match_run.running {
+            return;
+        }
+        match_run.running = false;
+        self.player_options = match_run.configurations;
+
+        let tally = &match_run.tally;
+        let name = match_run.name.clone();
+        self.event_log.push(self.strings.tr_args(
+            if tally.is_finished() {
+                "log-match-finished"
+            } else {
+                "log-match-stopped"
+            },
+            &[
+                ("name", &name),
+                ("games", &tally.games_played),
+                ("wins", &tally.first_wins),
+                ("draws", &tally.draws),
+                ("losses", &tally.second_wins),
+                (
+                    "differential",
+                    &format!("{:+.1}", tally.average_disc_differential()),
+                ),
+            ],
+        ));
+        for statistics in [
+            &mut self.statistics_file.statistics,
+            &mut self.session_statistics,
+        ] {
+            statistics.add_match(name.clone(), tally);
+        }
+        self.statistics_changed();
+    }
+
+    fn is_arena_running(&self) -> bool {
+        self.arena.as_ref().is_some_and(Arena::is_running)
+    }
+
+    // call this from the UI thread
+    fn start_arena(&mut self) {
+        if self.is_arena_running() || self.arena_contestants.len() < 2 {
+            return;
+        }
+
+        self.arenas_run += 1;
+        self.arena_csv_result = None;
+        let games = 2 * self.arena_games_per_color;
+        self.log(self.strings.tr_args(
+            "log-arena-started",
+            &[
+                ("arena", &self.arenas_run),
+                ("count", &self.arena_contestants.len()),
+                ("games", &games),
+            ],
+        ));
+        self.arena = Some(Arena::start(self.arena_contestants.clone(), games));
+        self.arena_games_recorded = 0;
+    }
+
+    // call this from the UI thread
+    // the pairings played in part count in the statistics as far as they got
+    fn cancel_arena(&mut self) {
+        let Some(arena) = &mut self.arena else {
+            return;
+        };
+        if !arena.is_running() {
+            return;
+        }
+
+        arena.cancel();
+        let games_played = arena.games_played();
+        self.log(self.strings.tr_args(
+            "log-arena-cancelled",
+            &[("arena", &self.arenas_run), ("games", &games_played)],
+        ));
+        self.record_arena_statistics();
+    }
+
+    // call this from the UI thread
+    fn poll_arena(&mut self) {
+        let Some(arena) = &mut self.arena else {
+            return;
+        };
+        if !arena.is_running() {
+            return;
+        }
+
+        let finished = arena.poll();
+        let is_over = !arena.is_running();
+        let error = arena.error.clone();
+        if arena.games_played() != self.arena_games_recorded
+            && (is_over
+                || !finished.is_empty()
+                || self.arena_recorded_at.elapsed() >= ARENA_STATISTICS_INTERVAL)
+        {
+            self.record_arena_statistics();
+        }
+        if let Some(error) = error {
+            self.log(self.strings.tr_args(
+                "log-arena-stopped",
+                &[("arena", &self.arenas_run), ("error", &error)],
+            ));
+        } else if is_over {
+            self.log(
+                self.strings
+                    .tr_args("log-arena-finished", &[("arena", &self.arenas_run)]),
+            );
+        }
+    }
+
+    // every pairing as far as it got, partial until its last game is in
+    fn record_arena_statistics(&mut self) {
+        let Some(arena) = &self.arena else {
+            return;
+        };
+
+        for pairing in arena.pairings.iter() {
+            let name = format!(
+                "Arena {}: {} vs {}",
+                self.arenas_run,
+                arena.contestants[pairing.first].name(),
+                arena.contestants[pairing.second].name()
+            );
+            for statistics in [
+                &mut self.statistics_file.statistics,
+                &mut self.session_statistics,
+            ] {
+                statistics.add_match(name.clone(), &pairing.tally);
+            }
+        }
+        self.arena_games_recorded = arena.games_played();
+        self.arena_recorded_at = Instant::now();
+        self.statistics_changed();
+    }
+
+    fn export_arena_csv(&mut self) {
+        let Some(arena) = &self.arena else {
+            return;
+        };
+
+        let path = std::path::Path::new(self.arena_csv_path.trim()).with_extension("csv");
+        let result = if path.file_stem().is_none() {
+            Err(self.strings.tr("file-name-missing").to_string())
+        } else {
+            self.write_file(&path, arena.to_csv())
+        };
+        match &result {
+            Ok(message) | Err(message) => self.log(message.clone()),
+        }
+        self.arena_csv_result = Some(result);
+    }
+
+    fn update_arena_controls(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.strings.tr("arena")).show(ui, |ui| {
+            let is_running = self.is_arena_running();
+            let ai_types = [
+                AiType::Random,
+                AiType::Minimax,
+                AiType::AlphaBeta,
+                AiType::Positional,
+            ];
+            let ai_type_tooltips =
+                ai_types.map(|ai_type| self.ai_type_tooltip(ai_type).to_string());
+            ui.add_enabled_ui(!is_running, |ui| {
+                let mut removed = None;
+                for (index, contestant) in self.arena_contestants.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(("arena_ai_type", index))
+                            .selected_text(format!("{:?}", contestant.ai_type))
+                            .show_ui(ui, |ui| {
+                                for (ai_type, tooltip) in ai_types.iter().zip(&ai_type_tooltips) {
+                                    ui.selectable_value(
+                                        &mut contestant.ai_type,
+                                        *ai_type,
+                                        format!("{ai_type:?}"),
+                                    )
+                                    .on_hover_text(tooltip);
+                                }
+                            })
+                            .response
+                            .on_hover_text(self.strings.tr("arena-ai-type-tooltip"))
+                            .on_disabled_hover_text(self.strings.tr("disabled-arena"));
+                        ui.add(
+                            egui::DragValue::new(&mut contestant.recursion_depth)
+                                .range(1..=MAX_RECURSION_DEPTH)
+                                .prefix(format!("{} ", self.strings.tr("depth"))),
+                        )
+                        .on_hover_text(self.strings.tr("ai-recursion-depth-tooltip"))
+                        .on_disabled_hover_text(self.strings.tr("disabled-arena"));
+                        if ui
+                            .button(self.strings.tr("arena-remove"))
+                            .on_hover_text(self.strings.tr("arena-remove-tooltip"))
+                            .on_disabled_hover_text(self.strings.tr("disabled-arena"))
+                            .clicked()
+                        {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    self.arena_contestants.remove(index);
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.arena_contestants.len() < arena::MAX_CONTESTANTS,
+                            egui::Button::new(self.strings.tr("arena-add")),
+                        )
+                        .on_hover_text(self.strings.tr("arena-add-tooltip"))
+                        .on_disabled_hover_text(if is_running {
+                            self.strings.tr("disabled-arena").to_string()
+                        } else {
+                            self.strings.tr_args(
+                                "disabled-arena-full",
+                                &[("count", &arena::MAX_CONTESTANTS)],
+                            )
+                        })
+                        .clicked()
+                    {
+                        let contestant = self
+                            .arena_contestants
+                            .last()
+                            .copied()
+                            .unwrap_or(arena::default_contestants()[0]);
+                        self.arena_contestants.push(contestant);
+                    }
+                    if ui
+                        .button(self.strings.tr("arena-reset"))
+                        .on_hover_text(self.strings.tr("arena-reset-tooltip"))
+                        .on_disabled_hover_text(self.strings.tr("disabled-arena"))
+                        .clicked()
+                    {
+                        self.arena_contestants = arena::default_contestants();
+                    }
+                });
+                ui.add(
+                    egui::Slider::new(&mut self.arena_games_per_color, 1..=50)
+                        .text(self.strings.tr("arena-games-per-color")),
+                )
+                .on_hover_text(self.strings.tr("arena-games-per-color-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-arena"));
+            });
+
+            if is_running {
+                if ui
+                    .button(self.strings.tr("arena-cancel"))
+                    .on_hover_text(self.strings.tr("arena-cancel-tooltip"))
+                    .clicked()
+                {
+                    self.cancel_arena();
+                }
+            } else if ui
+                .add_enabled(
+                    platform::HAS_THREADS && self.arena_contestants.len() >= 2,
+                    egui::Button::new(self.strings.tr("arena-run")),
+                )
+                .on_hover_text(self.strings.tr("arena-run-tooltip"))
+                .on_disabled_hover_text(self.strings.tr(if platform::HAS_THREADS {
+                    "disabled-arena-contestants"
+                } else {
+                    "disabled-no-threads"
+                }))
+                .clicked()
+            {
+                self.start_arena();
+            }
+
+            let Some(arena) = &self.arena else {
+                return;
+            };
+            let (games_played, total_games) = (arena.games_played(), arena.total_games());
+            ui.add(
+                egui::ProgressBar::new(games_played as f32 / total_games.max(1) as f32).text(
+                    self.strings.tr_args(
+                        "games-progress",
+                        &[("played", &games_played), ("total", &total_games)],
+                    ),
+                ),
+            );
+            if arena.is_running()
+                && let Some(remaining) = tournament::remaining_time(
+                    games_played,
+                    total_games,
+                    arena.started_at.elapsed(),
+                )
+            {
+                ui.label(self.strings.tr_args(
+                    "time-left",
+                    &[("time", &clock::format_remaining(remaining))],
+                ));
+            }
+            if let Some(error) = &arena.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            // the contestants are numbered, so that the columns stay narrow
+            let table = arena.crosstable();
+            egui::Grid::new("arena_crosstable")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for index in 0..arena.contestants.len() {
+                        ui.label(format!("{}", index + 1));
+                    }
+                    ui.label(self.strings.tr("arena-total"));
+                    ui.label(self.strings.tr("arena-score"))
+                        .on_hover_text(self.strings.tr("arena-score-tooltip"));
+                    ui.end_row();
+                    let games = arena.games_by_contestant();
+                    for (index, row) in table.iter().enumerate() {
+                        ui.label(format!(
+                            "{}. {}",
+                            index + 1,
+                            arena.contestants[index].name()
+                        ));
+                        for points in row.iter() {
+                            ui.label(points.map_or("–".to_string(), |points| points.to_string()));
+                        }
+                        let points = row.iter().flatten().sum::<f64>();
+                        ui.label(points.to_string());
+                        ui.label(score_summary(points, games[index] as f64).unwrap_or_default());
+                        ui.end_row();
+                    }
+                });
+
+            let csv = arena.to_csv();
+            if platform::HAS_FILE_SYSTEM {
+                ui.text_edit_singleline(&mut self.arena_csv_path)
+                    .on_hover_text(self.strings.tr("arena-csv-path-tooltip"));
+            }
+            ui.horizontal(|ui| {
+                if platform::HAS_FILE_SYSTEM
+                    && ui
+                        .button(self.strings.tr("export-csv"))
+                        .on_hover_text(self.strings.tr("export-csv-tooltip"))
+                        .clicked()
+                {
+                    self.export_arena_csv();
+                }
+                if ui
+                    .button(self.strings.tr("copy-csv"))
+                    .on_hover_text(self.strings.tr("copy-csv-tooltip"))
+                    .clicked()
+                {
+                    ui.ctx().copy_text(csv);
+                }
+            });
+            match &self.arena_csv_result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn can_start_multi_board(&self) -> bool {
+        self.player_options.iter().all(|options| options.ai_enabled)
+            && self.multi_board.is_none()
+            && !self.is_match_running()
+            && self.network.is_none()
+            && self.puzzle.is_none()
+            && self.editor.is_none()
+            && self.replay.is_none()
+            && self.auto_finish.is_none()
+    }
+
+    // call this from the UI thread
+    fn tick_multi_board(&mut self) {
+        let seats = self.player_options.map(|options| Seat {
+            ai_type: options.ai_type,
+            recursion_depth: options.ai_recursion_depth,
+        });
+        let move_time = self.ai_move_time();
+        let pause = Duration::from_secs(self.options.pause_at_win_seconds);
+        let Some(multi_board) = &mut self.multi_board else {
+            return;
+        };
+
+        let finished = multi_board.tick(&seats, move_time, pause, Instant::now());
+        let games: Vec<(GameResult, String)> = finished
+            .into_iter()
+            .filter_map(|index| {
+                let game = &multi_board.games[index];
+                Some((game.result()?, notation::transcript(&game.history, false)))
+            })
+            .collect();
+        for (result, transcript) in games {
+            if self.options.should_take_statistics {
+                // the agents of the boards are neither seeded nor timed
+                self.add_statistics_datum(
+                    result,
+                    self.options.first_player,
+                    transcript,
+                    None,
+                    [ThinkingTime::default(); 2],
+                );
+            }
+        }
+    }
+
+    fn update_multi_board_controls(&mut self, ui: &mut egui::Ui) {
+        ui.label(self.strings.tr("multi-board"));
+        if let Some(multi_board) = &self.multi_board {
+            let tally = &multi_board.tally;
+            ui.label(self.strings.tr_args(
+                "multi-board-tally",
+                &[
+                    ("count", &tally.games()),
+                    ("black", &tally.black_wins),
+                    ("white", &tally.white_wins),
+                    ("draws", &tally.draws),
+                ],
+            ));
+            if ui
+                .button(self.strings.tr("multi-board-stop"))
+                .on_hover_text(self.strings.tr("multi-board-stop-tooltip"))
+                .clicked()
+            {
+                self.multi_board = None;
+            }
+            return;
+        }
+
+        ui.add(
+            egui::Slider::new(&mut self.multi_board_count, 2..=multi_board::MAX_BOARDS)
+                .text(self.strings.tr("multi-board-count")),
+        )
+        .on_hover_text(self.strings.tr("multi-board-count-tooltip"));
+        if ui
+            .add_enabled(
+                self.can_start_multi_board(),
+                egui::Button::new(self.strings.tr("multi-board-start")),
+            )
+            .on_hover_text(self.strings.tr("multi-board-start-tooltip"))
+            .on_disabled_hover_text(self.multi_board_disabled_reason())
+            .clicked()
+        {
+            self.multi_board = Some(MultiBoard::new(
+                self.multi_board_count,
+                self.options.first_player,
+            ));
+        }
+    }
+
+    // the boards side by side, or the zoomed one alone
+    fn show_multi_board(&mut self, ui: &mut egui::Ui) {
+        let theme = self.options.board_theme.theme();
+        let disc_pattern = self.options.disc_pattern;
+        // the grid has no human side to turn to
+        let orientation = self.options.orientation;
+        let Some(multi_board) = &mut self.multi_board else {
+            return;
+        };
+
+        let rect = ui.available_rect_before_wrap();
+        if let Some(index) = multi_board.zoomed {
+            let size = rect.width().min(rect.height() - MINI_BOARD_LABEL_HEIGHT);
+            let board_rect = egui::Rect::from_min_size(rect.min, egui::Vec2::splat(size));
+            if show_board_game(
+                ui,
+                board_rect,
+                &multi_board.games[index],
+                &theme,
+                disc_pattern,
+                orientation,
+                &self.strings,
+            )
+            .clicked()
+            {
+                multi_board.zoomed = None;
+            }
+            return;
+        }
+
+        let count = multi_board.games.len();
+        let columns = (count as f32).sqrt().ceil() as usize;
+        let rows = count.div_ceil(columns);
+        let cell_width = rect.width() / columns as f32;
+        let cell_height = rect.height() / rows as f32;
+        let size = (cell_width - MINI_BOARD_GAP).min(cell_height - MINI_BOARD_LABEL_HEIGHT);
+        for (index, game) in multi_board.games.iter().enumerate() {
+            let min = rect.min
+                + egui::vec2(
+                    (index % columns) as f32 * cell_width,
+                    (index / columns) as f32 * cell_height,
+                );
+            let board_rect = egui::Rect::from_min_size(min, egui::Vec2::splat(size));
+            if show_board_game(
+                ui,
+                board_rect,
+                game,
+                &theme,
+                disc_pattern,
+                orientation,
+                &self.strings,
+            )
+            .clicked()
+            {
+                multi_board.zoomed = Some(index);
+            }
+        }
+    }
+
+    fn update_match_controls(&mut self, ui: &mut egui::Ui) {
+        ui.label(self.strings.tr("match"));
+        let both_ai = self.player_options.iter().all(|options| options.ai_enabled)
+            && self.multi_board.is_none();
+
+        if self.is_match_running() {
+            if ui
+                .button(self.strings.tr("match-stop"))
+                .on_hover_text(self.strings.tr("match-stop-tooltip"))
+                .clicked()
+            {
+                self.finish_match();
+                // the game in progress is not part of the match
+                self.reset();
+            }
+        } else {
+            ui.add(
+                egui::Slider::new(&mut self.match_games, 1..=1000)
+                    .text(self.strings.tr("match-games")),
+            )
+            .on_hover_text(self.strings.tr("match-games-tooltip"));
+            if ui
+                .add_enabled(both_ai, egui::Button::new(self.strings.tr("match-run")))
+                .on_hover_text(self.strings.tr("match-run-tooltip"))
+                .on_disabled_hover_text(if self.multi_board.is_some() {
+                    self.strings.tr("disabled-multi-board")
+                } else {
+                    self.strings.tr("disabled-needs-two-ais")
+                })
+                .clicked()
+            {
+                self.start_match();
+            }
+            if !both_ai {
+                ui.label(self.strings.tr("match-needs-ai"));
+            }
+        }
+
+        if let Some(match_run) = &self.match_run {
+            let tally = &match_run.tally;
+            ui.label(
+                self.strings.tr_args(
+                    "match-game",
+                    &[
+                        (
+                            "index",
+                            &(tally.games_played + usize::from(match_run.running))
+                                .min(tally.total_games),
+                        ),
+                        ("count", &tally.total_games),
+                    ],
+                ),
+            );
+            ui.label(self.strings.tr_args(
+                "match-wins",
+                &[
+                    ("name", &match_run.configurations[0].name()),
+                    ("count", &tally.first_wins),
+                ],
+            ));
+            ui.label(
+                self.strings
+                    .tr_args("match-draws", &[("count", &tally.draws)]),
+            );
+            ui.label(self.strings.tr_args(
+                "match-wins",
+                &[
+                    ("name", &match_run.configurations[1].name()),
+                    ("count", &tally.second_wins),
+                ],
+            ));
+            ui.label(self.strings.tr_args(
+                "match-disc-differential",
+                &[(
+                    "differential",
+                    &format!("{:+.1}", tally.average_disc_differential()),
+                )],
+            ));
+            if let Some(score) = score_summary(tally.first_points(), tally.games_played as f64) {
+                ui.label(self.strings.tr_args(
+                    "match-score",
+                    &[
+                        ("name", &match_run.configurations[0].name()),
+                        ("score", &score),
+                    ],
+                ))
+                .on_hover_text(self.strings.tr("match-score-tooltip"));
+            }
+            if match_run.running
+                && let Some(remaining) = tally.remaining_time(match_run.started_at.elapsed())
+            {
+                ui.label(self.strings.tr_args(
+                    "time-left",
+                    &[("time", &clock::format_remaining(remaining))],
+                ));
+            }
+        }
+    }
+
+    fn is_puzzle_answered(&self) -> bool {
+        self.puzzle
+            .as_ref()
+            .is_some_and(|puzzle| puzzle.solved.is_some())
+    }
+
+    // the first unsolved puzzle from the next one on, or just the next one once all are solved
+    fn next_puzzle_index(&self) -> usize {
+        let solved = &self.statistics_file.solved_puzzles;
+        (0..PUZZLES.len())
+            .map(|offset| (self.next_puzzle + offset) % PUZZLES.len())
+            .find(|&index| !solved.iter().any(|title| title == PUZZLES[index].title))
+            .unwrap_or(self.next_puzzle)
+    }
+
+    // call this from the UI thread
+    fn load_puzzle(&mut self, index: usize) {
+        if self.network.is_some() || self.is_match_running() {
+            return;
+        }
+
+        // the puzzle is for the human to solve
+        for player_options in self.player_options.iter_mut() {
+            player_options.ai_enabled = false;
+        }
+        let puzzle = &PUZZLES[index];
+        let (board, player) = puzzle.setup();
+        self.start_from_position(board, player);
+
+        let mut agent = Agent::new();
+        agent.log_searches = false;
+        self.puzzle = Some(PuzzleRun {
+            index,
+            solutions: puzzle.solutions(&mut agent),
+            solved: None,
+            revealed: false,
+        });
+        self.next_puzzle = (index + 1) % PUZZLES.len();
+    }
+
+    fn answer_puzzle(&mut self, next_move: Move) {
+        let Some(puzzle) = &mut self.puzzle else {
+            return;
+        };
+        let solved = puzzle.solutions.contains(&next_move);
+        puzzle.solved = Some(solved);
+
+        let title = PUZZLES[puzzle.index].title;
+        if solved
+            && !puzzle.revealed
+            && !self
+                .statistics_file
+                .solved_puzzles
+                .iter()
+                .any(|solved_title| solved_title == title)
+        {
+            self.statistics_file.solved_puzzles.push(title.to_string());
+            save::write_statistics(&self.statistics_file);
+        }
+    }
+
+    fn update_puzzle_controls(&mut self, ui: &mut egui::Ui) {
+        let solved_count = PUZZLES
+            .iter()
+            .filter(|puzzle| {
+                self.statistics_file
+                    .solved_puzzles
+                    .iter()
+                    .any(|title| title == puzzle.title)
+            })
+            .count();
+        ui.label(self.strings.tr_args(
+            "puzzles-solved",
+            &[("solved", &solved_count), ("count", &PUZZLES.len())],
+        ));
+
+        let to_move = match self.current_phase {
+            Phase::Turn(player) => Some(
+                self.strings
+                    .tr_args("puzzle-to-move", &[("player", &self.player_name(player))]),
+            ),
+            Phase::Win(..) | Phase::Tie => None,
+        };
+        let mut load = None;
+        if let Some(puzzle) = &mut self.puzzle {
+            ui.label(self.strings.tr(PUZZLES[puzzle.index].key));
+            match puzzle.solved {
+                None => {
+                    if let Some(to_move) = to_move {
+                        ui.label(to_move);
+                    }
+                }
+                Some(true) => {
+                    ui.colored_label(success_color(ui), self.strings.tr("puzzle-solved"));
+                }
+                Some(false) => {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        self.strings.tr("puzzle-not-best"),
+                    );
+                }
+            }
+            if puzzle.revealed {
+                let solutions: Vec<String> = puzzle
+                    .solutions
+                    .iter()
+                    .map(|&solution| notation::format_move(solution))
+                    .collect();
+                ui.label(self.strings.tr_args(
+                    "puzzle-solution",
+                    &[(
+                        "moves",
+                        &solutions.join(&format!(" {} ", self.strings.tr("puzzle-or"))),
+                    )],
+                ));
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button(self.strings.tr("puzzle-retry"))
+                    .on_hover_text(self.strings.tr("puzzle-retry-tooltip"))
+                    .clicked()
+                {
+                    load = Some(puzzle.index);
+                }
+                if ui
+                    .add_enabled(
+                        !puzzle.revealed,
+                        egui::Button::new(self.strings.tr("puzzle-reveal")),
+                    )
+                    .on_hover_text(self.strings.tr("puzzle-reveal-tooltip"))
+                    .on_disabled_hover_text(self.strings.tr("disabled-puzzle-revealed"))
+                    .clicked()
+                {
+                    puzzle.revealed = true;
+                }
+            });
+        }
+
+        if ui
+            .add_enabled(
+                self.network.is_none() && !self.is_match_running(),
+                egui::Button::new(self.strings.tr("puzzle-next")),
+            )
+            .on_hover_text(self.strings.tr("puzzle-next-tooltip"))
+            .on_disabled_hover_text(self.network_or_match_reason())
+            .clicked()
+        {
+            load = Some(self.next_puzzle_index());
+        }
+        if let Some(index) = load {
+            self.load_puzzle(index);
+        }
+    }
+
+    // call this from the UI thread
+    // call this from the UI thread
+    fn open_editor(&mut self) {
+        if self.network.is_some() || self.is_match_running() || self.editor.is_some() {
+            return;
+        }
+
+        self.replay = None;
+        self.flip_animation = None;
+        self.editor = Some(PositionEditor {
+            board: self.board.clone(),
+            player: match self.current_phase {
+                Phase::Turn(player) => player,
+                Phase::Win(..) | Phase::Tie => Player::Black,
+            },
+            undo_stack: Vec::new(),
+            error: None,
+        });
+    }
+
+    // empty, black, white and empty again
+    fn edit_cell(&mut self, (row, col): Move) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        editor.undo_stack.push(editor.board.clone());
+        editor.error = None;
+        let cell = &mut editor.board.grid[row][col];
+        *cell = match *cell {
+            Cell::Empty => Cell::Taken(Player::Black),
+            Cell::Taken(Player::Black) => Cell::Taken(Player::White),
+            Cell::Taken(Player::White) => Cell::Empty,
+        };
+    }
+
+    fn replace_edited_board(&mut self, board: Board) {
+        if let Some(editor) = &mut self.editor {
+            let previous = std::mem::replace(&mut editor.board, board);
+            editor.undo_stack.push(previous);
+            editor.error = None;
+        }
+    }
+
+    // call this from the UI thread
+    // play resumes from the edited position, unless the side to move could not move there
+    fn close_editor(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+
+        let mut valid_moves = CellList::default();
+        self.referee
+            .find_all_valid_moves(&editor.board, editor.player, &mut valid_moves);
+        if valid_moves.count == 0 {
+            let error = self.strings.tr_args(
+                "editor-no-valid-move",
+                &[("player", &self.player_name(editor.player))],
+            );
+            if let Some(editor) = &mut self.editor {
+                editor.error = Some(error);
+            }
+            return;
+        }
+
+        let (board, player) = (editor.board.clone(), editor.player);
+        self.start_from_position(board, player);
+        self.log(self.strings.tr_args(
+            "log-edited-position",
+            &[("position", &notation::format_position(&self.board, player))],
+        ));
+    }
+
+    fn update_editor_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(editor) = &mut self.editor else {
+            if ui
+                .add_enabled(
+                    self.network.is_none() && !self.is_match_running(),
+                    egui::Button::new(self.strings.tr("edit-position")),
+                )
+                .on_hover_text(self.strings.tr("edit-position-tooltip"))
+                .on_disabled_hover_text(self.network_or_match_reason())
+                .clicked()
+            {
+                self.open_editor();
+            }
+            return;
+        };
+
+        ui.label(self.strings.tr("editor-instructions"));
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("editor-to-move"));
+            ui.radio_value(
+                &mut editor.player,
+                Player::Black,
+                self.strings.tr("player-black"),
+            )
+            .on_hover_text(self.strings.tr("editor-to-move-tooltip"));
+            ui.radio_value(
+                &mut editor.player,
+                Player::White,
+                self.strings.tr("player-white"),
+            )
+            .on_hover_text(self.strings.tr("editor-to-move-tooltip"));
+        });
+        if let Some(error) = &editor.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        let can_undo = !editor.undo_stack.is_empty();
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(self.strings.tr("editor-clear-board"))
+                .on_hover_text(self.strings.tr("editor-clear-board-tooltip"))
+                .clicked()
+            {
+                self.replace_edited_board(Board {
+                    grid: [[Cell::Empty; Board::SIZE]; Board::SIZE],
+                });
+            }
+            if ui
+                .button(self.strings.tr("editor-standard-start"))
+                .on_hover_text(self.strings.tr("editor-standard-start-tooltip"))
+                .clicked()
+            {
+                self.replace_edited_board(Board::default());
+            }
+            if ui
+                .add_enabled(can_undo, egui::Button::new(self.strings.tr("editor-undo")))
+                .on_hover_text(self.strings.tr("editor-undo-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-nothing-to-undo-edit"))
+                .clicked()
+                && let Some(editor) = &mut self.editor
+                && let Some(board) = editor.undo_stack.pop()
+            {
+                editor.board = board;
+                editor.error = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button(self.strings.tr("editor-done"))
+                .on_hover_text(self.strings.tr("editor-done-tooltip"))
+                .clicked()
+            {
+                self.close_editor();
+            }
+            if ui
+                .button(self.strings.tr("cancel"))
+                .on_hover_text(self.strings.tr("editor-cancel-tooltip"))
+                .clicked()
+            {
+                // back to the game as it was
+                self.editor = None;
+            }
+        });
+    }
+
+    fn can_auto_finish(&self) -> bool {
+        matches!(self.current_phase, Phase::Turn(_))
+            && self.network.is_none()
+            && self.puzzle.is_none()
+            && self.editor.is_none()
+            && self.replay.is_none()
+            && !self.is_match_running()
+            && self
+                .player_options
+                .iter()
+                .any(|options| !options.ai_enabled)
+    }
+
+    // call this from the UI thread
+    // the AI plays the human seats until the game is over, as if they were set up that way
+    fn start_auto_finish(&mut self) {
+        if self.auto_finish.is_some() || !self.can_auto_finish() {
+            return;
+        }
+
+        self.auto_finish = Some(self.player_options);
+        let auto_finish_ai = self.auto_finish_ai;
+        for player_options in self
+            .player_options
+            .iter_mut()
+            .filter(|player_options| !player_options.ai_enabled)
+        {
+            *player_options = auto_finish_ai;
+        }
+        // nobody played this game to the end with the original settings
+        self.is_board_untouched = false;
+        self.block_statistics(StatisticsBlock::AutoFinished);
+    }
+
+    fn stop_auto_finish(&mut self) {
+        if let Some(player_options) = self.auto_finish.take() {
+            self.player_options = player_options;
+        }
+    }
+
+    fn can_swap_sides(&self) -> bool {
+        matches!(self.current_phase, Phase::Turn(_))
+            && self.can_change_player_options()
+            && self.editor.is_none()
+            && self.replay.is_none()
+            && self.multi_board.is_none()
+            && self.seat_change.is_none()
+    }
+
+    // call this from the UI thread
+    // the two player options change colors, the position and the turn stay as they are
+    // the clocks belong to the colors, so they keep running for the side to move
+    fn swap_sides(&mut self) {
+        if !self.can_swap_sides() {
+            return;
+        }
+
+        self.player_options.swap(0, 1);
+        self.log(self.strings.tr("log-swap-sides").to_string());
+        // a reply still on its way for the now human seat is dropped when it arrives
+        if let Phase::Turn(player) = self.current_phase
+            && !self.player_options[player as usize].ai_enabled
+        {
+            self.ai_requests[player as usize] = None;
+        }
+        // a hint or a selection was for a human who may have handed the seat to the AI
+        self.hint = None;
+        self.pending_move = None;
+        // neither side played this game with the same settings all the way through
+        self.is_board_untouched = false;
+        self.block_statistics(StatisticsBlock::SwappedSides);
+    }
+
+    fn update_auto_finish_controls(&mut self, ui: &mut egui::Ui) {
+        if self.auto_finish.is_some() {
+            if ui
+                .button(self.strings.tr("stop-auto-finish"))
+                .on_hover_text(self.strings.tr("stop-auto-finish-tooltip"))
+                .clicked()
+            {
+                self.stop_auto_finish();
+            }
+            return;
+        }
+
+        if ui
+            .add_enabled(
+                self.can_auto_finish(),
+                egui::Button::new(self.strings.tr("finish-with-ai")),
+            )
+            .on_hover_text(self.strings.tr("finish-with-ai-tooltip"))
+            .on_disabled_hover_text(self.auto_finish_disabled_reason())
+            .clicked()
+        {
+            self.start_auto_finish();
+        }
+        ui.horizontal(|ui| {
+            for (ai_type, text) in AI_TYPE_NAMES {
+                ui.radio_value(&mut self.auto_finish_ai.ai_type, ai_type, text)
+                    .on_hover_text(self.ai_type_tooltip(ai_type));
+            }
+        });
+        ui.add(
+            egui::Slider::new(
+                &mut self.auto_finish_ai.ai_recursion_depth,
+                1..=MAX_RECURSION_DEPTH,
+            )
+            .text(self.strings.tr("depth")),
+        )
+        .on_hover_text(self.strings.tr("ai-recursion-depth-tooltip"));
+    }
+
+    // the local human whose move the board and the move input wait for
+    fn human_to_move(&self) -> Option<Player> {
+        match self.current_phase {
+            Phase::Turn(player)
+                if self.replay.is_none()
+                    && self.editor.is_none()
+                    && self.pass_notice.is_none()
+                    && !self.is_puzzle_answered()
+                    && !self.is_network_frozen()
+                    && self.remote_player() != Some(player)
+                    && !self.player_options[player as usize].ai_enabled =>
+            {
+                Some(player)
+            }
+            _ => None,
+        }
+    }
+
+    fn update_move_input_controls(&mut self, ui: &mut egui::Ui) {
+        let human = self.human_to_move();
+        ui.add_enabled_ui(human.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(self.strings.tr("move-input"));
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.move_input)
+                            .hint_text(self.strings.tr("move-input-hint"))
+                            .desired_width(48.0),
+                    )
+                    .on_hover_text(self.strings.tr("move-input-tooltip"))
+                    .on_disabled_hover_text(self.strings.tr("disabled-not-your-turn"));
+                if response.lost_focus()
+                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                    && let Some(player) = human
+                {
+                    match typed_move(
+                        &self.strings,
+                        &mut self.referee,
+                        &self.board,
+                        player,
+                        &self.move_input,
+                    ) {
+                        Ok(next_move) => {
+                            self.move_input.clear();
+                            self.move_input_error = None;
+                            if let Err(e) = self.make_move(next_move, player) {
+                                self.report_move_error(player, e);
+                            }
+                        }
+                        Err(e) => self.move_input_error = Some(e),
+                    }
+                    // ready for the next move
+                    response.request_focus();
+                }
+            });
+        });
+        if human.is_some() {
+            let legal: Vec<String> = self.valid_moves.iter().map(notation::format_move).collect();
+            ui.label(
+                self.strings
+                    .tr_args("legal", &[("moves", &legal.join(" "))]),
+            );
+        }
+        if let Some(error) = &self.move_input_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    // call this from the UI thread
+    fn restart(&mut self) {
+        if let Some(network) = &self.network {
+            network.connection.send(Message::Restart);
+        }
+        self.reset();
+    }
+
+    // call this from the UI thread
+    fn request_hint(&mut self) {
+        let Some(player) = self.human_to_move() else {
+            return;
+        };
+        if self.hint.is_some() || self.is_ai_thinking(player) {
+            return;
+        }
+
+        if self.options.show_heatmap {
+            self.request_position_scores();
+        }
+        let id = self.hint_runner.send(AgentRequest::Move(MoveRequest {
+            board: self.board.clone(),
+            player,
+            algorithm_choice: AiType::Positional,
+            recursion_depth: HINT_DEPTH,
+            time_budget: None,
+        }));
+        self.hint = Some(Hint::Pending(id));
+    }
+
+    fn poll_hint(&mut self) {
+        let Some(Hint::Pending(id)) = self.hint else {
+            return;
+        };
+        let received = self.hint_runner.try_recv();
+        if self.hint_runner.take_restart() {
+            self.log(self.strings.tr("log-hint-ai-restarted").to_string());
+        }
+        match received {
+            Ok(Some((answered, move_result))) if answered == id => {
+                self.hint = (move_result.board.grid == self.board.grid
+                    && self.human_to_move() == Some(move_result.player))
+                .then_some(Hint::Ready(move_result.next_move));
+            }
+            // the answer to a hint dropped before this one
+            Ok(_) => {}
+            Err(e) => {
+                self.hint = None;
+                self.toast = Some((
+                    self.strings.tr_args("no-hint", &[("error", &e)]),
+                    Instant::now() + TOAST_DURATION,
+                ));
+            }
+        }
+    }
+
+    // whether the action can be taken now, by shortcut or button
+    fn is_action_enabled(&self, action: Action) -> bool {
+        match action {
+            Action::Restart => !self.is_match_running() && !self.is_network_frozen(),
+            Action::Undo => self.can_undo(),
+            Action::Redo => self.can_redo(),
+            Action::TakeBack => self.take_back_player().is_some(),
+            Action::Hint => self
+                .human_to_move()
+                .is_some_and(|player| self.hint.is_none() && !self.is_ai_thinking(player)),
+            Action::Step => self.can_step(),
+            Action::ToggleValidMoves | Action::ToggleEffects | Action::ToggleCompactMode => true,
+            Action::Cancel => {
+                self.pending_move.is_some()
+                    || self.hint.is_some()
+                    || self.shows_result_overlay()
+                    || self.seat_change.is_some()
+                    || self
+                        .pass_notice
+                        .as_ref()
+                        .is_some_and(|notice| notice.dismiss_at.is_none())
+            }
+        }
+    }
+
+    // the first of the states that keep the game on the board from being played with, for the
+    // tooltip of a control they disable
+    fn game_lock_reason(&self) -> Option<&str> {
+        if self.is_match_running() {
+            Some(self.strings.tr("disabled-match"))
+        } else if self.network.is_some() {
+            Some(self.strings.tr("disabled-network"))
+        } else if self.puzzle.is_some() {
+            Some(self.strings.tr("disabled-puzzle"))
+        } else if self.editor.is_some() {
+            Some(self.strings.tr("disabled-editor"))
+        } else {
+            None
+        }
+    }
+
+    // why the controls of the action are grayed out, as far as `is_action_enabled` goes
+    fn action_disabled_reason(&self, action: Action) -> &str {
+        match action {
+            Action::Restart if self.is_match_running() => self.strings.tr("disabled-match"),
+            Action::Restart => self.strings.tr("disabled-network-frozen"),
+            Action::Undo => self
+                .game_lock_reason()
+                .unwrap_or(self.strings.tr("disabled-nothing-to-undo")),
+            Action::Redo => self
+                .game_lock_reason()
+                .unwrap_or(self.strings.tr("disabled-nothing-to-redo")),
+            Action::TakeBack => {
+                if let Some(reason) = self.game_lock_reason() {
+                    reason
+                } else if self.replay.is_some() {
+                    self.strings.tr("disabled-replay")
+                } else if self
+                    .player_options
+                    .iter()
+                    .filter(|options| options.ai_enabled)
+                    .count()
+                    != 1
+                {
+                    self.strings.tr("disabled-take-back-seats")
+                } else {
+                    self.strings.tr("disabled-take-back-no-move")
+                }
+            }
+            Action::Hint if self.hint.is_some() => self.strings.tr("disabled-hint-shown"),
+            Action::Hint if matches!(self.current_phase, Phase::Turn(player) if self.is_ai_thinking(player)) => {
+                self.strings.tr("disabled-ai-thinking")
+            }
+            Action::Hint => self.strings.tr("disabled-not-your-turn"),
+            Action::Step if self.is_match_running() => self.strings.tr("disabled-match"),
+            Action::Step if !self.options.step_mode => self.strings.tr("disabled-step-mode-off"),
+            Action::Step => self.strings.tr("disabled-nothing-to-step"),
+            Action::ToggleValidMoves
+            | Action::ToggleEffects
+            | Action::ToggleCompactMode
+            | Action::Cancel => "",
+        }
+    }
+
+    fn auto_finish_disabled_reason(&self) -> &str {
+        if !matches!(self.current_phase, Phase::Turn(_)) {
+            self.strings.tr("disabled-game-over")
+        } else if let Some(reason) = self.game_lock_reason() {
+            reason
+        } else if self.replay.is_some() {
+            self.strings.tr("disabled-replay")
+        } else {
+            self.strings.tr("disabled-no-human")
+        }
+    }
+
+    // the puzzles and the editor take the board, which neither game may give up
+    fn network_or_match_reason(&self) -> &str {
+        if self.network.is_some() {
+            self.strings.tr("disabled-network")
+        } else {
+            self.strings.tr("disabled-match")
+        }
+    }
+
+    // as far as `can_start_multi_board` goes, the button is not shown while the boards play
+    fn multi_board_disabled_reason(&self) -> &str {
+        if !self.player_options.iter().all(|options| options.ai_enabled) {
+            self.strings.tr("disabled-needs-two-ais")
+        } else if let Some(reason) = self.game_lock_reason() {
+            reason
+        } else if self.replay.is_some() {
+            self.strings.tr("disabled-replay")
+        } else {
+            self.strings.tr("disabled-auto-finish")
+        }
+    }
+
+    fn ai_type_tooltip(&self, ai_type: AiType) -> &str {
+        match ai_type {
+            AiType::Random => self.strings.tr("ai-random-tooltip"),
+            AiType::Minimax => self.strings.tr("ai-minimax-tooltip"),
+            AiType::AlphaBeta => self.strings.tr("ai-alphabeta-tooltip"),
+            AiType::Positional => self.strings.tr("ai-positional-tooltip"),
+        }
+    }
+
+    fn resign_disabled_reason(&self) -> &str {
+        if !matches!(self.current_phase, Phase::Turn(_)) {
+            self.strings.tr("disabled-game-over")
+        } else if let Some(reason) = self.game_lock_reason() {
+            reason
+        } else if self.replay.is_some() {
+            self.strings.tr("disabled-replay")
+        } else {
+            self.strings.tr("disabled-not-your-turn")
+        }
+    }
+
+    fn swap_sides_disabled_reason(&self) -> &str {
+        if !matches!(self.current_phase, Phase::Turn(_)) {
+            self.strings.tr("disabled-game-over")
+        } else if let Some(reason) = self.player_options_lock_reason() {
+            reason
+        } else if self.editor.is_some() {
+            self.strings.tr("disabled-editor")
+        } else if self.replay.is_some() {
+            self.strings.tr("disabled-replay")
+        } else if self.multi_board.is_some() {
+            self.strings.tr("disabled-multi-board")
+        } else {
+            self.strings.tr("disabled-seat-change")
+        }
+    }
+
+    // why the player options cannot be changed, as far as `can_change_player_options` goes
+    fn player_options_lock_reason(&self) -> Option<&str> {
+        if self.is_match_running() {
+            Some(self.strings.tr("disabled-match"))
+        } else if self.network.is_some() {
+            Some(self.strings.tr("disabled-network"))
+        } else if self.puzzle.is_some() {
+            Some(self.strings.tr("disabled-puzzle"))
+        } else if self.auto_finish.is_some() {
+            Some(self.strings.tr("disabled-auto-finish"))
+        } else {
+            None
+        }
+    }
+
+    // call this from the UI thread
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::Restart => self.restart(),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::TakeBack => self.take_back(),
+            Action::Hint => self.request_hint(),
+            Action::Step => self.step(),
+            Action::ToggleValidMoves => {
+                self.options.show_valid_moves = !self.options.show_valid_moves;
+            }
+            Action::ToggleEffects => {
+                self.options.show_effects_of_moves = !self.options.show_effects_of_moves;
+            }
+            Action::ToggleCompactMode => {
+                self.options.compact_mode = !self.options.compact_mode;
+            }
+            Action::Cancel => {
+                self.pending_move = None;
+                self.hint = None;
+                self.result_overlay_closed = true;
+                self.seat_change = None;
+                if self
+                    .pass_notice
+                    .as_ref()
+                    .is_some_and(|notice| notice.dismiss_at.is_none())
+                {
+                    self.pass_notice = None;
+                }
+            }
+        }
+    }
+
+    // the seats, then the options of each player
+    fn update_players_section(&mut self, ui: &mut egui::Ui) {
+        // the match owns the player options until it is over
+        let match_running = self.is_match_running();
+        // neither local input nor the local AI may play the remote seat
+        let remote_player = self.remote_player();
+        // a puzzle is for the human to solve
+        let puzzle_active = self.puzzle.is_some();
+        // the options are restored after auto-finishing
+        let auto_finishing = self.auto_finish.is_some();
+        // every board plays with the same options, all the way through
+        let multi_board_running = self.multi_board.is_some();
+        let lock_reason = if multi_board_running {
+            Some(self.strings.tr("disabled-multi-board"))
+        } else {
+            self.player_options_lock_reason()
+        };
+        if let Some(reason) = lock_reason {
+            ui.weak(reason);
+        }
+        ui.add_enabled_ui(
+            !match_running
+                && !multi_board_running
+                && !puzzle_active
+                && !auto_finishing
+                && self.network.is_none()
+                && self.seat_change.is_none(),
+            |ui| {
+                self.update_seat_controls(ui);
+            },
+        );
+
+        for player in [Player::Black, Player::White] {
+            ui.separator();
+            let enabled = !match_running
+                && !multi_board_running
+                && !puzzle_active
+                && !auto_finishing
+                && remote_player != Some(player);
+            if remote_player == Some(player) {
+                ui.weak(self.strings.tr("disabled-remote-seat"));
+            }
+            ui.add_enabled_ui(enabled, |ui| {
+                self.update_player_options_controls(ui, player);
+            });
+        }
+    }
+
+    fn update_control_section(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(
+                self.is_action_enabled(Action::Restart),
+                egui::Button::new(self.strings.tr("restart-game")),
+            )
+            .on_hover_text(with_shortcut(
+                self.strings.tr("restart-game-tooltip"),
+                ui.ctx(),
+                Action::Restart,
+            ))
+            .on_disabled_hover_text(self.action_disabled_reason(Action::Restart))
+            .clicked()
+        {
+            self.restart();
+        }
+        self.update_seed_controls(ui);
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.can_undo(), egui::Button::new(self.strings.tr("undo")))
+                .on_hover_text(with_shortcut(
+                    self.strings.tr("undo-tooltip"),
+                    ui.ctx(),
+                    Action::Undo,
+                ))
+                .on_disabled_hover_text(self.action_disabled_reason(Action::Undo))
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui
+                .add_enabled(self.can_redo(), egui::Button::new(self.strings.tr("redo")))
+                .on_hover_text(with_shortcut(
+                    self.strings.tr("redo-tooltip"),
+                    ui.ctx(),
+                    Action::Redo,
+                ))
+                .on_disabled_hover_text(self.action_disabled_reason(Action::Redo))
+                .clicked()
+            {
+                self.redo();
+            }
+            if ui
+                .add_enabled(
+                    self.is_action_enabled(Action::Hint),
+                    egui::Button::new(self.strings.tr("hint")),
+                )
+                .on_hover_text(with_shortcut(
+                    self.strings.tr("hint-tooltip"),
+                    ui.ctx(),
+                    Action::Hint,
+                ))
+                .on_disabled_hover_text(self.action_disabled_reason(Action::Hint))
+                .clicked()
+            {
+                self.request_hint();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.square_marks.is_empty(),
+                    egui::Button::new(self.strings.tr("clear-marks")),
+                )
+                .on_hover_text(self.strings.tr("clear-marks-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-no-marks"))
+                .clicked()
+            {
+                self.square_marks.clear();
+            }
+            ui.checkbox(
+                &mut self.options.clear_marks_on_new_game,
+                self.strings.tr("clear-marks-on-new-game"),
+            )
+            .on_hover_text(self.strings.tr("clear-marks-on-new-game-tooltip"));
+        });
+        if ui
+            .add_enabled(
+                self.resigning_player().is_some(),
+                egui::Button::new(self.strings.tr("resign")),
+            )
+            .on_hover_text(self.strings.tr("resign-tooltip"))
+            .on_disabled_hover_text(self.resign_disabled_reason())
+            .clicked()
+        {
+            self.resign();
+        }
+        if ui
+            .add_enabled(
+                self.take_back_player().is_some(),
+                egui::Button::new(self.strings.tr("take-back")),
+            )
+            .on_hover_text(with_shortcut(
+                self.strings.tr("take-back-tooltip"),
+                ui.ctx(),
+                Action::TakeBack,
+            ))
+            .on_disabled_hover_text(self.action_disabled_reason(Action::TakeBack))
+            .clicked()
+        {
+            self.take_back();
+        }
+        if ui
+            .add_enabled(
+                self.can_swap_sides(),
+                egui::Button::new(self.strings.tr("swap-sides")),
+            )
+            .on_hover_text(self.strings.tr("swap-sides-tooltip"))
+            .on_disabled_hover_text(self.swap_sides_disabled_reason())
+            .clicked()
+        {
+            self.swap_sides();
+        }
+        self.update_auto_finish_controls(ui);
+        ui.checkbox(
+            &mut self.options.auto_restart,
+            self.strings.tr("auto-restart"),
+        )
+        .on_hover_text(self.strings.tr("auto-restart-tooltip"));
+        ui.add_enabled(
+            self.options.auto_restart,
+            egui::Checkbox::new(
+                &mut self.options.alternate_colors,
+                self.strings.tr("alternate-colors"),
+            ),
+        )
+        .on_hover_text(self.strings.tr("alternate-colors-tooltip"))
+        .on_disabled_hover_text(self.strings.tr("disabled-needs-auto-restart"));
+        if self.options.auto_restart && self.options.alternate_colors {
+            ui.label(self.strings.tr_args(
+                "alternating-seats",
+                &[
+                    ("black", &self.player_options[Player::Black as usize].name()),
+                    ("white", &self.player_options[Player::White as usize].name()),
+                ],
+            ));
+        }
+        if ui
+            .button(self.strings.tr("copy-transcript"))
+            .on_hover_text(self.strings.tr("copy-transcript-tooltip"))
+            .clicked()
+        {
+            ui.ctx().copy_text(notation::transcript(
+                &self.history,
+                self.options.mark_passes_in_transcript,
+            ));
+        }
+        ui.checkbox(
+            &mut self.options.mark_passes_in_transcript,
+            self.strings.tr("mark-passes"),
+        )
+        .on_hover_text(self.strings.tr("mark-passes-tooltip"));
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.transcript_input);
+            if ui
+                .button(self.strings.tr("load-transcript"))
+                .on_hover_text(self.strings.tr("load-transcript-tooltip"))
+                .clicked()
+            {
+                self.import_transcript();
+            }
+        });
+        if let Some(error) = &self.transcript_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn update_flow_section(&mut self, ui: &mut egui::Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.options.ai_move_seconds, 0.0..=3.0)
+                .step_by(0.1)
+                .text(self.strings.tr("playback-speed"))
+                .custom_formatter(|seconds, _| {
+                    if seconds == 0.0 {
+                        self.strings.tr("playback-speed-instant").to_string()
+                    } else {
+                        format!("{seconds:.1} s")
+                    }
+                }),
+        )
+        .on_hover_text(self.strings.tr("playback-speed-tooltip"));
+        ui.add(
+            egui::Slider::new(&mut self.options.flip_animation_seconds, 0.0..=1.0)
+                .step_by(0.1)
+                .text(self.strings.tr("flip-animation"))
+                .custom_formatter(|seconds, _| {
+                    if seconds == 0.0 {
+                        self.strings.tr("flip-animation-off").to_string()
+                    } else {
+                        format!("{seconds:.1} s")
+                    }
+                }),
+        )
+        .on_hover_text(self.strings.tr("flip-animation-tooltip"));
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.options.step_mode, self.strings.tr("step-mode"))
+                .on_hover_text(self.strings.tr("step-mode-tooltip"))
+                .changed()
+            {
+                self.step_requested = false;
+            }
+            if ui
+                .add_enabled(
+                    self.can_step(),
+                    egui::Button::new(self.strings.tr("next-move")),
+                )
+                .on_hover_text(with_shortcut(
+                    self.strings.tr("next-move-tooltip"),
+                    ui.ctx(),
+                    Action::Step,
+                ))
+                .on_disabled_hover_text(self.action_disabled_reason(Action::Step))
+                .clicked()
+            {
+                self.step();
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut self.options.pause_at_win_seconds, 0..=30)
+                .text(self.strings.tr("pause-at-win"))
+                .suffix(" s"),
+        )
+        .on_hover_text(self.strings.tr("pause-at-win-tooltip"));
+    }
+
+    fn update_help_section(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.options.show_valid_moves,
+            self.strings.tr("show-valid-moves"),
+        )
+        .on_hover_text(with_shortcut(
+            self.strings.tr("show-valid-moves-tooltip"),
+            ui.ctx(),
+            Action::ToggleValidMoves,
+        ));
+        ui.checkbox(
+            &mut self.options.show_opponent_move_count,
+            self.strings.tr("show-opponent-moves"),
+        )
+        .on_hover_text(self.strings.tr("show-opponent-moves-tooltip"));
+        if ui
+            .checkbox(
+                &mut self.options.confirm_moves,
+                self.strings.tr("confirm-moves"),
+            )
+            .on_hover_text(self.strings.tr("confirm-moves-tooltip"))
+            .changed()
+        {
+            self.pending_move = None;
+        }
+        ui.checkbox(
+            &mut self.options.show_effects_of_moves,
+            self.strings.tr("show-effects"),
+        )
+        .on_hover_text(with_shortcut(
+            self.strings.tr("show-effects-tooltip"),
+            ui.ctx(),
+            Action::ToggleEffects,
+        ));
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.options.show_move_preview,
+                self.strings.tr("preview-moves"),
+            )
+            .on_hover_text(self.strings.tr("preview-moves-tooltip"));
+            ui.add_enabled(
+                self.options.show_move_preview,
+                egui::Checkbox::new(
+                    &mut self.options.preview_flips,
+                    self.strings.tr("preview-flips"),
+                ),
+            )
+            .on_hover_text(self.strings.tr("preview-flips-tooltip"))
+            .on_disabled_hover_text(self.strings.tr("disabled-needs-preview"));
+        });
+        ui.checkbox(
+            &mut self.options.show_flip_counts,
+            self.strings.tr("show-flip-counts"),
+        )
+        .on_hover_text(self.strings.tr("show-flip-counts-tooltip"));
+        ui.checkbox(
+            &mut self.options.show_last_move,
+            self.strings.tr("show-last-move"),
+        )
+        .on_hover_text(self.strings.tr("show-last-move-tooltip"));
+        ui.add_enabled(
+            self.history.starts_from_standard_position(),
+            egui::Checkbox::new(
+                &mut self.options.show_move_numbers,
+                self.strings.tr("show-move-numbers"),
+            ),
+        )
+        .on_hover_text(self.strings.tr("show-move-numbers-tooltip"))
+        .on_disabled_hover_text(self.strings.tr("show-move-numbers-disabled"));
+        ui.checkbox(
+            &mut self.options.show_stable_discs,
+            self.strings.tr("show-stable-discs"),
+        )
+        .on_hover_text(self.strings.tr("show-stable-discs-tooltip"));
+        ui.checkbox(
+            &mut self.options.show_frontier_discs,
+            self.strings.tr("show-frontier-discs"),
+        )
+        .on_hover_text(self.strings.tr("show-frontier-discs-tooltip"));
+        ui.checkbox(
+            &mut self.options.show_evaluation_bar,
+            self.strings.tr("show-evaluation-bar"),
+        )
+        .on_hover_text(self.strings.tr("show-evaluation-bar-tooltip"));
+        ui.checkbox(
+            &mut self.options.show_coordinates,
+            self.strings.tr("show-coordinates"),
+        )
+        .on_hover_text(self.strings.tr("show-coordinates-tooltip"));
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.options.play_sounds, self.strings.tr("sounds"))
+                .on_hover_text(self.strings.tr("sounds-tooltip"));
+            ui.add(
+                egui::Slider::new(&mut self.options.sound_volume, 0.0..=1.0)
+                    .show_value(false)
+                    .text(self.strings.tr("volume")),
+            )
+            .on_hover_text(self.strings.tr("volume-tooltip"));
+        });
+    }
+
+    fn update_appearance_section(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("appearance"));
+            egui::ComboBox::from_id_salt("appearance")
+                .selected_text(self.strings.tr(self.options.appearance.key()))
+                .show_ui(ui, |ui| {
+                    for appearance in Appearance::ALL {
+                        ui.selectable_value(
+                            &mut self.options.appearance,
+                            appearance,
+                            self.strings.tr(appearance.key()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(self.strings.tr("appearance-tooltip"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("board-theme"));
+            egui::ComboBox::from_id_salt("board_theme")
+                .selected_text(self.strings.tr(self.options.board_theme.key()))
+                .show_ui(ui, |ui| {
+                    for name in BoardThemeName::ALL {
+                        ui.selectable_value(
+                            &mut self.options.board_theme,
+                            name,
+                            self.strings.tr(name.key()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(self.strings.tr("board-theme-tooltip"));
+        });
+        self.update_orientation_controls(ui);
+        ui.add(
+            egui::Slider::new(&mut self.options.ui_scale, UI_SCALE_RANGE)
+                .step_by(0.05)
+                .text(self.strings.tr("ui-scale"))
+                .custom_formatter(|scale, _| format!("{scale:.2}×")),
+        )
+        .on_hover_text(self.strings.tr("ui-scale-tooltip"));
+    }
+
+    fn update_orientation_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("rotation"));
+            for rotation in Rotation::ALL {
+                ui.radio_value(
+                    &mut self.options.orientation.rotation,
+                    rotation,
+                    format!("{}°", rotation.degrees()),
+                )
+                .on_hover_text(self.strings.tr("rotation-tooltip"));
+            }
+        });
+        ui.checkbox(
+            &mut self.options.orientation.mirrored,
+            self.strings.tr("mirror-board"),
+        )
+        .on_hover_text(self.strings.tr("mirror-board-tooltip"));
+        ui.checkbox(
+            &mut self.options.auto_orient,
+            self.strings.tr("auto-orient"),
+        )
+        .on_hover_text(self.strings.tr("auto-orient-tooltip"));
+    }
+
+    fn update_accessibility_section(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.options.valid_move_shapes,
+            self.strings.tr("valid-move-crosses"),
+        )
+        .on_hover_text(self.strings.tr("valid-move-crosses-tooltip"));
+        ui.checkbox(
+            &mut self.options.marker_shapes,
+            self.strings.tr("shaped-markers"),
+        )
+        .on_hover_text(self.strings.tr("shaped-markers-tooltip"));
+        ui.checkbox(
+            &mut self.options.disc_pattern,
+            self.strings.tr("disc-pattern"),
+        )
+        .on_hover_text(self.strings.tr("disc-pattern-tooltip"));
+        if ui
+            .checkbox(
+                &mut self.options.reduce_motion,
+                self.strings.tr("reduce-motion"),
+            )
+            .on_hover_text(self.strings.tr("reduce-motion-tooltip"))
+            .changed()
+        {
+            self.animations.clear();
+        }
+    }
+
+    fn update_settings_section(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(
+                !self.is_match_running() && self.auto_finish.is_none(),
+                egui::Button::new(self.strings.tr("reset-to-defaults")),
+            )
+            .on_hover_text(self.strings.tr("reset-to-defaults-tooltip"))
+            .on_disabled_hover_text(if self.auto_finish.is_some() {
+                self.strings.tr("disabled-auto-finish")
+            } else {
+                self.strings.tr("disabled-match")
+            })
+            .clicked()
+        {
+            self.reset_settings();
+        }
+        self.update_language_controls(ui);
+        self.update_preset_controls(ui);
+    }
+
+    fn update_language_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("language"));
+            egui::ComboBox::from_id_salt("language")
+                .selected_text(self.strings.name())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        let selected =
+                            self.strings.file().is_none() && self.strings.language() == language;
+                        // picking the language again drops a loaded file
+                        if ui.selectable_label(selected, language.label()).clicked() {
+                            self.options.language = language;
+                            self.strings = Strings::new(language);
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(self.strings.tr("language-tooltip"));
+        });
+        if !platform::HAS_FILE_SYSTEM {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.translation_path);
+            if ui
+                .button(self.strings.tr("load-translation"))
+                .on_hover_text(self.strings.tr("load-translation-tooltip"))
+                .clicked()
+            {
+                self.load_translation();
+            }
+        });
+        match &self.translation_result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
+        }
+    }
+
+    fn update_statistics_section(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.options.should_take_statistics,
+            self.strings.tr("take-statistics"),
+        )
+        .on_hover_text(self.strings.tr("take-statistics-tooltip"));
+        let (status, reason) = match (self.statistics_block, self.options.should_take_statistics) {
+            (None, true) => (
+                self.strings.tr("statistics-will-be-taken"),
+                self.strings
+                    .tr("statistics-will-be-taken-tooltip")
+                    .to_string(),
+            ),
+            (Some(block), true) => (
+                self.strings.tr("statistics-cannot-be-taken"),
+                self.statistics_block_reason(block),
+            ),
+            (_, false) => (
+                self.strings.tr("statistics-will-not-be-taken"),
+                self.strings
+                    .tr("statistics-will-not-be-taken-tooltip")
+                    .to_string(),
+            ),
+        };
+        ui.label(status).on_hover_text(reason);
+        ui.horizontal(|ui| {
+            let all_time = ui
+                .radio_value(
+                    &mut self.show_session_statistics,
+                    false,
+                    self.strings.tr("statistics-all-time"),
+                )
+                .on_hover_text(self.strings.tr("statistics-all-time-tooltip"))
+                .changed();
+            let session = ui
+                .radio_value(
+                    &mut self.show_session_statistics,
+                    true,
+                    self.strings.tr("statistics-session"),
+                )
+                .on_hover_text(self.strings.tr("statistics-session-tooltip"))
+                .changed();
+            if all_time || session {
+                self.refresh_statistics_views();
+            }
+            if ui
+                .add_enabled(
+                    !self.session_statistics.is_empty(),
+                    egui::Button::new(self.strings.tr("reset-session")),
+                )
+                .on_hover_text(self.strings.tr("reset-session-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-no-session-games"))
+                .clicked()
+            {
+                self.reset_session_statistics();
+            }
+        });
+        if ui
+            .add_enabled(
+                !self.shown_statistics().is_empty(),
+                egui::Button::new(self.strings.tr("copy-markdown")),
+            )
+            .on_hover_text(self.strings.tr("copy-markdown-tooltip"))
+            .on_disabled_hover_text(self.strings.tr("disabled-no-statistics"))
+            .clicked()
+        {
+            let date = format!("{} UTC", save::format_timestamp(GameRecord::now()));
+            let heading = self.strings.tr(if self.show_session_statistics {
+                "statistics-heading-session"
+            } else {
+                "statistics-heading"
+            });
+            ui.ctx()
+                .copy_text(self.shown_statistics().to_markdown(heading, &date));
+        }
+        if ui
+            .add_enabled(
+                // the numbering of matches and arenas starts over
+                !self.is_match_running()
+                    && !self.is_arena_running()
+                    && (!self.statistics_file.statistics.is_empty()
+                        || !self.statistics_file.solved_puzzles.is_empty()),
+                egui::Button::new(self.strings.tr("clear-statistics")),
+            )
+            .on_hover_text(self.strings.tr("clear-statistics-tooltip"))
+            .on_disabled_hover_text(if self.is_match_running() {
+                self.strings.tr("disabled-match")
+            } else if self.is_arena_running() {
+                self.strings.tr("disabled-arena")
+            } else {
+                self.strings.tr("disabled-no-statistics")
+            })
+            .clicked()
+        {
+            self.clearing_statistics = true;
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("min-games"));
+            ui.add(egui::DragValue::new(&mut self.options.min_statistics_games).range(1..=1000))
+                .on_hover_text(self.strings.tr("min-games-tooltip"));
+        });
+        self.update_statistics_table(ui);
+        self.update_rename_controls(ui);
+        self.update_head_to_head(ui);
+        self.update_opening_statistics(ui);
+        self.update_depth_plot(ui);
+        self.update_depth_gaps(ui);
+        self.update_game_history_controls(ui);
+
+        if !self.ratings.is_empty() {
+            ui.separator();
+            ui.label(self.strings.tr("ratings"))
+                .on_hover_text(self.strings.tr("ratings-tooltip"));
+            for (index, rating) in self.ratings.iter().enumerate() {
+                ui.label(self.strings.tr_args(
+                    if rating.provisional {
+                        "rating-provisional"
+                    } else {
+                        "rating"
+                    },
+                    &[
+                        ("index", &(index + 1)),
+                        ("name", &rating.name),
+                        ("rating", &format!("{:.0}", rating.rating)),
+                        ("games", &format!("{:.0}", rating.games)),
+                    ],
+                ));
+            }
+        }
+
+        let thinking_times = self.shown_statistics().thinking_times();
+        if !thinking_times.is_empty() {
+            ui.separator();
+            ui.label(self.strings.tr("thinking-times"))
+                .on_hover_text(self.strings.tr("thinking-times-tooltip"));
+            for (participant, time) in thinking_times {
+                ui.label(self.strings.tr_args(
+                    "thinking-times-entry",
+                    &[
+                        ("participant", &participant),
+                        ("time", &time),
+                        ("count", &time.moves),
+                    ],
+                ));
+            }
+        }
+    }
+
+    // an entry a row, sorted by the column whose header was clicked last and narrowed down by
+    // the filter, the rest of what is known of an entry on hovering over its row
+    fn update_statistics_table(&mut self, ui: &mut egui::Ui) {
+        if self.shown_statistics().is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("statistics-filter"));
+            ui.add(egui::TextEdit::singleline(&mut self.statistics_filter).desired_width(120.0))
+                .on_hover_text(self.strings.tr("statistics-filter-tooltip"));
+        });
+
+        let sort = self.options.statistics_sort;
+        let min_games = self.options.min_statistics_games as f64;
+        let weak_color = ui.visuals().weak_text_color();
+        let mut clicked_column = None;
+        let mut removed = None;
+        let rows = self.shown_statistics().rows(sort, &self.statistics_filter);
+        egui::ScrollArea::horizontal()
+            .id_salt("statistics-table")
+            .show(ui, |ui| {
+                egui::Grid::new("statistics-table")
+                    .striped(true)
+                    .num_columns(StatisticsColumn::ALL.len() + 1)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for column in StatisticsColumn::ALL {
+                            let name = match column {
+                                StatisticsColumn::Name => self.strings.tr("statistics-column-name"),
+                                StatisticsColumn::Games => {
+                                    self.strings.tr("statistics-column-games")
+                                }
+                                StatisticsColumn::Wins => self.strings.tr("statistics-column-wins"),
+                                StatisticsColumn::Ties => self.strings.tr("statistics-column-ties"),
+                                StatisticsColumn::Losses => {
+                                    self.strings.tr("statistics-column-losses")
+                                }
+                                StatisticsColumn::DiscDifferential => {
+                                    self.strings.tr("statistics-column-disc-differential")
+                                }
+                            };
+                            let arrow = match (sort.column == column, sort.descending) {
+                                (false, _) => "",
+                                (true, true) => " ⏷",
+                                (true, false) => " ⏶",
+                            };
+                            let header = |ui: &mut egui::Ui| {
+                                ui.selectable_label(sort.column == column, format!("{name}{arrow}"))
+                            };
+                            let response = if column == StatisticsColumn::Name {
+                                header(ui)
+                            } else {
+                                right_aligned(ui, header)
+                            };
+                            if response
+                                .on_hover_text(self.strings.tr("statistics-sort-tooltip"))
+                                .clicked()
+                            {
+                                clicked_column = Some(column);
+                            }
+                        }
+                        ui.end_row();
+
+                        for row in rows.iter() {
+                            let statistic = row.statistic;
+                            let details = self.strings.tr_args(
+                                "statistics-row-tooltip",
+                                &[(
+                                    "details",
+                                    &statistic_details(
+                                        &self.strings,
+                                        statistic,
+                                        row.matchup.map(|matchup| &matchup.participants),
+                                    ),
+                                )],
+                            );
+                            let text = |text: String| {
+                                let text = egui::RichText::new(text);
+                                if statistic.games() < min_games {
+                                    text.color(weak_color)
+                                } else {
+                                    text
+                                }
+                            };
+                            if ui
+                                .small_button("✕")
+                                .on_hover_text(self.strings.tr("delete-statistics-entry-tooltip"))
+                                .clicked()
+                            {
+                                removed = Some((row.name.clone(), row.matchup.cloned()));
+                            }
+                            let name = if statistic.is_partial() {
+                                self.strings
+                                    .tr_args("statistics-partial", &[("name", &row.name)])
+                            } else {
+                                row.name.clone()
+                            };
+                            ui.label(text(name)).on_hover_text(&details);
+                            let percent = |ratio: f64| format!("{:.1}", ratio * 100.0);
+                            let numbers = [
+                                format!("{:.0}", statistic.games()),
+                                percent(statistic.win_ratio()),
+                                percent(statistic.tie_ratio()),
+                                percent(statistic.loss_ratio()),
+                                statistic
+                                    .average_disc_differential()
+                                    .map_or("–".to_string(), |differential| {
+                                        format!("{differential:+.1}")
+                                    }),
+                            ];
+                            for number in numbers {
+                                right_aligned(ui, |ui| ui.label(text(number)))
+                                    .on_hover_text(&details);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(column) = clicked_column {
+            self.options.statistics_sort = sort.clicked(column);
+        }
+        match removed {
+            Some((_, Some(matchup))) => self.remove_matchup(&matchup),
+            Some((name, None)) => self.remove_statistics_summary(&name),
+            None => {}
+        }
+    }
+
+    // a section opens and closes on a click on its header, and stays as it was left
+    fn show_panel_section(&mut self, ui: &mut egui::Ui, section: PanelSection) {
+        if section == PanelSection::Network && !platform::HAS_NETWORK {
+            return;
+        }
+        let open = self.options.open_sections.is_open(section);
+        let title = egui::RichText::new(self.strings.tr(section.key())).strong();
+        let response = egui::CollapsingHeader::new(title)
+            .id_salt(section.key())
+            .open(Some(open))
+            .show(ui, |ui| match section {
+                PanelSection::Moves => {
+                    self.update_move_list(ui);
+                    self.update_score_graph(ui);
+                }
+                PanelSection::Players => self.update_players_section(ui),
+                PanelSection::Control => self.update_control_section(ui),
+                PanelSection::Replay => self.update_replay_controls(ui),
+                PanelSection::Analysis => {
+                    self.update_analysis_controls(ui);
+                    if platform::HAS_FILE_SYSTEM {
+                        ui.separator();
+                        self.update_wthor_controls(ui);
+                    }
+                }
+                PanelSection::ExportImage => self.update_image_export_controls(ui),
+                PanelSection::Clocks => self.update_clock_controls(ui),
+                PanelSection::Matches => {
+                    self.update_match_controls(ui);
+                    self.update_arena_controls(ui);
+                    ui.separator();
+                    self.update_multi_board_controls(ui);
+                }
+                PanelSection::Puzzles => self.update_puzzle_controls(ui),
+                PanelSection::PositionEditor => self.update_editor_controls(ui),
+                PanelSection::Network => self.update_network_controls(ui),
+                PanelSection::Flow => self.update_flow_section(ui),
+                PanelSection::Help => self.update_help_section(ui),
+                PanelSection::Appearance => self.update_appearance_section(ui),
+                PanelSection::Accessibility => self.update_accessibility_section(ui),
+                PanelSection::Shortcuts => self.update_shortcut_controls(ui),
+                PanelSection::EventLog => self.update_event_log_controls(ui),
+                PanelSection::Settings => self.update_settings_section(ui),
+                PanelSection::Statistics => self.update_statistics_section(ui),
+            });
+        if response.header_response.clicked() {
+            self.options.open_sections.set_open(section, !open);
+        }
+    }
+
+    fn update_shortcut_controls(&mut self, ui: &mut egui::Ui) {
+        for (shortcut, _, description) in SHORTCUTS {
+            ui.label(format!(
+                "{}: {}",
+                ui.ctx().format_shortcut(&shortcut),
+                self.strings.tr(description)
+            ));
+        }
+    }
+
+    fn update_seed_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr_args("seed", &[("seed", &self.seed)]))
+                .on_hover_text(self.strings.tr("seed-tooltip"));
+            if ui
+                .small_button(self.strings.tr("copy"))
+                .on_hover_text(self.strings.tr("copy-seed-tooltip"))
+                .clicked()
+            {
+                ui.ctx().copy_text(self.seed.to_string());
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.is_action_enabled(Action::Restart),
+                    egui::Button::new(self.strings.tr("replay-same-seed")),
+                )
+                .on_hover_text(self.strings.tr("replay-same-seed-tooltip"))
+                .on_disabled_hover_text(self.action_disabled_reason(Action::Restart))
+                .clicked()
+            {
+                self.pinned_seed = Some(self.seed);
+                self.restart();
+            }
+            if self.pinned_seed.is_some()
+                && ui
+                    .button(self.strings.tr("unpin-seed"))
+                    .on_hover_text(self.strings.tr("unpin-seed-tooltip"))
+                    .clicked()
+            {
+                self.pinned_seed = None;
+            }
+        });
+    }
+
+    fn update_event_log_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.event_log.is_empty(),
+                    egui::Button::new(self.strings.tr("copy-log")),
+                )
+                .on_hover_text(self.strings.tr("copy-log-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-log-empty"))
+                .clicked()
+            {
+                ui.ctx().copy_text(self.event_log.to_text());
+            }
+            if ui
+                .add_enabled(
+                    !self.event_log.is_empty(),
+                    egui::Button::new(self.strings.tr("clear")),
+                )
+                .on_hover_text(self.strings.tr("clear-log-tooltip"))
+                .on_disabled_hover_text(self.strings.tr("disabled-log-empty"))
+                .clicked()
+            {
+                self.event_log.clear();
+            }
+        });
+        ui.checkbox(
+            &mut self.options.clear_log_on_reset,
+            self.strings.tr("clear-log-on-restart"),
+        )
+        .on_hover_text(self.strings.tr("clear-log-on-restart-tooltip"));
+        egui::ScrollArea::vertical()
+            .id_salt("event_log")
+            .max_height(150.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in self.event_log.lines() {
+                    ui.label(egui::RichText::new(line).monospace().small());
+                }
+            });
+    }
+
+    // the local human to move, who may resign
+    fn resigning_player(&self) -> Option<Player> {
+        match self.current_phase {
+            Phase::Turn(player)
+                if self.replay.is_none()
+                    && self.editor.is_none()
+                    && self.puzzle.is_none()
+                    && self.network.is_none()
+                    && !self.player_options[player as usize].ai_enabled =>
+            {
+                Some(player)
+            }
+            _ => None,
+        }
+    }
+
+    // call this from the UI thread
+    fn resign(&mut self) {
+        let Some(player) = self.resigning_player() else {
+            return;
+        };
+
+        // the game is over, a reply still on its way is dropped when it arrives
+        self.ai_requests = [None, None];
+        self.pass_notice = None;
+        self.log(
+            self.strings
+                .tr_args("log-resign", &[("player", &self.player_name(player))]),
+        );
+        self.end_game(Outcome::Won(player.opponent(), WinReason::Resignation));
+    }
+
+    fn end_game(&mut self, outcome: Outcome) {
+        self.current_phase = Phase::from(outcome);
+        let message = match outcome {
+            Outcome::Won(player, reason) => self.strings.tr_args(
+                match reason {
+                    WinReason::Disks => "log-won",
+                    WinReason::Time => "log-won-on-time",
+                    WinReason::Resignation => "log-won-by-resignation",
+                },
+                &[("player", &self.player_name(player))],
+            ),
+            Outcome::Tie => self.strings.tr("log-tie").to_string(),
+        };
+        self.log(message);
+
+        self.take_statistics(outcome);
+        self.stop_auto_finish();
+        if let Some(match_run) = self
+            .match_run
+            .as_mut()
+            .filter(|match_run| match_run.running)
+        {
+            let (black_count, white_count) = count_pieces(&self.board);
+            match_run
+                .tally
+                .record(&outcome, black_count, white_count, self.history.plies.len());
+            // partial until the last game is in
+            for statistics in [
+                &mut self.statistics_file.statistics,
+                &mut self.session_statistics,
+            ] {
+                statistics.add_match(match_run.name.clone(), &match_run.tally);
+            }
+            self.statistics_changed();
+        }
+        save::clear_autosave();
+
+        // only used if auto_restart is enabled
+        self.game_over_at = Some(Instant::now());
+        self.result_overlay_closed = false;
+        self.animate(AnimationKey::GameOver, GAME_OVER_FADE_IN);
+        self.play_sound(Sound::GameOver);
+    }
+
+    // call this from the UI thread
+    // matches and arenas are numbered from 1 again
+    fn clear_statistics(&mut self) {
+        self.statistics_file = StatisticsFile::default();
+        self.session_statistics = Statistics::default();
+        self.refresh_statistics_views();
+        self.matches_played = 0;
+        self.arenas_run = 0;
+        self.game_records.clear();
+        self.openings = OpeningTree::default();
+        self.game_history_filter = None;
+        save::clear_statistics();
+        save::clear_game_records();
+        self.log(self.strings.tr("log-statistics-cleared").to_string());
+    }
+
+    fn remove_matchup(&mut self, matchup: &Matchup) {
+        for statistics in [
+            &mut self.statistics_file.statistics,
+            &mut self.session_statistics,
+        ] {
+            statistics.remove_matchup(matchup);
+        }
+        self.log(
+            self.strings
+                .tr_args("log-statistics-deleted", &[("name", matchup)]),
+        );
+        self.statistics_changed();
+    }
+
+    fn remove_statistics_summary(&mut self, name: &str) {
+        for statistics in [
+            &mut self.statistics_file.statistics,
+            &mut self.session_statistics,
+        ] {
+            statistics.data.remove(name);
+        }
+        self.log(
+            self.strings
+                .tr_args("log-statistics-deleted", &[("name", &name)]),
+        );
+        self.statistics_changed();
+    }
+
+    fn rename_participant(&mut self) {
+        let Some(from) = self.rename_from.take() else {
+            return;
+        };
+        let to = Participant::parse(self.rename_to.trim());
+        for statistics in [
+            &mut self.statistics_file.statistics,
+            &mut self.session_statistics,
+        ] {
+            statistics.rename(&from, &to);
+        }
+        self.log(
+            self.strings
+                .tr_args("log-participant-renamed", &[("from", &from), ("to", &to)]),
+        );
+        self.rename_to.clear();
+        self.statistics_changed();
+    }
+
+    fn load_game_record(&mut self, index: usize) {
+        let record = &self.game_records[index];
+        let result = notation::parse_transcript(&record.transcript)
+            .map_err(|e| e.to_string())
+            .and_then(|moves| {
+                self.referee
+                    .replay(&Board::default(), record.start_player, &moves)
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(replay) => {
+                self.load_replay(replay);
+                self.show_replay_position(0);
+            }
+            Err(e) => self.log(
+                self.strings
+                    .tr_args("log-record-replay-failed", &[("error", &e)]),
+            ),
+        }
+    }
+
+    // every matchup of two depths of the AI type is a point, further right the further apart
+    // the depths, larger the more games it has
+    fn update_depth_plot(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.strings.tr("depth-plot")).show(ui, |ui| {
+            egui::ComboBox::from_id_salt("depth_plot_ai_type")
+                .selected_text(format!("{:?}", self.depth_plot_ai_type))
+                .show_ui(ui, |ui| {
+                    // the random AI plays the same at every depth
+                    for ai_type in [AiType::Minimax, AiType::AlphaBeta, AiType::Positional] {
+                        ui.selectable_value(
+                            &mut self.depth_plot_ai_type,
+                            ai_type,
+                            format!("{ai_type:?}"),
+                        );
+                    }
+                });
+
+            let points = self
+                .shown_statistics()
+                .depth_points(self.depth_plot_ai_type);
+            if points.is_empty() {
+                ui.label(self.strings.tr_args(
+                    "depth-plot-empty",
+                    &[("ai", &format!("{:?}", self.depth_plot_ai_type))],
+                ));
+                return;
+            }
+
+            // the differences from 0 to the largest possible one, with half a step of margin
+            let difference_range = MAX_RECURSION_DEPTH as f32;
+            let (rect, response) = ui.allocate_exact_size(
+                egui::vec2(ui.available_width(), DEPTH_PLOT_HEIGHT),
+                egui::Sense::hover(),
+            );
+            let to_pos = |difference: usize, win_ratio: f64| {
+                egui::pos2(
+                    rect.left() + (difference as f32 + 0.5) / difference_range * rect.width(),
+                    rect.bottom() - win_ratio as f32 * rect.height(),
+                )
+            };
+
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+            // an even score
+            painter.hline(
+                rect.x_range(),
+                rect.center().y,
+                egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+            );
+            let radius = |games: f64| (2.0 + games.sqrt() as f32).min(10.0);
+            for point in points.iter() {
+                painter.circle_filled(
+                    to_pos(point.depth_difference(), point.win_ratio),
+                    radius(point.games),
+                    egui::Color32::from_rgb(70, 130, 220).gamma_multiply(0.7),
+                );
+            }
+            ui.label(
+                self.strings
+                    .tr_args("depth-plot-axes", &[("max", &(MAX_RECURSION_DEPTH - 1))]),
+            );
+
+            let Some(position) = response.hover_pos() else {
+                return;
+            };
+            let hovered = points.iter().min_by(|a, b| {
+                let distance = |point: &DepthPoint| {
+                    to_pos(point.depth_difference(), point.win_ratio).distance(position)
+                };
+                distance(a).total_cmp(&distance(b))
+            });
+            if let Some(point) = hovered.filter(|point| {
+                to_pos(point.depth_difference(), point.win_ratio).distance(position)
+                    <= radius(point.games) + 3.0
+            }) {
+                response.on_hover_text_at_pointer(self.strings.tr_args(
+                    "depth-plot-point",
+                    &[
+                        ("deeper", &point.depths[0]),
+                        ("shallower", &point.depths[1]),
+                        ("ratio", &format!("{:.1}", point.win_ratio * 100.0)),
+                        ("games", &format!("{:.0}", point.games)),
+                    ],
+                ));
+            }
+        });
+    }
+
+    // how many extra plies of search it takes to win most games, for the AI of the plot
+    fn update_depth_gaps(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.strings.tr("depth-gaps")).show(ui, |ui| {
+            let ai_type = self.depth_plot_ai_type;
+            let gaps = self.shown_statistics().depth_gaps(ai_type);
+            if gaps.is_empty() {
+                ui.label(
+                    self.strings
+                        .tr_args("depth-plot-empty", &[("ai", &format!("{ai_type:?}"))]),
+                );
+                return;
+            }
+
+            let min_games = self.options.min_statistics_games as f64;
+            egui::Grid::new("depth_gaps").striped(true).show(ui, |ui| {
+                ui.label(self.strings.tr("depth-gap"));
+                ui.label(self.strings.tr("depth-gap-deeper-wins"));
+                ui.label(self.strings.tr("statistics-column-games"));
+                ui.end_row();
+                for gap in gaps.iter() {
+                    let depths: Vec<String> = gap
+                        .depths
+                        .iter()
+                        .map(|[deeper, shallower]| {
+                            self.strings.tr_args(
+                                "depth-gap-pair",
+                                &[("deeper", deeper), ("shallower", shallower)],
+                            )
+                        })
+                        .collect();
+                    let mut hover_text = depths.join("\n");
+                    let mut texts = [
+                        egui::RichText::new(format!("+{}", gap.depth_difference)),
+                        egui::RichText::new(format!("{:.1}%", gap.win_ratio * 100.0)),
+                        egui::RichText::new(format!("{:.0}", gap.games)),
+                    ];
+                    if gap.games < min_games {
+                        hover_text.push('\n');
+                        hover_text.push_str(self.strings.tr("depth-gap-unreliable"));
+                        texts = texts.map(|text| text.color(ui.visuals().weak_text_color()));
+                    }
+                    for text in texts {
+                        ui.label(text).on_hover_text(&hover_text);
+                    }
+                    ui.end_row();
+                }
+            });
+
+            match DepthGap::smallest_reaching(&gaps, DEPTH_GAP_WIN_RATIO, min_games) {
+                Some(gap) => ui.label(self.strings.tr_args(
+                    "depth-gap-reached",
+                    &[
+                        ("ai", &format!("{ai_type:?}")),
+                        ("ratio", &format!("{:.0}", DEPTH_GAP_WIN_RATIO * 100.0)),
+                        ("plies", &gap.depth_difference),
+                    ],
+                )),
+                None => ui.label(self.strings.tr_args(
+                    "depth-gap-none",
+                    &[
+                        ("ratio", &format!("{:.0}", DEPTH_GAP_WIN_RATIO * 100.0)),
+                        ("games", &self.options.min_statistics_games),
+                    ],
+                )),
+            };
+        });
+    }
+
+    // narrows the game history down to the games of the two, in the standard variant unless
+    // only the other one was recorded
+    fn show_matchup_games(&mut self, participants: [Participant; 2]) {
+        let (matchup, _) = Matchup::new(participants, false);
+        let recorded = self
+            .game_records
+            .iter()
+            .find(|record| record.matchup.participants == matchup.participants);
+        self.game_history_filter = Some(match recorded {
+            Some(record) if record.matchup != matchup => record.matchup.clone(),
+            _ => matchup,
+        });
+        self.open_game_history = true;
+    }
+
+    fn refresh_openings(&mut self) {
+        if self.openings.plies == self.options.opening_plies {
+            return;
+        }
+        self.openings = OpeningTree::new(self.options.opening_plies);
+        for record in self.game_records.iter() {
+            add_opening(&mut self.openings, record);
+        }
+    }
+
+    // the recorded openings of the current game that the valid moves lead into, with the score
+    // of the side to move after each
+    fn opening_scores(&mut self) -> Vec<(Move, f64)> {
+        if self.history.start_board.grid != Board::default().grid {
+            return Vec::new();
+        }
+        self.refresh_openings();
+        let mut line: Vec<Move> = self
+            .history
+            .plies
+            .iter()
+            .filter_map(|ply| ply.next_move())
+            .collect();
+        if line.len() >= self.openings.plies {
+            return Vec::new();
+        }
+        let mut scores = Vec::new();
+        for next_move in self.valid_moves.iter() {
+            line.push(next_move);
+            if let Some(opening) = self.openings.lines.get(&canonical_opening(&line)) {
+                scores.push((next_move, opening.record.score()));
+            }
+            line.pop();
+        }
+        scores
+    }
+
+    // the most played continuations first, every line opening into its own continuations
+    fn update_opening_statistics(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.strings.tr("openings")).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(self.strings.tr("opening-plies"));
+                ui.add(egui::DragValue::new(&mut self.options.opening_plies).range(1..=20))
+                    .on_hover_text(self.strings.tr("opening-plies-tooltip"));
+                ui.checkbox(
+                    &mut self.options.show_opening_scores,
+                    self.strings.tr("show-opening-scores"),
+                )
+                .on_hover_text(self.strings.tr("show-opening-scores-tooltip"));
+            });
+            self.refresh_openings();
+            if self.openings.lines.is_empty() {
+                ui.label(self.strings.tr("openings-empty"));
+                return;
+            }
+            ui.label(self.strings.tr("openings-mirrored"))
+                .on_hover_text(self.strings.tr("openings-mirrored-tooltip"));
+            egui::ScrollArea::vertical()
+                .id_salt("openings")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    opening_rows(ui, &self.strings, &self.openings, &[])
+                });
+        });
+    }
+
+    // the row participant's score against the column participant's in every cell
+    fn update_head_to_head(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.strings.tr("head-to-head")).show(ui, |ui| {
+            let participants = &self.head_to_head.participants;
+            if participants.len() < 2 {
+                ui.label(self.strings.tr("head-to-head-empty"));
+                return;
+            }
+
+            let mut selected = None;
+            egui::ScrollArea::both()
+                .id_salt("head_to_head")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("head_to_head_grid").show(ui, |ui| {
+                        // the columns are numbered after the rows to stay narrow
+                        ui.label("");
+                        for (column, participant) in participants.iter().enumerate() {
+                            ui.label((column + 1).to_string())
+                                .on_hover_text(participant.to_string());
+                        }
+                        ui.end_row();
+
+                        for (row, (participant, cells)) in participants
+                            .iter()
+                            .zip(self.head_to_head.cells.iter())
+                            .enumerate()
+                        {
+                            ui.label(format!("{}. {participant}", row + 1));
+                            for (column, cell) in cells.iter().enumerate() {
+                                let Some(cell) = cell else {
+                                    ui.label("");
+                                    continue;
+                                };
+                                let text = egui::RichText::new(cell.to_string())
+                                    .color(egui::Color32::BLACK)
+                                    .background_color(score_color(cell.score()));
+                                let clicked = ui
+                                    .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                    .on_hover_text(self.strings.tr_args(
+                                        "head-to-head-cell",
+                                        &[
+                                            ("participant", participant),
+                                            ("opponent", &participants[column]),
+                                            ("wins", &cell.wins),
+                                            ("ties", &cell.ties),
+                                            ("losses", &cell.losses),
+                                            ("score", &format!("{:.1}", cell.score() * 100.0)),
+                                        ],
+                                    ))
+                                    .clicked();
+                                if clicked {
+                                    selected =
+                                        Some([participant.clone(), participants[column].clone()]);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            if let Some(pair) = selected {
+                self.show_matchup_games(pair);
+            }
+        });
+    }
+
+    fn update_game_history_controls(&mut self, ui: &mut egui::Ui) {
+        let open = self.open_game_history.then_some(true);
+        self.open_game_history = false;
+        egui::CollapsingHeader::new(self.strings.tr("game-history"))
+            .open(open)
+            .show(ui, |ui| {
+                let mut matchups: Vec<&Matchup> = self
+                    .game_records
+                    .iter()
+                    .map(|record| &record.matchup)
+                    .collect();
+                matchups.sort();
+                matchups.dedup();
+                let selected_text = self
+                    .game_history_filter
+                    .as_ref()
+                    .map_or(self.strings.tr("all-matchups").to_string(), |matchup| {
+                        matchup.to_string()
+                    });
+                let mut filter = self.game_history_filter.clone();
+                egui::ComboBox::from_id_salt("game_history_filter")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut filter, None, self.strings.tr("all-matchups"));
+                        for matchup in matchups {
+                            ui.selectable_value(
+                                &mut filter,
+                                Some(matchup.clone()),
+                                matchup.to_string(),
+                            );
+                        }
+                    });
+                self.game_history_filter = filter;
+
+                // the latest first
+                let shown: Vec<usize> = (0..self.game_records.len())
+                    .rev()
+                    .filter(|&index| {
+                        self.game_history_filter
+                            .as_ref()
+                            .is_none_or(|matchup| self.game_records[index].matchup == *matchup)
+                    })
+                    .collect();
+                if shown.is_empty() {
+                    ui.label(self.strings.tr("game-history-empty"));
+                    return;
+                }
+                let mut selected_game = None;
+                egui::ScrollArea::vertical()
+                    .id_salt("game_history")
+                    .max_height(150.0)
+                    .show_rows(ui, 18.0, shown.len(), |ui, rows| {
+                        for row in rows {
+                            let record = &self.game_records[shown[row]];
+                            let result = match record.winner {
+                                Some(player) => self.strings.tr_args(
+                                    "status-won",
+                                    &[("player", &self.player_name(player))],
+                                ),
+                                None => self.strings.tr("game-history-tie").to_string(),
+                            };
+                            let label = self.strings.tr_args(
+                                "game-history-row",
+                                &[
+                                    ("date", &record.date()),
+                                    ("black", &record.players[Player::Black as usize]),
+                                    ("white", &record.players[Player::White as usize]),
+                                    ("black_count", &record.disc_counts[0]),
+                                    ("white_count", &record.disc_counts[1]),
+                                    ("result", &result),
+                                ],
+                            );
+                            if ui
+                                .selectable_label(false, label)
+                                .on_hover_text(self.strings.tr("game-history-replay-tooltip"))
+                                .clicked()
+                            {
+                                selected_game = Some(shown[row]);
+                            }
+                        }
+                    });
+                if let Some(index) = selected_game {
+                    self.load_game_record(index);
+                }
+            });
+    }
+
+    fn update_rename_controls(&mut self, ui: &mut egui::Ui) {
+        let participants = self.shown_statistics().participants();
+        if participants.is_empty() {
+            return;
+        }
+        if self
+            .rename_from
+            .as_ref()
+            .is_some_and(|from| !participants.contains(from))
+        {
+            self.rename_from = None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("rename-participant"));
+            let selected_text = self
+                .rename_from
+                .as_ref()
+                .map_or(self.strings.tr("none-selected").to_string(), |from| {
+                    from.to_string()
+                });
+            egui::ComboBox::from_id_salt("rename_participant")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for participant in participants {
+                        let text = participant.to_string();
+                        ui.selectable_value(&mut self.rename_from, Some(participant), text);
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label(self.strings.tr("rename-to"));
+            ui.text_edit_singleline(&mut self.rename_to);
+            if ui
+                .add_enabled(
+                    self.rename_from.is_some() && !self.rename_to.trim().is_empty(),
+                    egui::Button::new(self.strings.tr("rename-participant")),
+                )
+                .on_hover_text(self.strings.tr("rename-participant-tooltip"))
+                .clicked()
+            {
+                self.rename_participant();
+            }
+        });
+    }
+
+    fn shown_statistics(&self) -> &Statistics {
+        if self.show_session_statistics {
+            &self.session_statistics
+        } else {
+            &self.statistics_file.statistics
+        }
+    }
+
+    fn refresh_statistics_views(&mut self) {
+        self.ratings = self.shown_statistics().ratings();
+        self.head_to_head = self.shown_statistics().head_to_head();
+    }
+
+    // call this after every new result
+    fn statistics_changed(&mut self) {
+        self.refresh_statistics_views();
+        save::write_statistics(&self.statistics_file);
+    }
+
+    // the all-time statistics stay as they are
+    fn reset_session_statistics(&mut self) {
+        self.session_statistics = Statistics::default();
+        self.refresh_statistics_views();
+        self.log(self.strings.tr("log-session-statistics-reset").to_string());
+    }
+
+    // the option as it is when the game ends decides, a game is never counted later
+    fn take_statistics(&mut self, outcome: Outcome) {
+        if self.can_take_statistics() && self.options.should_take_statistics {
+            let (black_count, white_count) = count_pieces(&self.board);
+            let result = GameResult {
+                outcome,
+                disc_counts: [black_count, white_count],
+                plies: self.history.plies.len(),
+            };
+            let transcript = notation::transcript(&self.history, false);
+            self.add_statistics_datum(
+                result,
+                self.history.start_player,
+                transcript,
+                Some(self.seed),
+                self.thinking_times,
+            );
+        }
+        self.statistics_block = Some(StatisticsBlock::Counted);
+    }
+
+    // a game of the current player options that started with `start_player`, from the standard
+    // position
+    fn add_statistics_datum(
+        &mut self,
+        result: GameResult,
+        start_player: Player,
+        transcript: String,
+        seed: Option<u64>,
+        thinking_times: [ThinkingTime; 2],
+    ) {
+        let players = self.player_options.map(|options| options.participant());
+        let record = GameRecord {
+            timestamp: GameRecord::now(),
+            matchup: Matchup::new(players.clone(), start_player == Player::White).0,
+            players: players.clone(),
+            start_player,
+            transcript,
+            disc_counts: result.disc_counts,
+            winner: match result.outcome {
+                Outcome::Won(player, _) => Some(player),
+                Outcome::Tie => None,
+            },
+            seed,
+        };
+        save::append_game_record(&record);
+        add_opening(&mut self.openings, &record);
+        self.game_records.push(record);
+
+        for statistics in [
+            &mut self.statistics_file.statistics,
+            &mut self.session_statistics,
+        ] {
+            statistics.add_game(players.clone(), start_player, &result);
+            statistics.add_thinking_times(players.clone(), start_player, thinking_times);
+        }
+        self.statistics_changed();
+    }
+
+    fn update_player_options_controls(&mut self, ui: &mut egui::Ui, player: Player) {
+        section_header(
+            ui,
+            &self
+                .strings
+                .tr_args("player-options", &[("player", &self.player_name(player))]),
+        );
+        if self.player_options.iter().all(|options| options.ai_enabled) {
+            self.show_thinking_indicator(ui, player, self.strings.tr("thinking"));
+        }
+        if let Some(clock) = &self.clock {
+            let text = self.strings.tr_args(
+                "player-clock",
+                &[("time", &clock::format_remaining(clock.remaining(player)))],
+            );
+            if clock.is_low(player) {
+                ui.colored_label(ui.visuals().error_fg_color, text);
+            } else {
+                ui.label(text);
+            }
+        }
+        if ui
+            .checkbox(
+                &mut self.player_options[player as usize].ai_enabled,
+                self.strings.tr("enable-ai"),
+            )
+            .on_hover_text(self.strings.tr("enable-ai-tooltip"))
+            .changed()
+        {
+            self.ai_setting_changed(SettingChange::AiEnabled(player));
+        }
+        ui.label(self.strings.tr("ai-type"));
+        self.player_options[player as usize].ai_type = self.update_ai_type_radio_buttons(
+            ui,
+            self.player_options[player as usize].ai_type,
+            player,
+        );
+        // a slider for the minimax algorithm recursion depth
+        ui.label(self.strings.tr("ai-recursion-depth"));
+        if ui
+            .add(
+                egui::Slider::new(
+                    &mut self.player_options[player as usize].ai_recursion_depth,
+                    1..=MAX_RECURSION_DEPTH,
+                )
+                .text(""),
+            )
+            .on_hover_text(self.strings.tr("ai-recursion-depth-tooltip"))
+            .changed()
+            && self.player_options[player as usize].ai_enabled
+            && self.player_options[player as usize].ai_type != AiType::Random
+        {
+            self.ai_setting_changed(SettingChange::AiDepth(player));
+        }
+    }
+
+    // closure that handles the dynamic depth options
+    fn update_ai_type_radio_buttons(
+        &mut self,
+        ui: &mut egui::Ui,
+        ai_type: AiType,
+        player: Player,
+    ) -> AiType {
+        let mut result = ai_type;
+
+        // Display dynamic depth options in a loop
+        for (option, text) in AI_TYPE_NAMES {
+            if ui
+                .radio(ai_type == option, text)
+                .on_hover_text(self.ai_type_tooltip(option))
+                .clicked()
+            {
+                result = option;
+
+                if self.player_options[player as usize].ai_enabled {
+                    self.ai_setting_changed(SettingChange::AiType(player));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+// the largest board that fits whole in the available rect and its square size, leaving room for
+// the evaluation bar on the right and, with coordinates, for their frame on every side, all of it
+// centered in the rect
+fn board_layout(rect: egui::Rect, bar_space: f32, coordinates: bool) -> (egui::Rect, f32) {
+    let margin = if coordinates { COORDINATE_MARGIN } else { 0.0 };
+    let squares = Board::SIZE as f32 + 2.0 * margin;
+    let square_size = ((rect.width() - bar_space).min(rect.height()) / squares).max(0.0);
+    let framed_size = square_size * squares;
+    let origin = rect.center() - egui::vec2(framed_size + bar_space, framed_size) / 2.0;
+    let board_rect = egui::Rect::from_min_size(
+        origin + egui::Vec2::splat(square_size * margin),
+        egui::Vec2::splat(square_size * Board::SIZE as f32),
+    );
+    (board_rect, square_size)
+}
+
+// the names of the lines of squares shown as columns above and below the board and of those
+// shown as rows beside it, the letters of the columns and the numbers of the rows unless the
+// board is turned, placed by the squares they name
+fn draw_coordinates(
+    ui: &egui::Ui,
+    square_size: f32,
+    orientation: Orientation,
+    square_rect: impl Fn(usize, usize) -> egui::Rect,
+) {
+    let offset = square_size * (1.0 + COORDINATE_MARGIN) / 2.0;
+    let font = egui::FontId::proportional(square_size * 0.3);
+    let color = ui.visuals().text_color();
+    let last = Board::SIZE - 1;
+    let view_rect = |view| {
+        let (row, col) = orientation.to_board(view);
+        square_rect(row, col)
+    };
+    for index in 0..Board::SIZE {
+        let column = orientation.view_column(index).label();
+        let row = orientation.view_row(index).label();
+        let labels = [
+            (
+                view_rect((0, index)).center(),
+                egui::vec2(0.0, -offset),
+                &column,
+            ),
+            (
+                view_rect((last, index)).center(),
+                egui::vec2(0.0, offset),
+                &column,
+            ),
+            (
+                view_rect((index, 0)).center(),
+                egui::vec2(-offset, 0.0),
+                &row,
+            ),
+            (
+                view_rect((index, last)).center(),
+                egui::vec2(offset, 0.0),
+                &row,
+            ),
+        ];
+        for (center, offset, label) in labels {
+            ui.painter().text(
+                center + offset,
+                egui::Align2::CENTER_CENTER,
+                label,
+                font.clone(),
+                color,
+            );
+        }
+    }
+}
+
+// the square of the view under the position, None off the board
+fn square_at(board_rect: egui::Rect, position: egui::Pos2) -> Option<Move> {
+    if !board_rect.contains(position) {
+        return None;
+    }
+    let square_size = board_rect.width() / Board::SIZE as f32;
+    let row = ((position.y - board_rect.top()) / square_size) as usize;
+    let col = ((position.x - board_rect.left()) / square_size) as usize;
+    (row < Board::SIZE && col < Board::SIZE).then_some((row, col))
+}
+
+// the move typed for the player, or what is wrong with it
+fn typed_move(
+    strings: &Strings,
+    referee: &mut Referee,
+    board: &Board,
+    player: Player,
+    text: &str,
+) -> Result<Move, String> {
+    let text = text.trim();
+    let next_move = notation::parse_move(text)
+        .ok_or_else(|| strings.tr_args("move-error-not-a-square", &[("text", &text)]))?;
+    if !referee.validate_move(board, player, next_move) {
+        return Err(MoveError::IllegalMove(next_move).text(strings));
+    }
+    Ok(next_move)
+}
+
+// the title of a group of controls in the side panel
+fn section_header(ui: &mut egui::Ui, text: &str) {
+    ui.label(
+        egui::RichText::new(text)
+            .strong()
+            .color(ui.visuals().strong_text_color()),
+    );
+}
+
+// green that reads on a dark or a light panel
+fn success_color(ui: &egui::Ui) -> egui::Color32 {
+    if ui.visuals().dark_mode {
+        egui::Color32::LIGHT_GREEN
+    } else {
+        egui::Color32::DARK_GREEN
+    }
+}
+
+// the shortcuts of the action, for the hover text of its button
+fn shortcut_text(ctx: &egui::Context, action: Action) -> String {
+    SHORTCUTS
+        .iter()
+        .filter(|(_, other, _)| *other == action)
+        .map(|(shortcut, _, _)| ctx.format_shortcut(shortcut))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// the tooltip of a control with the shortcuts of its action after it
+fn with_shortcut(text: &str, ctx: &egui::Context, action: Action) -> String {
+    format!("{text} ({})", shortcut_text(ctx, action))
+}
+
+// a disc of the theme's color with its outline, `radius` narrowed horizontally while it turns
+// a white disc gets a ring in black's color with `pattern`
+fn draw_disc(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    radius: egui::Vec2,
+    player: Player,
+    theme: &BoardTheme,
+    pattern: bool,
+) {
+    painter.add(egui::Shape::ellipse_filled(
+        center,
+        radius,
+        theme.disc(player),
+    ));
+    if theme.disc_outline != egui::Color32::TRANSPARENT {
+        painter.add(egui::Shape::ellipse_stroke(
+            center,
+            radius,
+            egui::Stroke::new(1.0, theme.disc_outline),
+        ));
+    }
+    if pattern && player == Player::White {
+        painter.add(egui::Shape::ellipse_stroke(
+            center,
+            radius * 0.55,
+            egui::Stroke::new(radius.y * 0.12, theme.disc(Player::Black)),
+        ));
+    }
+}
+
+// a circle, a cross or a letter over the disc on the square, in the middle of it
+fn draw_mark(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    square_size: f32,
+    mark: SquareMark,
+    color: egui::Color32,
+) {
+    let reach = square_size * 0.3;
+    let stroke = egui::Stroke::new(square_size * 0.07, color);
+    match mark {
+        SquareMark::Circle => {
+            painter.circle_stroke(center, reach, stroke);
+        }
+        SquareMark::Cross => {
+            let arm = egui::Vec2::splat(reach * 0.8);
+            painter.line_segment([center - arm, center + arm], stroke);
+            painter.line_segment(
+                [
+                    center + egui::vec2(-arm.x, arm.y),
+                    center + egui::vec2(arm.x, -arm.y),
+                ],
+                stroke,
+            );
+        }
+        SquareMark::Letter(letter) => {
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                letter,
+                egui::FontId::proportional(reach * 2.0),
+                color,
+            );
+        }
+    }
+}
+
+// a padlock of `size` across, centered on the point
+fn draw_lock(painter: &egui::Painter, center: egui::Pos2, size: f32, color: egui::Color32) {
+    let body = egui::Rect::from_min_size(
+        center - egui::vec2(size / 2.0, size * 0.1),
+        egui::vec2(size, size * 0.6),
+    );
+    painter.rect_filled(body, size * 0.1, color);
+    // the shackle, an arch over the body
+    let points: Vec<egui::Pos2> = (0..=12)
+        .map(|step| {
+            let angle = std::f32::consts::PI * (1.0 + step as f32 / 12.0);
+            body.center_top() + size * 0.3 * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(size * 0.15, color),
+    ));
+}
+
+// the number of discs the move on the square would flip, in its middle
+fn draw_flip_count(
+    painter: &egui::Painter,
+    square_rect: egui::Rect,
+    count: usize,
+    color: egui::Color32,
+) {
+    painter.text(
+        square_rect.center(),
+        egui::Align2::CENTER_CENTER,
+        count.to_string(),
+        egui::FontId::proportional(square_rect.height() * 0.4),
+        color,
+    );
+}
+
+// a cross of `color` edged in `edge`, to be seen on any board
+fn draw_cross(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    size: f32,
+    color: egui::Color32,
+    edge: egui::Color32,
+) {
+    let arms = [
+        [
+            center + egui::vec2(-size, -size),
+            center + egui::vec2(size, size),
+        ],
+        [
+            center + egui::vec2(-size, size),
+            center + egui::vec2(size, -size),
+        ],
+    ];
+    for (width, color) in [(size * 0.45, edge), (size * 0.25, color)] {
+        for arm in arms {
+            painter.line_segment(arm, egui::Stroke::new(width, color));
+        }
+    }
+}
+
+// a shaft and a triangular head from one point to another, in the proportions of the exported
+// pictures
+fn draw_arrow(
+    painter: &egui::Painter,
+    from: egui::Pos2,
+    to: egui::Pos2,
+    square_size: f32,
+    color: egui::Color32,
+) {
+    let length = (to - from).length().max(1.0);
+    let direction = (to - from) / length;
+    let base = to - direction * (square_size * 0.3).min(length);
+    let side = direction.rot90() * square_size * 0.15;
+    // the shaft stops at the head, so that a see-through arrow is not darker where they meet
+    painter.line_segment([from, base], egui::Stroke::new(square_size * 0.08, color));
+    painter.add(egui::Shape::convex_polygon(
+        vec![to, base + side, base - side],
+        color,
+        egui::Stroke::NONE,
+    ));
+}
+
+// from the player's own disc closing the longest line the move flips to the move, None for an
+// invalid move
+fn capture_arrow(board: &Board, player: Player, square: Move) -> Option<(Move, Move)> {
+    let mut flip_cells = CellList::default();
+    if !Referee::default().find_flip_cells_for_move(board, player, square, &mut flip_cells) {
+        return None;
+    }
+    flip_cells
+        .iter()
+        .filter(|&cell| board.cell(cell) == Cell::Taken(player))
+        .max_by_key(|cell| cell.0.abs_diff(square.0).max(cell.1.abs_diff(square.1)))
+        .map(|anchor| (anchor, square))
+}
+
+// a ring of dashes, which tells it apart from the solid rings by its shape
+fn draw_dashed_ring(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    radius: f32,
+    stroke: egui::Stroke,
+) {
+    let points: Vec<egui::Pos2> = (0..=48)
+        .map(|step| {
+            let angle = step as f32 / 48.0 * std::f32::consts::TAU;
+            center + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    painter.extend(egui::Shape::dashed_line(
+        &points,
+        stroke,
+        radius * 0.25,
+        radius * 0.15,
+    ));
+}
+
+// a board of the grid view with its score below, clicking it zooms in or out
+fn show_board_game(
+    ui: &mut egui::Ui,
+    board_rect: egui::Rect,
+    game: &BoardGame,
+    theme: &BoardTheme,
+    disc_pattern: bool,
+    orientation: Orientation,
+    strings: &Strings,
+) -> egui::Response {
+    let response = ui.allocate_rect(board_rect, egui::Sense::click());
+    let painter = ui.painter();
+    let square_size = board_rect.width() / Board::SIZE as f32;
+
+    painter.rect_filled(board_rect, 0.0, theme.board);
+    for row in 0..Board::SIZE {
+        for col in 0..Board::SIZE {
+            let (view_row, view_col) = orientation.to_view((row, col));
+            let square_rect = egui::Rect::from_min_size(
+                board_rect.min + egui::vec2(view_col as f32, view_row as f32) * square_size,
+                egui::Vec2::splat(square_size),
+            );
+            painter.rect_stroke(
+                square_rect,
+                0.0,
+                egui::Stroke::new(0.5, theme.grid),
+                egui::StrokeKind::Inside,
+            );
+            if let Cell::Taken(player) = game.board.grid[row][col] {
+                draw_disc(
+                    painter,
+                    square_rect.center(),
+                    egui::Vec2::splat(square_size / 2.0 * 0.9),
+                    player,
+                    theme,
+                    disc_pattern,
+                );
+            }
+        }
+    }
+    if response.hovered() {
+        painter.rect_stroke(
+            board_rect,
+            0.0,
+            egui::Stroke::new(2.0, theme.hover),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    let (black_count, white_count) = count_pieces(&game.board);
+    let winner = match game.outcome {
+        Some(Outcome::Won(Player::Black, _)) => strings.tr("player-black"),
+        Some(Outcome::Won(Player::White, _)) => strings.tr("player-white"),
+        Some(Outcome::Tie) | None => "",
+    };
+    let key = match game.outcome {
+        Some(Outcome::Won(..)) => "mini-board-won",
+        Some(Outcome::Tie) => "mini-board-tie",
+        None => "mini-board-playing",
+    };
+    painter.text(
+        egui::pos2(board_rect.left(), board_rect.bottom() + 2.0),
+        egui::Align2::LEFT_TOP,
+        strings.tr_args(
+            key,
+            &[
+                ("black", &black_count),
+                ("white", &white_count),
+                ("player", &winner),
+            ],
+        ),
+        egui::FontId::proportional(12.0),
+        ui.visuals().text_color(),
+    );
+
+    response
+}
+
+fn add_opening(openings: &mut OpeningTree, record: &GameRecord) {
+    if let Ok(moves) = notation::parse_transcript(&record.transcript) {
+        openings.add_game(record.start_player, &moves, record.winner);
+    }
+}
+
+// one row per continuation of `line`, which opens into its own continuations
+fn opening_rows(ui: &mut egui::Ui, strings: &Strings, openings: &OpeningTree, line: &[Move]) {
+    for (moves, opening) in openings.continuations(line) {
+        let Some(&next_move) = moves.last() else {
+            continue;
+        };
+        let player = strings.tr(match opening.player {
+            Player::Black => "player-black",
+            Player::White => "player-white",
+        });
+        let text = strings.tr_args(
+            "opening-row",
+            &[
+                (
+                    "move",
+                    &notation::format_move(next_move).to_ascii_uppercase(),
+                ),
+                ("player", &player),
+                ("record", &opening.record),
+                ("score", &format!("{:.0}", opening.record.score() * 100.0)),
+            ],
+        );
+        let transcript: String = moves
+            .iter()
+            .map(|&next_move| notation::format_move(next_move).to_ascii_uppercase())
+            .collect();
+        let hover_text = strings.tr_args(
+            "opening-row-tooltip",
+            &[
+                ("transcript", &transcript),
+                ("record", &opening.record),
+                ("player", &player),
+            ],
+        );
+        if moves.len() < openings.plies && !openings.continuations(moves).is_empty() {
+            egui::CollapsingHeader::new(text)
+                .id_salt(&transcript)
+                .show(ui, |ui| opening_rows(ui, strings, openings, moves))
+                .header_response
+                .on_hover_text(hover_text);
+        } else {
+            ui.label(text).on_hover_text(hover_text);
+        }
+    }
+}
+
+// red for a lost, gray for an even and green for a won score
+fn score_color(score: f64) -> egui::Color32 {
+    let even = egui::Color32::from_gray(190);
+    if score < 0.5 {
+        egui::Color32::from_rgb(230, 110, 110).lerp_to_gamma(even, (score * 2.0) as f32)
+    } else {
+        even.lerp_to_gamma(
+            egui::Color32::from_rgb(110, 200, 110),
+            ((score - 0.5) * 2.0) as f32,
+        )
+    }
+}
+
+// grayed out below `min_games`, returns whether the delete button was clicked
+// the results by color first, which the table has no room for, then everything else known of
+// the entry
+fn statistic_details(
+    strings: &Strings,
+    statistic: &Statistic,
+    participants: Option<&[Participant; 2]>,
+) -> String {
+    let mut text = statistic.color_breakdown().unwrap_or_default();
+    if let Some(score) = statistic.score_summary() {
+        text.push('\n');
+        text.push_str(&strings.tr_args("statistic-score", &[("score", &score)]));
+    }
+    text.push_str(&format!("\n{statistic}"));
+    if let Some(form) = statistic.form() {
+        text.push('\n');
+        text.push_str(&strings.tr_args("statistic-form", &[("form", &form)]));
+    }
+    for (participant, time) in participants
+        .into_iter()
+        .flatten()
+        .zip(statistic.thinking_times())
+    {
+        if time.moves > 0 {
+            text.push('\n');
+            text.push_str(&strings.tr_args(
+                "statistic-thinking-time",
+                &[("participant", participant), ("time", &time)],
+            ));
+        }
+    }
+    text.trim_start().to_string()
+}
+
+// the message of the status area with the move to play and the empty squares left before it,
+// or with the length of the game after it once the game is over
+fn status_line(strings: &Strings, message: String, progress: Option<GameProgress>) -> String {
+    let mut parts = Vec::new();
+    match progress {
+        None => parts.push(message),
+        Some(GameProgress::Turn {
+            move_number,
+            empties,
+        }) => {
+            if let Some(number) = move_number {
+                parts.push(strings.tr_args("status-move-number", &[("number", &number)]));
+            }
+            parts.push(message);
+            parts.push(if empties == 1 {
+                strings.tr("status-one-empty").to_string()
+            } else {
+                strings.tr_args("status-empties", &[("count", &empties)])
+            });
+        }
+        Some(GameProgress::Over { moves }) => {
+            parts.push(message);
+            parts.push(if moves == 1 {
+                strings.tr("status-over-after-one-move").to_string()
+            } else {
+                strings.tr_args("status-over-after", &[("count", &moves)])
+            });
+        }
+    }
+    parts.join(" · ")
+}
+
+// for the numbers of a table, lined up on their last digit
+fn right_aligned(
+    ui: &mut egui::Ui,
+    add: impl FnOnce(&mut egui::Ui) -> egui::Response,
+) -> egui::Response {
+    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), add)
+        .inner
+}
+
+// the referee's flip cells close every line with the player's own disc, which does not turn over
+fn turned_discs(board: &Board, player: Player, flip_cells: &CellList) -> CellList {
+    let mut turned = CellList::default();
+    for square in flip_cells.iter() {
+        if board.cell(square) == Cell::Taken(player.opponent()) {
+            turned.push_back(square);
+        }
+    }
+    turned
+}
 
 pub fn count_pieces(board: &Board) -> (usize, usize) {
     let mut black_count = 0;
     let mut white_count = 0;
 
-    for row in 0..8 {
-        for col in 0..8 {
-            match board.grid[row][col] {
-                Cell::Taken(Player::Black) => black_count += 1,
-                Cell::Taken(Player::White) => white_count += 1,
-                Cell::Empty => {}
-            }
+    for row in 0..8 {
+        for col in 0..8 {
+            match board.grid[row][col] {
+                Cell::Taken(Player::Black) => black_count += 1,
+                Cell::Taken(Player::White) => white_count += 1,
+                Cell::Empty => {}
+            }
+        }
+    }
+
+    (black_count, white_count)
+}
+
+impl eframe::App for Game {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.apply_appearance(ctx);
+        self.apply_language();
+        self.show_resume_offer(ctx);
+        self.show_seat_change_prompt(ctx);
+        self.show_clear_statistics_prompt(ctx);
+        if self.replay.is_none() && self.editor.is_none() {
+            self.show_pass_notice(ctx);
+        }
+
+        self.poll_analysis();
+        self.poll_arena();
+        self.poll_network();
+        self.update_clock(Instant::now());
+
+        self.poll_hint();
+        self.poll_position_scores();
+        self.tick_multi_board();
+        // typing into a text field is no shortcut
+        let mut tabbed = false;
+        if !ctx.wants_keyboard_input() {
+            for (shortcut, action, _) in SHORTCUTS {
+                if self.is_action_enabled(action)
+                    && ctx.input_mut(|i| i.consume_shortcut(&shortcut))
+                {
+                    tabbed |= action == Action::ToggleCompactMode;
+                    self.perform(action);
+                }
+            }
+        }
+
+        if self.options.compact_mode {
+            self.show_compact_status(ctx);
+        } else {
+            self.show_side_panel(ctx);
+        }
+
+        // after the side panel, so that the board only gets the space left beside it
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // the main game waits while the grid is shown
+            if self.multi_board.is_some() {
+                self.show_multi_board(ui);
+                ctx.request_repaint();
+                return;
+            }
+
+            // UI drawing
+            let rect = ui.available_rect_before_wrap();
+            let bar_space = if self.options.show_evaluation_bar {
+                EVALUATION_BAR_WIDTH + EVALUATION_BAR_GAP
+            } else {
+                0.0
+            };
+            let (board_rect, square_size) =
+                board_layout(rect, bar_space, self.options.show_coordinates);
+            let line_width = square_size * 0.01;
+            // presses and releases on the board, drawn on below
+            let board_response = ui.allocate_rect(board_rect, egui::Sense::click());
+
+            let theme = self.options.board_theme.theme();
+            let to_color = |player| theme.disc(player);
+
+            // the board's own squares, wherever the orientation shows them
+            let orientation = self.orientation();
+            let get_square_rect = |row, col| {
+                let (row, col) = orientation.to_view((row, col));
+                let square_pos = egui::Pos2 {
+                    x: board_rect.left() + col as f32 * square_size,
+                    y: board_rect.top() + row as f32 * square_size,
+                };
+                egui::Rect::from_min_size(square_pos, egui::Vec2::splat(square_size))
+            };
+            let board_square_at =
+                |position| square_at(board_rect, position).map(|view| orientation.to_board(view));
+
+            let now = Instant::now();
+            if self
+                .flip_animation
+                .as_ref()
+                .is_some_and(|animation| animation.is_finished(now))
+            {
+                self.flip_animation = None;
+                // the valid moves were hidden while the discs turned
+                self.animate(AnimationKey::ValidMoves, VALID_MOVES_FADE_IN);
+                self.play_sound(Sound::Flip);
+            }
+
+            self.refresh_disc_marks();
+
+            // draw the current board state, or the replayed or edited one
+            let board = match (&self.editor, &self.replay) {
+                (Some(editor), _) => &editor.board,
+                (None, Some(replay)) => &replay.board,
+                (None, None) => &self.board,
+            };
+            let animation = self.flip_animation.as_ref();
+            let placed = self
+                .last_move
+                .as_ref()
+                .filter(|_| self.replay.is_none() && self.editor.is_none())
+                .map(|last_move| last_move.next_move);
+            let placed_scale = self.animations.progress(AnimationKey::PlacedDisc, now);
+            for row in 0..Board::SIZE {
+                for col in 0..Board::SIZE {
+                    let square_rect = get_square_rect(row, col);
+
+                    ui.painter().rect_filled(square_rect, 0.0, theme.board);
+
+                    let stroke = egui::Stroke {
+                        width: line_width,
+                        color: theme.grid,
+                    };
+                    ui.painter()
+                        .rect_stroke(square_rect, 0.0, stroke, egui::StrokeKind::Inside);
+
+                    let radius = square_size / 2.0 * 0.93;
+                    let turn = animation.and_then(|animation| animation.turn((row, col), now));
+                    match (board.grid[row][col], turn) {
+                        (Cell::Taken(cell_state), _) if placed == Some((row, col)) => {
+                            draw_disc(
+                                ui.painter(),
+                                square_rect.center(),
+                                egui::Vec2::splat(radius * placed_scale),
+                                cell_state,
+                                &theme,
+                                self.options.disc_pattern,
+                            );
+                        }
+                        // seen edge-on half way, when the color changes
+                        (Cell::Taken(cell_state), Some(progress)) => {
+                            let color = if progress < 0.5 {
+                                cell_state.opponent()
+                            } else {
+                                cell_state
+                            };
+                            draw_disc(
+                                ui.painter(),
+                                square_rect.center(),
+                                egui::vec2(radius * (1.0 - 2.0 * progress).abs(), radius),
+                                color,
+                                &theme,
+                                self.options.disc_pattern,
+                            );
+                        }
+                        (Cell::Taken(cell_state), None) => {
+                            draw_disc(
+                                ui.painter(),
+                                square_rect.center(),
+                                egui::Vec2::splat(radius),
+                                cell_state,
+                                &theme,
+                                self.options.disc_pattern,
+                            );
+                        }
+                        (Cell::Empty, _) => {}
+                    }
+                }
+            }
+
+            // under the markers of the moves, which it would hide otherwise
+            for (square, level, score) in self.heatmap() {
+                let [r, g, b] = board_image::heat_color(level);
+                let square_rect = get_square_rect(square.0, square.1);
+                ui.painter().rect_filled(
+                    square_rect.shrink(line_width),
+                    0.0,
+                    egui::Color32::from_rgb(r, g, b).gamma_multiply(0.75),
+                );
+                let [r, g, b] = board_image::heat_text_color(level);
+                ui.painter().text(
+                    square_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    analysis::format_score(score),
+                    egui::FontId::proportional(square_size * 0.25),
+                    egui::Color32::from_rgb(r, g, b),
+                );
+            }
+
+            // in the top right corner, clear of the heatmap's score in the middle
+            for (square, text, best) in self.annotations() {
+                let square_rect = get_square_rect(square.0, square.1);
+                let (size, color) = if best {
+                    (0.22, theme.best_move)
+                } else {
+                    (0.16, theme.annotation)
+                };
+                ui.painter().text(
+                    square_rect.right_top() + egui::vec2(-0.06, 0.06) * square_size,
+                    egui::Align2::RIGHT_TOP,
+                    text,
+                    egui::FontId::proportional(square_size * size),
+                    color,
+                );
+            }
+
+            if self.options.show_coordinates {
+                draw_coordinates(ui, square_size, orientation, get_square_rect);
+            }
+
+            if self.options.show_evaluation_bar {
+                let margin = if self.options.show_coordinates {
+                    square_size * COORDINATE_MARGIN
+                } else {
+                    0.0
+                };
+                let bar_rect = egui::Rect::from_min_size(
+                    egui::pos2(
+                        board_rect.right() + margin + EVALUATION_BAR_GAP,
+                        board_rect.top(),
+                    ),
+                    egui::vec2(EVALUATION_BAR_WIDTH, board_rect.height()),
+                );
+                self.draw_evaluation_bar(ui, bar_rect);
+            }
+
+            // mark the last move, and more subtly the discs it flipped
+            if self.options.show_last_move
+                && self.replay.is_none()
+                && self.editor.is_none()
+                && let Some(last_move) = &self.last_move
+            {
+                let marker_color = theme.last_move;
+                for &(row, col) in last_move.flipped.iter() {
+                    ui.painter().circle_stroke(
+                        get_square_rect(row, col).center(),
+                        square_size * 0.12,
+                        egui::Stroke::new(1.5, marker_color.gamma_multiply(0.6)),
+                    );
+                }
+                let (row, col) = last_move.next_move;
+                let center = get_square_rect(row, col).center();
+                if self.options.marker_shapes {
+                    // edged in the other color, as it sits on a disc of the mover's
+                    let edge = match board.grid[row][col] {
+                        Cell::Taken(player) => to_color(player.opponent()),
+                        Cell::Empty => theme.grid,
+                    };
+                    ui.painter().rect(
+                        egui::Rect::from_center_size(center, egui::Vec2::splat(square_size * 0.2)),
+                        0.0,
+                        marker_color,
+                        egui::Stroke::new(1.5, edge),
+                        egui::StrokeKind::Outside,
+                    );
+                } else {
+                    ui.painter()
+                        .circle_filled(center, square_size * 0.1, marker_color);
+                }
+            }
+
+            // over the last move marker, which would hide the number otherwise
+            if self.options.show_move_numbers
+                && let Some(move_numbers) = self.move_numbers()
+            {
+                for (row, row_numbers) in move_numbers.iter().enumerate() {
+                    for (col, number) in row_numbers.iter().enumerate() {
+                        if let (Cell::Taken(player), Some(number)) = (board.grid[row][col], number)
+                        {
+                            ui.painter().text(
+                                get_square_rect(row, col).center(),
+                                egui::Align2::CENTER_CENTER,
+                                number.to_string(),
+                                egui::FontId::proportional(square_size * 0.35),
+                                to_color(player.opponent()),
+                            );
+                        }
+                    }
+                }
+            }
+
+            // around the edge of the disc, which leaves room for the lock of a stable one
+            if let Some(frontier) = &self.frontier_discs {
+                for (row, row_discs) in frontier.discs.iter().enumerate() {
+                    for (col, &is_frontier) in row_discs.iter().enumerate() {
+                        if is_frontier && board.grid[row][col] != Cell::Empty {
+                            ui.painter().circle_stroke(
+                                get_square_rect(row, col).center(),
+                                square_size / 2.0 * 0.93,
+                                egui::Stroke::new(square_size * 0.04, theme.frontier),
+                            );
+                        }
+                    }
+                }
+            }
+
+            // in the corner of the disc, clear of its move number
+            if let Some(stable) = &self.stable_discs {
+                for (row, row_discs) in stable.discs.iter().enumerate() {
+                    for (col, &is_stable) in row_discs.iter().enumerate() {
+                        if let (Cell::Taken(player), true) = (board.grid[row][col], is_stable) {
+                            draw_lock(
+                                ui.painter(),
+                                get_square_rect(row, col).center()
+                                    + egui::Vec2::splat(square_size * 0.2),
+                                square_size * 0.18,
+                                to_color(player.opponent()),
+                            );
+                        }
+                    }
+                }
+            }
+
+            // ring the hinted move
+            if let Some(Hint::Ready((row, col))) = self.hint
+                && row < Board::SIZE
+                && col < Board::SIZE
+            {
+                let center = get_square_rect(row, col).center();
+                let stroke = egui::Stroke::new(3.0, theme.hint);
+                if self.options.marker_shapes {
+                    draw_dashed_ring(ui.painter(), center, square_size / 2.0 * 0.8, stroke);
+                } else {
+                    ui.painter()
+                        .circle_stroke(center, square_size / 2.0 * 0.8, stroke);
+                }
+            }
+
+            // and point at it, like at the move the analysis preferred, along the line it flips
+            for (from, to, color) in self.arrows() {
+                draw_arrow(
+                    ui.painter(),
+                    get_square_rect(from.0, from.1).center(),
+                    get_square_rect(to.0, to.1).center(),
+                    square_size,
+                    color.gamma_multiply(theme.arrow_opacity),
+                );
+            }
+
+            // a right click marks the square, whatever the game is up to, and leaves the moves
+            // to the left button
+            if let Some(square) = board_response
+                .secondary_clicked()
+                .then(|| board_response.interact_pointer_pos())
+                .flatten()
+                .and_then(board_square_at)
+            {
+                self.square_marks.cycle(square);
+            }
+            for (square, mark) in self.square_marks.iter() {
+                draw_mark(
+                    ui.painter(),
+                    get_square_rect(square.0, square.1).center(),
+                    square_size,
+                    mark,
+                    theme.marks,
+                );
+            }
+
+            // ring the solutions of a revealed puzzle
+            if let Some(puzzle) = self.puzzle.as_ref().filter(|puzzle| puzzle.revealed) {
+                for &(row, col) in puzzle.solutions.iter() {
+                    ui.painter().circle_stroke(
+                        get_square_rect(row, col).center(),
+                        square_size / 2.0 * 0.8,
+                        egui::Stroke::new(3.0, theme.solution),
+                    );
+                }
+            }
+
+            match self.current_phase {
+                _ if self.editor.is_some() => {
+                    // the game is suspended while the position is edited
+                    if board_response.clicked()
+                        && let Some(clicked) = board_response
+                            .interact_pointer_pos()
+                            .and_then(board_square_at)
+                    {
+                        self.edit_cell(clicked);
+                    }
+                }
+                _ if self.replay.is_some() => {
+                    // the game is suspended, only highlight the move about to be played
+                    if let Some(replay) = &self.replay
+                        && let Some(&Ply::Move {
+                            player,
+                            next_move: (row, col),
+                        }) = self.history.plies.get(replay.index)
+                    {
+                        ui.painter().circle_stroke(
+                            get_square_rect(row, col).center(),
+                            square_size / 2.0 * 0.8,
+                            egui::Stroke::new(3.0, to_color(player)),
+                        );
+                    }
+
+                    // and the move the engine would have preferred, if the game was analyzed
+                    if let Some(replay) = &self.replay
+                        && let Some(analysis) = self.current_analysis()
+                        && let Some(Some(position)) = analysis.positions.get(replay.index)
+                        && let Some(((row, col), _)) = position.best()
+                    {
+                        ui.painter().circle_stroke(
+                            get_square_rect(row, col).center(),
+                            square_size / 2.0 * 0.6,
+                            egui::Stroke::new(3.0, theme.best_move),
+                        );
+                    }
+                }
+                _ if self.pass_notice.is_some() => {
+                    // the game waits until the pass has been acknowledged
+                }
+                _ if self.flip_animation.is_some() => {
+                    // the next move waits until the discs have turned over
+                }
+                Phase::Turn(_) if self.is_puzzle_answered() => {
+                    // the puzzle took its one move
+                }
+                Phase::Turn(player)
+                    if self.is_network_frozen() || self.remote_player() == Some(player) =>
+                {
+                    // the move comes in over the network, if at all
+                }
+                Phase::Turn(player) if self.player_options[player as usize].ai_enabled => {
+                    // AI moves, in step mode only once asked to, until the move is in
+                    if !self.is_stepping() || self.is_ai_thinking(player) || self.step_requested {
+                        self.step_requested = false;
+                        self.tick_ai(player);
+                    }
+                }
+                Phase::Turn(player) => {
+                    // ai is disabled
+
+                    if self.options.show_opening_scores {
+                        for ((row, col), score) in self.opening_scores() {
+                            let square_rect = get_square_rect(row, col);
+                            ui.painter().rect_filled(
+                                square_rect.shrink(square_size * 0.2),
+                                square_size * 0.1,
+                                score_color(score),
+                            );
+                            ui.painter().text(
+                                square_rect.center(),
+                                egui::Align2::CENTER_CENTER,
+                                format!("{:.0}%", score * 100.0),
+                                egui::FontId::proportional(square_size * 0.22),
+                                egui::Color32::BLACK,
+                            );
+                        }
+                    }
+
+                    // Awaiting human move
+                    if self.options.show_valid_moves {
+                        let fade_in = self
+                            .animations
+                            .progress(AnimationKey::ValidMoves, Instant::now());
+                        for (valid_row, valid_col) in self.valid_moves.iter() {
+                            let square_rect = get_square_rect(valid_row, valid_col);
+                            if self.options.valid_move_shapes {
+                                draw_cross(
+                                    ui.painter(),
+                                    square_rect.center(),
+                                    square_size * 0.12,
+                                    to_color(player).gamma_multiply(fade_in),
+                                    to_color(player.opponent()).gamma_multiply(fade_in),
+                                );
+                            } else {
+                                let highlight_color = theme.valid_moves[player as usize];
+                                ui.painter().rect_filled(
+                                    square_rect,
+                                    0.0,
+                                    highlight_color.gamma_multiply(fade_in),
+                                );
+                            }
+                        }
+                    }
+
+                    // hovering shows what the move would flip
+                    if let Some((row, col)) = board_response.hover_pos().and_then(board_square_at) {
+                        let hover_flips = self.hover_flips(player, (row, col));
+                        if let Some(flip_cells) = hover_flips {
+                            self.flip_cells = flip_cells;
+                            // the selected move has a ghost of its own below
+                            if self.options.show_move_preview
+                                && self.pending_move != Some((row, col))
+                            {
+                                // the full color outline keeps the ghost visible on any board
+                                let ghost_color =
+                                    to_color(player).gamma_multiply(HOVER_GHOST_OPACITY);
+                                let outline = egui::Stroke::new(1.5, to_color(player));
+                                let radius = square_size / 2.0 * 0.93;
+                                ui.painter().circle(
+                                    get_square_rect(row, col).center(),
+                                    radius,
+                                    ghost_color,
+                                    outline,
+                                );
+                                if self.options.preview_flips {
+                                    for (flip_row, flip_col) in self.flip_cells.iter() {
+                                        ui.painter().circle_filled(
+                                            get_square_rect(flip_row, flip_col).center(),
+                                            radius,
+                                            ghost_color,
+                                        );
+                                    }
+                                }
+                            }
+
+                            // the selected move shows its own count below
+                            if self.options.show_flip_counts
+                                && self.pending_move != Some((row, col))
+                            {
+                                draw_flip_count(
+                                    ui.painter(),
+                                    get_square_rect(row, col),
+                                    self.flip_cells.count,
+                                    theme.hover,
+                                );
+                            }
+
+                            // show move effects with connecting lines
+                            if self.options.show_effects_of_moves {
+                                let hovered_rect = get_square_rect(row, col);
+                                let hovered_center = hovered_rect.center();
+
+                                // Highlight the hovered square with a bright border
+                                ui.painter().rect_stroke(
+                                    hovered_rect,
+                                    0.0,
+                                    egui::Stroke::new(3.0, theme.hover),
+                                    egui::StrokeKind::Inside,
+                                );
+
+                                for (flip_row, flip_col) in self.flip_cells.iter() {
+                                    let flip_rect = get_square_rect(flip_row, flip_col);
+                                    let flip_center = flip_rect.center();
+
+                                    // Draw line from hovered center to flip center
+                                    ui.painter().line_segment(
+                                        [hovered_center, flip_center],
+                                        egui::Stroke::new(2.0, to_color(player)),
+                                    );
+
+                                    // Simple dot at flip cell instead of arrowhead
+                                    ui.painter().circle_filled(
+                                        flip_center,
+                                        square_size * 0.08,
+                                        to_color(player),
+                                    );
+
+                                    // Highlight the flip cell with a border
+                                    ui.painter().rect_stroke(
+                                        flip_rect,
+                                        0.0,
+                                        egui::Stroke::new(2.0, to_color(player)),
+                                        egui::StrokeKind::Inside,
+                                    );
+                                }
+                            }
+                        }
+                    }
+
+                    // the selected move as a ghost disc, with the disks it would flip
+                    if let Some((row, col)) = self.pending_move {
+                        if self.referee.find_flip_cells_for_move(
+                            &self.board,
+                            player,
+                            (row, col),
+                            &mut self.flip_cells,
+                        ) {
+                            self.flip_cells = turned_discs(&self.board, player, &self.flip_cells);
+                            ui.painter().circle_filled(
+                                get_square_rect(row, col).center(),
+                                square_size / 2.0 * 0.93,
+                                to_color(player).gamma_multiply(PENDING_GHOST_OPACITY),
+                            );
+                            for (flip_row, flip_col) in self.flip_cells.iter() {
+                                ui.painter().circle_filled(
+                                    get_square_rect(flip_row, flip_col).center(),
+                                    square_size * 0.15,
+                                    to_color(player),
+                                );
+                            }
+                            if self.options.show_flip_counts {
+                                draw_flip_count(
+                                    ui.painter(),
+                                    get_square_rect(row, col),
+                                    self.flip_cells.count,
+                                    theme.hover,
+                                );
+                            }
+                        } else {
+                            // selected for another position
+                            self.pending_move = None;
+                        }
+                    }
+
+                    // a click counts on the square where the button is released, and only if it
+                    // was pressed on the board too
+                    let clicked = board_response
+                        .clicked()
+                        .then(|| board_response.interact_pointer_pos())
+                        .flatten()
+                        .and_then(board_square_at)
+                        .filter(|&clicked| {
+                            self.referee.validate_move(&self.board, player, clicked)
+                        });
+                    match clicked {
+                        Some(clicked)
+                            if !self.options.confirm_moves
+                                || self.pending_move == Some(clicked) =>
+                        {
+                            if let Err(e) = self.make_move(clicked, player) {
+                                self.report_move_error(player, e);
+                            }
+                        }
+                        Some(clicked) => self.pending_move = Some(clicked),
+                        // a click anywhere else cancels the selection
+                        None if ui.input(|i| i.pointer.primary_clicked()) => {
+                            self.pending_move = None;
+                        }
+                        None => {}
+                    }
+                }
+                Phase::Win(..) | Phase::Tie if self.is_match_running() => {
+                    self.start_next_match_game();
+                }
+                Phase::Win(..) | Phase::Tie => {
+                    // restarting a network game is up to the players
+                    if self.restart_countdown() == Some(Duration::ZERO) {
+                        self.restart_automatically();
+                    }
+                }
+            }
+
+            if self.shows_result_overlay() {
+                self.show_result_overlay(ui, board_rect);
+            }
+        });
+
+        // egui moves the focus on Tab as well, which would leave a button to take Space or Enter
+        if tabbed && let Some(focused) = ctx.memory(|memory| memory.focused()) {
+            ctx.memory_mut(|memory| memory.surrender_focus(focused));
+        }
+        if let Some(delay) = self.repaint_delay() {
+            ctx.request_repaint_after(delay);
+        }
+        self.save_settings_if_changed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn startup_args_override_the_defaults() {
+        let mut startup = StartupArgs {
+            auto_restart: true,
+            no_pace_ai: true,
+            ..Default::default()
+        };
+        startup.players[Player::White as usize] = PlayerArgs {
+            kind: Some(PlayerKind::Ai(AiType::AlphaBeta)),
+            recursion_depth: Some(6),
+        };
+
+        let mut options = GameOptions::default();
+        options.apply_startup_args(&startup);
+        assert!(options.auto_restart);
+        assert_eq!(options.ai_move_seconds, 0.0);
+        assert!(!options.show_valid_moves);
+
+        let mut player_options = [PlayerOptions::default(); 2];
+        for (player_options, player_args) in player_options.iter_mut().zip(&startup.players) {
+            player_options.apply_startup_args(player_args);
+        }
+        assert!(!player_options[Player::Black as usize].ai_enabled);
+        let white = player_options[Player::White as usize];
+        assert!(white.ai_enabled);
+        assert_eq!(white.ai_type, AiType::AlphaBeta);
+        assert_eq!(white.ai_recursion_depth, 6);
+    }
+
+    #[test]
+    fn human_keeps_the_ai_settings_for_later() {
+        let mut player_options = PlayerOptions {
+            ai_enabled: true,
+            ai_type: AiType::Minimax,
+            ai_recursion_depth: 4,
+        };
+        player_options.apply_startup_args(&PlayerArgs {
+            kind: Some(PlayerKind::Human),
+            recursion_depth: None,
+        });
+        assert!(!player_options.ai_enabled);
+        assert_eq!(player_options.ai_type, AiType::Minimax);
+    }
+
+    // saving is off in tests, so this starts from the defaults without touching any files
+    fn new_game() -> Game {
+        Game::new(StartupArgs::default())
+    }
+
+    fn assert_untouched(game: &Game) {
+        assert_eq!(game.board.grid, Board::default().grid);
+        assert!(game.history.plies.is_empty());
+        assert!(matches!(game.current_phase, Phase::Turn(Player::Black)));
+    }
+
+    // a seat's request whose answer came in
+    fn answered_request(move_result: MoveResult) -> AiRequest {
+        AiRequest {
+            id: 0,
+            requested_at: Instant::now(),
+            thinking_seconds: 0.0,
+            move_result: Some(move_result),
+        }
+    }
+
+    #[test]
+    fn rejected_human_moves_leave_the_game_untouched() {
+        let mut game = new_game();
+
+        assert_eq!(
+            game.make_move((0, 0), Player::Black),
+            Err(MoveError::IllegalMove((0, 0)))
+        );
+        assert_eq!(
+            game.make_move((2, 3), Player::White),
+            Err(MoveError::WrongTurn(Player::White))
+        );
+        assert_untouched(&game);
+
+        game.current_phase = Phase::Tie;
+        assert_eq!(
+            game.make_move((2, 3), Player::Black),
+            Err(MoveError::GameOver)
+        );
+    }
+
+    #[test]
+    fn a_rejected_move_keeps_the_selection_and_the_hint() {
+        let mut game = new_game();
+        game.pending_move = Some((2, 3));
+        game.hint = Some(Hint::Ready((3, 2)));
+
+        // a stale AI answer, then a square that flips nothing
+        assert_eq!(
+            game.make_move((2, 3), Player::White),
+            Err(MoveError::WrongTurn(Player::White))
+        );
+        assert_eq!(
+            game.make_move((0, 0), Player::Black),
+            Err(MoveError::IllegalMove((0, 0)))
+        );
+        assert_untouched(&game);
+        assert_eq!(game.pending_move, Some((2, 3)));
+        assert!(matches!(game.hint, Some(Hint::Ready((3, 2)))));
+
+        // both were for the position the accepted move leaves behind
+        assert_eq!(game.make_move((2, 3), Player::Black), Ok(()));
+        assert_eq!(game.pending_move, None);
+        assert!(game.hint.is_none());
+    }
+
+    #[test]
+    fn the_clock_stands_still_while_the_game_waits_and_starts_over_with_a_new_game() {
+        let mut game = new_game();
+        game.options.use_clocks = true;
+        game.options.clock_minutes = 1;
+        game.reset();
+        assert_eq!(game.clock_running_for(), Some(Player::Black));
+
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let tick = |game: &mut Game, seconds| {
+            let running = game.clock_running_for();
+            game.clock.as_mut().unwrap().update(running, at(seconds));
+        };
+        let black_left = |game: &Game| game.clock.as_ref().unwrap().remaining(Player::Black);
+        tick(&mut game, 0);
+        tick(&mut game, 10);
+        assert_eq!(black_left(&game), Duration::from_secs(50));
+
+        game.pass_notice = Some(PassNotice {
+            player: Player::White,
+            dismiss_at: None,
+        });
+        assert_eq!(game.clock_running_for(), None);
+        tick(&mut game, 20);
+        game.pass_notice = None;
+
+        game.show_replay_position(0);
+        assert_eq!(game.clock_running_for(), None);
+        tick(&mut game, 30);
+        game.replay = None;
+
+        game.open_editor();
+        assert_eq!(game.clock_running_for(), None);
+        tick(&mut game, 40);
+        game.editor = None;
+
+        tick(&mut game, 45);
+        assert_eq!(black_left(&game), Duration::from_secs(50));
+        tick(&mut game, 47);
+        assert_eq!(black_left(&game), Duration::from_secs(48));
+
+        // a new game takes the time of the options again
+        game.reset();
+        assert_eq!(black_left(&game), Duration::from_secs(60));
+        game.options.use_clocks = false;
+        game.reset();
+        assert!(game.clock.is_none());
+        assert_eq!(game.clock_running_for(), None);
+    }
+
+    #[test]
+    fn running_out_of_time_wins_over_an_ai_move_of_the_same_frame() {
+        let mut game = new_game();
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.options.ai_move_seconds = 0.0;
+        game.options.use_clocks = true;
+        game.reset();
+        game.clock = Some(GameClock::new(Duration::from_secs(1), Duration::ZERO));
+
+        let start = Instant::now();
+        game.update_clock(start);
+        game.ai_requests[Player::Black as usize] = Some(answered_request(MoveResult {
+            board: game.board.clone(),
+            player: Player::Black,
+            next_move: (2, 3),
+            score: None,
+        }));
+        // the frame in which the answer arrives is the one the flag falls in
+        game.update_clock(start + Duration::from_millis(1_500));
+        game.tick_ai(Player::Black);
+
+        assert!(matches!(
+            game.current_phase,
+            Phase::Win(Player::White, WinReason::Time)
+        ));
+        assert_eq!(game.board.grid, Board::default().grid);
+        assert!(game.history.plies.is_empty());
+    }
+
+    #[test]
+    fn rejected_ai_moves_are_asked_for_again() {
+        let mut game = new_game();
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.options.ai_move_seconds = 0.0;
+
+        game.ai_requests[Player::Black as usize] = Some(answered_request(MoveResult {
+            board: Board::default(),
+            player: Player::Black,
+            next_move: (0, 0),
+            score: None,
+        }));
+        game.tick_ai(Player::Black);
+
+        assert_untouched(&game);
+        assert!(game.player_options[Player::Black as usize].ai_enabled);
+        assert!(game.toast.is_some());
+        assert!(!game.is_ai_thinking(Player::Black));
+        assert_eq!(game.thinking_times[Player::Black as usize].moves, 0);
+
+        // the next tick asks for a fresh move
+        game.tick_ai(Player::Black);
+        assert!(game.is_ai_thinking(Player::Black));
+    }
+
+    #[test]
+    fn the_thinking_time_runs_until_the_answer_and_not_through_the_pacing() {
+        let mut game = new_game();
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        assert_eq!(game.ai_thinking_time(Player::Black), None);
+
+        game.ai_requests[Player::Black as usize] = Some(AiRequest {
+            id: 0,
+            requested_at: Instant::now() - Duration::from_secs(2),
+            thinking_seconds: 0.0,
+            move_result: None,
+        });
+        assert!(game.ai_thinking_time(Player::Black).unwrap() >= 2.0);
+        // only the seat to move thinks
+        assert_eq!(game.ai_thinking_time(Player::White), None);
+
+        // the answer is in, the minimum move time still running
+        let mut request = answered_request(MoveResult {
+            board: game.board.clone(),
+            player: Player::Black,
+            next_move: (2, 3),
+            score: None,
+        });
+        request.thinking_seconds = 0.5;
+        game.ai_requests[Player::Black as usize] = Some(request);
+        assert_eq!(game.ai_thinking_time(Player::Black), Some(0.5));
+
+        // a new request starts from zero
+        game.ai_requests[Player::Black as usize] = Some(AiRequest {
+            id: 1,
+            requested_at: Instant::now(),
+            thinking_seconds: 0.0,
+            move_result: None,
+        });
+        assert!(game.ai_thinking_time(Player::Black).unwrap() < 1.0);
+    }
+
+    #[test]
+    fn the_legal_moves_are_counted_in_the_position_on_display() {
+        let mut game = new_game();
+        game.options.show_opponent_move_count = true;
+        game.update_move_counts();
+        assert_eq!(game.move_count_text().unwrap(), "Legal moves: 4 (White: 4)");
+
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.update_move_counts();
+        assert_eq!(game.move_count_text().unwrap(), "Legal moves: 3 (Black: 3)");
+
+        // a replay counts the position it shows, with the side that moved next there
+        game.replay = Some(ReplayView {
+            index: 0,
+            board: Board::default(),
+        });
+        game.update_move_counts();
+        assert_eq!(game.move_count_text().unwrap(), "Legal moves: 4 (White: 4)");
+        game.replay = None;
+
+        // white has to pass, with no disc of black's to take
+        game.board.grid = [[Cell::Empty; Board::SIZE]; Board::SIZE];
+        game.board.grid[0][0] = Cell::Taken(Player::White);
+        game.board.grid[7][7] = Cell::Taken(Player::Black);
+        game.pass_notice = Some(PassNotice {
+            player: Player::White,
+            dismiss_at: None,
+        });
+        game.options.show_opponent_move_count = false;
+        game.update_move_counts();
+        assert_eq!(
+            game.move_count_text().unwrap(),
+            "Legal moves: none, White must pass"
+        );
+
+        game.pass_notice = None;
+        game.current_phase = Phase::Tie;
+        game.update_move_counts();
+        assert_eq!(game.move_count_text(), None);
+    }
+
+    #[test]
+    fn the_language_changes_without_a_restart() {
+        let mut game = new_game();
+        assert_eq!(game.status_message(), "Move 1 · Black to play · 60 empties");
+        game.options.language = Language::French;
+        game.apply_language();
+        assert_eq!(
+            game.status_message(),
+            "Coup 1 · Au tour de Noir · 60 cases vides"
+        );
+
+        // a loaded file stands until another language is picked
+        game.strings
+            .load("test.lang".to_string(), "status-turn = {player} zieht")
+            .unwrap();
+        game.apply_language();
+        // with English for what it leaves out
+        assert_eq!(game.status_message(), "Move 1 · Black zieht · 60 empties");
+        game.options.language = Language::English;
+        game.apply_language();
+        assert_eq!(game.status_message(), "Move 1 · Black to play · 60 empties");
+    }
+
+    #[test]
+    fn the_status_counts_moves_without_the_passes() {
+        let strings = Strings::new(Language::English);
+        let line = |progress| status_line(&strings, "Black to play".to_string(), progress);
+        assert_eq!(
+            line(Some(GameProgress::Turn {
+                move_number: Some(23),
+                empties: 37,
+            })),
+            "Move 23 · Black to play · 37 empties"
+        );
+        // the earlier moves of a position set up in the editor are unknown
+        assert_eq!(
+            line(Some(GameProgress::Turn {
+                move_number: None,
+                empties: 1,
+            })),
+            "Black to play · 1 empty"
+        );
+        assert_eq!(line(None), "Black to play");
+
+        // a game ended with squares still empty, by a wipeout or on time
+        let mut game = new_game();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.make_move((2, 2), Player::White).unwrap();
+        game.history.push_pass(Player::Black);
+        assert_eq!(
+            game.game_progress(),
+            Some(GameProgress::Turn {
+                move_number: Some(3),
+                empties: 58,
+            })
+        );
+        game.current_phase = Phase::Win(Player::White, WinReason::Time);
+        assert_eq!(
+            game.status_message(),
+            "White won on time · Game over after 2 moves"
+        );
+        assert_eq!(
+            status_line(
+                &strings,
+                "Tie".to_string(),
+                Some(GameProgress::Over { moves: 1 })
+            ),
+            "Tie · Game over after 1 move"
+        );
+
+        game.history.start_board.grid[0][0] = Cell::Taken(Player::Black);
+        game.current_phase = Phase::Turn(Player::Black);
+        assert!(matches!(
+            game.game_progress(),
+            Some(GameProgress::Turn {
+                move_number: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn a_forced_pass_is_recorded_and_replays_from_the_save() {
+        let mut board = Board {
+            grid: [[Cell::Empty; Board::SIZE]; Board::SIZE],
+        };
+        board.grid[0][0] = Cell::Taken(Player::Black);
+        board.grid[0][1] = Cell::Taken(Player::White);
+        board.grid[7][7] = Cell::Taken(Player::Black);
+        board.grid[7][6] = Cell::Taken(Player::White);
+        let mut game = new_game();
+        game.start_from_position(board, Player::Black);
+
+        // c1 takes b1, and g8 cannot take h8 back
+        game.make_move((0, 2), Player::Black).unwrap();
+        assert_eq!(
+            game.history.plies,
+            [
+                Ply::Move {
+                    player: Player::Black,
+                    next_move: (0, 2)
+                },
+                Ply::Pass {
+                    player: Player::White
+                }
+            ]
+        );
+        assert_eq!(game.pass_notice.as_ref().unwrap().player, Player::White);
+        assert!(matches!(game.current_phase, Phase::Turn(Player::Black)));
+
+        game.make_move((7, 5), Player::Black).unwrap();
+        assert_eq!(game.history.plies.len(), 3);
+        assert!(matches!(game.current_phase, Phase::Win(Player::Black, _)));
+        assert_eq!(notation::transcript(&game.history, true), "C1--F8");
+        assert_eq!(notation::transcript(&game.history, false), "C1F8");
+
+        // the save keeps the moves only, the pass comes back from the position
+        let saved_game =
+            SavedGame::from_json(&SavedGame::new(&game.history, game.player_options, 7).to_json())
+                .unwrap();
+        assert_eq!(saved_game.transcript, "C1--F8");
+        let replay = saved_game.replay(&mut Referee::default()).unwrap();
+        assert_eq!(replay.history.plies, game.history.plies);
+        assert_eq!(replay.board.grid, game.board.grid);
+        assert_eq!(replay.next_player, None);
+    }
+
+    #[test]
+    fn the_discs_closing_a_line_are_not_counted_as_flipped() {
+        let mut game = new_game();
+        // d3 turns d4 over, d5 closes the line
+        game.make_move((2, 3), Player::Black).unwrap();
+        let last_move = game.last_move.as_ref().unwrap();
+        assert_eq!(last_move.flipped, [(3, 3)]);
+        assert_eq!(game.flip_cells.count, 1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn the_hover_flips_are_worked_out_once_per_square_and_position() {
+        let mut game = new_game();
+        for _ in 0..10 {
+            assert_eq!(game.hover_flips(Player::Black, (2, 3)).unwrap().count, 1);
+        }
+        assert_eq!(game.hover_flip_computations, 1);
+        for _ in 0..10 {
+            assert!(game.hover_flips(Player::Black, (0, 0)).is_none());
+        }
+        assert_eq!(game.hover_flip_computations, 2);
+
+        // a move changes the position under the same square
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.hover_flips(Player::Black, (0, 0)).is_none());
+        assert_eq!(game.hover_flip_computations, 3);
+        assert!(game.hover_flips(Player::White, (2, 2)).is_some());
+        assert_eq!(game.hover_flip_computations, 4);
+
+        // and so does an undo
+        game.undo();
+        assert!(game.hover_flips(Player::White, (2, 2)).is_none());
+        assert_eq!(game.hover_flip_computations, 5);
+    }
+
+    #[test]
+    fn frames_are_only_asked_for_while_something_runs_on_its_own() {
+        let mut game = new_game();
+        game.animations.clear();
+        // a human to move, nothing to wait for
+        assert_eq!(game.repaint_delay(), None);
+
+        // the AI is asked at once, then looked at now and then
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        assert_eq!(game.repaint_delay(), Some(Duration::ZERO));
+        game.ai_requests[Player::Black as usize] = Some(AiRequest {
+            id: 0,
+            requested_at: Instant::now(),
+            thinking_seconds: 0.0,
+            move_result: None,
+        });
+        assert_eq!(game.repaint_delay(), Some(AI_POLL_INTERVAL));
+
+        // in step mode it waits for Next Move
+        game.ai_requests[Player::Black as usize] = None;
+        game.options.step_mode = true;
+        assert_eq!(game.repaint_delay(), None);
+
+        // a finished game counts down to the restart
+        game.options.step_mode = false;
+        game.player_options[Player::Black as usize].ai_enabled = false;
+        game.options.auto_restart = true;
+        game.options.pause_at_win_seconds = 5;
+        game.end_game(Outcome::Tie);
+        game.animations.clear();
+        assert_eq!(game.repaint_delay(), Some(Duration::from_secs(1)));
+    }
+
+    // a game of two random AIs, played out by ticking them
+    fn play_random_game(game: &mut Game) -> Vec<Move> {
+        game.options.ai_move_seconds = 0.0;
+        for player_options in game.player_options.iter_mut() {
+            player_options.ai_enabled = true;
+            player_options.ai_type = AiType::Random;
+        }
+        while let Phase::Turn(player) = game.current_phase {
+            game.pass_notice = None;
+            game.tick_ai(player);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        game.history.moves()
+    }
+
+    #[test]
+    fn a_stable_disc_keeps_its_color_to_the_end_of_the_game() {
+        for _ in 0..5 {
+            let mut game = new_game();
+            game.options.ai_move_seconds = 0.0;
+            for player_options in game.player_options.iter_mut() {
+                player_options.ai_enabled = true;
+                player_options.ai_type = AiType::Random;
+            }
+            let mut stable = Vec::new();
+            while let Phase::Turn(player) = game.current_phase {
+                for (row, row_discs) in Agent::stable_disks(&game.board).iter().enumerate() {
+                    for (col, &is_stable) in row_discs.iter().enumerate() {
+                        if is_stable {
+                            stable.push(((row, col), game.board.grid[row][col]));
+                        }
+                    }
+                }
+                game.pass_notice = None;
+                game.tick_ai(player);
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            // some discs settle before the end in any game
+            assert!(!stable.is_empty());
+            for (square, cell) in stable {
+                assert_eq!(game.board.cell(square), cell);
+            }
+        }
+    }
+
+    #[test]
+    fn the_stable_disc_overlay_follows_the_position_shown() {
+        let mut game = new_game();
+        game.refresh_disc_marks();
+        assert!(game.stable_discs.is_none());
+
+        game.options.should_take_statistics = true;
+        game.options.show_stable_discs = true;
+        game.refresh_disc_marks();
+        let none = [[false; Board::SIZE]; Board::SIZE];
+        assert_eq!(game.stable_discs.as_ref().unwrap().discs, none);
+
+        play_random_game(&mut game);
+        for _ in 0..10 {
+            game.refresh_disc_marks();
+        }
+        assert_eq!(game.disc_mark_computations, 2);
+        assert_eq!(
+            game.stable_discs.as_ref().unwrap().discs,
+            Agent::stable_disks(&game.board)
+        );
+        // it only shows, the game still counted
+        assert_eq!(game.game_records.len(), 1);
+
+        // a replay shows its own position
+        game.show_replay_position(0);
+        game.refresh_disc_marks();
+        assert_eq!(game.disc_mark_computations, 3);
+        assert_eq!(game.stable_discs.as_ref().unwrap().discs, none);
+    }
+
+    #[test]
+    fn the_frontier_overlay_counts_each_color_and_goes_along_with_the_stable_one() {
+        let mut game = new_game();
+        game.refresh_disc_marks();
+        assert_eq!(game.frontier_count_text(), None);
+
+        game.options.show_frontier_discs = true;
+        game.refresh_disc_marks();
+        assert_eq!(
+            game.frontier_count_text().unwrap(),
+            "Frontier discs: Black 2, White 2"
+        );
+
+        // d3 leaves black's three discs and white's one all next to empty squares
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.options.show_stable_discs = true;
+        game.refresh_disc_marks();
+        assert_eq!(game.disc_mark_computations, 3);
+        assert_eq!(
+            game.frontier_count_text().unwrap(),
+            "Frontier discs: Black 4, White 1"
+        );
+
+        // the two sets are worked out apart, and a disc can be in both or neither
+        play_random_game(&mut game);
+        game.refresh_disc_marks();
+        game.refresh_disc_marks();
+        assert_eq!(game.disc_mark_computations, 5);
+        let frontier = game.frontier_discs.as_ref().unwrap();
+        let stable = game.stable_discs.as_ref().unwrap();
+        assert_eq!(frontier.discs, Agent::frontier_disks(&game.board));
+        assert_eq!(stable.discs, Agent::stable_disks(&game.board));
+        let (black_count, white_count) = count_pieces(&game.board);
+        assert!(frontier.count(Player::Black) <= black_count);
+        assert!(frontier.count(Player::White) <= white_count);
+
+        game.options.show_frontier_discs = false;
+        game.refresh_disc_marks();
+        assert!(game.frontier_discs.is_none());
+        assert!(game.stable_discs.is_some());
+    }
+
+    // the game's statistics count, whether the status label said so and whether it was right
+    fn end_with_status(game: &mut Game) -> (usize, bool) {
+        let will = game.can_take_statistics() && game.options.should_take_statistics;
+        let before = game.game_records.len();
+        game.end_game(Outcome::Tie);
+        assert_eq!(game.game_records.len() > before, will);
+        assert!(!game.can_take_statistics());
+        (game.game_records.len() - before, will)
+    }
+
+    #[test]
+    fn the_take_statistics_box_at_the_end_of_the_game_decides() {
+        let mut game = new_game();
+        game.options.should_take_statistics = false;
+        assert_eq!(end_with_status(&mut game), (0, false));
+        // checking the box after the end does not count the game either
+        game.options.should_take_statistics = true;
+        game.take_statistics(Outcome::Tie);
+        assert!(game.game_records.is_empty());
+
+        // unchecked in the middle of the game
+        game.restart();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.options.should_take_statistics = false;
+        assert_eq!(end_with_status(&mut game), (0, false));
+
+        // checked again in the middle of the game
+        game.restart();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.options.should_take_statistics = true;
+        assert_eq!(end_with_status(&mut game), (1, true));
+    }
+
+    #[test]
+    fn an_invalidated_game_is_not_counted_whatever_the_box_says() {
+        let mut game = new_game();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.player_options[Player::White as usize].ai_enabled = true;
+        game.ai_setting_changed(SettingChange::AiEnabled(Player::White));
+        assert_eq!(
+            game.statistics_block,
+            Some(StatisticsBlock::SettingChanged(SettingChange::AiEnabled(
+                Player::White
+            )))
+        );
+        assert_eq!(
+            game.statistics_block_reason(game.statistics_block.unwrap()),
+            "White's AI was turned on or off during the game."
+        );
+        // the first reason stays
+        game.swap_sides();
+        assert!(matches!(
+            game.statistics_block,
+            Some(StatisticsBlock::SettingChanged(_))
+        ));
+
+        // the box is checked
+        assert_eq!(end_with_status(&mut game), (0, false));
+    }
+
+    #[test]
+    fn a_running_match_is_in_the_statistics_after_every_game() {
+        let mut game = new_game();
+        for player_options in game.player_options.iter_mut() {
+            player_options.ai_enabled = true;
+            player_options.ai_type = AiType::Random;
         }
+        game.match_games = 3;
+        game.start_match();
+        play_random_game(&mut game);
+
+        let summary = |game: &Game| {
+            let (name, statistic) = game.statistics_file.statistics.data.iter().next().unwrap();
+            (name.clone(), statistic.games(), statistic.is_partial())
+        };
+        assert_eq!(
+            summary(&game),
+            ("Match 1: Random vs Random".to_string(), 1.0, true)
+        );
+
+        // stopped early, the games so far stay, marked as partial
+        game.finish_match();
+        assert_eq!(
+            summary(&game),
+            ("Match 1: Random vs Random".to_string(), 1.0, true)
+        );
+        assert_eq!(game.statistics_file.statistics.data.len(), 1);
     }
 
-    (black_count, white_count)
-}
+    #[test]
+    fn the_statistics_sort_is_kept_with_the_settings_and_the_rows_tell_the_colors_first() {
+        let mut game = new_game();
+        play_random_game(&mut game);
+        play_random_game(&mut game);
+        game.options.statistics_sort = game
+            .options
+            .statistics_sort
+            .clicked(StatisticsColumn::Losses);
+        let saved = serde_json::to_string(&game.options).unwrap();
+        let options: GameOptions = serde_json::from_str(&saved).unwrap();
+        assert_eq!(options.statistics_sort.column, StatisticsColumn::Losses);
+        assert!(options.statistics_sort.descending);
 
-impl eframe::App for Game {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // UI drawing
-            let rect = ui.available_rect_before_wrap();
-            let square_size = rect.width().min(rect.height()) / Board::SIZE as f32;
-            let line_width = square_size * 0.01;
+        // the filter narrows the rows down, but is not a setting
+        game.statistics_filter = "random".to_string();
+        assert!(!saved.contains("random"));
+        let statistics = game.shown_statistics();
+        let rows = statistics.rows(game.options.statistics_sort, &game.statistics_filter);
+        assert_eq!(rows.len(), 1);
+        let details = statistic_details(
+            &game.strings,
+            rows[0].statistic,
+            rows[0].matchup.map(|matchup| &matchup.participants),
+        );
+        assert!(details.starts_with("as Black: "), "{details}");
+        assert!(details.contains("\nScore "), "{details}");
+        assert!(
+            statistics
+                .rows(game.options.statistics_sort, "no such player")
+                .is_empty()
+        );
+    }
 
-            let to_color = |player| match player {
-                Player::Black => egui::Color32::BLACK,
-                Player::White => egui::Color32::WHITE,
-            };
+    #[test]
+    fn the_session_starts_empty_and_resets_without_touching_all_time() {
+        let mut game = new_game();
+        play_random_game(&mut game);
 
-            let get_square_rect = |row, col| {
-                let square_pos = egui::Pos2 {
-                    x: rect.left() + col as f32 * square_size,
-                    y: rect.top() + row as f32 * square_size,
-                };
-                egui::Rect::from_min_size(square_pos, egui::Vec2::splat(square_size))
-            };
+        // a restart loads the saved statistics but not the session
+        let saved = serde_json::to_string(&game.statistics_file).unwrap();
+        let mut game = new_game();
+        game.statistics_file = serde_json::from_str(&saved).unwrap();
+        game.refresh_statistics_views();
+        play_random_game(&mut game);
 
-            // draw the current board state
-            for row in 0..Board::SIZE {
-                for col in 0..Board::SIZE {
-                    let square_rect = get_square_rect(row, col);
+        let games = |statistics: &Statistics| {
+            statistics
+                .matchups
+                .iter()
+                .map(|entry| entry.statistic.games())
+                .sum::<f64>()
+        };
+        assert_eq!(games(&game.statistics_file.statistics), 2.0);
+        assert_eq!(games(&game.session_statistics), 1.0);
 
-                    ui.painter()
-                        .rect_filled(square_rect, 0.0, egui::Color32::DARK_GREEN);
+        // the ratings follow the statistics shown
+        let all_time_games = game.ratings[0].games;
+        game.show_session_statistics = true;
+        game.refresh_statistics_views();
+        assert_eq!(game.ratings[0].games * 2.0, all_time_games);
 
-                    let stroke = egui::Stroke {
-                        width: line_width,
-                        color: egui::Color32::BLACK,
-                    };
-                    ui.painter()
-                        .rect_stroke(square_rect, 0.0, stroke, egui::StrokeKind::Inside);
+        game.reset_session_statistics();
+        assert!(game.session_statistics.is_empty());
+        assert!(game.ratings.is_empty());
+        assert_eq!(games(&game.statistics_file.statistics), 2.0);
+    }
 
-                    if let Cell::Taken(cell_state) = self.board.grid[row][col] {
-                        ui.painter().circle_filled(
-                            square_rect.center(),
-                            square_size / 2.0 * 0.93,
-                            to_color(cell_state),
-                        );
-                    }
-                }
+    #[test]
+    fn marks_stay_through_undo_and_redo_and_go_with_the_game_if_asked() {
+        let mut game = new_game();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.square_marks.cycle((2, 3));
+        game.square_marks.cycle((0, 0));
+        game.square_marks.cycle((0, 0));
+        let marks = game.square_marks.clone();
+
+        game.undo();
+        assert_eq!(game.square_marks, marks);
+        game.redo();
+        assert_eq!(game.square_marks, marks);
+        assert_eq!(
+            game.display_options().1.marks,
+            [((0, 0), SquareMark::Cross), ((2, 3), SquareMark::Circle)]
+        );
+        game.image_export.marks = false;
+        assert!(game.display_options().1.marks.is_empty());
+
+        game.options.clear_marks_on_new_game = false;
+        game.restart();
+        assert_eq!(game.square_marks, marks);
+        game.options.clear_marks_on_new_game = true;
+        game.restart();
+        assert!(game.square_marks.is_empty());
+    }
+
+    #[test]
+    fn a_flip_animation_ends_with_the_position_it_shows() {
+        let mut game = new_game();
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.flip_animation.is_some());
+
+        game.undo();
+        assert!(game.flip_animation.is_none());
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.show_replay_position(0);
+        assert!(game.flip_animation.is_none());
+
+        game.replay = None;
+        game.make_move((2, 2), Player::White).unwrap();
+        game.reset();
+        assert!(game.flip_animation.is_none());
+
+        // nothing to watch at instant playback speed
+        game.options.ai_move_seconds = 0.0;
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.flip_animation.is_none());
+    }
+
+    #[test]
+    fn animations_ease_in_and_reduce_motion_turns_them_off() {
+        let mut animations = Animations::default();
+        let now = Instant::now();
+        assert_eq!(animations.progress(AnimationKey::GameOver, now), 1.0);
+
+        animations
+            .running
+            .insert(AnimationKey::GameOver, (now, Duration::from_secs(2)));
+        assert_eq!(animations.progress(AnimationKey::GameOver, now), 0.0);
+        let half_way = animations.progress(AnimationKey::GameOver, now + Duration::from_secs(1));
+        assert_eq!(half_way, 0.75);
+        let later = now + Duration::from_secs(3);
+        assert_eq!(animations.progress(AnimationKey::GameOver, later), 1.0);
+        animations.clear();
+        assert!(animations.running.is_empty());
+
+        let mut game = new_game();
+        game.options.reduce_motion = true;
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.flip_animation.is_none());
+        assert!(
+            !game
+                .animations
+                .running
+                .contains_key(&AnimationKey::PlacedDisc)
+        );
+    }
+
+    #[test]
+    fn the_result_overlay_shows_until_closed_and_goes_into_the_image() {
+        let mut game = new_game();
+        play_random_game(&mut game);
+        assert!(game.shows_result_overlay());
+        let text = game.result_text().unwrap();
+        let (black_count, white_count) = count_pieces(&game.board);
+        match game.current_phase {
+            Phase::Win(winner, _) => {
+                assert!(text.starts_with(&format!("{winner:?} wins")));
+                let high = black_count.max(white_count);
+                assert!(text.ends_with(&format!("{high}–{}", black_count.min(white_count))));
             }
+            _ => assert_eq!(text, format!("Tie {black_count}–{white_count}")),
+        }
+        let (_, options) = game.display_options();
+        assert_eq!(options.result, game.result_overlay());
 
-            match self.current_phase {
-                Phase::Turn(player) if self.player_options[player as usize].ai_enabled => {
-                    // AI moves
-                    self.tick_ai(player);
+        assert!(game.is_action_enabled(Action::Cancel));
+        game.perform(Action::Cancel);
+        assert!(!game.shows_result_overlay());
+        assert!(game.display_options().1.result.is_none());
+
+        // the next game brings it back when it ends
+        game.reset();
+        assert!(!game.shows_result_overlay());
+        play_random_game(&mut game);
+        assert!(game.shows_result_overlay());
+    }
+
+    #[test]
+    fn the_heatmap_shows_the_scores_of_the_position_on_the_board_only() {
+        let mut game = new_game();
+        game.options.show_heatmap = true;
+        game.request_hint();
+        while game
+            .position_scores
+            .as_ref()
+            .is_some_and(|scores| scores.move_scores.is_none())
+        {
+            game.poll_position_scores();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let heatmap = game.heatmap();
+        let mut squares: Vec<Move> = heatmap.iter().map(|&(square, _, _)| square).collect();
+        squares.sort();
+        let mut valid_moves: Vec<Move> = game.valid_moves.iter().collect();
+        valid_moves.sort();
+        assert_eq!(squares, valid_moves);
+        // the four first moves mirror each other, so none ranks above another
+        assert!(heatmap.iter().all(|&(_, level, _)| level == 0.5));
+        assert_eq!(game.display_options().1.heatmap, heatmap);
+
+        game.options.show_heatmap = false;
+        assert!(game.heatmap().is_empty());
+        game.options.show_heatmap = true;
+
+        // gone with the position it belongs to
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.heatmap().is_empty());
+    }
+
+    #[test]
+    fn every_panel_section_can_be_drawn_open() {
+        let ctx = egui::Context::default();
+        let mut game = new_game();
+        for section in PanelSection::ALL {
+            game.options.open_sections.set_open(section, true);
+        }
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                for section in PanelSection::ALL {
+                    game.show_panel_section(ui, section);
                 }
-                Phase::Turn(player) => {
-                    // ai is disabled
+            });
+        });
+        assert!(
+            game.settings()
+                .options
+                .open_sections
+                .is_open(PanelSection::Network)
+        );
+    }
 
-                    // Awaiting human move
-                    if self.options.show_valid_moves {
-                        for (valid_row, valid_col) in self.valid_moves.iter() {
-                            let square_rect = get_square_rect(valid_row, valid_col);
-                            let highlight_color = match player {
-                                Player::Black => {
-                                    egui::Color32::from_rgba_premultiplied(0, 100, 0, 40)
-                                }
-                                Player::White => {
-                                    egui::Color32::from_rgba_premultiplied(100, 100, 100, 30)
-                                }
-                            };
-                            ui.painter().rect_filled(square_rect, 0.0, highlight_color);
-                        }
-                    }
+    // the descriptions are keys the locale test cannot see, as they are not looked up literally
+    #[test]
+    fn every_shortcut_description_has_an_english_string() {
+        let strings = Strings::new(Language::English);
+        for (_, _, description) in SHORTCUTS {
+            assert_ne!(strings.tr(description), description);
+        }
+    }
 
-                    // Mouse handling
-                    let mut row = Board::SIZE;
-                    let mut col = Board::SIZE;
+    #[test]
+    fn compact_mode_is_toggled_and_kept_with_the_settings() {
+        let mut game = new_game();
+        assert!(
+            SHORTCUTS
+                .iter()
+                .any(|(shortcut, action, _)| *action == Action::ToggleCompactMode
+                    && shortcut.logical_key == egui::Key::Tab)
+        );
+        assert!(game.is_action_enabled(Action::ToggleCompactMode));
 
-                    let mut is_valid_move = false;
+        game.perform(Action::ToggleCompactMode);
+        assert!(game.options.compact_mode);
+        assert!(game.settings().options.compact_mode);
+        game.perform(Action::ToggleCompactMode);
+        assert!(!game.options.compact_mode);
+    }
 
-                    // check mouse hovering
-                    if let Some(mouse_pos) = ui.input(|i| i.pointer.latest_pos()) {
-                        row = ((mouse_pos.y - rect.top()) / square_size) as usize;
-                        col = ((mouse_pos.x - rect.left()) / square_size) as usize;
+    #[test]
+    fn the_side_panel_is_dragged_to_a_width_kept_with_the_settings() {
+        let ctx = egui::Context::default();
+        let mut game = new_game();
+        // the width left to the board
+        let frame = |game: &mut Game, screen_width: f32, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(screen_width, 800.0),
+                )),
+                events,
+                ..Default::default()
+            };
+            let mut left = 0.0;
+            let _ = ctx.run(input, |ctx| {
+                game.show_side_panel(ctx);
+                left = ctx.available_rect().width();
+            });
+            left
+        };
+        let pointer = |x: f32, pressed: Option<bool>| {
+            let pos = egui::pos2(x, 400.0);
+            match pressed {
+                None => egui::Event::PointerMoved(pos),
+                Some(pressed) => egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers: egui::Modifiers::NONE,
+                },
+            }
+        };
 
-                        if row < Board::SIZE && col < Board::SIZE {
-                            // this could be optimized by only doing it when the mouse changes cells
-                            is_valid_move = self.referee.find_flip_cells_for_move(
-                                &self.board,
-                                player,
-                                (row, col),
-                                &mut self.flip_cells,
-                            );
+        assert_eq!(frame(&mut game, 1200.0, Vec::new()), 920.0);
+        game.options.side_panel_width = 400.0;
+        assert_eq!(frame(&mut game, 1200.0, Vec::new()), 800.0);
+        // a narrow window leaves the board its share, and gives the width back when it grows
+        assert_eq!(frame(&mut game, 500.0, Vec::new()), 200.0);
+        assert_eq!(frame(&mut game, 1200.0, Vec::new()), 800.0);
 
-                            if is_valid_move {
-                                // show move effects with connecting lines
-                                if self.options.show_effects_of_moves {
-                                    let hovered_rect = get_square_rect(row, col);
-                                    let hovered_center = hovered_rect.center();
+        // the edge dragged 100 to the left, the setting changing once it is let go
+        frame(
+            &mut game,
+            1200.0,
+            vec![pointer(800.0, None), pointer(800.0, Some(true))],
+        );
+        for x in [790.0, 750.0, 700.0] {
+            frame(&mut game, 1200.0, vec![pointer(x, None)]);
+        }
+        assert_eq!(game.options.side_panel_width, 400.0);
+        frame(&mut game, 1200.0, vec![pointer(700.0, Some(false))]);
+        assert_eq!(game.options.side_panel_width, 500.0);
+        assert_eq!(game.settings().options.side_panel_width, 500.0);
+        assert_eq!(frame(&mut game, 1200.0, Vec::new()), 700.0);
 
-                                    // Highlight the hovered square with a bright border
-                                    ui.painter().rect_stroke(
-                                        hovered_rect,
-                                        0.0,
-                                        egui::Stroke::new(
-                                            3.0,
-                                            egui::Color32::from_rgb(255, 255, 0),
-                                        ),
-                                        egui::StrokeKind::Inside,
-                                    );
+        game.reset_settings();
+        assert_eq!(frame(&mut game, 1200.0, Vec::new()), 920.0);
+    }
 
-                                    for (flip_row, flip_col) in self.flip_cells.iter() {
-                                        let flip_rect = get_square_rect(flip_row, flip_col);
-                                        let flip_center = flip_rect.center();
+    #[test]
+    fn the_score_graph_starts_with_the_start_position() {
+        let mut game = new_game();
+        // nothing played yet
+        game.refresh_move_list();
+        assert_eq!(game.move_list.counts, [(2, 2)]);
 
-                                        // Draw line from hovered center to flip center
-                                        ui.painter().line_segment(
-                                            [hovered_center, flip_center],
-                                            egui::Stroke::new(2.0, to_color(player)),
-                                        );
+        // an edited start position with no moves either
+        game.history.start_board.grid[0][0] = Cell::Taken(Player::Black);
+        game.refresh_move_list();
+        assert_eq!(game.move_list.counts, [(3, 2)]);
+    }
 
-                                        // Simple dot at flip cell instead of arrowhead
-                                        ui.painter().circle_filled(
-                                            flip_center,
-                                            square_size * 0.08,
-                                            to_color(player),
-                                        );
+    #[test]
+    fn the_ui_scale_is_applied_and_follows_a_keyboard_zoom() {
+        let ctx = egui::Context::default();
+        let mut game = new_game();
+        // a zoom takes effect when the next frame begins
+        let frame = |game: &mut Game| {
+            let _ = ctx.run(Default::default(), |ctx| game.apply_appearance(ctx));
+        };
+        game.options.ui_scale = 2.0;
+        frame(&mut game);
+        frame(&mut game);
+        assert_eq!(ctx.zoom_factor(), 2.0);
+        assert_eq!(game.options.ui_scale, 2.0);
 
-                                        // Highlight the flip cell with a border
-                                        ui.painter().rect_stroke(
-                                            flip_rect,
-                                            0.0,
-                                            egui::Stroke::new(2.0, to_color(player)),
-                                            egui::StrokeKind::Inside,
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }
+        // as Ctrl+Minus does
+        ctx.set_zoom_factor(1.5);
+        frame(&mut game);
+        assert_eq!(game.options.ui_scale, 1.5);
+        frame(&mut game);
+        assert_eq!(ctx.zoom_factor(), 1.5);
 
-                    // handle mouse clicks to make moves
-                    if ui.input(|i| i.pointer.any_down())
-                        && row < Board::SIZE
-                        && col < Board::SIZE
-                        && is_valid_move
-                    {
-                        assert!(self.make_move((row, col), player));
-                    }
-                }
-                Phase::Win(_) | Phase::Tie => {
-                    if self.options.auto_restart && Instant::now() >= self.scheduled_restart {
-                        self.reset();
-                    }
-                }
+        // kept in the range of the slider
+        ctx.set_zoom_factor(0.3);
+        frame(&mut game);
+        assert_eq!(game.options.ui_scale, 0.75);
+        frame(&mut game);
+        assert_eq!(ctx.zoom_factor(), 0.75);
+    }
+
+    #[test]
+    fn every_ai_move_of_a_game_is_timed() {
+        let mut game = new_game();
+        let moves = play_random_game(&mut game);
+
+        let thinking_times = game.statistics_file.statistics.thinking_times();
+        assert_eq!(thinking_times.len(), 1);
+        let (participant, time) = &thinking_times[0];
+        assert_eq!(*participant, Participant::ai(AiType::Random, 1));
+        assert_eq!(time.moves, moves.len());
+        assert!(time.max_seconds <= time.total_seconds);
+
+        game.restart();
+        assert_eq!(game.thinking_times[Player::Black as usize].moves, 0);
+    }
+
+    #[test]
+    fn the_same_seed_plays_the_same_game() {
+        let mut game = new_game();
+        let moves = play_random_game(&mut game);
+
+        // as Replay with Same Seed does
+        game.pinned_seed = Some(game.seed);
+        game.restart();
+        assert_eq!(play_random_game(&mut game), moves);
+
+        let mut game = Game::new(StartupArgs {
+            seed: Some(game.seed),
+            ..Default::default()
+        });
+        assert_eq!(play_random_game(&mut game), moves);
+
+        game.pinned_seed = Some(game.seed.wrapping_add(1));
+        game.restart();
+        assert_ne!(play_random_game(&mut game), moves);
+    }
+
+    #[test]
+    fn move_numbers_follow_the_board_on_display() {
+        let mut game = new_game();
+        let moves = play_random_game(&mut game);
+        let (row, col) = moves[0];
+
+        let numbers = game.move_numbers().unwrap();
+        assert_eq!(numbers[row][col], Some(1));
+        assert_eq!(numbers.iter().flatten().flatten().count(), moves.len());
+
+        game.show_replay_position(0);
+        let numbers = game.move_numbers().unwrap();
+        assert_eq!(numbers.iter().flatten().flatten().count(), 0);
+
+        game.replay = None;
+        game.open_editor();
+        assert_eq!(game.move_numbers(), None);
+    }
+
+    #[test]
+    fn a_hint_on_its_way_is_not_taken_for_the_move_of_a_seat_given_to_the_ai() {
+        let mut game = new_game();
+        game.request_hint();
+        let Some(Hint::Pending(hint_id)) = game.hint else {
+            panic!("no hint asked for");
+        };
+
+        // Finish with AI while the hint is thought about
+        game.options.ai_move_seconds = 0.0;
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.player_options[Player::Black as usize].ai_type = AiType::Random;
+        while game.history.plies.is_empty() {
+            game.tick_ai(Player::Black);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(!game.is_ai_thinking(Player::Black));
+
+        // the hint's answer is still there, for the hint
+        let (answered, move_result) = loop {
+            if let Some(answer) = game.hint_runner.try_recv().unwrap() {
+                break answer;
             }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        };
+        assert_eq!(answered, hint_id);
+        assert_eq!(move_result.board.grid, Board::default().grid);
+    }
+
+    #[test]
+    fn an_answer_to_a_dropped_request_is_skipped() {
+        let mut game = new_game();
+        game.options.ai_move_seconds = 0.0;
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.player_options[Player::Black as usize].ai_type = AiType::Random;
+        game.tick_ai(Player::Black);
+        let dropped = game.ai_requests[Player::Black as usize]
+            .as_ref()
+            .unwrap()
+            .id;
+        // e.g. a take back, then the seat is asked again
+        game.ai_requests[Player::Black as usize] = None;
+        game.tick_ai(Player::Black);
+        let asked = game.ai_requests[Player::Black as usize]
+            .as_ref()
+            .unwrap()
+            .id;
+        assert_ne!(dropped, asked);
+
+        while game.history.plies.is_empty() {
+            game.tick_ai(Player::Black);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        // one move was played, for the request still standing, and nothing is left to answer
+        assert_eq!(game.history.plies.len(), 1);
+        assert!(!game.is_ai_thinking(Player::Black));
+        assert!(
+            game.agent_runners[Player::Black as usize]
+                .try_recv()
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn swapping_sides_drops_the_request_of_the_now_human_seat() {
+        let mut game = new_game();
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.player_options[Player::Black as usize].ai_type = AiType::Random;
+        game.player_options[Player::White as usize].ai_enabled = false;
+        game.tick_ai(Player::Black);
+        assert!(game.is_ai_thinking(Player::Black));
+
+        let board = game.board.clone();
+        game.swap_sides();
+
+        assert!(!game.player_options[Player::Black as usize].ai_enabled);
+        assert!(game.player_options[Player::White as usize].ai_enabled);
+        assert_eq!(
+            game.player_options[Player::White as usize].ai_type,
+            AiType::Random
+        );
+        assert!(!game.is_ai_thinking(Player::Black));
+        assert!(!game.can_take_statistics());
+        assert_eq!(game.board.grid, board.grid);
+        assert!(matches!(game.current_phase, Phase::Turn(Player::Black)));
+
+        game.end_game(Outcome::Tie);
+        assert!(!game.can_swap_sides());
+    }
+
+    #[test]
+    fn the_score_graph_follows_the_history() {
+        let mut game = new_game();
+        play_random_game(&mut game);
+        game.refresh_move_list();
+
+        let counts = &game.move_list.counts;
+        assert_eq!(counts.len(), game.history.plies.len() + 1);
+        assert_eq!(counts[0], (2, 2));
+        assert_eq!(counts.last(), Some(&count_pieces(&game.board)));
+
+        game.reset();
+        game.refresh_move_list();
+        assert_eq!(game.move_list.counts, [(2, 2)]);
+    }
+
+    #[test]
+    fn positions_resolve_to_the_square_under_them() {
+        let board_rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(80.0, 80.0));
+
+        assert_eq!(square_at(board_rect, egui::pos2(10.0, 20.0)), Some((0, 0)));
+        assert_eq!(square_at(board_rect, egui::pos2(29.9, 25.0)), Some((0, 1)));
+        assert_eq!(square_at(board_rect, egui::pos2(30.0, 39.9)), Some((1, 2)));
+        assert_eq!(square_at(board_rect, egui::pos2(89.9, 99.9)), Some((7, 7)));
+    }
+
+    #[test]
+    fn the_coordinates_frame_the_board_in_any_rect() {
+        // wider than high, with room for the evaluation bar
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(300.0, 90.0));
+        let (board_rect, square_size) = board_layout(rect, 32.0, true);
+        assert_eq!(square_size, 10.0);
+        // centered, the frame and the bar taking 122 of the 300 wide
+        assert_eq!(board_rect.min, egui::pos2(104.0, 25.0));
+        assert_eq!(board_rect.size(), egui::Vec2::splat(80.0));
+        // clicks land on the square they are over, not on the frame
+        assert_eq!(square_at(board_rect, egui::pos2(101.0, 50.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(104.0, 25.0)), Some((0, 0)));
+        assert_eq!(
+            square_at(board_rect, egui::pos2(183.9, 104.9)),
+            Some((7, 7))
+        );
+
+        // higher than wide, the bar taking its share of the width
+        let rect = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(122.0, 300.0));
+        let (board_rect, square_size) = board_layout(rect, 32.0, true);
+        assert_eq!(square_size, 10.0);
+        assert_eq!(
+            board_rect.right() + square_size * COORDINATE_MARGIN + 32.0,
+            122.0
+        );
+
+        // and every square in sight, centered vertically
+        assert!(rect.contains_rect(board_rect));
+        assert_eq!(board_rect.center().y, rect.center().y);
+
+        let (board_rect, square_size) = board_layout(rect, 32.0, false);
+        assert_eq!(board_rect.left(), rect.left());
+        assert_eq!(square_size * Board::SIZE as f32, 90.0);
+
+        // a window too short for the board shrinks it rather than cutting off rows
+        let rect = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(1000.0, 45.0));
+        let (board_rect, _) = board_layout(rect, 32.0, true);
+        assert!(rect.contains_rect(board_rect));
+        assert_eq!(board_rect.center().x + 16.0, rect.center().x);
+    }
+
+    #[test]
+    fn the_board_turns_to_a_lone_white_human_when_asked() {
+        let mut game = new_game();
+        game.options.orientation.mirrored = true;
+        game.player_options[Player::Black as usize].ai_enabled = true;
+        game.player_options[Player::White as usize].ai_enabled = false;
+        assert_eq!(game.orientation(), game.options.orientation);
+
+        game.options.auto_orient = true;
+        let turned = Orientation {
+            rotation: Rotation::Half,
+            mirrored: true,
+        };
+        assert_eq!(game.orientation(), turned);
+        // the pictures are drawn as the board is shown
+        assert_eq!(game.static_display_options().orientation, turned);
+
+        // not for black, nor for two humans
+        game.player_options[Player::Black as usize].ai_enabled = false;
+        assert_eq!(game.orientation(), game.options.orientation);
+        game.player_options[Player::White as usize].ai_enabled = true;
+        assert_eq!(game.orientation(), game.options.orientation);
+    }
+
+    #[test]
+    fn arrows_start_from_the_disc_closing_the_longest_flipped_line() {
+        let mut board = Board {
+            grid: [[Cell::Empty; Board::SIZE]; Board::SIZE],
+        };
+        // a1 flips one disc along the first row and two down the first column
+        board.grid[0][1] = Cell::Taken(Player::White);
+        board.grid[0][2] = Cell::Taken(Player::Black);
+        board.grid[1][0] = Cell::Taken(Player::White);
+        board.grid[2][0] = Cell::Taken(Player::White);
+        board.grid[3][0] = Cell::Taken(Player::Black);
+        assert_eq!(
+            capture_arrow(&board, Player::Black, (0, 0)),
+            Some(((3, 0), (0, 0)))
+        );
+        assert_eq!(capture_arrow(&board, Player::White, (0, 0)), None);
+    }
+
+    #[test]
+    fn the_hint_arrow_goes_with_the_position_and_into_the_pictures() {
+        let mut game = new_game();
+        game.hint = Some(Hint::Ready((2, 3)));
+        let hint_color = game.options.board_theme.theme().hint;
+        assert_eq!(game.arrows(), [((4, 3), (2, 3), hint_color)]);
+        assert_eq!(game.display_options().1.arrows, [((4, 3), (2, 3))]);
+        game.image_export.arrows = false;
+        assert!(game.display_options().1.arrows.is_empty());
+
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.arrows().is_empty());
+    }
 
-            ctx.request_repaint();
+    #[test]
+    fn analyzed_positions_score_every_valid_move_in_its_corner() {
+        let mut game = new_game();
+        game.make_move((2, 3), Player::Black).unwrap();
+        game.make_move((2, 2), Player::White).unwrap();
+        let mut analysis = Analysis::new(game.history.plies.clone(), 1);
+        analysis.positions[0] = Some(analysis::PositionAnalysis {
+            player: Player::Black,
+            played_move: (2, 3),
+            move_scores: vec![((2, 3), 2.0), ((3, 2), 2.0), ((4, 5), -1.0), ((5, 4), 0.0)],
         });
+        game.analysis = Some(analysis);
 
-        egui::SidePanel::right("right_panel").show(ctx, move |ui| {
-            ui.separator();
+        game.show_replay_position(0);
+        assert_eq!(
+            game.annotations(),
+            [
+                ((2, 3), "+2".to_string(), true),
+                ((3, 2), "+2".to_string(), true),
+                ((4, 5), "−1".to_string(), false),
+                ((5, 4), "0".to_string(), false)
+            ]
+        );
+        assert_eq!(game.display_options().1.annotations, game.annotations());
+        game.image_export.annotations = false;
+        assert!(game.display_options().1.annotations.is_empty());
 
-            // Current-status message
-            let message = match self.current_phase {
-                Phase::Turn(player) => {
-                    if self.awaiting_ai_move && self.player_options[player as usize].ai_enabled {
-                        format!("{:?} is thinking...", player)
-                    } else {
-                        format!("{:?}'s turn", player)
-                    }
-                }
-                Phase::Win(player) => {
-                    format!("{:?} won", player)
-                }
-                Phase::Tie => "Tie".to_string(),
-            };
+        // none for a position that was not analyzed, nor outside the replay
+        game.show_replay_position(1);
+        assert!(game.annotations().is_empty());
+        game.show_replay_position(0);
+        game.options.annotate_analyzed_moves = false;
+        assert!(game.annotations().is_empty());
+        game.options.annotate_analyzed_moves = true;
+        game.replay = None;
+        assert!(game.annotations().is_empty());
+    }
 
-            ui.label(message);
+    #[test]
+    fn positions_off_the_board_have_no_square() {
+        let board_rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(80.0, 80.0));
 
-            let (black_score, white_score) = count_pieces(&self.board);
-            ui.label(format!("Black: {}", black_score));
-            ui.label(format!("White: {}", white_score));
+        assert_eq!(square_at(board_rect, egui::pos2(9.9, 50.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(50.0, 19.9)), None);
+        // the far edges belong to no square
+        assert_eq!(square_at(board_rect, egui::pos2(90.0, 50.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(50.0, 100.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(200.0, 200.0)), None);
 
-            ui.separator();
+        // left of and above a board at the origin, where rounding toward zero would give a1
+        let board_rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(80.0, 80.0));
+        assert_eq!(square_at(board_rect, egui::pos2(-0.1, 5.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(5.0, -9.9)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(-50.0, -50.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(f32::NAN, 5.0)), None);
+        assert_eq!(square_at(board_rect, egui::pos2(0.0, 0.0)), Some((0, 0)));
+    }
 
-            self.update_player_options_controls(ui, Player::Black);
+    #[test]
+    fn typed_moves_are_parsed_and_checked() {
+        let strings = Strings::new(Language::English);
+        let mut referee = Referee::default();
+        let board = Board::default();
 
-            ui.separator();
+        assert_eq!(
+            typed_move(&strings, &mut referee, &board, Player::Black, " D3 "),
+            Ok((2, 3))
+        );
+        assert_eq!(
+            typed_move(&strings, &mut referee, &board, Player::Black, "a1"),
+            Err("a1 is not a valid move".to_string())
+        );
+        assert_eq!(
+            typed_move(&strings, &mut referee, &board, Player::Black, "d9"),
+            Err("\"d9\" is not a square such as d3".to_string())
+        );
+        assert!(typed_move(&strings, &mut referee, &board, Player::Black, "").is_err());
+    }
 
-            self.update_player_options_controls(ui, Player::White);
+    #[test]
+    fn a_typed_move_is_played() {
+        let mut game = new_game();
+        let next_move = typed_move(
+            &game.strings,
+            &mut game.referee,
+            &game.board,
+            Player::Black,
+            "d3",
+        )
+        .unwrap();
 
-            ui.separator();
+        assert_eq!(game.make_move(next_move, Player::Black), Ok(()));
+        assert_eq!(game.history.moves(), [(2, 3)]);
+        assert!(matches!(game.current_phase, Phase::Turn(Player::White)));
+    }
 
-            ui.label("Control");
-            if ui.button("Restart Game").clicked() {
-                self.reset();
+    #[test]
+    fn shortcuts_are_unique() {
+        for (index, (shortcut, _, _)) in SHORTCUTS.iter().enumerate() {
+            assert!(
+                SHORTCUTS[index + 1..]
+                    .iter()
+                    .all(|(other, _, _)| other != shortcut),
+                "{shortcut:?} is registered twice"
+            );
+        }
+    }
+
+    #[test]
+    fn shortcuts_respect_disabled_actions() {
+        let mut game = new_game();
+        assert!(!game.is_action_enabled(Action::Undo));
+        assert!(!game.is_action_enabled(Action::Cancel));
+
+        game.make_move((2, 3), Player::Black).unwrap();
+        assert!(game.is_action_enabled(Action::Undo));
+        game.perform(Action::Undo);
+        assert!(game.history.plies.is_empty());
+        assert!(game.is_action_enabled(Action::Redo));
+    }
+
+    #[test]
+    fn play_as_matches_the_seats_it_sets_up() {
+        for play_as in [PlayAs::Black, PlayAs::White, PlayAs::Both, PlayAs::Neither] {
+            let mut player_options = [PlayerOptions::default(); 2];
+            for player in [Player::Black, Player::White] {
+                player_options[player as usize].ai_enabled = !play_as.is_human(player);
             }
-            ui.checkbox(&mut self.options.auto_restart, "Auto Restart");
+            assert_eq!(PlayAs::from_player_options(&player_options), play_as);
+        }
+    }
 
-            ui.separator();
+    #[test]
+    fn renamed_and_deleted_statistics_update_the_ratings() {
+        let mut game = new_game();
+        game.player_options[Player::White as usize].ai_enabled = true;
+        let result = GameResult {
+            outcome: Outcome::Won(Player::Black, WinReason::Disks),
+            disc_counts: [40, 24],
+            plies: 60,
+        };
+        game.add_statistics_datum(
+            result,
+            Player::Black,
+            String::new(),
+            None,
+            [ThinkingTime::default(); 2],
+        );
 
-            ui.label("Flow");
-            ui.checkbox(&mut self.options.pace_ai, "Pace AI");
-            ui.checkbox(&mut self.options.pause_at_win, "Pause at Win");
+        game.rename_from = Some(Participant::Human);
+        game.rename_to = " Alice ".to_string();
+        game.rename_participant();
+        let names: Vec<&str> = game
+            .ratings
+            .iter()
+            .map(|rating| rating.name.as_str())
+            .collect();
+        assert_eq!(names, ["Alice", "Random"]);
+        assert!(game.rename_to.is_empty());
 
-            ui.separator();
+        let matchup = game.statistics_file.statistics.matchups[0].matchup.clone();
+        assert_eq!(matchup.to_string(), "Random vs Alice");
+        game.remove_matchup(&matchup);
+        assert!(game.statistics_file.statistics.is_empty());
+        assert!(game.ratings.is_empty());
+    }
 
-            ui.label("Help");
-            ui.checkbox(&mut self.options.show_valid_moves, "Show Valid Moves");
-            ui.checkbox(
-                &mut self.options.show_effects_of_moves,
-                "Show Effects of Moves",
-            );
+    #[test]
+    fn a_head_to_head_cell_opens_the_games_of_its_matchup() {
+        let mut game = new_game();
+        game.player_options[Player::White as usize].ai_enabled = true;
+        let result = GameResult {
+            outcome: Outcome::Won(Player::White, WinReason::Disks),
+            disc_counts: [24, 40],
+            plies: 60,
+        };
+        game.add_statistics_datum(
+            result,
+            Player::White,
+            String::new(),
+            None,
+            [ThinkingTime::default(); 2],
+        );
 
-            ui.separator();
+        let head_to_head = &game.head_to_head;
+        assert_eq!(head_to_head.participants.len(), 2);
+        assert!(head_to_head.cells[0][0].is_none());
 
-            ui.label("Statistics");
-            ui.checkbox(&mut self.options.should_take_statistics, "Take Statistics");
-            let modus = match (
-                self.can_take_statistics,
-                self.options.should_take_statistics,
-            ) {
-                (true, true) => "will",
-                (false, true) => "cannot",
-                (_, false) => "will not",
-            };
-            ui.label(format!("Statistics {modus} be taken"));
+        // only the white first variant was played, so that is the one shown
+        game.show_matchup_games([Participant::ai(AiType::Random, 1), Participant::Human]);
+        let matchup = game.game_history_filter.clone().unwrap();
+        assert_eq!(matchup.to_string(), "Human vs Random (White first)");
+        assert!(game.open_game_history);
+    }
 
-            ui.separator();
+    #[test]
+    fn every_orientation_of_a_recorded_opening_is_scored_on_the_board() {
+        let mut game = new_game();
+        let result = GameResult {
+            outcome: Outcome::Won(Player::Black, WinReason::Disks),
+            disc_counts: [40, 24],
+            plies: 60,
+        };
+        game.add_statistics_datum(
+            result,
+            Player::Black,
+            "D3C5".to_string(),
+            None,
+            [ThinkingTime::default(); 2],
+        );
 
-            ui.label("Won%, Tied%, Lost%, (Total):");
-            for (name, statistic) in self.statistics.data.iter() {
-                ui.label(format!("{name}:\n{statistic}"));
-            }
-        });
+        let mut scores = game.opening_scores();
+        scores.sort_by_key(|&(next_move, _)| next_move);
+        let first_moves: Vec<String> = scores
+            .iter()
+            .map(|&(next_move, _)| notation::format_move(next_move))
+            .collect();
+        assert_eq!(first_moves, ["d3", "c4", "f5", "e6"]);
+        assert!(scores.iter().all(|&(_, score)| score == 1.0));
+
+        // the lines are rebuilt from the records at another length
+        game.options.opening_plies = 1;
+        game.refresh_openings();
+        assert_eq!(game.openings.lines.len(), 1);
+    }
+
+    #[test]
+    fn recorded_games_are_replayed_from_the_history() {
+        let mut game = new_game();
+        let moves = play_random_game(&mut game);
+
+        assert_eq!(game.game_records.len(), 1);
+        let record = &game.game_records[0];
+        assert_eq!(record.seed, Some(game.seed));
+        assert_eq!(record.matchup.to_string(), "Random vs Random");
+        let (black_count, white_count) = count_pieces(&game.board);
+        assert_eq!(record.disc_counts, [black_count, white_count]);
+
+        game.reset();
+        game.load_game_record(0);
+        assert_eq!(game.history.moves(), moves);
+        assert_eq!(game.replay.as_ref().map(|replay| replay.index), Some(0));
     }
 }
@@ -0,0 +1,378 @@
+use std::process::ExitCode;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::ai::Agent;
+use crate::ai::AiType;
+use crate::ai::MoveRequest;
+use crate::board::Board;
+use crate::board::Player;
+use crate::common::CellList;
+use crate::history::History;
+use crate::notation;
+use crate::referee::Advance;
+use crate::referee::Outcome;
+use crate::referee::Referee;
+use crate::tournament::MatchTally;
+
+const USAGE: &str = "\
+usage: FIA-Othello --headless [--games N] [--first ENGINE] [--second ENGINE] [--json]
+
+ENGINE is TYPE[:DEPTH[:SEED]] with TYPE one of random, minimax, alphabeta, positional,
+e.g. alphabeta:4 or random:1:42. The first engine plays black in odd games.";
+
+// one side of a headless match
+#[derive(Clone, Copy)]
+pub struct EngineConfig {
+    pub ai_type: AiType,
+    pub recursion_depth: usize,
+    // without a seed, the moves picked among equally good ones differ from run to run
+    pub seed: Option<u64>,
+}
+
+impl EngineConfig {
+    // parses "type[:depth[:seed]]", e.g. "alphabeta:4" or "random:1:42"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.split(':');
+
+        let ai_type = match parts
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "random" => AiType::Random,
+            "minimax" => AiType::Minimax,
+            "alphabeta" => AiType::AlphaBeta,
+            "positional" => AiType::Positional,
+            other => return Err(format!("unknown engine type \"{other}\"")),
+        };
+        let recursion_depth = match parts.next() {
+            Some(depth) => match depth.parse() {
+                Ok(depth) if depth > 0 => depth,
+                _ => return Err(format!("invalid depth \"{depth}\"")),
+            },
+            None => 1,
+        };
+        let seed = match parts.next() {
+            Some(seed) => Some(
+                seed.parse()
+                    .map_err(|_| format!("invalid seed \"{seed}\""))?,
+            ),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(format!("too many parts in engine \"{text}\""));
+        }
+
+        Ok(EngineConfig {
+            ai_type,
+            recursion_depth,
+            seed,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        self.ai_type.describe(self.recursion_depth)
+    }
+
+    pub fn agent(&self) -> Agent {
+        let mut agent = match self.seed {
+            Some(seed) => Agent::with_seed(seed),
+            None => Agent::new(),
+        };
+        // keeps stdout clean for the report
+        agent.log_searches = false;
+        agent
+    }
+}
+
+pub struct HeadlessOptions {
+    // the first one plays black in the first game
+    pub engines: [EngineConfig; 2],
+    pub games: usize,
+    pub json: bool,
+}
+
+impl HeadlessOptions {
+    // everything but the arguments of a headless match is rejected
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = HeadlessOptions {
+            engines: [
+                EngineConfig {
+                    ai_type: AiType::AlphaBeta,
+                    recursion_depth: 3,
+                    seed: None,
+                },
+                EngineConfig {
+                    ai_type: AiType::Random,
+                    recursion_depth: 1,
+                    seed: None,
+                },
+            ],
+            games: 10,
+            json: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => {}
+                "--json" => options.json = true,
+                "--games" | "--first" | "--second" => {
+                    let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+                    match arg.as_str() {
+                        "--games" => {
+                            options.games = value
+                                .parse()
+                                .map_err(|_| format!("invalid number of games \"{value}\""))?;
+                        }
+                        "--first" => options.engines[0] = EngineConfig::parse(value)?,
+                        _ => options.engines[1] = EngineConfig::parse(value)?,
+                    }
+                }
+                _ => return Err(format!("unknown argument \"{arg}\"")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[derive(Serialize)]
+pub struct GameRecord {
+    pub black: String,
+    pub white: String,
+    // "black", "white" or "tie"
+    pub result: String,
+    pub black_discs: usize,
+    pub white_discs: usize,
+    pub transcript: String,
+}
+
+#[derive(Serialize)]
+pub struct MatchReport {
+    pub engines: [String; 2],
+    pub games: Vec<GameRecord>,
+    // from the first engine's perspective
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    // per engine
+    pub average_discs: [f64; 2],
+    pub average_move_time_ms: [f64; 2],
+}
+
+// the time spent on moves, and how many moves that was
+#[derive(Default, Clone, Copy)]
+pub struct MoveTime {
+    total: Duration,
+    moves: u32,
+}
+
+impl MoveTime {
+    fn average_ms(&self) -> f64 {
+        if self.moves == 0 {
+            0.0
+        } else {
+            self.total.as_secs_f64() * 1000.0 / self.moves as f64
+        }
+    }
+}
+
+pub struct PlayedGame {
+    pub outcome: Outcome,
+    pub board: Board,
+    pub history: History,
+}
+
+// plays a game from the start position to the end, indexed by player
+// the agents move through the same `Referee::play_move` step as the GUI
+pub fn play_game(
+    agents: [&mut Agent; 2],
+    engines: [EngineConfig; 2],
+    mut move_times: [&mut MoveTime; 2],
+) -> Result<PlayedGame, String> {
+    let mut referee = Referee::default();
+    let mut board = Board::default();
+    let mut history = History::default();
+    let mut flip_cells = CellList::default();
+    let mut valid_moves = CellList::default();
+    let mut player = Player::Black;
+
+    loop {
+        let engine = engines[player as usize];
+        let started = Instant::now();
+        let move_result = agents[player as usize].answer_move_request(MoveRequest {
+            board: board.clone(),
+            player,
+            algorithm_choice: engine.ai_type,
+            recursion_depth: engine.recursion_depth,
+            time_budget: None,
+        });
+        let move_time = &mut move_times[player as usize];
+        move_time.total += started.elapsed();
+        move_time.moves += 1;
+
+        let advance = referee
+            .play_move(
+                &mut board,
+                &mut history,
+                player,
+                move_result.next_move,
+                &mut flip_cells,
+                &mut valid_moves,
+            )
+            .ok_or_else(|| {
+                format!(
+                    "{} played the illegal move {:?} as {player:?}",
+                    engine.name(),
+                    move_result.next_move
+                )
+            })?;
+
+        match advance {
+            Advance::Turn(next_player) => player = next_player,
+            Advance::Pass { .. } => {}
+            Advance::Over(outcome) => {
+                return Ok(PlayedGame {
+                    outcome,
+                    board,
+                    history,
+                });
+            }
+        }
+    }
+}
+
+// plays the whole match, printing every game as it finishes unless the report is wanted as JSON
+pub fn run_match(options: &HeadlessOptions) -> Result<MatchReport, String> {
+    let engines = options.engines;
+    let [mut first_agent, mut second_agent] = engines.map(|engine| engine.agent());
+    let mut move_times = [MoveTime::default(); 2];
+    let mut discs = [0; 2];
+    let mut tally = MatchTally::new(options.games);
+    let mut games = Vec::with_capacity(options.games);
+
+    while !tally.is_finished() {
+        let [first_time, second_time] = &mut move_times;
+        let (agents, colors, times) = match tally.first_color() {
+            Player::Black => (
+                [&mut first_agent, &mut second_agent],
+                [engines[0], engines[1]],
+                [first_time, second_time],
+            ),
+            Player::White => (
+                [&mut second_agent, &mut first_agent],
+                [engines[1], engines[0]],
+                [second_time, first_time],
+            ),
+        };
+
+        let game = play_game(agents, colors, times)?;
+        let (black_count, white_count) = Referee::count_disks(&game.board, Player::Black);
+        let (black_count, white_count) = (black_count as usize, white_count as usize);
+        let (first_count, second_count) = match tally.first_color() {
+            Player::Black => (black_count, white_count),
+            Player::White => (white_count, black_count),
+        };
+        discs[0] += first_count;
+        discs[1] += second_count;
+
+        let record = GameRecord {
+            black: colors[Player::Black as usize].name(),
+            white: colors[Player::White as usize].name(),
+            result: match game.outcome {
+                Outcome::Won(Player::Black, _) => "black".to_string(),
+                Outcome::Won(Player::White, _) => "white".to_string(),
+                Outcome::Tie => "tie".to_string(),
+            },
+            black_discs: black_count,
+            white_discs: white_count,
+            transcript: notation::transcript(&game.history, false),
+        };
+        if !options.json {
+            println!(
+                "Game {}: {} (black) {}-{} {} (white), {}",
+                games.len() + 1,
+                record.black,
+                record.black_discs,
+                record.white_discs,
+                record.white,
+                match game.outcome {
+                    Outcome::Won(player, _) => format!("{player:?} won"),
+                    Outcome::Tie => "tie".to_string(),
+                }
+            );
+        }
+        games.push(record);
+        tally.record(
+            &game.outcome,
+            black_count,
+            white_count,
+            game.history.plies.len(),
+        );
+    }
+
+    let played = tally.games_played.max(1) as f64;
+    Ok(MatchReport {
+        engines: engines.map(|engine| engine.name()),
+        games,
+        wins: tally.first_wins,
+        draws: tally.draws,
+        losses: tally.second_wins,
+        average_discs: discs.map(|count| count as f64 / played),
+        average_move_time_ms: move_times.map(|move_time| move_time.average_ms()),
+    })
+}
+
+fn summary_text(report: &MatchReport) -> String {
+    format!(
+        "{} vs {}: {} wins, {} draws, {} losses\n\
+         average discs: {:.1} - {:.1}\n\
+         average time per move: {:.2} ms - {:.2} ms",
+        report.engines[0],
+        report.engines[1],
+        report.wins,
+        report.draws,
+        report.losses,
+        report.average_discs[0],
+        report.average_discs[1],
+        report.average_move_time_ms[0],
+        report.average_move_time_ms[1]
+    )
+}
+
+// the entry point of `--headless`, no window is ever opened
+pub fn main(args: &[String]) -> ExitCode {
+    let options = match HeadlessOptions::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run_match(&options) {
+        Ok(report) if options.json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => {
+                println!("{json}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Cannot write the report: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(report) => {
+            println!("{}", summary_text(&report));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("The match was aborted: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::board::Board;
+use crate::board::Move;
+use crate::board::Player;
+
+// a single half-move, as it was applied to the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ply {
+    Move { player: Player, next_move: Move },
+    // the player had no valid move, so the turn went back to the opponent
+    Pass { player: Player },
+}
+
+impl Ply {
+    pub fn next_move(&self) -> Option<Move> {
+        match *self {
+            Ply::Move { next_move, .. } => Some(next_move),
+            Ply::Pass { .. } => None,
+        }
+    }
+}
+
+// the number of the move that placed the disc on each square, see `History::move_numbers`
+pub type MoveNumbers = [[Option<usize>; Board::SIZE]; Board::SIZE];
+
+// everything needed to reconstruct the current position from the starting one
+#[derive(Clone, Debug)]
+pub struct History {
+    // the standard start position, unless the game was set up otherwise
+    pub start_board: Board,
+    pub start_player: Player,
+    pub plies: Vec<Ply>,
+    // moves taken back by undo, most recently undone last
+    pub undone: Vec<Ply>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            start_board: Board::default(),
+            start_player: Player::Black,
+            plies: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+}
+
+impl History {
+    pub fn push(&mut self, player: Player, next_move: Move) {
+        let ply = Ply::Move { player, next_move };
+
+        // redoing keeps the rest of the undone plies, any other move discards them
+        if self.undone.last() == Some(&ply) {
+            self.undone.pop();
+        } else {
+            self.undone.clear();
+        }
+
+        self.plies.push(ply);
+    }
+
+    pub fn push_pass(&mut self, player: Player) {
+        self.plies.push(Ply::Pass { player });
+    }
+
+    // takes back the last move together with any passes that followed it
+    pub fn undo(&mut self) -> Option<Ply> {
+        let index = self
+            .plies
+            .iter()
+            .rposition(|ply| matches!(ply, Ply::Move { .. }))?;
+        let ply = self.plies[index];
+        self.plies.truncate(index);
+        self.undone.push(ply);
+        Some(ply)
+    }
+
+    // undoes until it is the player's turn where they last moved, passes included
+    // returns how many moves were taken back, None if the player has not moved yet
+    pub fn take_back(&mut self, player: Player) -> Option<usize> {
+        if !self
+            .plies
+            .iter()
+            .any(|ply| matches!(*ply, Ply::Move { player: mover, .. } if mover == player))
+        {
+            return None;
+        }
+
+        let mut taken_back = 0;
+        while let Some(Ply::Move { player: mover, .. }) = self.undo() {
+            taken_back += 1;
+            if mover == player {
+                break;
+            }
+        }
+        Some(taken_back)
+    }
+
+    pub fn next_redo(&self) -> Option<Ply> {
+        self.undone.last().copied()
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        self.moves_until(self.plies.len())
+    }
+
+    // the moves among the first `ply_count` plies, passes left out
+    pub fn moves_until(&self, ply_count: usize) -> Vec<Move> {
+        self.plies[..ply_count.min(self.plies.len())]
+            .iter()
+            .filter_map(Ply::next_move)
+            .collect()
+    }
+
+    // whether every disc on the board got there by a move of the history
+    pub fn starts_from_standard_position(&self) -> bool {
+        self.start_board.grid == Board::default().grid
+    }
+
+    // the moves among the first `ply_count` plies numbered from 1, passes left out, by the square
+    // they were played on, whatever color the disc has since
+    // None for a game set up in another position, whose earlier moves are unknown
+    pub fn move_numbers(&self, ply_count: usize) -> Option<MoveNumbers> {
+        if !self.starts_from_standard_position() {
+            return None;
+        }
+
+        let mut numbers = [[None; Board::SIZE]; Board::SIZE];
+        for (index, (row, col)) in self.moves_until(ply_count).into_iter().enumerate() {
+            numbers[row][col] = Some(index + 1);
+        }
+        Some(numbers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_back_reverts_the_reply_and_the_move() {
+        let mut history = History::default();
+        history.push(Player::Black, (2, 3));
+        history.push(Player::White, (2, 2));
+        history.push(Player::Black, (2, 1));
+        history.push(Player::White, (4, 2));
+
+        assert_eq!(history.take_back(Player::Black), Some(2));
+        assert_eq!(history.moves(), [(2, 3), (2, 2)]);
+        assert_eq!(history.take_back(Player::White), Some(1));
+        assert_eq!(history.moves(), [(2, 3)]);
+    }
+
+    #[test]
+    fn take_back_without_the_reply_reverts_one_move() {
+        let mut history = History::default();
+        history.push(Player::Black, (2, 3));
+        history.push(Player::White, (2, 2));
+        history.push(Player::Black, (2, 1));
+
+        assert_eq!(history.take_back(Player::Black), Some(1));
+        assert_eq!(history.moves(), [(2, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn take_back_goes_through_passes() {
+        let mut history = History::default();
+        history.push(Player::Black, (2, 3));
+        history.push(Player::White, (2, 2));
+        history.push_pass(Player::Black);
+        history.push(Player::White, (4, 2));
+        history.push_pass(Player::Black);
+
+        assert_eq!(history.take_back(Player::White), Some(1));
+        assert_eq!(history.moves(), [(2, 3), (2, 2)]);
+        assert_eq!(history.plies.len(), 3);
+        assert_eq!(history.take_back(Player::Black), Some(2));
+        assert!(history.plies.is_empty());
+    }
+
+    #[test]
+    fn move_numbers_skip_passes_and_stop_at_the_ply() {
+        let mut history = History::default();
+        history.push(Player::Black, (2, 3));
+        history.push_pass(Player::White);
+        history.push(Player::Black, (2, 2));
+        history.push(Player::White, (2, 1));
+
+        let numbers = history.move_numbers(3).unwrap();
+        assert_eq!(numbers[2][3], Some(1));
+        assert_eq!(numbers[2][2], Some(2));
+        assert_eq!(numbers[2][1], None);
+        assert_eq!(numbers[3][3], None);
+
+        history.start_board.grid[0][0] = crate::board::Cell::Taken(Player::Black);
+        assert_eq!(history.move_numbers(3), None);
+    }
+
+    #[test]
+    fn take_back_before_moving_does_nothing() {
+        let mut history = History::default();
+        history.push(Player::Black, (2, 3));
+
+        assert_eq!(history.take_back(Player::White), None);
+        assert_eq!(history.plies.len(), 1);
+    }
+}
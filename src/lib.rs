@@ -0,0 +1,48 @@
+//! The rules and the AI of Othello, without any UI.
+//!
+//! The egui app, the headless mode and the engine protocol are all built on this library.
+//!
+//! ```
+//! use fia_othello::ai::Agent;
+//! use fia_othello::ai::AiType;
+//! use fia_othello::ai::MoveRequest;
+//! use fia_othello::board::Board;
+//! use fia_othello::board::Player;
+//! use fia_othello::common::CellList;
+//! use fia_othello::history::History;
+//! use fia_othello::referee::Advance;
+//! use fia_othello::referee::Referee;
+//!
+//! let mut agent = Agent::with_seed(7);
+//! agent.log_searches = false;
+//! let mut referee = Referee::default();
+//! let mut board = Board::default();
+//! let mut history = History::default();
+//!
+//! let move_result = agent.answer_move_request(MoveRequest {
+//!     board: board.clone(),
+//!     player: Player::Black,
+//!     algorithm_choice: AiType::AlphaBeta,
+//!     recursion_depth: 3,
+//!     time_budget: None,
+//! });
+//! let advance = referee.play_move(
+//!     &mut board,
+//!     &mut history,
+//!     Player::Black,
+//!     move_result.next_move,
+//!     &mut CellList::default(),
+//!     &mut CellList::default(),
+//! );
+//! assert_eq!(advance, Some(Advance::Turn(Player::White)));
+//! ```
+
+pub mod ai;
+pub mod board;
+pub mod common;
+pub mod history;
+pub mod notation;
+pub mod platform;
+pub mod referee;
+pub mod statistics;
+pub mod tournament;
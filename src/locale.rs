@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::board::Player;
+
+// the built-in translations, English being the one every missing string falls back to
+const ENGLISH: &str = include_str!("../locales/en.lang");
+const FRENCH: &str = include_str!("../locales/fr.lang");
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    // in the language itself, so that it can be found without reading the current one
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => ENGLISH,
+            Language::French => FRENCH,
+        }
+    }
+}
+
+// the strings of the interface by key, in the chosen language with English for the missing ones
+pub struct Strings {
+    language: Language,
+    // the translation file loaded in place of the language's own strings
+    file: Option<String>,
+    english: HashMap<String, String>,
+    chosen: HashMap<String, String>,
+}
+
+impl Strings {
+    pub fn new(language: Language) -> Self {
+        Strings {
+            language,
+            file: None,
+            english: parse(ENGLISH).expect("the English strings are valid"),
+            chosen: parse(language.source()).expect("the built-in translations are valid"),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    // the translation or the file it was loaded from
+    pub fn name(&self) -> &str {
+        self.file.as_deref().unwrap_or(self.language.label())
+    }
+
+    // the strings of a translation file in place of the language's own, the number of them
+    pub fn load(&mut self, file: String, text: &str) -> Result<usize, String> {
+        let chosen = parse(text)?;
+        let count = chosen.len();
+        self.chosen = chosen;
+        self.file = Some(file);
+        Ok(count)
+    }
+
+    // the key itself for a string that is not even in English, so that it shows up
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.chosen
+            .get(key)
+            .or_else(|| self.english.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    pub fn player_name(&self, player: Player) -> &str {
+        match player {
+            Player::Black => self.tr("player-black"),
+            Player::White => self.tr("player-white"),
+        }
+    }
+
+    // with every `{name}` in the string replaced by the value of that name
+    pub fn tr_args(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.tr(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+}
+
+// one `key = value` a line, blank lines and lines starting with `#` left out, `\n` in a value
+// for a line break
+pub fn parse(text: &str) -> Result<HashMap<String, String>, String> {
+    let mut strings = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_number = index + 1;
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("Line {line_number}: expected key = value"));
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("Line {line_number}: invalid key {key:?}"));
+        }
+        let value = value.trim().replace("\\n", "\n");
+        if strings.insert(key.to_string(), value).is_some() {
+            return Err(format!("Line {line_number}: {key} is given twice"));
+        }
+    }
+    Ok(strings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panel::PanelSection;
+    use crate::puzzle::PUZZLES;
+    use crate::theme::Appearance;
+    use crate::theme::BoardThemeName;
+
+    // every module of the interface, so that a string looked up by a literal key anywhere is
+    // caught, whether the module shows text today or not
+    const SOURCES: [&str; 21] = [
+        include_str!("analysis.rs"),
+        include_str!("animation.rs"),
+        include_str!("arena.rs"),
+        include_str!("board_image.rs"),
+        include_str!("cli.rs"),
+        include_str!("clock.rs"),
+        include_str!("engine.rs"),
+        include_str!("event_log.rs"),
+        include_str!("game.rs"),
+        include_str!("headless.rs"),
+        include_str!("main.rs"),
+        include_str!("marks.rs"),
+        include_str!("multi_board.rs"),
+        include_str!("network.rs"),
+        include_str!("orientation.rs"),
+        include_str!("panel.rs"),
+        include_str!("puzzle.rs"),
+        include_str!("save.rs"),
+        include_str!("sound.rs"),
+        include_str!("theme.rs"),
+        include_str!("wthor.rs"),
+    ];
+
+    // the literal keys of every `.tr("…")` and `.tr_args("…", …)` call
+    fn keys_in(source: &str) -> Vec<&str> {
+        let mut keys = Vec::new();
+        for call in [".tr(", ".tr_args("] {
+            for (start, _) in source.match_indices(call) {
+                let rest = source[start + call.len()..].trim_start();
+                if let Some(rest) = rest.strip_prefix('"') {
+                    keys.push(&rest[..rest.find('"').unwrap()]);
+                }
+            }
+        }
+        keys
+    }
+
+    // the modules that draw the interface, the others print to the command line, speak a
+    // protocol or write files, and pass their error details up as they are
+    const INTERFACE_SOURCES: [&str; 13] = [
+        include_str!("analysis.rs"),
+        include_str!("animation.rs"),
+        include_str!("arena.rs"),
+        include_str!("clock.rs"),
+        include_str!("event_log.rs"),
+        include_str!("game.rs"),
+        include_str!("marks.rs"),
+        include_str!("multi_board.rs"),
+        include_str!("orientation.rs"),
+        include_str!("panel.rs"),
+        include_str!("puzzle.rs"),
+        include_str!("sound.rs"),
+        include_str!("theme.rs"),
+    ];
+
+    // words the interface shows as they are: a stored player name, a key and file names
+    const SHOWN_AS_IS: [&str; 4] = ["Human", "Tab", "board.png", "arena.csv"];
+
+    // the calls whose text ends up on screen
+    const TEXT_CALLS: [&str; 13] = [
+        ".label(",
+        ".button(",
+        "Button::new(",
+        ".on_hover_text(",
+        ".on_disabled_hover_text(",
+        ".heading(",
+        ".hint_text(",
+        "RichText::new(",
+        ".selectable_label(",
+        ".checkbox(",
+        ".colored_label(",
+        ".small(",
+        ".monospace(",
+    ];
+
+    // the length of the string literal `source` starts with, quotes included
+    fn literal_length(source: &str) -> usize {
+        let mut escaped = false;
+        for (i, c) in source.char_indices().skip(1) {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return i + 1,
+                _ => {}
+            }
+        }
+        source.len()
+    }
+
+    // what is between the parenthesis `source` starts after and the one closing it
+    fn arguments(source: &str) -> &str {
+        let mut depth = 1;
+        let mut i = 0;
+        while i < source.len() {
+            match source.as_bytes()[i] {
+                b'"' if !source[..i].ends_with('\'') => {
+                    i += literal_length(&source[i..]);
+                    continue;
+                }
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &source[..i];
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        source
+    }
+
+    // the contents of the string literals in `source`, char literals left out
+    fn literals(source: &str) -> Vec<&str> {
+        let mut literals = Vec::new();
+        let mut i = 0;
+        while let Some(offset) = source[i..].find('"') {
+            let start = i + offset;
+            if source[..start].ends_with('\'') {
+                i = start + 1;
+                continue;
+            }
+            let length = literal_length(&source[start..]);
+            literals.push(&source[start + 1..start + length - 1]);
+            i = start + length;
+        }
+        literals
+    }
+
+    // the literals put on screen or made into a `String` outside the tests, leaving out the
+    // keys and placeholder names of the lookups
+    fn shown_literals(source: &str) -> Vec<&str> {
+        let code = source.split("#[cfg(test)]\nmod tests").next().unwrap();
+        let mut shown = Vec::new();
+        for call in TEXT_CALLS {
+            for (start, _) in code.match_indices(call) {
+                let mut arguments = arguments(&code[start + call.len()..]);
+                // a lookup is checked by its key
+                if let Some(lookup) = [".tr(", ".tr_args("]
+                    .iter()
+                    .filter_map(|lookup| arguments.find(lookup))
+                    .min()
+                {
+                    arguments = &arguments[..lookup];
+                }
+                shown.extend(literals(arguments));
+            }
+        }
+        for (end, _) in code.match_indices("\".to_string()") {
+            let start = code[..end].rfind('"').unwrap();
+            shown.push(&code[start + 1..end]);
+        }
+        shown
+    }
+
+    // whether the text has a word in it outside its placeholders
+    fn has_words(text: &str) -> bool {
+        let mut depth = 0;
+        let mut letters = 0;
+        for c in text.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ if depth == 0 && c.is_ascii_alphabetic() => {
+                    letters += 1;
+                    if letters == 2 {
+                        return true;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            letters = 0;
+        }
+        false
+    }
+
+    // the names in braces
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn every_key_in_the_code_has_an_english_string() {
+        let english = parse(ENGLISH).unwrap();
+        let mut keys: Vec<&str> = SOURCES.iter().flat_map(|source| keys_in(source)).collect();
+        assert!(keys.len() > 100);
+        keys.extend(PanelSection::ALL.map(PanelSection::key));
+        keys.extend(Appearance::ALL.map(Appearance::key));
+        keys.extend(BoardThemeName::ALL.map(BoardThemeName::key));
+        keys.extend(PUZZLES.iter().map(|puzzle| puzzle.key));
+        for key in keys {
+            assert!(english.contains_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn the_interface_shows_no_text_that_is_not_looked_up() {
+        let shown: Vec<&str> = INTERFACE_SOURCES
+            .iter()
+            .flat_map(|source| shown_literals(source))
+            .collect();
+        assert!(shown.len() > 10);
+        for text in shown {
+            assert!(!has_words(text) || SHOWN_AS_IS.contains(&text), "{text}");
+        }
+
+        // the check finds what it looks for
+        let sample = "ui.label(format!(\"Score: {count}\"));\n\
+                      ui.button(self.strings.tr(\"restart\"));\n\
+                      let pass = \"pass\".to_string();";
+        assert_eq!(shown_literals(sample), ["Score: {count}", "pass"]);
+        assert!(has_words("Score: {count}"));
+        assert!(!has_words("{index}. {symbol} {notation}"));
+    }
+
+    #[test]
+    fn the_translations_only_have_english_keys_with_the_same_values_in_them() {
+        let english = parse(ENGLISH).unwrap();
+        for language in Language::ALL {
+            for (key, value) in parse(language.source()).unwrap() {
+                let Some(english_value) = english.get(&key) else {
+                    panic!("{language:?} {key} is not in English");
+                };
+                assert_eq!(
+                    placeholders(&value),
+                    placeholders(english_value),
+                    "{language:?} {key}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn missing_strings_fall_back_to_english_and_values_are_filled_in() {
+        let mut strings = Strings::new(Language::French);
+        assert_eq!(strings.tr("player-black"), "Noir");
+        strings
+            .load(
+                "de.lang".to_string(),
+                "player-black = Schwarz\n# a comment\n\n",
+            )
+            .unwrap();
+        assert_eq!(strings.name(), "de.lang");
+        assert_eq!(strings.tr("player-black"), "Schwarz");
+        assert_eq!(strings.tr("player-white"), "White");
+        assert_eq!(strings.tr("no-such-key"), "no-such-key");
+        assert_eq!(
+            strings.tr_args("status-won", &[("player", &"Schwarz")]),
+            "Schwarz won"
+        );
+    }
+
+    #[test]
+    fn malformed_translation_files_are_refused() {
+        assert_eq!(
+            parse("a = 1\nb").unwrap_err(),
+            "Line 2: expected key = value"
+        );
+        assert_eq!(
+            parse("a = 1\na = 2").unwrap_err(),
+            "Line 2: a is given twice"
+        );
+        assert!(parse("two words = 1").is_err());
+        assert_eq!(parse("a = one\\ntwo").unwrap()["a"], "one\ntwo");
+    }
+}
@@ -1,21 +1,106 @@
-mod ai;
-mod board;
-mod common;
+mod analysis;
+mod animation;
+mod arena;
+mod board_image;
+// the browser has no command line, only the defaults are used there
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+mod cli;
+mod clock;
+#[cfg(not(target_arch = "wasm32"))]
+mod engine;
+mod event_log;
 mod game;
-mod referee;
-mod statistics;
+// the arena plays its games through the headless match code, but only where there are threads
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+mod headless;
+mod locale;
+mod marks;
+mod multi_board;
+mod network;
+mod orientation;
+mod panel;
+mod puzzle;
+mod save;
+mod sound;
+mod theme;
+mod wthor;
 
+// the rules and the AI come from the library, under the same paths as the app's own modules
+use fia_othello::ai;
+use fia_othello::board;
+use fia_othello::common;
+use fia_othello::history;
+use fia_othello::notation;
+use fia_othello::platform;
+use fia_othello::referee;
+use fia_othello::statistics;
+use fia_othello::tournament;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::ExitCode;
+
+#[cfg(not(target_arch = "wasm32"))]
+use cli::Command;
+#[cfg(not(target_arch = "wasm32"))]
 use eframe::egui;
 use game::Game;
 
-fn main() {
-    let app = Game::default();
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let startup = match cli::parse(&args) {
+        Ok(Command::Gui(startup)) => *startup,
+        Ok(Command::Headless) => return headless::main(&args),
+        Ok(Command::Engine) => return engine::main(),
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\nRun with --help to see every option.");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let app = Game::new(startup);
     let _ = eframe::run_native(
         "Othello",
         eframe::NativeOptions {
-            viewport: egui::ViewportBuilder::default().with_inner_size([1024.0, 768.0]),
+            // room for the side panel and a board that can still be played on
+            viewport: egui::ViewportBuilder::default()
+                .with_inner_size([1024.0, 768.0])
+                .with_min_inner_size([720.0, 480.0]),
             ..Default::default()
         },
         Box::new(|_cc| Ok(Box::new(app))),
     );
+
+    ExitCode::SUCCESS
+}
+
+// trunk starts this on the canvas of index.html
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast;
+    use eframe::web_sys;
+
+    wasm_bindgen_futures::spawn_local(async {
+        let canvas = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("the_canvas_id"))
+            .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+            .expect("index.html should have a canvas with the id the_canvas_id");
+
+        let app = Game::default();
+        if let Err(e) = eframe::WebRunner::new()
+            .start(
+                canvas,
+                eframe::WebOptions::default(),
+                Box::new(|_cc| Ok(Box::new(app))),
+            )
+            .await
+        {
+            web_sys::console::error_1(&e);
+        }
+    });
 }
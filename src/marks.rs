@@ -0,0 +1,110 @@
+use crate::board::Board;
+use crate::board::Move;
+
+// the letters a square can be marked with, in the order they are handed out
+pub const MARK_LETTERS: [char; Board::SIZE] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'];
+
+// what a right click leaves on a square to study a position
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SquareMark {
+    Circle,
+    Cross,
+    Letter(char),
+}
+
+// the marks on the squares, which belong to the squares and not to the moves, so that undoing
+// and redoing leaves them where they are
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SquareMarks {
+    marks: [[Option<SquareMark>; Board::SIZE]; Board::SIZE],
+}
+
+impl SquareMarks {
+    // none, a circle, a cross, the first letter no other square has and none again, skipping
+    // the letter once every one of them is taken
+    pub fn cycle(&mut self, (row, col): Move) {
+        let next = match self.marks[row][col] {
+            None => Some(SquareMark::Circle),
+            Some(SquareMark::Circle) => Some(SquareMark::Cross),
+            Some(SquareMark::Cross) => self.free_letter().map(SquareMark::Letter),
+            Some(SquareMark::Letter(_)) => None,
+        };
+        self.marks[row][col] = next;
+    }
+
+    fn free_letter(&self) -> Option<char> {
+        MARK_LETTERS.into_iter().find(|&letter| {
+            self.iter()
+                .all(|(_, mark)| mark != SquareMark::Letter(letter))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Move, SquareMark)> + '_ {
+        self.marks.iter().enumerate().flat_map(|(row, row_marks)| {
+            row_marks
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, mark)| mark.map(|mark| ((row, col), mark)))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        *self = SquareMarks::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mark_at(marks: &SquareMarks, (row, col): Move) -> Option<SquareMark> {
+        marks.marks[row][col]
+    }
+
+    #[test]
+    fn a_square_cycles_through_the_marks_and_letters_are_not_shared() {
+        let mut marks = SquareMarks::default();
+        let cycled = |marks: &mut SquareMarks, square, times| {
+            for _ in 0..times {
+                marks.cycle(square);
+            }
+            mark_at(marks, square)
+        };
+        assert_eq!(cycled(&mut marks, (0, 0), 1), Some(SquareMark::Circle));
+        assert_eq!(cycled(&mut marks, (0, 0), 1), Some(SquareMark::Cross));
+        assert_eq!(cycled(&mut marks, (0, 0), 1), Some(SquareMark::Letter('A')));
+        assert_eq!(cycled(&mut marks, (0, 1), 3), Some(SquareMark::Letter('B')));
+        assert_eq!(cycled(&mut marks, (0, 0), 1), None);
+        // the letter given up goes to the next square asking for one
+        assert_eq!(cycled(&mut marks, (0, 2), 3), Some(SquareMark::Letter('A')));
+        assert_eq!(
+            marks.iter().collect::<Vec<_>>(),
+            [
+                ((0, 1), SquareMark::Letter('B')),
+                ((0, 2), SquareMark::Letter('A'))
+            ]
+        );
+
+        marks.clear();
+        assert!(marks.is_empty());
+    }
+
+    #[test]
+    fn a_cross_goes_back_to_nothing_once_every_letter_is_taken() {
+        let mut marks = SquareMarks::default();
+        for col in 0..Board::SIZE {
+            for _ in 0..3 {
+                marks.cycle((0, col));
+            }
+        }
+        assert_eq!(mark_at(&marks, (0, 7)), Some(SquareMark::Letter('H')));
+        marks.cycle((1, 0));
+        marks.cycle((1, 0));
+        marks.cycle((1, 0));
+        assert_eq!(mark_at(&marks, (1, 0)), None);
+    }
+}
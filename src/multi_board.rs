@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use crate::ai::AgentRequest;
+use crate::ai::AiType;
+use crate::ai::MoveRequest;
+use crate::ai::MoveResult;
+use crate::board::Board;
+use crate::board::Player;
+use crate::common::CellList;
+use crate::history::History;
+use crate::platform::AgentRunner;
+use crate::platform::Instant;
+use crate::platform::RequestId;
+use crate::referee::Advance;
+use crate::referee::Outcome;
+use crate::referee::Referee;
+use crate::statistics::GameResult;
+
+pub const MAX_BOARDS: usize = 9;
+
+// how a color plays on every board
+#[derive(Clone, Copy)]
+pub struct Seat {
+    pub ai_type: AiType,
+    pub recursion_depth: usize,
+}
+
+// the results of all boards together
+#[derive(Default)]
+pub struct Tally {
+    pub black_wins: usize,
+    pub white_wins: usize,
+    pub draws: usize,
+}
+
+impl Tally {
+    fn record(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Won(Player::Black, _) => self.black_wins += 1,
+            Outcome::Won(Player::White, _) => self.white_wins += 1,
+            Outcome::Tie => self.draws += 1,
+        }
+    }
+
+    pub fn games(&self) -> usize {
+        self.black_wins + self.white_wins + self.draws
+    }
+}
+
+// a game of the grid, played by the AIs alone
+// every board has an agent of its own, so an answer can only land on the board that asked
+pub struct BoardGame {
+    pub board: Board,
+    pub history: History,
+    // None once the game is over
+    pub next_player: Option<Player>,
+    pub outcome: Option<Outcome>,
+    referee: Referee,
+    flip_cells: CellList,
+    valid_moves: CellList,
+    runner: AgentRunner,
+    // the move being thought about and when it was asked for
+    request: Option<(RequestId, Instant)>,
+    // an answer that came in before the minimum time per move was up
+    move_result: Option<MoveResult>,
+    over_at: Option<Instant>,
+}
+
+impl BoardGame {
+    fn new(first_player: Player) -> Self {
+        BoardGame {
+            board: Board::default(),
+            history: History {
+                start_player: first_player,
+                ..Default::default()
+            },
+            next_player: Some(first_player),
+            outcome: None,
+            referee: Referee::default(),
+            flip_cells: CellList::default(),
+            valid_moves: CellList::default(),
+            runner: AgentRunner::new(),
+            request: None,
+            move_result: None,
+            over_at: None,
+        }
+    }
+
+    // a reply still on its way is for the old board, it is dropped on arrival
+    fn restart(&mut self, first_player: Player) {
+        self.board = Board::default();
+        self.history = History {
+            start_player: first_player,
+            ..Default::default()
+        };
+        self.next_player = Some(first_player);
+        self.outcome = None;
+        self.request = None;
+        self.move_result = None;
+        self.over_at = None;
+    }
+
+    // the final discs and length of the game, None while it goes on
+    pub fn result(&self) -> Option<GameResult> {
+        let (black_count, white_count) = Referee::count_disks(&self.board, Player::Black);
+        Some(GameResult {
+            outcome: self.outcome?,
+            disc_counts: [black_count as usize, white_count as usize],
+            plies: self.history.plies.len(),
+        })
+    }
+
+    // returns the outcome once the game ends
+    fn tick(&mut self, seats: &[Seat; 2], move_time: Duration, now: Instant) -> Option<Outcome> {
+        let player = self.next_player?;
+        let Some((id, requested_at)) = self.request else {
+            let seat = seats[player as usize];
+            let id = self.runner.send(AgentRequest::Move(MoveRequest {
+                board: self.board.clone(),
+                player,
+                algorithm_choice: seat.ai_type,
+                recursion_depth: seat.recursion_depth,
+                time_budget: None,
+            }));
+            self.request = Some((id, now));
+            return None;
+        };
+
+        if self.move_result.is_none() {
+            match self.runner.try_recv() {
+                Ok(Some((answered, move_result))) if answered == id => {
+                    self.move_result = Some(move_result);
+                }
+                // the answer to a request from before a restart
+                Ok(_) => {}
+                // the runner has restarted its thread already, ask it once more
+                Err(_) => {
+                    self.request = None;
+                    return None;
+                }
+            }
+        }
+        if now < requested_at + move_time {
+            return None;
+        }
+        let move_result = self.move_result.take()?;
+        // whatever the answer, the next tick asks for the next move or again
+        self.request = None;
+        if move_result.board.grid != self.board.grid || move_result.player != player {
+            return None;
+        }
+
+        match self.referee.play_move(
+            &mut self.board,
+            &mut self.history,
+            player,
+            move_result.next_move,
+            &mut self.flip_cells,
+            &mut self.valid_moves,
+        )? {
+            Advance::Turn(next_player) => {
+                self.next_player = Some(next_player);
+                None
+            }
+            Advance::Pass { .. } => None,
+            Advance::Over(outcome) => {
+                self.next_player = None;
+                self.outcome = Some(outcome);
+                self.over_at = Some(now);
+                Some(outcome)
+            }
+        }
+    }
+}
+
+// several games of the same two AIs at once, each restarting on its own
+pub struct MultiBoard {
+    pub games: Vec<BoardGame>,
+    pub tally: Tally,
+    // into games, the board shown at full size
+    pub zoomed: Option<usize>,
+    first_player: Player,
+}
+
+impl MultiBoard {
+    pub fn new(count: usize, first_player: Player) -> Self {
+        MultiBoard {
+            games: (0..count.clamp(1, MAX_BOARDS))
+                .map(|_| BoardGame::new(first_player))
+                .collect(),
+            tally: Tally::default(),
+            zoomed: None,
+            first_player,
+        }
+    }
+
+    // a finished game stays on its board for `pause` before it restarts
+    // returns the boards whose game ended in this tick
+    pub fn tick(
+        &mut self,
+        seats: &[Seat; 2],
+        move_time: Duration,
+        pause: Duration,
+        now: Instant,
+    ) -> Vec<usize> {
+        let mut finished = Vec::new();
+        for (index, game) in self.games.iter_mut().enumerate() {
+            if game.over_at.is_some_and(|over_at| now >= over_at + pause) {
+                game.restart(self.first_player);
+            }
+            if let Some(outcome) = game.tick(seats, move_time, now) {
+                self.tally.record(&outcome);
+                finished.push(index);
+            }
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn every_board_plays_its_own_game() {
+        let seats = [
+            Seat {
+                ai_type: AiType::Random,
+                recursion_depth: 1,
+            },
+            Seat {
+                ai_type: AiType::AlphaBeta,
+                recursion_depth: 1,
+            },
+        ];
+        let mut multi_board = MultiBoard::new(3, Player::Black);
+
+        // long enough a pause that no board restarts before all are over
+        while multi_board.games.iter().any(|game| game.outcome.is_none()) {
+            multi_board.tick(
+                &seats,
+                Duration::ZERO,
+                Duration::from_secs(60),
+                Instant::now(),
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(multi_board.tally.games(), 3);
+        for game in multi_board.games.iter() {
+            let replay = Referee::default()
+                .replay(&Board::default(), Player::Black, &game.history.moves())
+                .unwrap();
+            assert_eq!(replay.board.grid, game.board.grid);
+            assert_eq!(replay.next_player, None);
+        }
+    }
+
+    #[test]
+    fn finished_boards_restart_after_the_pause() {
+        let seats = [Seat {
+            ai_type: AiType::Random,
+            recursion_depth: 1,
+        }; 2];
+        let mut multi_board = MultiBoard::new(2, Player::White);
+
+        while multi_board.tally.games() < 4 {
+            multi_board.tick(&seats, Duration::ZERO, Duration::ZERO, Instant::now());
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        for game in multi_board.games.iter() {
+            assert_eq!(game.history.start_player, Player::White);
+        }
+    }
+}
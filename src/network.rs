@@ -0,0 +1,450 @@
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::Shutdown;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::board::Move;
+use crate::board::Player;
+
+// bumped whenever the messages change, both sides must agree
+const PROTOCOL_VERSION: u32 = 2;
+
+// how often a waiting host checks whether it was told to stop
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// a side with nothing to say pings this often, so that the other side knows it is still there
+const PING_INTERVAL: Duration = if cfg!(test) {
+    Duration::from_millis(100)
+} else {
+    Duration::from_secs(5)
+};
+
+// a peer not heard from, or not taking what is written to it, for this long is gone
+const PEER_TIMEOUT: Duration = if cfg!(test) {
+    Duration::from_millis(500)
+} else {
+    Duration::from_secs(20)
+};
+
+// far beyond any message, a longer line is refused before it is held in memory
+const MAX_LINE: u64 = 4096;
+
+// sent as one line of JSON each
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    // the host's first message, the joining side plays the other color
+    Hello { version: u32, host_player: Player },
+    // `ply` counts the plies before the move, passes included, so both sides can tell that
+    // they agree on every pass in between
+    Move { ply: usize, next_move: Move },
+    Resign,
+    Restart,
+    // keeps the connection alive, never passed on as an event
+    Ping,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NetworkEvent {
+    Connected { local_player: Player },
+    Received(Message),
+    Disconnected(String),
+}
+
+// one end of a game between two instances, the connection is handled on background threads
+// messages are only sent once connected, and the connection closes when this is dropped
+pub struct Connection {
+    outgoing: mpsc::Sender<Message>,
+    events: mpsc::Receiver<NetworkEvent>,
+    // where a host is listening, useful when binding to port 0
+    pub local_address: Option<SocketAddr>,
+}
+
+impl Connection {
+    pub fn send(&self, message: Message) {
+        // a closed connection has already been reported as an event
+        let _ = self.outgoing.send(message);
+    }
+
+    // non-blocking
+    pub fn poll(&self) -> Option<NetworkEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+// listens on the address and plays black against whoever connects first
+pub fn host(address: &str) -> io::Result<Connection> {
+    let listener = TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+    let local_address = listener.local_addr().ok();
+
+    let (outgoing, outgoing_receiver) = mpsc::channel::<Message>();
+    let (event_sender, events) = mpsc::channel::<NetworkEvent>();
+
+    thread::spawn(move || {
+        let stream = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    // stop listening once the connection is dropped
+                    if let Err(mpsc::TryRecvError::Disconnected) = outgoing_receiver.try_recv() {
+                        return;
+                    }
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    let _ = event_sender.send(NetworkEvent::Disconnected(e.to_string()));
+                    return;
+                }
+            }
+        };
+
+        let host_player = Player::Black;
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION,
+            host_player,
+        };
+        if let Err(e) = stream
+            .set_nonblocking(false)
+            .and_then(|_| set_timeouts(&stream))
+            .and_then(|_| write_message(&stream, &hello))
+        {
+            let _ = event_sender.send(NetworkEvent::Disconnected(e.to_string()));
+            return;
+        }
+
+        let reader = match stream.try_clone() {
+            Ok(reading_stream) => BufReader::new(reading_stream),
+            Err(e) => {
+                let _ = event_sender.send(NetworkEvent::Disconnected(e.to_string()));
+                return;
+            }
+        };
+
+        let _ = event_sender.send(NetworkEvent::Connected {
+            local_player: host_player,
+        });
+        exchange_messages(stream, reader, outgoing_receiver, event_sender);
+    });
+
+    Ok(Connection {
+        outgoing,
+        events,
+        local_address,
+    })
+}
+
+// connects to a host in the background, the host decides who plays which color
+pub fn join(address: &str) -> Connection {
+    let address = address.to_string();
+    let (outgoing, outgoing_receiver) = mpsc::channel::<Message>();
+    let (event_sender, events) = mpsc::channel::<NetworkEvent>();
+
+    thread::spawn(move || {
+        let (stream, reading_stream) = match TcpStream::connect(&address).and_then(|stream| {
+            set_timeouts(&stream)?;
+            Ok((stream.try_clone()?, stream))
+        }) {
+            Ok(streams) => streams,
+            Err(e) => {
+                let _ = event_sender.send(NetworkEvent::Disconnected(format!(
+                    "cannot connect to {address}: {e}"
+                )));
+                return;
+            }
+        };
+
+        // kept for the rest of the game, it may already hold the messages after the greeting
+        let mut reader = BufReader::new(reading_stream);
+        match read_message(&mut reader) {
+            Ok(Some(Message::Hello {
+                version,
+                host_player,
+            })) if version == PROTOCOL_VERSION => {
+                let _ = event_sender.send(NetworkEvent::Connected {
+                    local_player: host_player.opponent(),
+                });
+            }
+            Ok(Some(Message::Hello { version, .. })) => {
+                let _ = event_sender.send(NetworkEvent::Disconnected(format!(
+                    "the host speaks protocol version {version}, expected {PROTOCOL_VERSION}"
+                )));
+                return;
+            }
+            Ok(_) => {
+                let _ = event_sender.send(NetworkEvent::Disconnected(
+                    "the host did not greet".to_string(),
+                ));
+                return;
+            }
+            Err(e) => {
+                let _ = event_sender.send(NetworkEvent::Disconnected(describe_read_error(e)));
+                return;
+            }
+        }
+        exchange_messages(stream, reader, outgoing_receiver, event_sender);
+    });
+
+    Connection {
+        outgoing,
+        events,
+        local_address: None,
+    }
+}
+
+// reads on a thread of its own and writes on this one, until either side goes away
+fn exchange_messages(
+    stream: TcpStream,
+    mut reader: BufReader<TcpStream>,
+    outgoing_receiver: mpsc::Receiver<Message>,
+    event_sender: mpsc::Sender<NetworkEvent>,
+) {
+    let reader_event_sender = event_sender.clone();
+    thread::spawn(move || {
+        loop {
+            let event = match read_message(&mut reader) {
+                Ok(Some(Message::Ping)) => continue,
+                Ok(Some(message)) => NetworkEvent::Received(message),
+                Ok(None) => NetworkEvent::Disconnected("the other side left".to_string()),
+                Err(e) => NetworkEvent::Disconnected(describe_read_error(e)),
+            };
+            let disconnected = matches!(event, NetworkEvent::Disconnected(_));
+            if reader_event_sender.send(event).is_err() || disconnected {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let message = match outgoing_receiver.recv_timeout(PING_INTERVAL) {
+            Ok(message) => message,
+            Err(mpsc::RecvTimeoutError::Timeout) => Message::Ping,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        if let Err(e) = write_message(&stream, &message) {
+            let _ = event_sender.send(NetworkEvent::Disconnected(e.to_string()));
+            break;
+        }
+    }
+
+    // also ends the reading thread
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+fn write_message(mut stream: &TcpStream, message: &Message) -> io::Result<()> {
+    let line = serde_json::to_string(message).map_err(io::Error::other)?;
+    writeln!(stream, "{line}")?;
+    stream.flush()
+}
+
+// on the socket, so for both of its ends
+fn set_timeouts(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(PEER_TIMEOUT))?;
+    stream.set_write_timeout(Some(PEER_TIMEOUT))
+}
+
+// a read running into the timeout means the other side stopped pinging
+fn describe_read_error(e: io::Error) -> String {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => format!(
+            "the other side went silent for {} seconds",
+            PEER_TIMEOUT.as_secs()
+        ),
+        _ => e.to_string(),
+    }
+}
+
+// None once the other side closed the connection
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Message>> {
+    let mut line = String::new();
+    let length = reader.take(MAX_LINE).read_line(&mut line)?;
+    if length == 0 {
+        return Ok(None);
+    }
+    if length as u64 == MAX_LINE && !line.ends_with('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("a message longer than {MAX_LINE} bytes"),
+        ));
+    }
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::common::CellList;
+    use crate::history::History;
+    use crate::notation;
+    use crate::referee::Advance;
+    use crate::referee::Referee;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn next_event(connection: &Connection) -> NetworkEvent {
+        connection
+            .events
+            .recv_timeout(TIMEOUT)
+            .expect("an event should arrive")
+    }
+
+    fn connect() -> (Connection, Connection) {
+        let host = host("127.0.0.1:0").unwrap();
+        let guest = join(&host.local_address.unwrap().to_string());
+
+        assert_eq!(
+            next_event(&host),
+            NetworkEvent::Connected {
+                local_player: Player::Black
+            }
+        );
+        assert_eq!(
+            next_event(&guest),
+            NetworkEvent::Connected {
+                local_player: Player::White
+            }
+        );
+        (host, guest)
+    }
+
+    // each side keeps its own board and validates every received move with its own referee
+    struct Side {
+        connection: Connection,
+        referee: Referee,
+        board: Board,
+        history: History,
+    }
+
+    impl Side {
+        fn play(&mut self, player: Player, next_move: Move) -> Option<Advance> {
+            self.referee.play_move(
+                &mut self.board,
+                &mut self.history,
+                player,
+                next_move,
+                &mut CellList::default(),
+                &mut CellList::default(),
+            )
+        }
+    }
+
+    #[test]
+    fn scripted_game_over_loopback() {
+        let (host, guest) = connect();
+        let mut sides = [host, guest].map(|connection| Side {
+            connection,
+            referee: Referee::default(),
+            board: Board::default(),
+            history: History::default(),
+        });
+
+        let moves = notation::parse_transcript("F5D6C3D3C4F4F6F3E6E7").unwrap();
+        let mut player = Player::Black;
+        for next_move in moves {
+            let [host, guest] = &mut sides;
+            let (mover, receiver) = match player {
+                Player::Black => (host, guest),
+                Player::White => (guest, host),
+            };
+
+            let ply = mover.history.plies.len();
+            let advance = mover.play(player, next_move).expect("the move is legal");
+            mover.connection.send(Message::Move { ply, next_move });
+
+            let NetworkEvent::Received(Message::Move {
+                ply: received_ply,
+                next_move: received_move,
+            }) = next_event(&receiver.connection)
+            else {
+                panic!("expected a move");
+            };
+            assert_eq!(received_ply, receiver.history.plies.len());
+            assert_eq!(receiver.play(player, received_move), Some(advance));
+
+            player = match advance {
+                Advance::Turn(next_player) => next_player,
+                Advance::Pass { .. } => player,
+                Advance::Over(_) => break,
+            };
+        }
+
+        assert_eq!(sides[0].board.grid, sides[1].board.grid);
+        assert_eq!(sides[0].history.plies, sides[1].history.plies);
+
+        sides[1].connection.send(Message::Resign);
+        assert_eq!(
+            next_event(&sides[0].connection),
+            NetworkEvent::Received(Message::Resign)
+        );
+    }
+
+    #[test]
+    fn leaving_is_reported() {
+        let (host, guest) = connect();
+        drop(guest);
+
+        assert!(matches!(next_event(&host), NetworkEvent::Disconnected(_)));
+    }
+
+    #[test]
+    fn an_idle_connection_stays_open() {
+        let (host, guest) = connect();
+        thread::sleep(PEER_TIMEOUT * 2);
+
+        assert_eq!(host.poll(), None);
+        guest.send(Message::Restart);
+        assert_eq!(next_event(&host), NetworkEvent::Received(Message::Restart));
+    }
+
+    #[test]
+    fn a_silent_peer_is_reported() {
+        let host = host("127.0.0.1:0").unwrap();
+        // connects and reads nothing, sends nothing
+        let _silent = TcpStream::connect(host.local_address.unwrap()).unwrap();
+
+        assert!(matches!(next_event(&host), NetworkEvent::Connected { .. }));
+        let NetworkEvent::Disconnected(error) = next_event(&host) else {
+            panic!("expected the silence to be reported");
+        };
+        assert!(error.contains("silent"), "{error}");
+    }
+
+    #[test]
+    fn an_overlong_line_is_refused() {
+        let line = format!("{}\n", "x".repeat(MAX_LINE as usize * 2));
+        let error = read_message(&mut io::Cursor::new(line)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let line = format!("{}\n", serde_json::to_string(&Message::Resign).unwrap());
+        assert_eq!(
+            read_message(&mut io::Cursor::new(line)).unwrap(),
+            Some(Message::Resign)
+        );
+    }
+
+    #[test]
+    fn joining_nobody_is_reported() {
+        // nothing listens on the port of a listener that is gone
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let guest = join(&address.to_string());
+
+        assert!(matches!(next_event(&guest), NetworkEvent::Disconnected(_)));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::board::Board;
+use crate::board::Cell;
+use crate::board::Move;
+use crate::board::Player;
+use crate::history::History;
+use crate::history::Ply;
+
+// passes are not part of the conventional transcript, but can be made explicit with this marker
+pub const PASS_MARKER: &str = "--";
+
+// columns are lettered a-h from the left, rows numbered 1-8 from the top
+pub fn format_move((row, col): Move) -> String {
+    format!("{}{}", (b'a' + col as u8) as char, row + 1)
+}
+
+// the conventional "F5D6C3..." string, optionally with "--" wherever a player had to pass
+pub fn transcript(history: &History, mark_passes: bool) -> String {
+    let mut result = String::with_capacity(history.plies.len() * 2);
+
+    for ply in history.plies.iter() {
+        match ply {
+            Ply::Move { next_move, .. } => {
+                result.push_str(&format_move(*next_move).to_ascii_uppercase());
+            }
+            Ply::Pass { .. } if mark_passes => result.push_str(PASS_MARKER),
+            Ply::Pass { .. } => {}
+        }
+    }
+
+    result
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TranscriptError {
+    // index of the offending move, passes not counted
+    pub ply: usize,
+    pub token: String,
+}
+
+impl std::fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unrecognized move \"{}\" at ply {}",
+            self.token,
+            self.ply + 1
+        )
+    }
+}
+
+// accepts both "f5" and "F5"
+pub fn parse_move(text: &str) -> Option<Move> {
+    let mut chars = text.chars();
+    let (column, row) = (chars.next()?, chars.next()?);
+    if chars.next().is_some() {
+        return None;
+    }
+
+    let col = (column.to_ascii_lowercase() as usize).checked_sub('a' as usize)?;
+    let row = (row as usize).checked_sub('1' as usize)?;
+
+    if row < Board::SIZE && col < Board::SIZE {
+        Some((row, col))
+    } else {
+        None
+    }
+}
+
+// the inverse of `transcript`, ignoring whitespace and pass markers
+pub fn parse_transcript(text: &str) -> Result<Vec<Move>, TranscriptError> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut moves = Vec::with_capacity(chars.len() / 2);
+
+    for token in chars.chunks(2) {
+        let token: String = token.iter().collect();
+        if token == PASS_MARKER {
+            continue;
+        }
+
+        match parse_move(&token) {
+            Some(next_move) => moves.push(next_move),
+            None => {
+                return Err(TranscriptError {
+                    ply: moves.len(),
+                    token,
+                });
+            }
+        }
+    }
+
+    Ok(moves)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PositionError {
+    // the number of cells found instead of 64
+    WrongLength(usize),
+    InvalidCell { index: usize, found: char },
+    InvalidPlayer(char),
+}
+
+impl std::fmt::Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::WrongLength(cells) => write!(
+                f,
+                "a position needs {} cells, found {cells}",
+                Board::SIZE * Board::SIZE
+            ),
+            PositionError::InvalidCell { index, found } => write!(
+                f,
+                "unrecognized cell \"{found}\" at {}, expected X, O or -",
+                format_move((index / Board::SIZE, index % Board::SIZE))
+            ),
+            PositionError::InvalidPlayer(found) => {
+                write!(
+                    f,
+                    "unrecognized player to move \"{found}\", expected X or O"
+                )
+            }
+        }
+    }
+}
+
+// the position in the format `parse_position` reads, with the player to move
+pub fn format_position(board: &Board, player: Player) -> String {
+    let mut text: String = board
+        .grid
+        .iter()
+        .flatten()
+        .map(|cell| match cell {
+            Cell::Empty => '-',
+            Cell::Taken(Player::Black) => 'X',
+            Cell::Taken(Player::White) => 'O',
+        })
+        .collect();
+    text.push_str(match player {
+        Player::Black => " X",
+        Player::White => " O",
+    });
+    text
+}
+
+// the common one-line position format, row by row from a1 to h8, e.g.
+// "---------------------------OX------XO--------------------------- X"
+// X (or B, *) is black, O (or W) is white, - (or .) is empty, and the optional last character
+// is the player to move, black if left out
+pub fn parse_position(text: &str) -> Result<(Board, Player), PositionError> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let cell_count = Board::SIZE * Board::SIZE;
+
+    let player = match chars.len() {
+        n if n == cell_count => Player::Black,
+        n if n == cell_count + 1 => match chars[cell_count].to_ascii_uppercase() {
+            'X' | 'B' | '*' => Player::Black,
+            'O' | 'W' => Player::White,
+            found => return Err(PositionError::InvalidPlayer(found)),
+        },
+        n => return Err(PositionError::WrongLength(n)),
+    };
+
+    let mut board = Board::default();
+    for (index, &found) in chars[..cell_count].iter().enumerate() {
+        board.grid[index / Board::SIZE][index % Board::SIZE] = match found.to_ascii_uppercase() {
+            'X' | 'B' | '*' => Cell::Taken(Player::Black),
+            'O' | 'W' => Cell::Taken(Player::White),
+            '-' | '.' => Cell::Empty,
+            _ => return Err(PositionError::InvalidCell { index, found }),
+        };
+    }
+
+    Ok((board, player))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::referee::Referee;
+    use crate::referee::ReplayError;
+
+    // the plies the moves replay to from the start of the history
+    fn replayed(history: &History, moves: &[Move]) -> Vec<Ply> {
+        Referee::default()
+            .replay(&history.start_board, history.start_player, moves)
+            .unwrap()
+            .history
+            .plies
+    }
+
+    // c1 takes b1, white has nothing left to take and passes, f8 takes g8 and the last white disc
+    fn game_with_a_pass() -> History {
+        let mut history = History::default();
+        history.start_board.grid = [[Cell::Empty; Board::SIZE]; Board::SIZE];
+        history.start_board.grid[0][0] = Cell::Taken(Player::Black);
+        history.start_board.grid[0][1] = Cell::Taken(Player::White);
+        history.start_board.grid[7][7] = Cell::Taken(Player::Black);
+        history.start_board.grid[7][6] = Cell::Taken(Player::White);
+        history.push(Player::Black, (0, 2));
+        history.push_pass(Player::White);
+        history.push(Player::Black, (7, 5));
+        history
+    }
+
+    #[test]
+    fn a_transcript_with_passes_parses_back_to_the_same_game() {
+        let history = game_with_a_pass();
+        let text = transcript(&history, true);
+        assert_eq!(text, "C1--F8");
+        assert_eq!(
+            replayed(&history, &parse_transcript(&text).unwrap()),
+            history.plies
+        );
+
+        // the passes follow from the position without the marks as well
+        let text = transcript(&history, false);
+        assert_eq!(text, "C1F8");
+        assert!(!text.contains(PASS_MARKER));
+        assert_eq!(
+            replayed(&history, &parse_transcript(&text).unwrap()),
+            history.plies
+        );
+    }
+
+    #[test]
+    fn a_game_ending_early_parses_back_to_the_same_game() {
+        // the white discs are all gone after nine moves, with 51 squares still empty
+        let moves = parse_transcript("d3c3b3d2e1d6d7e3f4").unwrap();
+        let replay = Referee::default()
+            .replay(&Board::default(), Player::Black, &moves)
+            .unwrap();
+        assert_eq!(replay.next_player, None);
+
+        let text = transcript(&replay.history, true);
+        assert_eq!(text, "D3C3B3D2E1D6D7E3F4");
+        assert_eq!(parse_transcript(&text).unwrap(), moves);
+        assert_eq!(replayed(&replay.history, &moves), replay.history.plies);
+    }
+
+    #[test]
+    fn moves_are_read_in_either_case() {
+        assert_eq!(parse_move("f5"), Some((4, 5)));
+        assert_eq!(parse_move("F5"), Some((4, 5)));
+        assert_eq!(parse_move("a1"), Some((0, 0)));
+        assert_eq!(parse_move("H8"), Some((7, 7)));
+        for garbage in ["", "f", "f55", "i1", "a0", "a9", "5f", " f5", "--"] {
+            assert_eq!(parse_move(garbage), None, "{garbage:?}");
+        }
+    }
+
+    #[test]
+    fn transcripts_are_read_in_any_case_and_spacing() {
+        let moves = vec![(4, 5), (5, 3), (2, 2)];
+        for text in [
+            "f5d6c3",
+            "F5D6C3",
+            "f5D6c3",
+            "  F5 d6\tC3\n",
+            "f 5d\n6 c3",
+            "F5 -- D6C3",
+        ] {
+            assert_eq!(parse_transcript(text).unwrap(), moves, "{text:?}");
+        }
+        assert_eq!(parse_transcript("").unwrap(), []);
+        assert_eq!(parse_transcript("  ").unwrap(), []);
+        // a game cut short is read up to where it stops
+        assert_eq!(parse_transcript("f5d6").unwrap(), moves[..2]);
+    }
+
+    #[test]
+    fn garbage_is_refused_with_its_ply_and_token() {
+        assert_eq!(
+            parse_transcript("f5d6z9c3"),
+            Err(TranscriptError {
+                ply: 2,
+                token: "z9".to_string(),
+            })
+        );
+        // passes are not counted in the ply
+        assert_eq!(
+            parse_transcript("f5--d6 xx").unwrap_err(),
+            TranscriptError {
+                ply: 2,
+                token: "xx".to_string(),
+            }
+        );
+        // a partial move at the end
+        let error = parse_transcript("F5D6C").unwrap_err();
+        assert_eq!(error.ply, 2);
+        assert_eq!(error.token, "C");
+        assert_eq!(error.to_string(), "unrecognized move \"C\" at ply 3");
+    }
+
+    #[test]
+    fn illegal_moves_are_refused_with_their_ply_and_square() {
+        let moves = parse_transcript("f5 d6 a1").unwrap();
+        assert_eq!(
+            Referee::default()
+                .replay(&Board::default(), Player::Black, &moves)
+                .err(),
+            Some(ReplayError::IllegalMove {
+                ply: 2,
+                next_move: (0, 0),
+            })
+        );
+    }
+}
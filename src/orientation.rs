@@ -0,0 +1,195 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::board::Board;
+use crate::board::Move;
+use crate::notation;
+
+// how far the view of the board is turned clockwise
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Rotation {
+    pub const ALL: [Rotation; 4] = [
+        Rotation::None,
+        Rotation::Quarter,
+        Rotation::Half,
+        Rotation::ThreeQuarters,
+    ];
+
+    pub fn degrees(self) -> u32 {
+        self.quarters() * 90
+    }
+
+    fn quarters(self) -> u32 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Quarter => 1,
+            Rotation::Half => 2,
+            Rotation::ThreeQuarters => 3,
+        }
+    }
+
+    fn from_quarters(quarters: u32) -> Self {
+        Rotation::ALL[quarters as usize % Rotation::ALL.len()]
+    }
+}
+
+// a line of squares, named by a column's letter or a row's number
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Line {
+    Column(usize),
+    Row(usize),
+}
+
+impl Line {
+    pub fn label(self) -> String {
+        match self {
+            Line::Column(col) => notation::format_move((0, col))[..1].to_string(),
+            Line::Row(row) => notation::format_move((row, 0))[1..].to_string(),
+        }
+    }
+}
+
+// how the board is shown, mirrored left to right first and then turned, the board itself, its
+// history and the notation of its squares staying as they are
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    pub mirrored: bool,
+}
+
+impl Orientation {
+    // the same view turned upside down, as from the other side of the table
+    pub fn half_turned(self) -> Self {
+        Orientation {
+            rotation: Rotation::from_quarters(self.rotation.quarters() + 2),
+            ..self
+        }
+    }
+
+    // where the square of the board is shown, as a row and a column of the view
+    pub fn to_view(self, (row, col): Move) -> Move {
+        let col = if self.mirrored { LAST - col } else { col };
+        turn((row, col), self.rotation.quarters())
+    }
+
+    // the square of the board shown at a row and a column of the view
+    pub fn to_board(self, view: Move) -> Move {
+        let (row, col) = turn(view, 4 - self.rotation.quarters());
+        (row, if self.mirrored { LAST - col } else { col })
+    }
+
+    // the line of the board shown as a column of the view, named above and below it
+    pub fn view_column(self, index: usize) -> Line {
+        let (row, col) = self.to_board((0, index));
+        if self.to_board((LAST, index)).1 == col {
+            Line::Column(col)
+        } else {
+            Line::Row(row)
+        }
+    }
+
+    // the line of the board shown as a row of the view, named left and right of it
+    pub fn view_row(self, index: usize) -> Line {
+        let (row, col) = self.to_board((index, 0));
+        if self.to_board((index, LAST)).0 == row {
+            Line::Row(row)
+        } else {
+            Line::Column(col)
+        }
+    }
+}
+
+const LAST: usize = Board::SIZE - 1;
+
+// a quarter turn clockwise takes the left column to the top row
+fn turn((row, col): Move, quarters: u32) -> Move {
+    match quarters % 4 {
+        0 => (row, col),
+        1 => (col, LAST - row),
+        2 => (LAST - row, LAST - col),
+        _ => (LAST - col, row),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> impl Iterator<Item = Orientation> {
+        Rotation::ALL
+            .into_iter()
+            .flat_map(|rotation| [false, true].map(|mirrored| Orientation { rotation, mirrored }))
+    }
+
+    #[test]
+    fn every_orientation_shows_each_square_once_and_finds_it_again() {
+        for orientation in all() {
+            let mut shown = [[false; Board::SIZE]; Board::SIZE];
+            for row in 0..Board::SIZE {
+                for col in 0..Board::SIZE {
+                    let view = orientation.to_view((row, col));
+                    assert!(!shown[view.0][view.1], "{orientation:?}");
+                    shown[view.0][view.1] = true;
+                    assert_eq!(orientation.to_board(view), (row, col), "{orientation:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_views_turn_clockwise_after_mirroring() {
+        let a1 = (0, 0);
+        let view = |rotation, mirrored| Orientation { rotation, mirrored }.to_view(a1);
+        assert_eq!(view(Rotation::None, false), (0, 0));
+        assert_eq!(view(Rotation::Quarter, false), (0, LAST));
+        assert_eq!(view(Rotation::Half, false), (LAST, LAST));
+        assert_eq!(view(Rotation::ThreeQuarters, false), (LAST, 0));
+        assert_eq!(view(Rotation::None, true), (0, LAST));
+        assert_eq!(view(Rotation::Quarter, true), (LAST, LAST));
+
+        let turned = Orientation::default().half_turned();
+        assert_eq!(turned.rotation, Rotation::Half);
+        assert_eq!(turned.half_turned(), Orientation::default());
+    }
+
+    #[test]
+    fn the_lines_along_the_edges_are_named_by_what_they_show() {
+        let standard = Orientation::default();
+        assert_eq!(standard.view_column(0), Line::Column(0));
+        assert_eq!(standard.view_row(0), Line::Row(0));
+
+        let turned = Orientation {
+            rotation: Rotation::Half,
+            mirrored: false,
+        };
+        assert_eq!(turned.view_column(0).label(), "h");
+        assert_eq!(turned.view_row(0).label(), "8");
+
+        // a quarter turn puts the rows across the top, the first one on the right
+        let quarter = Orientation {
+            rotation: Rotation::Quarter,
+            mirrored: false,
+        };
+        assert_eq!(quarter.view_column(0), Line::Row(LAST));
+        assert_eq!(quarter.view_column(LAST), Line::Row(0));
+        assert_eq!(quarter.view_row(0), Line::Column(0));
+
+        for orientation in all() {
+            for index in 0..Board::SIZE {
+                let square = orientation.to_board((index, index));
+                let names = [orientation.view_column(index), orientation.view_row(index)];
+                assert!(names.contains(&Line::Column(square.1)), "{orientation:?}");
+                assert!(names.contains(&Line::Row(square.0)), "{orientation:?}");
+            }
+        }
+    }
+}
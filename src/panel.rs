@@ -0,0 +1,184 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+// the collapsible sections of the side panel, from top to bottom
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PanelSection {
+    Moves,
+    Players,
+    Control,
+    Replay,
+    Analysis,
+    ExportImage,
+    Clocks,
+    Matches,
+    Puzzles,
+    PositionEditor,
+    Network,
+    Flow,
+    Help,
+    Appearance,
+    Accessibility,
+    Shortcuts,
+    EventLog,
+    Settings,
+    Statistics,
+}
+
+impl PanelSection {
+    pub const ALL: [PanelSection; 19] = [
+        PanelSection::Moves,
+        PanelSection::Players,
+        PanelSection::Control,
+        PanelSection::Replay,
+        PanelSection::Analysis,
+        PanelSection::ExportImage,
+        PanelSection::Clocks,
+        PanelSection::Matches,
+        PanelSection::Puzzles,
+        PanelSection::PositionEditor,
+        PanelSection::Network,
+        PanelSection::Flow,
+        PanelSection::Help,
+        PanelSection::Appearance,
+        PanelSection::Accessibility,
+        PanelSection::Shortcuts,
+        PanelSection::EventLog,
+        PanelSection::Settings,
+        PanelSection::Statistics,
+    ];
+
+    // the key of its title in the strings of the interface
+    pub fn key(self) -> &'static str {
+        match self {
+            PanelSection::Moves => "section-moves",
+            PanelSection::Players => "section-players",
+            PanelSection::Control => "section-control",
+            PanelSection::Replay => "section-replay",
+            PanelSection::Analysis => "section-analysis",
+            PanelSection::ExportImage => "section-export-image",
+            PanelSection::Clocks => "section-clocks",
+            PanelSection::Matches => "section-matches",
+            PanelSection::Puzzles => "section-puzzles",
+            PanelSection::PositionEditor => "section-position-editor",
+            PanelSection::Network => "section-network",
+            PanelSection::Flow => "section-flow",
+            PanelSection::Help => "section-help",
+            PanelSection::Appearance => "section-appearance",
+            PanelSection::Accessibility => "section-accessibility",
+            PanelSection::Shortcuts => "section-shortcuts",
+            PanelSection::EventLog => "section-event-log",
+            PanelSection::Settings => "section-settings",
+            PanelSection::Statistics => "section-statistics",
+        }
+    }
+}
+
+// which sections are open, kept with the settings by name so that a new section does not shift
+// the others; the ones used in every game start open
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenSections {
+    moves: bool,
+    players: bool,
+    control: bool,
+    replay: bool,
+    analysis: bool,
+    export_image: bool,
+    clocks: bool,
+    matches: bool,
+    puzzles: bool,
+    position_editor: bool,
+    network: bool,
+    flow: bool,
+    help: bool,
+    appearance: bool,
+    accessibility: bool,
+    shortcuts: bool,
+    event_log: bool,
+    settings: bool,
+    statistics: bool,
+}
+
+impl Default for OpenSections {
+    fn default() -> Self {
+        OpenSections {
+            moves: true,
+            players: true,
+            control: true,
+            replay: false,
+            analysis: false,
+            export_image: false,
+            clocks: false,
+            matches: false,
+            puzzles: false,
+            position_editor: false,
+            network: false,
+            flow: true,
+            help: true,
+            appearance: false,
+            accessibility: false,
+            shortcuts: false,
+            event_log: false,
+            settings: false,
+            statistics: true,
+        }
+    }
+}
+
+impl OpenSections {
+    pub fn is_open(&self, section: PanelSection) -> bool {
+        let mut sections = *self;
+        *sections.open_mut(section)
+    }
+
+    pub fn set_open(&mut self, section: PanelSection, open: bool) {
+        *self.open_mut(section) = open;
+    }
+
+    fn open_mut(&mut self, section: PanelSection) -> &mut bool {
+        match section {
+            PanelSection::Moves => &mut self.moves,
+            PanelSection::Players => &mut self.players,
+            PanelSection::Control => &mut self.control,
+            PanelSection::Replay => &mut self.replay,
+            PanelSection::Analysis => &mut self.analysis,
+            PanelSection::ExportImage => &mut self.export_image,
+            PanelSection::Clocks => &mut self.clocks,
+            PanelSection::Matches => &mut self.matches,
+            PanelSection::Puzzles => &mut self.puzzles,
+            PanelSection::PositionEditor => &mut self.position_editor,
+            PanelSection::Network => &mut self.network,
+            PanelSection::Flow => &mut self.flow,
+            PanelSection::Help => &mut self.help,
+            PanelSection::Appearance => &mut self.appearance,
+            PanelSection::Accessibility => &mut self.accessibility,
+            PanelSection::Shortcuts => &mut self.shortcuts,
+            PanelSection::EventLog => &mut self.event_log,
+            PanelSection::Settings => &mut self.settings,
+            PanelSection::Statistics => &mut self.statistics,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_are_kept_open_or_closed_by_name() {
+        let mut sections = OpenSections::default();
+        assert!(sections.is_open(PanelSection::Players));
+        assert!(!sections.is_open(PanelSection::Network));
+        for section in PanelSection::ALL {
+            let open = sections.is_open(section);
+            sections.set_open(section, !open);
+            assert_eq!(sections.is_open(section), !open, "{section:?}");
+        }
+
+        // a section missing from an older file keeps its default
+        let sections: OpenSections = serde_json::from_str(r#"{"players": false}"#).unwrap();
+        assert!(!sections.is_open(PanelSection::Players));
+        assert!(sections.is_open(PanelSection::Control));
+    }
+}
@@ -0,0 +1,406 @@
+// everything that differs between the desktop and the browser lives here
+
+// std::time::Instant on the desktop, performance.now() in the browser, where the former panics
+pub use web_time::Instant;
+// the same for the wall clock
+pub use web_time::SystemTime;
+
+// the browser has neither a file system to save to nor sockets to play over
+pub const HAS_FILE_SYSTEM: bool = cfg!(not(target_arch = "wasm32"));
+pub const HAS_NETWORK: bool = cfg!(not(target_arch = "wasm32"));
+// nor threads to play games in the background on
+pub const HAS_THREADS: bool = cfg!(not(target_arch = "wasm32"));
+
+// the system's dialog to choose a file to open, None once closed without one
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_file_to_open(filter: &str, extensions: &[&str]) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter(filter, extensions)
+        .pick_file()
+}
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file_to_open(_filter: &str, _extensions: &[&str]) -> Option<std::path::PathBuf> {
+    None
+}
+
+// the system's dialog to choose where a file goes, starting from `file_name`, None once closed
+// without one
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_file_to_save(
+    filter: &str,
+    extensions: &[&str],
+    file_name: &str,
+) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter(filter, extensions)
+        .set_file_name(file_name)
+        .save_file()
+}
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file_to_save(
+    _filter: &str,
+    _extensions: &[&str],
+    _file_name: &str,
+) -> Option<std::path::PathBuf> {
+    None
+}
+
+// tells the answer to a move request from the answers to earlier ones, the first request a
+// runner is sent being 0
+pub type RequestId = u64;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::AgentRunner;
+#[cfg(target_arch = "wasm32")]
+pub use web::AgentRunner;
+
+// each agent thinks on a thread of its own, so the UI never waits for it and no agent waits for
+// another
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::ai::Agent;
+    use crate::ai::AgentRequest;
+    use crate::ai::MoveRequest;
+    use crate::ai::MoveResult;
+
+    use super::RequestId;
+
+    // a request or an answer, with the id of the request
+    type Tagged<T> = (RequestId, T);
+
+    pub struct AgentRunner {
+        thread: Option<thread::JoinHandle<()>>,
+        request_sender: Option<mpsc::Sender<Tagged<AgentRequest>>>,
+        move_result_receiver: mpsc::Receiver<Tagged<MoveResult>>,
+        next_id: RequestId,
+        // the last move request not answered yet, resent once if the thread dies on it
+        outstanding_request: Option<(RequestId, MoveRequest)>,
+        is_resent: bool,
+        // the thread died and was restarted since the caller last asked
+        is_restarted: bool,
+        // the next threads sent a request panic first
+        #[cfg(test)]
+        panics_to_inject: usize,
+    }
+
+    fn spawn_thread() -> (
+        thread::JoinHandle<()>,
+        mpsc::Sender<Tagged<AgentRequest>>,
+        mpsc::Receiver<Tagged<MoveResult>>,
+    ) {
+        let (request_sender, request_receiver) = mpsc::channel::<Tagged<AgentRequest>>();
+        let (move_result_sender, move_result_receiver) = mpsc::channel::<Tagged<MoveResult>>();
+
+        let thread = thread::spawn(move || {
+            let mut agent = Agent::new();
+            while let Ok((id, request)) = request_receiver.recv() {
+                match request {
+                    AgentRequest::Move(move_request) => {
+                        let move_result = agent.answer_move_request(move_request);
+                        if move_result_sender.send((id, move_result)).is_err() {
+                            break;
+                        }
+                    }
+                    AgentRequest::Analysis(analysis_request) => agent.analyze(analysis_request),
+                    AgentRequest::Seed(seed) => agent.reseed(seed),
+                    #[cfg(test)]
+                    AgentRequest::Panic => panic!("injected AI thread panic"),
+                }
+            }
+        });
+
+        (thread, request_sender, move_result_receiver)
+    }
+
+    impl AgentRunner {
+        pub fn new() -> Self {
+            let (thread, request_sender, move_result_receiver) = spawn_thread();
+
+            AgentRunner {
+                thread: Some(thread),
+                request_sender: Some(request_sender),
+                move_result_receiver,
+                next_id: 0,
+                outstanding_request: None,
+                is_resent: false,
+                is_restarted: false,
+                #[cfg(test)]
+                panics_to_inject: 0,
+            }
+        }
+
+        // the id the answer to a move request comes back with
+        pub fn send(&mut self, request: AgentRequest) -> RequestId {
+            let id = self.next_id;
+            self.next_id += 1;
+            if let AgentRequest::Move(move_request) = &request {
+                self.outstanding_request = Some((id, move_request.clone()));
+                self.is_resent = false;
+            }
+            self.send_to_thread(id, request);
+            id
+        }
+
+        fn send_to_thread(&mut self, id: RequestId, request: AgentRequest) {
+            if let Some(request_sender) = &self.request_sender {
+                #[cfg(test)]
+                if self.panics_to_inject > 0 {
+                    self.panics_to_inject -= 1;
+                    let _ = request_sender.send((id, AgentRequest::Panic));
+                }
+                let _ = request_sender.send((id, request));
+            }
+        }
+
+        // non-blocking, answers come in the order their requests were sent
+        // fails once the thread died twice on the same move request
+        pub fn try_recv(&mut self) -> Result<Option<(RequestId, MoveResult)>, String> {
+            match self.move_result_receiver.try_recv() {
+                Ok((id, move_result)) => {
+                    if self
+                        .outstanding_request
+                        .as_ref()
+                        .is_some_and(|(outstanding, _)| *outstanding == id)
+                    {
+                        self.outstanding_request = None;
+                    }
+                    Ok(Some((id, move_result)))
+                }
+                Err(mpsc::TryRecvError::Empty) => Ok(None),
+                // the thread only lets go of its sender by panicking
+                Err(mpsc::TryRecvError::Disconnected) => self.restart_thread(),
+            }
+        }
+
+        // whether the thread died and was restarted since the last call, for the caller to log,
+        // its outstanding move request asked again
+        pub fn take_restart(&mut self) -> bool {
+            std::mem::take(&mut self.is_restarted)
+        }
+
+        fn restart_thread(&mut self) -> Result<Option<(RequestId, MoveResult)>, String> {
+            self.is_restarted = true;
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            let (thread, request_sender, move_result_receiver) = spawn_thread();
+            self.thread = Some(thread);
+            self.request_sender = Some(request_sender);
+            self.move_result_receiver = move_result_receiver;
+
+            match self.outstanding_request.clone() {
+                Some((id, move_request)) if !self.is_resent => {
+                    self.is_resent = true;
+                    self.send_to_thread(id, AgentRequest::Move(move_request));
+                    Ok(None)
+                }
+                Some(_) => {
+                    self.outstanding_request = None;
+                    Err("The AI failed twice on the same position".to_string())
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
+    impl Default for AgentRunner {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Drop for AgentRunner {
+        fn drop(&mut self) {
+            // Drop the sender so AI thread exits
+            self.request_sender = None;
+
+            // Wait for AI thread to exit
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::Duration;
+
+        use super::*;
+        use crate::ai::AiType;
+        use crate::board::Board;
+        use crate::board::Player;
+        use crate::referee::Referee;
+
+        fn opening_request() -> AgentRequest {
+            AgentRequest::Move(MoveRequest {
+                board: Board::default(),
+                player: Player::Black,
+                algorithm_choice: AiType::Minimax,
+                recursion_depth: 2,
+                time_budget: None,
+            })
+        }
+
+        fn wait_for_result(runner: &mut AgentRunner) -> Result<MoveResult, String> {
+            loop {
+                if let Some((_, move_result)) = runner.try_recv()? {
+                    return Ok(move_result);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        fn assert_valid_opening(move_result: &MoveResult) {
+            assert_eq!(move_result.player, Player::Black);
+            assert!(Referee::default().validate_move(
+                &Board::default(),
+                Player::Black,
+                move_result.next_move
+            ));
+        }
+
+        #[test]
+        fn agent_thread_answers_move_requests() {
+            let mut runner = AgentRunner::new();
+            runner.send(opening_request());
+
+            assert_valid_opening(&wait_for_result(&mut runner).unwrap());
+        }
+
+        #[test]
+        fn answers_carry_the_id_of_their_request() {
+            let mut runner = AgentRunner::new();
+            let first = runner.send(opening_request());
+            let second = runner.send(opening_request());
+            assert_ne!(first, second);
+
+            let mut ids = Vec::new();
+            while ids.len() < 2 {
+                if let Some((id, move_result)) = runner.try_recv().unwrap() {
+                    assert_valid_opening(&move_result);
+                    ids.push(id);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+            assert_eq!(ids, [first, second]);
+        }
+
+        #[test]
+        fn a_resent_request_keeps_its_id() {
+            let mut runner = AgentRunner::new();
+            runner.panics_to_inject = 1;
+            let id = runner.send(opening_request());
+
+            loop {
+                if let Some((answered, _)) = runner.try_recv().unwrap() {
+                    assert_eq!(answered, id);
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        #[test]
+        fn two_runners_answer_at_the_same_time() {
+            let mut runners = [AgentRunner::new(), AgentRunner::new()];
+            for runner in runners.iter_mut() {
+                runner.send(opening_request());
+            }
+
+            for runner in runners.iter_mut() {
+                assert_valid_opening(&wait_for_result(runner).unwrap());
+            }
+        }
+
+        #[test]
+        fn a_dead_thread_is_restarted_and_asked_again() {
+            let mut runner = AgentRunner::new();
+            runner.panics_to_inject = 1;
+            runner.send(opening_request());
+
+            assert_valid_opening(&wait_for_result(&mut runner).unwrap());
+            assert!(runner.take_restart());
+            assert!(!runner.take_restart());
+
+            // the restarted thread keeps answering
+            runner.send(opening_request());
+            assert_valid_opening(&wait_for_result(&mut runner).unwrap());
+            assert!(!runner.take_restart());
+        }
+
+        #[test]
+        fn dying_twice_on_a_request_fails_it() {
+            let mut runner = AgentRunner::new();
+            runner.panics_to_inject = 2;
+            runner.send(opening_request());
+
+            assert!(wait_for_result(&mut runner).is_err());
+
+            // and the next request is answered again
+            runner.send(opening_request());
+            assert_valid_opening(&wait_for_result(&mut runner).unwrap());
+        }
+    }
+}
+
+// there are no threads to think on, so the agent thinks on the main loop whenever asked, which
+// is fine for the random and shallow searches
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use crate::ai::Agent;
+    use crate::ai::AgentRequest;
+    use crate::ai::MoveResult;
+
+    use super::RequestId;
+
+    pub struct AgentRunner {
+        agent: Agent,
+        next_id: RequestId,
+        move_result: Option<(RequestId, MoveResult)>,
+    }
+
+    impl AgentRunner {
+        pub fn new() -> Self {
+            AgentRunner {
+                agent: Agent::new(),
+                next_id: 0,
+                move_result: None,
+            }
+        }
+    }
+
+    impl Default for AgentRunner {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl AgentRunner {
+        pub fn send(&mut self, request: AgentRequest) -> RequestId {
+            let id = self.next_id;
+            self.next_id += 1;
+            match request {
+                AgentRequest::Move(move_request) => {
+                    self.move_result = Some((id, self.agent.answer_move_request(move_request)));
+                }
+                AgentRequest::Analysis(analysis_request) => self.agent.analyze(analysis_request),
+                AgentRequest::Seed(seed) => self.agent.reseed(seed),
+                #[cfg(test)]
+                AgentRequest::Panic => panic!("injected AI panic"),
+            }
+            id
+        }
+
+        // the agent runs on the main loop, so it cannot die without taking the app along
+        pub fn try_recv(&mut self) -> Result<Option<(RequestId, MoveResult)>, String> {
+            Ok(self.move_result.take())
+        }
+
+        pub fn take_restart(&mut self) -> bool {
+            false
+        }
+    }
+}
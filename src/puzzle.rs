@@ -0,0 +1,125 @@
+use crate::ai::Agent;
+use crate::board::Board;
+use crate::board::Move;
+use crate::board::Player;
+
+use crate::notation;
+
+pub enum Solution {
+    // the one best move
+    Move(&'static str),
+    // an endgame small enough to be solved exactly, every move keeping the best result counts
+    Solver,
+}
+
+pub struct Puzzle {
+    // also identifies the puzzle in the statistics file
+    pub title: &'static str,
+    // of the title shown, in the translations
+    pub key: &'static str,
+    // in the format of `notation::parse_position`, side to move included
+    pub position: &'static str,
+    pub solution: Solution,
+}
+
+pub const PUZZLES: [Puzzle; 6] = [
+    Puzzle {
+        title: "Secure the corner before White does",
+        key: "puzzle-secure-corner",
+        position: "--OOOOO--XX-OOOX-XOXOOOO-OXOXXO-O--XX-XO--XX-O-X--X---X------X-- X",
+        solution: Solution::Move("h1"),
+    },
+    Puzzle {
+        title: "Take the corner Black is eyeing",
+        key: "puzzle-take-corner",
+        position: "------X--O-O--XOXXXXXXXX-OOOOXX----OXO-X--XXXOO----X-OO--------- O",
+        solution: Solution::Move("h1"),
+    },
+    Puzzle {
+        title: "Claim the open corner",
+        key: "puzzle-open-corner",
+        position: "-X------O-X------OOXOX----OXXX---O-OOXOO---OOXXX--XO-OX------XO- O",
+        solution: Solution::Move("h8"),
+    },
+    Puzzle {
+        title: "Win this 8-empties endgame",
+        key: "puzzle-eight-empties",
+        position: "--O-OOX-XOOOOOXXXXXOOXOOXXXOXOO-XXXOXOOXXXXOOOO-XXXXOOO-OOOOOOO- X",
+        solution: Solution::Solver,
+    },
+    Puzzle {
+        title: "Only one move wins this endgame",
+        key: "puzzle-only-move",
+        position: "XXXXXX--XXOOOX---XXOXXX-OXXXOXOOOOXXOXOXOOOXOOOXXXXOO-OX-XXOOOOX X",
+        solution: Solution::Solver,
+    },
+    Puzzle {
+        title: "Find the winning move with 8 empties",
+        key: "puzzle-winning-move",
+        position: "XXXXOOOOXXXOOOOXOOOOOOOXXOOOOXOX-XOOOOX-XXXXXXOXO--XXXXO----OOOX X",
+        solution: Solution::Solver,
+    },
+];
+
+impl Puzzle {
+    pub fn setup(&self) -> (Board, Player) {
+        // the built-in positions are known to be valid
+        notation::parse_position(self.position)
+            .unwrap_or_else(|_| (Board::default(), Player::Black))
+    }
+
+    // every move that solves the puzzle
+    pub fn solutions(&self, agent: &mut Agent) -> Vec<Move> {
+        match self.solution {
+            Solution::Move(text) => notation::parse_move(text).into_iter().collect(),
+            Solution::Solver => {
+                let (board, player) = self.setup();
+                let move_scores = agent.solve_endgame(&board, player);
+                let best_score = move_scores.iter().map(|&(_, score)| score).max();
+                move_scores
+                    .into_iter()
+                    .filter(|&(_, score)| Some(score) == best_score)
+                    .map(|(next_move, _)| next_move)
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::CellList;
+    use crate::referee::Referee;
+
+    #[test]
+    fn every_puzzle_has_a_valid_solution() {
+        let mut agent = Agent::with_seed(0);
+        for puzzle in PUZZLES.iter() {
+            let (board, player) = notation::parse_position(puzzle.position).unwrap();
+            let mut valid_moves = CellList::default();
+            assert!(Referee::default().find_all_valid_moves(&board, player, &mut valid_moves));
+
+            let solutions = puzzle.solutions(&mut agent);
+            assert_eq!(solutions.len(), 1, "{}", puzzle.title);
+            assert!(
+                valid_moves
+                    .iter()
+                    .any(|valid_move| valid_move == solutions[0])
+            );
+        }
+    }
+
+    #[test]
+    fn endgame_puzzles_are_won_by_the_solution_only() {
+        let mut agent = Agent::with_seed(0);
+        for puzzle in PUZZLES.iter() {
+            if let Solution::Solver = puzzle.solution {
+                let (board, player) = puzzle.setup();
+                let move_scores = agent.solve_endgame(&board, player);
+                let winning = move_scores.iter().filter(|&&(_, score)| score > 0).count();
+                assert_eq!(winning, 1, "{}", puzzle.title);
+            }
+        }
+    }
+}
@@ -1,16 +1,98 @@
 use crate::board::Board;
 use crate::board::Cell;
+use crate::board::Move;
 use crate::board::Player;
 use crate::common::CellList;
+use crate::history::History;
+use crate::notation;
 
+/// how a game ended
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Outcome {
-    Won(Player),
+    Won(Player, WinReason),
     Tie,
 }
 
-type Move = (usize, usize);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WinReason {
+    /// the game was played out and the winner has more disks
+    Disks,
+    /// the opponent ran out of time
+    Time,
+    /// the opponent gave up
+    Resignation,
+}
+
+/// what comes after a move, see `Referee::play_move`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Advance {
+    /// it's this player's turn
+    Turn(Player),
+    /// the opponent has no valid moves and passes, it's the same player's turn again
+    Pass {
+        passing_player: Player,
+    },
+    Over(Outcome),
+}
+
+/// the result of replaying a sequence of moves from a starting position
+pub struct Replay {
+    pub board: Board,
+    pub history: History,
+    /// None once neither player can move anymore
+    pub next_player: Option<Player>,
+}
+
+/// why a sequence of moves cannot be replayed, see `Referee::replay`
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// the move at this ply index is not legal in the reached position
+    IllegalMove { ply: usize, next_move: Move },
+    /// the game was already over before this ply index
+    GameOver { ply: usize },
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::IllegalMove {
+                ply,
+                next_move: (row, col),
+            } if *row < Board::SIZE && *col < Board::SIZE => write!(
+                f,
+                "illegal move \"{}\" at ply {}",
+                notation::format_move((*row, *col)),
+                ply + 1
+            ),
+            ReplayError::IllegalMove {
+                ply,
+                next_move: (row, col),
+            } => write!(f, "illegal move ({row}, {col}) at ply {}", ply + 1),
+            ReplayError::GameOver { ply } => {
+                write!(f, "the game is already over before ply {}", ply + 1)
+            }
+        }
+    }
+}
 
-// not thread-safe, every thread needs its own Referee
+/// knows the rules: which moves are valid, what they flip and when the game is over
+///
+/// not thread-safe, every thread needs its own Referee
+///
+/// ```
+/// use fia_othello::board::Board;
+/// use fia_othello::board::Player;
+/// use fia_othello::common::CellList;
+/// use fia_othello::referee::Referee;
+///
+/// let mut referee = Referee::default();
+/// let mut valid_moves = CellList::default();
+/// assert!(referee.find_all_valid_moves(&Board::default(), Player::Black, &mut valid_moves));
+/// assert_eq!(
+///     valid_moves.iter().collect::<Vec<_>>(),
+///     [(2, 3), (3, 2), (4, 5), (5, 4)]
+/// );
+/// ```
 #[derive(Default)]
 pub struct Referee {
     // a cache for the result of find_adjacent_opposites
@@ -21,6 +103,7 @@ pub struct Referee {
 }
 
 impl Referee {
+    /// whether the player may play the move, which must be on the board
     pub fn validate_move(&mut self, board: &Board, player: Player, maybe_move: Move) -> bool {
         Self::find_flip_cells_for_move_internal(
             board,
@@ -31,6 +114,25 @@ impl Referee {
         )
     }
 
+    /// whether the move is valid, leaving the disks it would flip in `result`, along with the
+    /// player's own disk closing each flipped line
+    ///
+    /// ```
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::common::CellList;
+    /// use fia_othello::referee::Referee;
+    ///
+    /// let mut flip_cells = CellList::default();
+    /// let valid = Referee::default().find_flip_cells_for_move(
+    ///     &Board::default(),
+    ///     Player::Black,
+    ///     (2, 3),
+    ///     &mut flip_cells,
+    /// );
+    /// assert!(valid);
+    /// assert_eq!(flip_cells.iter().collect::<Vec<_>>(), [(4, 3), (3, 3)]);
+    /// ```
     pub fn find_flip_cells_for_move(
         &mut self,
         board: &Board,
@@ -47,6 +149,7 @@ impl Referee {
         )
     }
 
+    /// whether the player has any valid move, leaving them all in `result`
     pub fn find_all_valid_moves(
         &mut self,
         board: &Board,
@@ -66,7 +169,15 @@ impl Referee {
         result.count != 0
     }
 
-    // returns (player disk count, opponent disk count)
+    /// returns (player disk count, opponent disk count)
+    ///
+    /// ```
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Referee;
+    ///
+    /// assert_eq!(Referee::count_disks(&Board::default(), Player::White), (2, 2));
+    /// ```
     pub fn count_disks(board: &Board, player: Player) -> (i32, i32) {
         let mut black_count = 0;
         let mut white_count = 0;
@@ -87,19 +198,187 @@ impl Referee {
         }
     }
 
+    /// the outcome by disk count, as if the game was over
     pub fn check_outcome(board: &Board) -> Outcome {
         let (black_count, white_count) = Self::count_disks(board, Player::Black);
 
         if black_count > white_count {
-            Outcome::Won(Player::Black)
+            Outcome::Won(Player::Black, WinReason::Disks)
         } else if white_count > black_count {
-            Outcome::Won(Player::White)
+            Outcome::Won(Player::White, WinReason::Disks)
         } else {
             Outcome::Tie
         }
     }
 
-    #[allow(dead_code)]
+    /// plays the moves one after the other, inserting passes where a player has no valid move
+    ///
+    /// ```
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Referee;
+    /// use fia_othello::referee::ReplayError;
+    ///
+    /// let mut referee = Referee::default();
+    /// let replay = referee
+    ///     .replay(&Board::default(), Player::Black, &[(4, 5), (5, 3)])
+    ///     .unwrap();
+    /// assert_eq!(replay.next_player, Some(Player::Black));
+    ///
+    /// let error = referee.replay(&Board::default(), Player::Black, &[(0, 0)]);
+    /// assert_eq!(
+    ///     error.err(),
+    ///     Some(ReplayError::IllegalMove {
+    ///         ply: 0,
+    ///         next_move: (0, 0)
+    ///     })
+    /// );
+    /// ```
+    pub fn replay(
+        &mut self,
+        start_board: &Board,
+        start_player: Player,
+        moves: &[Move],
+    ) -> Result<Replay, ReplayError> {
+        let mut board = start_board.clone();
+        let mut history = History {
+            start_board: start_board.clone(),
+            start_player,
+            plies: Vec::with_capacity(moves.len()),
+            undone: Vec::new(),
+        };
+        let mut valid_moves = CellList::default();
+        let mut flip_cells = CellList::default();
+
+        let mut next_player =
+            self.next_player_after(&board, start_player.opponent(), &mut valid_moves);
+        if next_player == Some(start_player.opponent()) {
+            history.push_pass(start_player);
+        }
+
+        for (ply, &next_move) in moves.iter().enumerate() {
+            let Some(player) = next_player else {
+                return Err(ReplayError::GameOver { ply });
+            };
+
+            let advance = self
+                .play_move(
+                    &mut board,
+                    &mut history,
+                    player,
+                    next_move,
+                    &mut flip_cells,
+                    &mut valid_moves,
+                )
+                .ok_or(ReplayError::IllegalMove { ply, next_move })?;
+
+            next_player = match advance {
+                Advance::Turn(player) => Some(player),
+                Advance::Pass { passing_player } => Some(passing_player.opponent()),
+                Advance::Over(_) => None,
+            };
+        }
+
+        Ok(Replay {
+            board,
+            history,
+            next_player,
+        })
+    }
+
+    /// the single step of every game loop: applies the move, records it (and a pass of the
+    /// opponent, if any) and decides how the game goes on
+    ///
+    /// returns None without touching anything if the move is not legal,
+    /// `flip_cells` is left with the flipped disks, `valid_moves` with the next player's moves
+    ///
+    /// ```
+    /// use fia_othello::board::Board;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::common::CellList;
+    /// use fia_othello::history::History;
+    /// use fia_othello::referee::Advance;
+    /// use fia_othello::referee::Referee;
+    ///
+    /// let mut referee = Referee::default();
+    /// let mut board = Board::default();
+    /// let mut history = History::default();
+    /// let mut flip_cells = CellList::default();
+    /// let mut valid_moves = CellList::default();
+    ///
+    /// let advance = referee.play_move(
+    ///     &mut board,
+    ///     &mut history,
+    ///     Player::Black,
+    ///     (2, 3),
+    ///     &mut flip_cells,
+    ///     &mut valid_moves,
+    /// );
+    /// assert_eq!(advance, Some(Advance::Turn(Player::White)));
+    /// assert_eq!(history.moves(), [(2, 3)]);
+    ///
+    /// // the square is taken now
+    /// let advance = referee.play_move(
+    ///     &mut board,
+    ///     &mut history,
+    ///     Player::White,
+    ///     (2, 3),
+    ///     &mut flip_cells,
+    ///     &mut valid_moves,
+    /// );
+    /// assert_eq!(advance, None);
+    /// ```
+    pub fn play_move(
+        &mut self,
+        board: &mut Board,
+        history: &mut History,
+        player: Player,
+        next_move: Move,
+        flip_cells: &mut CellList,
+        valid_moves: &mut CellList,
+    ) -> Option<Advance> {
+        let (row, col) = next_move;
+        if row >= Board::SIZE
+            || col >= Board::SIZE
+            || !self.find_flip_cells_for_move(board, player, next_move, flip_cells)
+        {
+            return None;
+        }
+
+        Self::apply_move(board, player, next_move, flip_cells);
+        history.push(player, next_move);
+
+        Some(match self.next_player_after(board, player, valid_moves) {
+            Some(next_player) if next_player == player => {
+                history.push_pass(player.opponent());
+                Advance::Pass {
+                    passing_player: player.opponent(),
+                }
+            }
+            Some(next_player) => Advance::Turn(next_player),
+            None => Advance::Over(Self::check_outcome(board)),
+        })
+    }
+
+    // whose turn it is after `player` has moved, or None if the game is over
+    fn next_player_after(
+        &mut self,
+        board: &Board,
+        player: Player,
+        valid_moves: &mut CellList,
+    ) -> Option<Player> {
+        if self.find_all_valid_moves(board, player.opponent(), valid_moves) {
+            Some(player.opponent())
+        } else if self.find_all_valid_moves(board, player, valid_moves) {
+            Some(player)
+        } else {
+            None
+        }
+    }
+
+    /// plays the first valid move at or after the square, going row by row, and returns it
+    ///
+    /// returns `(Board::SIZE, Board::SIZE)` without a valid move
     pub fn find_and_apply_next_valid_move(
         &mut self,
         board: &mut Board,
@@ -129,6 +408,7 @@ impl Referee {
         (Board::SIZE, Board::SIZE)
     }
 
+    /// places the disk and flips the cells, which must come from `find_flip_cells_for_move`
     pub fn apply_move(board: &mut Board, player: Player, (row, col): Move, flip_cells: &CellList) {
         // Place the current player's piece
         board.grid[row][col] = Cell::Taken(player);
@@ -0,0 +1,940 @@
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::arena::Contestant;
+use crate::board::Board;
+use crate::board::Move;
+use crate::board::Player;
+use crate::game::GameOptions;
+use crate::game::PlayerOptions;
+use crate::history::History;
+use crate::notation;
+use crate::platform;
+use crate::platform::SystemTime;
+use crate::referee::Referee;
+use crate::referee::Replay;
+use crate::referee::ReplayError;
+use crate::statistics::Matchup;
+use crate::statistics::Participant;
+use crate::statistics::Statistics;
+
+const SAVE_VERSION: u32 = 2;
+// files without a version are from before it was written, and have the layout of version 1
+const STATISTICS_VERSION: u32 = 3;
+// each upgrades a file of one version to the next, starting from version 1
+const STATISTICS_MIGRATIONS: [fn(&mut Statistics); STATISTICS_VERSION as usize - 1] = [
+    // single games were kept under names such as "Human vs Random"
+    Statistics::migrate_entries,
+    // disc differentials were kept as averages
+    Statistics::migrate_averages,
+];
+// lines without a version are from before it was written, and only lack it
+const GAME_RECORD_VERSION: u32 = 2;
+const APP_DIRECTORY: &str = "FIA-Othello";
+const AUTOSAVE_FILE: &str = "autosave.json";
+const STATISTICS_FILE: &str = "statistics.json";
+const SETTINGS_FILE: &str = "settings.json";
+const GAMES_FILE: &str = "games.jsonl";
+// the games file is moved here once it grows past MAX_GAMES_FILE_SIZE, replacing the one before
+const ARCHIVED_GAMES_FILE: &str = "games.old.jsonl";
+const MAX_GAMES_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+// the on-disk representation of a game, shared by manual saves and the autosave
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    // in the one-line position format, left out for games from the standard start position
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_position: Option<String>,
+    pub start_player: Player,
+    pub moves: Vec<Move>,
+    // for humans reading the file, the moves are authoritative
+    #[serde(default)]
+    pub transcript: String,
+    pub player_options: [PlayerOptions; 2],
+    // the seed the AIs picked among equally good moves with, to replay the game from the start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl SavedGame {
+    pub fn new(history: &History, player_options: [PlayerOptions; 2], seed: u64) -> Self {
+        SavedGame {
+            version: SAVE_VERSION,
+            start_position: (history.start_board.grid != Board::default().grid)
+                .then(|| notation::format_position(&history.start_board, history.start_player)),
+            start_player: history.start_player,
+            moves: history.moves(),
+            transcript: notation::transcript(history, true),
+            player_options,
+            seed: Some(seed),
+        }
+    }
+
+    pub fn replay(&self, referee: &mut Referee) -> Result<Replay, ReplayError> {
+        // the start position was checked when the file was read
+        let start_board = self
+            .start_position
+            .as_deref()
+            .and_then(|position| notation::parse_position(position).ok())
+            .map(|(board, _)| board)
+            .unwrap_or_default();
+        referee.replay(&start_board, self.start_player, &self.moves)
+    }
+
+    pub fn to_json(&self) -> String {
+        // serializing plain data into a string cannot fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let saved_game: SavedGame = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if saved_game.version > SAVE_VERSION {
+            return Err(format!(
+                "save file version {} is newer than the supported version {SAVE_VERSION}",
+                saved_game.version
+            ));
+        }
+        if let Some(position) = &saved_game.start_position {
+            notation::parse_position(position)
+                .map_err(|e| format!("invalid start position: {e}"))?;
+        }
+        Ok(saved_game)
+    }
+
+    pub fn write_atomically(&self, path: &PathBuf) -> io::Result<()> {
+        write_atomically(path, &self.to_json())
+    }
+
+    pub fn read(path: &PathBuf) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+// the version of a file or line, read before the rest so that one of a newer layout is told
+// apart from a corrupted one
+#[derive(Deserialize)]
+struct Version {
+    #[serde(default)]
+    version: u32,
+}
+
+impl Version {
+    // unversioned files count as the first version
+    fn of(json: &str) -> Result<u32, String> {
+        let version: Version = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(version.version.max(1))
+    }
+}
+
+// the statistics of every session so far, and the puzzles solved along the way
+#[derive(Serialize, Deserialize)]
+pub struct StatisticsFile {
+    #[serde(default)]
+    pub version: u32,
+    pub statistics: Statistics,
+    // by title
+    #[serde(default)]
+    pub solved_puzzles: Vec<String>,
+}
+
+impl Default for StatisticsFile {
+    fn default() -> Self {
+        StatisticsFile {
+            version: STATISTICS_VERSION,
+            statistics: Statistics::default(),
+            solved_puzzles: Vec::new(),
+        }
+    }
+}
+
+impl StatisticsFile {
+    pub fn to_json(&self) -> String {
+        // serializing plain data into a string cannot fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    // older files are brought up to the current version one version at a time
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let version = Version::of(json)?;
+        if version > STATISTICS_VERSION {
+            return Err(format!(
+                "statistics file version {version} is newer than the supported version \
+                 {STATISTICS_VERSION}"
+            ));
+        }
+        let mut statistics_file: StatisticsFile =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for migrate in &STATISTICS_MIGRATIONS[version as usize - 1..] {
+            migrate(&mut statistics_file.statistics);
+        }
+        statistics_file.version = STATISTICS_VERSION;
+        Ok(statistics_file)
+    }
+}
+
+// a game that counted in the statistics, one line of the games file
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    // seconds since the Unix epoch
+    pub timestamp: u64,
+    pub matchup: Matchup,
+    // by color
+    pub players: [Participant; 2],
+    pub start_player: Player,
+    // from the standard start position, passes left out
+    pub transcript: String,
+    // black and white
+    pub disc_counts: [usize; 2],
+    // None for a tie
+    pub winner: Option<Player>,
+    // None if the AIs did not play from the game's seed
+    pub seed: Option<u64>,
+}
+
+impl GameRecord {
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+
+    // the timestamp as "YYYY-MM-DD hh:mm" in UTC
+    pub fn date(&self) -> String {
+        format_timestamp(self.timestamp)
+    }
+
+    pub fn to_line(&self) -> String {
+        let line = GameRecordLine {
+            version: GAME_RECORD_VERSION,
+            record: self.clone(),
+        };
+        // serializing plain data into a string cannot fail
+        serde_json::to_string(&line).unwrap_or_default()
+    }
+
+    // every line that reads as a record, in order, and the number of lines left out because a
+    // newer version wrote them
+    // a crash mid-append leaves a partial last line, which is skipped like any other bad one
+    pub fn parse_lines(text: &str) -> (Vec<GameRecord>, usize) {
+        let mut records = Vec::new();
+        let mut newer = 0;
+        for line in text.lines() {
+            match Version::of(line) {
+                Ok(version) if version > GAME_RECORD_VERSION => newer += 1,
+                Ok(_) => {
+                    // version 1 only lacks the version, there is nothing to migrate yet
+                    if let Ok(line) = serde_json::from_str::<GameRecordLine>(line) {
+                        records.push(line.record);
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+        (records, newer)
+    }
+}
+
+// a record as written to the games file, with the version of its layout
+#[derive(Serialize, Deserialize)]
+struct GameRecordLine {
+    #[serde(default)]
+    version: u32,
+    #[serde(flatten)]
+    record: GameRecord,
+}
+
+// seconds since the Unix epoch as "YYYY-MM-DD hh:mm" in UTC
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let minutes = timestamp % 86400 / 60;
+    // the proleptic Gregorian calendar in eras of 400 years, starting in March
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+// the options of the side panel, kept from one session to the next
+// unknown fields are ignored and missing ones keep their defaults, so files of other versions load
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub options: GameOptions,
+    pub player_options: [PlayerOptions; 2],
+    pub presets: Vec<Preset>,
+    // the AI configurations of the arena, the built-in ones if empty
+    pub arena_contestants: Vec<Contestant>,
+}
+
+// named options to switch to at once, shared as a JSON snippet
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub options: GameOptions,
+    #[serde(default)]
+    pub player_options: [PlayerOptions; 2],
+    // presets from before the arena leave its configurations alone
+    #[serde(default)]
+    pub arena_contestants: Vec<Contestant>,
+}
+
+impl Preset {
+    pub fn to_json(&self) -> String {
+        // serializing plain data into a string cannot fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let preset: Preset = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if preset.name.trim().is_empty() {
+            return Err("the preset has no name".to_string());
+        }
+        Ok(preset)
+    }
+}
+
+impl Settings {
+    pub fn to_json(&self) -> String {
+        // serializing plain data into a string cannot fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
+
+// writes to a temporary file first, so that a crash mid-write leaves the old file intact
+fn write_atomically(path: &PathBuf, contents: &str) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)
+}
+
+// None without a file system, which turns saving into a no-op
+pub fn data_directory() -> Option<PathBuf> {
+    // tests never touch the user's files
+    if !platform::HAS_FILE_SYSTEM || cfg!(test) {
+        return None;
+    }
+    dirs::data_dir().map(|directory| directory.join(APP_DIRECTORY))
+}
+
+// settings go with the configuration rather than the data, None without a file system
+pub fn config_directory() -> Option<PathBuf> {
+    if !platform::HAS_FILE_SYSTEM || cfg!(test) {
+        return None;
+    }
+    dirs::config_dir().map(|directory| directory.join(APP_DIRECTORY))
+}
+
+pub fn write_autosave(saved_game: &SavedGame) {
+    if let Some(directory) = data_directory() {
+        write_autosave_in(&directory, saved_game);
+    }
+}
+
+fn write_autosave_in(directory: &Path, saved_game: &SavedGame) {
+    let path = directory.join(AUTOSAVE_FILE);
+    if let Err(e) = saved_game.write_atomically(&path) {
+        eprintln!("Failed to write autosave to {}: {e}", path.display());
+    }
+}
+
+pub fn clear_autosave() {
+    if let Some(directory) = data_directory() {
+        clear_autosave_in(&directory);
+    }
+}
+
+fn clear_autosave_in(directory: &Path) {
+    let path = directory.join(AUTOSAVE_FILE);
+    if path.exists()
+        && let Err(e) = fs::remove_file(&path)
+    {
+        eprintln!("Failed to remove autosave {}: {e}", path.display());
+    }
+}
+
+// returns the autosaved game and its replay, but only if it is still in progress
+pub fn load_unfinished_autosave(referee: &mut Referee) -> Option<(SavedGame, Replay)> {
+    load_unfinished_autosave_in(&data_directory()?, referee)
+}
+
+fn load_unfinished_autosave_in(
+    directory: &Path,
+    referee: &mut Referee,
+) -> Option<(SavedGame, Replay)> {
+    let path = directory.join(AUTOSAVE_FILE);
+    if !path.exists() {
+        return None;
+    }
+
+    let saved_game = match SavedGame::read(&path) {
+        Ok(saved_game) => saved_game,
+        Err(e) => {
+            eprintln!("Ignoring unreadable autosave {}: {e}", path.display());
+            clear_autosave_in(directory);
+            return None;
+        }
+    };
+
+    match saved_game.replay(referee) {
+        Ok(replay) if replay.next_player.is_some() && !replay.history.plies.is_empty() => {
+            Some((saved_game, replay))
+        }
+        Ok(_) => {
+            // a finished (or untouched) game is nothing worth resuming
+            clear_autosave_in(directory);
+            None
+        }
+        Err(e) => {
+            eprintln!("Ignoring invalid autosave {}: {e}", path.display());
+            clear_autosave_in(directory);
+            None
+        }
+    }
+}
+
+pub fn statistics_path() -> Option<PathBuf> {
+    data_directory().map(|directory| directory.join(STATISTICS_FILE))
+}
+
+// empty statistics if there are none yet, an error if they cannot be read, to start over with
+// empty ones as well
+// a file of another version is copied aside first, since the next write replaces it
+pub fn load_statistics() -> Result<StatisticsFile, String> {
+    let Some(path) = statistics_path().filter(|path| path.exists()) else {
+        return Ok(StatisticsFile::default());
+    };
+    read_statistics(&path)
+}
+
+fn read_statistics(path: &Path) -> Result<StatisticsFile, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Ignoring unreadable statistics {}: {e}", path.display()))?;
+
+    let backup = match Version::of(&json) {
+        Ok(version) if version != STATISTICS_VERSION => {
+            let backup = statistics_backup_path(path, version);
+            fs::copy(path, &backup).map_err(|e| {
+                format!(
+                    "Not loading statistics {} of version {version}, they could not be backed \
+                     up: {e}",
+                    path.display()
+                )
+            })?;
+            Some(backup)
+        }
+        _ => None,
+    };
+    let statistics_file = StatisticsFile::from_json(&json).map_err(|e| {
+        let kept = backup.as_ref().map_or(String::new(), |backup| {
+            format!(", they are kept in {}", backup.display())
+        });
+        format!("Ignoring statistics {}: {e}{kept}", path.display())
+    })?;
+    if let Some(backup) = backup {
+        eprintln!(
+            "Migrated statistics {} to version {STATISTICS_VERSION}, the original is kept in {}",
+            path.display(),
+            backup.display()
+        );
+    }
+    Ok(statistics_file)
+}
+
+// statistics.json of version 1 is backed up as statistics.v1.json, replacing an earlier backup
+// of the same version, which has fewer games
+fn statistics_backup_path(path: &Path, version: u32) -> PathBuf {
+    path.with_extension(format!("v{version}.json"))
+}
+
+pub fn write_statistics(statistics_file: &StatisticsFile) {
+    if let Some(path) = statistics_path()
+        && let Err(e) = write_atomically(&path, &statistics_file.to_json())
+    {
+        eprintln!("Failed to write statistics to {}: {e}", path.display());
+    }
+}
+
+pub fn clear_statistics() {
+    if let Some(path) = statistics_path()
+        && path.exists()
+        && let Err(e) = fs::remove_file(&path)
+    {
+        eprintln!("Failed to remove statistics {}: {e}", path.display());
+    }
+}
+
+pub fn games_path() -> Option<PathBuf> {
+    data_directory().map(|directory| directory.join(GAMES_FILE))
+}
+
+// the archived games first, oldest first, nothing without a file system, and the number of
+// games left out because a newer version wrote them
+pub fn load_game_records() -> (Vec<GameRecord>, usize) {
+    let Some(directory) = data_directory() else {
+        return (Vec::new(), 0);
+    };
+    let mut records = Vec::new();
+    let mut newer = 0;
+    for file in [ARCHIVED_GAMES_FILE, GAMES_FILE] {
+        if let Ok(text) = fs::read_to_string(directory.join(file)) {
+            let (file_records, file_newer) = GameRecord::parse_lines(&text);
+            records.extend(file_records);
+            newer += file_newer;
+        }
+    }
+    (records, newer)
+}
+
+pub fn append_game_record(record: &GameRecord) {
+    let Some(path) = games_path() else {
+        return;
+    };
+    if let Err(e) = append_line(&path, &record.to_line(), MAX_GAMES_FILE_SIZE) {
+        eprintln!("Failed to append the game to {}: {e}", path.display());
+    }
+}
+
+// archives the file first once it reached `max_size`
+fn append_line(path: &PathBuf, line: &str, max_size: u64) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= max_size) {
+        fs::rename(path, path.with_file_name(ARCHIVED_GAMES_FILE))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+
+    // a partial line left by a crash is ended first, so that it does not swallow this one
+    let mut last_byte = [b'\n'];
+    if file.metadata()?.len() > 0 {
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last_byte)?;
+    }
+    let separator = if last_byte[0] == b'\n' { "" } else { "\n" };
+    // a single write, so that a crash leaves at most the last line partial
+    file.write_all(format!("{separator}{line}\n").as_bytes())
+}
+
+pub fn clear_game_records() {
+    let Some(directory) = data_directory() else {
+        return;
+    };
+    for file in [ARCHIVED_GAMES_FILE, GAMES_FILE] {
+        let path = directory.join(file);
+        if path.exists()
+            && let Err(e) = fs::remove_file(&path)
+        {
+            eprintln!("Failed to remove game records {}: {e}", path.display());
+        }
+    }
+}
+
+pub fn settings_path() -> Option<PathBuf> {
+    config_directory().map(|directory| directory.join(SETTINGS_FILE))
+}
+
+// the defaults if there are no settings yet, or they cannot be read
+pub fn load_settings() -> Settings {
+    let Some(path) = settings_path().filter(|path| path.exists()) else {
+        return Settings::default();
+    };
+
+    match fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| Settings::from_json(&json))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Ignoring unreadable settings {}: {e}", path.display());
+            Settings::default()
+        }
+    }
+}
+
+pub fn write_settings(settings: &Settings) {
+    if let Some(path) = settings_path()
+        && let Err(e) = write_atomically(&path, &settings.to_json())
+    {
+        eprintln!("Failed to write settings to {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::AiType;
+    use crate::board::Cell;
+    use crate::referee::Outcome;
+    use crate::referee::WinReason;
+    use crate::statistics::GameResult;
+    use crate::statistics::Participant;
+
+    #[test]
+    fn settings_round_trip() {
+        let settings = Settings::from_json(
+            r#"{
+                "options": { "auto_restart": true, "ai_move_seconds": 0.5, "first_player": "White" },
+                "player_options": [
+                    { "ai_enabled": false, "ai_type": "Random", "ai_recursion_depth": 1 },
+                    { "ai_enabled": true, "ai_type": "Positional", "ai_recursion_depth": 5 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_ne!(settings, Settings::default());
+
+        assert_eq!(
+            Settings::from_json(&settings.to_json()),
+            Ok(settings.clone())
+        );
+        assert_eq!(
+            Settings::from_json(&Settings::default().to_json()),
+            Ok(Settings::default())
+        );
+    }
+
+    #[test]
+    fn missing_and_unknown_settings_fall_back_to_defaults() {
+        assert_eq!(Settings::from_json("{}"), Ok(Settings::default()));
+
+        let settings =
+            Settings::from_json(r#"{ "options": { "show_valid_moves": true }, "theme": "dark" }"#)
+                .unwrap();
+        assert_ne!(settings.options, GameOptions::default());
+        assert_eq!(settings.player_options, [PlayerOptions::default(); 2]);
+    }
+
+    #[test]
+    fn presets_round_trip() {
+        let preset = Preset::from_json(
+            r#"{ "name": "Teaching", "options": { "show_valid_moves": true, "ai_move_seconds": 2.0 } }"#,
+        )
+        .unwrap();
+        assert_eq!(preset.player_options, [PlayerOptions::default(); 2]);
+        assert!(preset.arena_contestants.is_empty());
+        assert_eq!(Preset::from_json(&preset.to_json()), Ok(preset.clone()));
+
+        let preset = Preset::from_json(
+            r#"{ "name": "Arena", "arena_contestants": [{ "ai_type": "AlphaBeta", "recursion_depth": 4 }] }"#,
+        )
+        .unwrap();
+        assert_eq!(preset.arena_contestants[0].name(), "AlphaBeta lvl 4");
+        assert_eq!(Preset::from_json(&preset.to_json()), Ok(preset.clone()));
+
+        let settings = Settings {
+            presets: vec![preset],
+            ..Default::default()
+        };
+        assert_eq!(Settings::from_json(&settings.to_json()), Ok(settings));
+    }
+
+    #[test]
+    fn presets_need_a_name() {
+        assert!(Preset::from_json(r#"{ "options": {} }"#).is_err());
+        assert!(Preset::from_json(r#"{ "name": " " }"#).is_err());
+    }
+
+    #[test]
+    fn statistics_round_trip() {
+        let mut statistics_file = StatisticsFile::default();
+        let random = Participant::ai(AiType::Random, 1);
+        statistics_file.statistics.add_game(
+            [Participant::Human, random.clone()],
+            Player::Black,
+            &GameResult {
+                outcome: Outcome::Won(Player::White, WinReason::Time),
+                disc_counts: [30, 20],
+                plies: 46,
+            },
+        );
+        statistics_file.statistics.add_game(
+            [random.clone(), random],
+            Player::White,
+            &GameResult {
+                outcome: Outcome::Tie,
+                disc_counts: [32, 32],
+                plies: 60,
+            },
+        );
+        statistics_file
+            .solved_puzzles
+            .push("Corner grab".to_string());
+
+        let read = StatisticsFile::from_json(&statistics_file.to_json()).unwrap();
+        assert_eq!(read.version, STATISTICS_VERSION);
+        assert_eq!(read.solved_puzzles, statistics_file.solved_puzzles);
+        assert_eq!(read.statistics.matchups.len(), 2);
+        for (read, entry) in read
+            .statistics
+            .matchups
+            .iter()
+            .zip(statistics_file.statistics.matchups.iter())
+        {
+            assert_eq!(read.matchup, entry.matchup);
+            assert_eq!(read.statistic.to_string(), entry.statistic.to_string());
+        }
+    }
+
+    #[test]
+    fn unversioned_statistics_are_migrated() {
+        let read = StatisticsFile::from_json(
+            r#"{ "statistics": { "data": {
+                "Random": {
+                    "win_ratio": 1.0, "tie_ratio": 0.0, "lose_ratio": 0.0, "count": 3.0, "on_time_count": 0.0
+                },
+                "Human vs Random": {
+                    "win_ratio": 1.0, "tie_ratio": 0.0, "lose_ratio": 0.0, "count": 2.0, "on_time_count": 0.0
+                }
+            } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(read.version, STATISTICS_VERSION);
+        assert!(read.solved_puzzles.is_empty());
+        assert_eq!(
+            read.statistics.data["Random"].to_string(),
+            "100.0%, 0.0%, 0.0%, (3)"
+        );
+        // the single games moved into a matchup
+        assert_eq!(read.statistics.data.len(), 1);
+        assert_eq!(
+            read.statistics.matchups[0].matchup.to_string(),
+            "Human vs Random"
+        );
+        assert_eq!(
+            read.statistics.matchups[0].statistic.to_string(),
+            "100.0%, 0.0%, 0.0%, (2)"
+        );
+    }
+
+    #[test]
+    fn statistics_of_every_version_migrate_to_the_current_one() {
+        let current = include_str!("../tests/fixtures/statistics_v3.json");
+        let expected = StatisticsFile::from_json(current).unwrap().to_json();
+        for old in [
+            include_str!("../tests/fixtures/statistics_v1.json"),
+            include_str!("../tests/fixtures/statistics_v2.json"),
+        ] {
+            assert_eq!(StatisticsFile::from_json(old).unwrap().to_json(), expected);
+        }
+    }
+
+    #[test]
+    fn statistics_are_backed_up_before_a_migration() {
+        let directory =
+            std::env::temp_dir().join(format!("fia-othello-statistics-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(STATISTICS_FILE);
+
+        let old = include_str!("../tests/fixtures/statistics_v2.json");
+        fs::write(&path, old).unwrap();
+        assert_eq!(read_statistics(&path).unwrap().version, STATISTICS_VERSION);
+        let backup = directory.join("statistics.v2.json");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), old);
+
+        // a newer file is kept for the version that wrote it
+        let newer = r#"{ "version": 99, "statistics": { "renamed": [] } }"#;
+        fs::write(&path, newer).unwrap();
+        let e = read_statistics(&path).err().unwrap();
+        assert!(e.contains("newer"), "{e}");
+        assert!(e.contains("statistics.v99.json"), "{e}");
+        assert_eq!(
+            fs::read_to_string(directory.join("statistics.v99.json")).unwrap(),
+            newer
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn unreadable_statistics_are_an_error() {
+        assert!(StatisticsFile::from_json("{ \"statistics\": ").is_err());
+        assert!(StatisticsFile::from_json(r#"{ "solved_puzzles": [] }"#).is_err());
+        assert!(
+            StatisticsFile::from_json(r#"{ "version": 99, "statistics": { "data": {} } }"#)
+                .is_err()
+        );
+    }
+
+    fn game_record(transcript: &str) -> GameRecord {
+        let players = [Participant::ai(AiType::Random, 1), Participant::Human];
+        GameRecord {
+            timestamp: 1_700_000_000,
+            matchup: Matchup::new(players.clone(), false).0,
+            players,
+            start_player: Player::Black,
+            transcript: transcript.to_string(),
+            disc_counts: [40, 24],
+            winner: Some(Player::Black),
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn game_records_of_every_version_are_read() {
+        let (records, newer) =
+            GameRecord::parse_lines(include_str!("../tests/fixtures/games_v1.jsonl"));
+        assert_eq!(records, [game_record("f5d6c3"), game_record("f5f6")]);
+        assert_eq!(newer, 0);
+
+        let line = game_record("f5").to_line();
+        assert!(line.starts_with(&format!("{{\"version\":{GAME_RECORD_VERSION},")));
+        let text = format!("{line}\n{}\n", r#"{ "version": 99, "moves": ["f5"] }"#);
+        assert_eq!(GameRecord::parse_lines(&text), (vec![game_record("f5")], 1));
+    }
+
+    #[test]
+    fn game_records_are_dated_in_utc() {
+        assert_eq!(game_record("").date(), "2023-11-14 22:13");
+        let leap_day = GameRecord {
+            timestamp: 951_782_400,
+            ..game_record("")
+        };
+        assert_eq!(leap_day.date(), "2000-02-29 00:00");
+    }
+
+    #[test]
+    fn partial_game_record_lines_are_skipped() {
+        let records = [game_record("f5d6"), game_record("f5f6")];
+        let line = records[1].to_line();
+        let text = format!(
+            "{}\n{}\n{}",
+            records[0].to_line(),
+            records[1].to_line(),
+            &line[..line.len() / 2]
+        );
+
+        assert_eq!(GameRecord::parse_lines(&text), (records.to_vec(), 0));
+    }
+
+    #[test]
+    fn game_records_are_appended_and_archived() {
+        let directory =
+            std::env::temp_dir().join(format!("fia-othello-games-{}", std::process::id()));
+        let path = directory.join(GAMES_FILE);
+        let records = [game_record("f5"), game_record("f5d6"), game_record("f5f6")];
+
+        append_line(&path, &records[0].to_line(), u64::MAX).unwrap();
+        // a crash in the middle of the second append
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"timestamp\":").unwrap();
+        append_line(&path, &records[1].to_line(), u64::MAX).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(GameRecord::parse_lines(&text), (records[..2].to_vec(), 0));
+
+        // past the size limit the file moves aside and starts over
+        append_line(&path, &records[2].to_line(), 1).unwrap();
+        let archived = fs::read_to_string(directory.join(ARCHIVED_GAMES_FILE)).unwrap();
+        assert_eq!(archived, text);
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(GameRecord::parse_lines(&text), (records[2..].to_vec(), 0));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    fn autosave_directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fia-othello-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn an_unfinished_autosave_is_resumed() {
+        let directory = autosave_directory("autosave-unfinished");
+        let mut history = History::default();
+        history.push(Player::Black, (4, 5));
+        history.push(Player::White, (5, 3));
+        write_autosave_in(&directory, &SavedGame::new(&history, Default::default(), 7));
+
+        let (saved_game, replay) =
+            load_unfinished_autosave_in(&directory, &mut Referee::default()).unwrap();
+        assert_eq!(saved_game.moves, [(4, 5), (5, 3)]);
+        assert_eq!(saved_game.seed, Some(7));
+        assert_eq!(replay.history.plies, history.plies);
+        assert_eq!(replay.next_player, Some(Player::Black));
+        // the autosave stays until the game ends or another one starts
+        assert!(directory.join(AUTOSAVE_FILE).exists());
+
+        clear_autosave_in(&directory);
+        assert!(load_unfinished_autosave_in(&directory, &mut Referee::default()).is_none());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn the_autosave_of_a_finished_game_is_discarded() {
+        let directory = autosave_directory("autosave-finished");
+        // a1 takes b1 and leaves white without a disc
+        let mut history = History::default();
+        history.start_board.grid = [[Cell::Empty; Board::SIZE]; Board::SIZE];
+        history.start_board.grid[0][0] = Cell::Taken(Player::Black);
+        history.start_board.grid[0][1] = Cell::Taken(Player::White);
+        history.push(Player::Black, (0, 2));
+        write_autosave_in(&directory, &SavedGame::new(&history, Default::default(), 7));
+
+        assert!(load_unfinished_autosave_in(&directory, &mut Referee::default()).is_none());
+        assert!(!directory.join(AUTOSAVE_FILE).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn a_corrupt_autosave_is_discarded() {
+        let directory = autosave_directory("autosave-corrupt");
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(AUTOSAVE_FILE);
+
+        fs::write(&path, "{ \"version\": 2, \"moves\": [").unwrap();
+        assert!(load_unfinished_autosave_in(&directory, &mut Referee::default()).is_none());
+        assert!(!path.exists());
+
+        // readable, but with a move that cannot be played
+        let mut saved_game = SavedGame::new(&History::default(), Default::default(), 7);
+        saved_game.moves = vec![(0, 0)];
+        saved_game.write_atomically(&path).unwrap();
+        assert!(load_unfinished_autosave_in(&directory, &mut Referee::default()).is_none());
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn corrupted_settings_are_an_error() {
+        assert!(Settings::from_json("{ \"options\": ").is_err());
+        assert!(Settings::from_json(r#"{ "options": { "auto_restart": "yes" } }"#).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::platform::Instant;
+
+// no two sounds closer than this, so that a game at full speed does not turn into a buzz
+const MIN_GAP: Duration = Duration::from_millis(80);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sound {
+    // a disc placed on the board
+    Place,
+    // the discs of a move done turning over
+    Flip,
+    GameOver,
+    // a clock running low
+    LowTime,
+}
+
+impl Sound {
+    // the same sound again any sooner is dropped
+    fn min_interval(self) -> Duration {
+        match self {
+            Sound::Place | Sound::Flip => Duration::from_millis(150),
+            Sound::GameOver | Sound::LowTime => Duration::from_secs(1),
+        }
+    }
+
+    // the WAV file built into the app
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wav(self) -> &'static [u8] {
+        match self {
+            Sound::Place => include_bytes!("../assets/sounds/place.wav"),
+            Sound::Flip => include_bytes!("../assets/sounds/flip.wav"),
+            Sound::GameOver => include_bytes!("../assets/sounds/game_over.wav"),
+            Sound::LowTime => include_bytes!("../assets/sounds/low_time.wav"),
+        }
+    }
+}
+
+// where the sounds go, looked for when the first one is played
+enum Output {
+    Unopened,
+    // the default audio device, the stream stops playing once dropped
+    #[cfg(not(target_arch = "wasm32"))]
+    Device {
+        _stream: rodio::OutputStream,
+        handle: rodio::OutputStreamHandle,
+    },
+    // no audio device, or the browser: sounds are dropped without a word
+    Unavailable,
+}
+
+pub struct Sounds {
+    output: Output,
+    last_played: HashMap<Sound, Instant>,
+    last_any: Option<Instant>,
+}
+
+impl Default for Sounds {
+    fn default() -> Self {
+        Sounds {
+            output: Output::Unopened,
+            last_played: HashMap::new(),
+            last_any: None,
+        }
+    }
+}
+
+impl Sounds {
+    // whether the sound was let through, `volume` going from 0 for silence to 1
+    pub fn play(&mut self, sound: Sound, volume: f32, now: Instant) -> bool {
+        if volume <= 0.0 || !self.is_due(sound, now) {
+            return false;
+        }
+        self.last_played.insert(sound, now);
+        self.last_any = Some(now);
+
+        if matches!(self.output, Output::Unopened) {
+            self.output = open_output();
+        }
+        match &self.output {
+            #[cfg(not(target_arch = "wasm32"))]
+            Output::Device { handle, .. } => play_on(handle, sound, volume),
+            Output::Unopened | Output::Unavailable => {}
+        }
+        true
+    }
+
+    fn is_due(&self, sound: Sound, now: Instant) -> bool {
+        let since = |played: Instant| now.saturating_duration_since(played);
+        self.last_any.is_none_or(|played| since(played) >= MIN_GAP)
+            && self
+                .last_played
+                .get(&sound)
+                .is_none_or(|&played| since(played) >= sound.min_interval())
+    }
+}
+
+// tests stay quiet
+#[cfg(not(target_arch = "wasm32"))]
+fn open_output() -> Output {
+    if cfg!(test) {
+        return Output::Unavailable;
+    }
+    match rodio::OutputStream::try_default() {
+        Ok((stream, handle)) => Output::Device {
+            _stream: stream,
+            handle,
+        },
+        Err(_) => Output::Unavailable,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_output() -> Output {
+    Output::Unavailable
+}
+
+// a sound that cannot be decoded or played is skipped, the game goes on
+#[cfg(not(target_arch = "wasm32"))]
+fn play_on(handle: &rodio::OutputStreamHandle, sound: Sound, volume: f32) {
+    use rodio::Source;
+
+    if let Ok(decoder) = rodio::Decoder::new(std::io::Cursor::new(sound.wav())) {
+        let _ = handle.play_raw(decoder.amplify(volume).convert_samples());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_in_quick_succession_are_dropped() {
+        let mut sounds = Sounds::default();
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert!(sounds.play(Sound::Place, 0.5, at(0)));
+        // too close to any sound
+        assert!(!sounds.play(Sound::GameOver, 0.5, at(50)));
+        // too close to the last one of its kind
+        assert!(!sounds.play(Sound::Place, 0.5, at(100)));
+        assert!(sounds.play(Sound::Flip, 0.5, at(100)));
+        assert!(sounds.play(Sound::Place, 0.5, at(200)));
+
+        // silence is not played, and does not hold up the next sound
+        assert!(!sounds.play(Sound::GameOver, 0.0, at(1000)));
+        assert!(sounds.play(Sound::GameOver, 1.0, at(1000)));
+    }
+
+    #[test]
+    fn every_sound_decodes() {
+        use rodio::Source;
+
+        for sound in [Sound::Place, Sound::Flip, Sound::GameOver, Sound::LowTime] {
+            let decoder = rodio::Decoder::new(std::io::Cursor::new(sound.wav())).unwrap();
+            let duration = decoder.total_duration().unwrap();
+            assert!(
+                duration > Duration::ZERO && duration < Duration::from_secs(1),
+                "{sound:?}"
+            );
+        }
+    }
+}
@@ -1,12 +1,313 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt::Display,
+};
 
-use crate::{board::Player, referee::Outcome};
+use serde::{Deserialize, Serialize};
 
+use crate::{
+    ai::AiType,
+    board::{Board, Move, Player, Symmetry},
+    history::Ply,
+    referee::{Outcome, Referee, WinReason},
+    tournament::MatchTally,
+};
+
+/// the wins, ties and losses of a configuration with one color
+///
+/// ```
+/// use fia_othello::board::Player;
+/// use fia_othello::referee::Outcome;
+/// use fia_othello::referee::WinReason;
+/// use fia_othello::statistics::ColorRecord;
+///
+/// let mut record = ColorRecord::default();
+/// record.record(Player::White, &Outcome::Won(Player::White, WinReason::Disks));
+/// record.record(Player::White, &Outcome::Tie);
+/// assert_eq!(record.to_string(), "1-1-0");
+/// ```
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColorRecord {
+    pub wins: usize,
+    pub ties: usize,
+    pub losses: usize,
+}
+
+impl ColorRecord {
+    /// counts the outcome of a game played as `player`
+    pub fn record(&mut self, player: Player, outcome: &Outcome) {
+        match *outcome {
+            Outcome::Won(winner, _) if winner == player => self.wins += 1,
+            Outcome::Won(..) => self.losses += 1,
+            Outcome::Tie => self.ties += 1,
+        }
+    }
+
+    pub fn games(&self) -> usize {
+        self.wins + self.ties + self.losses
+    }
+
+    /// the share of the points taken, a tie counting as half a win
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.ties as f64 / 2.0) / self.games().max(1) as f64
+    }
+}
+
+impl Display for ColorRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.wins, self.ties, self.losses)
+    }
+}
+
+/// how a game ended for one side
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    Win,
+    Tie,
+    Loss,
+}
+
+impl GameOutcome {
+    /// the outcome for `player`
+    pub fn of(player: Player, outcome: &Outcome) -> Self {
+        match *outcome {
+            Outcome::Won(winner, _) if winner == player => GameOutcome::Win,
+            Outcome::Won(..) => GameOutcome::Loss,
+            Outcome::Tie => GameOutcome::Tie,
+        }
+    }
+
+    /// the outcome for the opponent
+    pub fn reversed(self) -> Self {
+        match self {
+            GameOutcome::Win => GameOutcome::Loss,
+            GameOutcome::Tie => GameOutcome::Tie,
+            GameOutcome::Loss => GameOutcome::Win,
+        }
+    }
+
+    fn letter(self) -> char {
+        match self {
+            GameOutcome::Win => 'W',
+            GameOutcome::Tie => 'T',
+            GameOutcome::Loss => 'L',
+        }
+    }
+}
+
+/// the games a `Form` keeps for its recent record
+pub const FORM_GAMES: usize = 20;
+
+/// the streaks and the last games of a configuration, to tell whether it is improving
+///
+/// ```
+/// use fia_othello::statistics::Form;
+/// use fia_othello::statistics::GameOutcome;
+///
+/// let mut form = Form::default();
+/// for outcome in [GameOutcome::Win, GameOutcome::Win, GameOutcome::Loss, GameOutcome::Tie] {
+///     form.record(outcome);
+/// }
+/// form.record(GameOutcome::Tie);
+/// assert_eq!(form.streak(), Some("T2".to_string()));
+/// assert_eq!(form.to_string(), "T2, longest win streak 2, last 5: 2-2-1");
+/// ```
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Form {
+    /// the outcome of the last game and how many games in a row ended the same
+    pub current: Option<(GameOutcome, usize)>,
+    pub longest_win_streak: usize,
+    /// kept for the opponent's longest win streak
+    pub longest_loss_streak: usize,
+    /// the outcomes of the last `FORM_GAMES` games, oldest first
+    pub recent: VecDeque<GameOutcome>,
+}
+
+impl Form {
+    pub fn record(&mut self, outcome: GameOutcome) {
+        let length = match self.current {
+            Some((last, length)) if last == outcome => length + 1,
+            _ => 1,
+        };
+        self.current = Some((outcome, length));
+        match outcome {
+            GameOutcome::Win => self.longest_win_streak = self.longest_win_streak.max(length),
+            GameOutcome::Loss => self.longest_loss_streak = self.longest_loss_streak.max(length),
+            GameOutcome::Tie => {}
+        }
+        self.recent.push_back(outcome);
+        if self.recent.len() > FORM_GAMES {
+            self.recent.pop_front();
+        }
+    }
+
+    /// the current streak, e.g. "W4", None before the first game
+    pub fn streak(&self) -> Option<String> {
+        self.current
+            .map(|(outcome, length)| format!("{}{length}", outcome.letter()))
+    }
+
+    /// the wins, ties and losses of the recent games
+    pub fn recent_record(&self) -> ColorRecord {
+        let count = |outcome| {
+            self.recent
+                .iter()
+                .filter(|&&recent| recent == outcome)
+                .count()
+        };
+        ColorRecord {
+            wins: count(GameOutcome::Win),
+            ties: count(GameOutcome::Tie),
+            losses: count(GameOutcome::Loss),
+        }
+    }
+
+    // the same games from the opponent's perspective
+    fn mirrored(self) -> Form {
+        Form {
+            current: self
+                .current
+                .map(|(outcome, length)| (outcome.reversed(), length)),
+            longest_win_streak: self.longest_loss_streak,
+            longest_loss_streak: self.longest_win_streak,
+            recent: self.recent.into_iter().map(GameOutcome::reversed).collect(),
+        }
+    }
+
+    // the games of the two cannot be told apart in time, so the other's count as the later ones
+    fn merge(&mut self, other: Form) {
+        self.longest_win_streak = self.longest_win_streak.max(other.longest_win_streak);
+        self.longest_loss_streak = self.longest_loss_streak.max(other.longest_loss_streak);
+        if other.current.is_some() {
+            self.current = other.current;
+        }
+        self.recent.extend(other.recent);
+        while self.recent.len() > FORM_GAMES {
+            self.recent.pop_front();
+        }
+    }
+}
+
+impl Display for Form {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, longest win streak {}, last {}: {}",
+            self.streak().unwrap_or_else(|| "–".to_string()),
+            self.longest_win_streak,
+            self.recent.len(),
+            self.recent_record()
+        )
+    }
+}
+
+/// how a game ended and what it took to get there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameResult {
+    pub outcome: Outcome,
+    /// the discs of black and white on the final board
+    pub disc_counts: [usize; 2],
+    /// the length of the game, passes included
+    pub plies: usize,
+}
+
+impl GameResult {
+    /// the discs of `player` minus those of the opponent on the final board
+    pub fn disc_differential(&self, player: Player) -> i64 {
+        self.disc_counts[player as usize] as i64
+            - self.disc_counts[player.opponent() as usize] as i64
+    }
+}
+
+/// the time a configuration took for its moves
+///
+/// ```
+/// use fia_othello::statistics::ThinkingTime;
+///
+/// let mut time = ThinkingTime::default();
+/// time.add(0.5);
+/// time.add(1.1);
+/// assert_eq!(time.to_string(), "avg 0.8 s/move, max 1.1 s");
+/// ```
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThinkingTime {
+    pub moves: usize,
+    pub total_seconds: f64,
+    pub max_seconds: f64,
+}
+
+impl ThinkingTime {
+    pub fn add(&mut self, seconds: f64) {
+        self.moves += 1;
+        self.total_seconds += seconds;
+        self.max_seconds = self.max_seconds.max(seconds);
+    }
+
+    pub fn merge(&mut self, other: ThinkingTime) {
+        self.moves += other.moves;
+        self.total_seconds += other.total_seconds;
+        self.max_seconds = self.max_seconds.max(other.max_seconds);
+    }
+
+    /// None before the first move
+    pub fn average_seconds(&self) -> Option<f64> {
+        (self.moves > 0).then(|| self.total_seconds / self.moves as f64)
+    }
+}
+
+impl Display for ThinkingTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "avg {:.1} s/move, max {:.1} s",
+            self.average_seconds().unwrap_or(0.0),
+            self.max_seconds
+        )
+    }
+}
+
+/// the share of wins, ties and losses of one player configuration
+#[derive(Serialize, Deserialize)]
 pub struct Statistic {
     win_ratio: f64,
     tie_ratio: f64,
     lose_ratio: f64,
     count: f64,
+    // decisive games that ended by a player running out of time
+    on_time_count: f64,
+    // decisive games that ended by a player resigning
+    #[serde(default)]
+    resignation_count: f64,
+    // summed over the games whose length is known, passes included
+    #[serde(default)]
+    plies_sum: f64,
+    #[serde(default)]
+    plies_count: f64,
+    // summed over the games whose final discs are known, from the configuration's perspective
+    #[serde(default)]
+    disc_differential_sum: f64,
+    #[serde(default)]
+    disc_differential_count: f64,
+    // files from before the sums were kept only have the average of whole matches, it is
+    // turned into sums on load
+    #[serde(default, skip_serializing)]
+    average_disc_differential: Option<f64>,
+    // by the color the configuration had, zero in files from before they were kept
+    #[serde(default)]
+    as_black: ColorRecord,
+    #[serde(default)]
+    as_white: ColorRecord,
+    // of the first and the second participant of the matchup, none in files from before they
+    // were kept and for humans
+    #[serde(default)]
+    thinking_times: [ThinkingTime; 2],
+    // a match or arena pairing still running, or stopped before its last game
+    #[serde(default)]
+    partial: bool,
+    // of single games only, empty in files from before it was kept
+    #[serde(default)]
+    form: Form,
 }
 impl Default for Statistic {
     fn default() -> Self {
@@ -15,10 +316,187 @@ impl Default for Statistic {
             tie_ratio: 0.0,
             lose_ratio: 0.0,
             count: 0.0,
+            on_time_count: 0.0,
+            resignation_count: 0.0,
+            plies_sum: 0.0,
+            plies_count: 0.0,
+            disc_differential_sum: 0.0,
+            disc_differential_count: 0.0,
+            average_disc_differential: None,
+            as_black: ColorRecord::default(),
+            as_white: ColorRecord::default(),
+            thinking_times: [ThinkingTime::default(); 2],
+            partial: false,
+            form: Form::default(),
         }
     }
 }
 
+impl Statistic {
+    // counts a game played as `player`
+    fn add(&mut self, player: Player, result: &GameResult) {
+        let outcome = &result.outcome;
+        let (win_value, tie_value, lose_value) = match *outcome {
+            Outcome::Won(winning_player, _) => {
+                if player == winning_player {
+                    (1.0, 0.0, 0.0)
+                } else {
+                    (0.0, 0.0, 1.0)
+                }
+            }
+            Outcome::Tie => (0.0, 1.0, 0.0),
+        };
+
+        let mut ratios = [
+            (&mut self.win_ratio, win_value),
+            (&mut self.tie_ratio, tie_value),
+            (&mut self.lose_ratio, lose_value),
+        ];
+
+        for (ratio, value) in ratios.iter_mut() {
+            let new_ratio = **ratio * self.count + *value;
+            **ratio = new_ratio / (self.count + 1.0);
+        }
+        self.count += 1.0;
+        let color_record = match player {
+            Player::Black => &mut self.as_black,
+            Player::White => &mut self.as_white,
+        };
+        color_record.record(player, outcome);
+        self.form.record(GameOutcome::of(player, outcome));
+        match *outcome {
+            Outcome::Won(_, WinReason::Time) => self.on_time_count += 1.0,
+            Outcome::Won(_, WinReason::Resignation) => self.resignation_count += 1.0,
+            Outcome::Won(_, WinReason::Disks) | Outcome::Tie => {}
+        }
+        self.plies_sum += result.plies as f64;
+        self.plies_count += 1.0;
+        self.disc_differential_sum += result.disc_differential(player) as f64;
+        self.disc_differential_count += 1.0;
+    }
+
+    // the same games from the opponent's perspective
+    fn mirrored(self) -> Statistic {
+        let mirror = |record: ColorRecord| ColorRecord {
+            wins: record.losses,
+            ties: record.ties,
+            losses: record.wins,
+        };
+        Statistic {
+            win_ratio: self.lose_ratio,
+            lose_ratio: self.win_ratio,
+            disc_differential_sum: -self.disc_differential_sum,
+            // the opponent had the other color
+            as_black: mirror(self.as_white),
+            as_white: mirror(self.as_black),
+            thinking_times: [self.thinking_times[1], self.thinking_times[0]],
+            form: self.form.mirrored(),
+            ..self
+        }
+    }
+
+    // adds the games of another statistic of the same perspective
+    fn merge(&mut self, other: Statistic) {
+        let count = self.count + other.count;
+        if count > 0.0 {
+            let (own_count, other_count) = (self.count, other.count);
+            let weighted = |a: f64, b: f64| (a * own_count + b * other_count) / count;
+            self.win_ratio = weighted(self.win_ratio, other.win_ratio);
+            self.tie_ratio = weighted(self.tie_ratio, other.tie_ratio);
+            self.lose_ratio = weighted(self.lose_ratio, other.lose_ratio);
+        }
+        self.count = count;
+        self.plies_sum += other.plies_sum;
+        self.plies_count += other.plies_count;
+        self.disc_differential_sum += other.disc_differential_sum;
+        self.disc_differential_count += other.disc_differential_count;
+        self.on_time_count += other.on_time_count;
+        self.resignation_count += other.resignation_count;
+        for (record, other_record) in [
+            (&mut self.as_black, other.as_black),
+            (&mut self.as_white, other.as_white),
+        ] {
+            record.wins += other_record.wins;
+            record.ties += other_record.ties;
+            record.losses += other_record.losses;
+        }
+        for (time, other_time) in self.thinking_times.iter_mut().zip(other.thinking_times) {
+            time.merge(other_time);
+        }
+        self.form.merge(other.form);
+    }
+
+    /// the mean length of the games, passes included, None if no length is known
+    pub fn average_plies(&self) -> Option<f64> {
+        (self.plies_count > 0.0).then(|| self.plies_sum / self.plies_count)
+    }
+
+    /// the mean of the final disc counts of the configuration minus its opponent's, None if no
+    /// final discs are known
+    pub fn average_disc_differential(&self) -> Option<f64> {
+        (self.disc_differential_count > 0.0)
+            .then(|| self.disc_differential_sum / self.disc_differential_count)
+    }
+
+    pub fn games(&self) -> f64 {
+        self.count
+    }
+
+    /// the share of the games won, from 0 to 1
+    pub fn win_ratio(&self) -> f64 {
+        self.win_ratio
+    }
+
+    /// the share of the games tied, from 0 to 1
+    pub fn tie_ratio(&self) -> f64 {
+        self.tie_ratio
+    }
+
+    /// the share of the games lost, from 0 to 1
+    pub fn loss_ratio(&self) -> f64 {
+        self.lose_ratio
+    }
+
+    /// of the first and the second participant of the matchup
+    pub fn thinking_times(&self) -> [ThinkingTime; 2] {
+        self.thinking_times
+    }
+
+    /// the share of the points taken, a tie counting as half a win
+    pub fn score(&self) -> f64 {
+        self.win_ratio + self.tie_ratio / 2.0
+    }
+
+    /// the score with its 95% Wilson interval, e.g. "62% [48%, 74%] over 50 games", None
+    /// without games
+    pub fn score_summary(&self) -> Option<String> {
+        score_summary(self.score() * self.count, self.count)
+    }
+
+    /// the streaks and recent games of the first participant of the matchup, None for
+    /// summaries of matches and entries from before it was kept
+    pub fn form(&self) -> Option<&Form> {
+        self.form.current.is_some().then_some(&self.form)
+    }
+
+    /// whether this is the summary of a match or arena pairing that did not play all its games
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// the results as black and as white, e.g. "as Black: 12-1-3, as White: 8-2-6", None if
+    /// the colors of the games are unknown
+    pub fn color_breakdown(&self) -> Option<String> {
+        if self.as_black.games() + self.as_white.games() == 0 {
+            return None;
+        }
+        Some(format!(
+            "as Black: {}, as White: {}",
+            self.as_black, self.as_white
+        ))
+    }
+}
+
 impl Display for Statistic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -28,39 +506,1955 @@ impl Display for Statistic {
             self.tie_ratio * 100.0,
             self.lose_ratio * 100.0,
             self.count
+        )?;
+        if self.on_time_count > 0.0 {
+            write!(f, ", {:.0} on time", self.on_time_count)?;
+        }
+        if self.resignation_count > 0.0 {
+            write!(f, ", {:.0} by resignation", self.resignation_count)?;
+        }
+        if let Some(differential) = self.average_disc_differential() {
+            write!(f, ", {differential:+.1} discs on average")?;
+        }
+        if let Some(plies) = self.average_plies() {
+            write!(f, ", {plies:.1} plies on average")?;
+        }
+        if self.partial {
+            write!(f, ", partial")?;
+        }
+        Ok(())
+    }
+}
+
+/// a column of the statistics table, which its rows can be sorted by
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum StatisticsColumn {
+    Name,
+    #[default]
+    Games,
+    Wins,
+    Ties,
+    Losses,
+    DiscDifferential,
+}
+
+impl StatisticsColumn {
+    pub const ALL: [StatisticsColumn; 6] = [
+        StatisticsColumn::Name,
+        StatisticsColumn::Games,
+        StatisticsColumn::Wins,
+        StatisticsColumn::Ties,
+        StatisticsColumn::Losses,
+        StatisticsColumn::DiscDifferential,
+    ];
+
+    // from the smallest to the largest, entries without a differential first
+    fn compare(self, a: &StatisticsRow, b: &StatisticsRow) -> Ordering {
+        let value = |row: &StatisticsRow| match self {
+            StatisticsColumn::Name => 0.0,
+            StatisticsColumn::Games => row.statistic.count,
+            StatisticsColumn::Wins => row.statistic.win_ratio,
+            StatisticsColumn::Ties => row.statistic.tie_ratio,
+            StatisticsColumn::Losses => row.statistic.lose_ratio,
+            StatisticsColumn::DiscDifferential => row
+                .statistic
+                .average_disc_differential()
+                .unwrap_or(f64::NEG_INFINITY),
+        };
+        match self {
+            StatisticsColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            _ => value(a).total_cmp(&value(b)),
+        }
+    }
+}
+
+/// the column the statistics table is sorted by and its direction, the most played entries
+/// first by default
+///
+/// ```
+/// use fia_othello::statistics::StatisticsColumn;
+/// use fia_othello::statistics::StatisticsSort;
+///
+/// let sort = StatisticsSort::default().clicked(StatisticsColumn::Name);
+/// assert_eq!(sort.column, StatisticsColumn::Name);
+/// assert!(!sort.descending);
+/// assert!(sort.clicked(StatisticsColumn::Name).descending);
+/// assert!(sort.clicked(StatisticsColumn::Wins).descending);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatisticsSort {
+    pub column: StatisticsColumn,
+    pub descending: bool,
+}
+
+impl Default for StatisticsSort {
+    fn default() -> Self {
+        StatisticsSort {
+            column: StatisticsColumn::Games,
+            descending: true,
+        }
+    }
+}
+
+impl StatisticsSort {
+    /// the sort after a click on the header of `column`: the other way round for the column
+    /// sorted by, names from A and numbers from the largest for another
+    pub fn clicked(self, column: StatisticsColumn) -> Self {
+        if column == self.column {
+            StatisticsSort {
+                descending: !self.descending,
+                ..self
+            }
+        } else {
+            StatisticsSort {
+                column,
+                descending: column != StatisticsColumn::Name,
+            }
+        }
+    }
+}
+
+/// an entry of the statistics table, the matchup it counts if it is not the summary of a match
+/// or arena pairing
+pub struct StatisticsRow<'a> {
+    pub name: String,
+    pub matchup: Option<&'a Matchup>,
+    pub statistic: &'a Statistic,
+}
+
+/// ratings are centered on this
+pub const MEAN_RATING: f64 = 1500.0;
+/// a configuration with fewer games than this has a provisional rating
+pub const PROVISIONAL_GAMES: f64 = 10.0;
+// a configuration that won or lost every game would drift away forever
+const MAX_RATING_OFFSET: f64 = 800.0;
+const RATING_ITERATIONS: usize = 1000;
+
+/// the Elo rating of a player configuration over every game it played
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    pub name: String,
+    pub rating: f64,
+    pub games: f64,
+    pub provisional: bool,
+}
+
+/// who played one side of a game
+///
+/// the derived order is the canonical one, a matchup lists its participants in it whatever
+/// their colors
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Participant {
+    Human,
+    Ai {
+        ai_type: AiType,
+        recursion_depth: usize,
+    },
+    /// a configuration only known by its name, such as one of an entry that could not be
+    /// parsed when the statistics were migrated
+    Named(String),
+}
+
+impl Participant {
+    pub fn ai(ai_type: AiType, recursion_depth: usize) -> Self {
+        Participant::Ai {
+            ai_type,
+            // the random AI plays the same at every depth
+            recursion_depth: match ai_type {
+                AiType::Random => 1,
+                _ => recursion_depth,
+            },
+        }
+    }
+
+    /// the participant shown as `name`, the inverse of its `Display`
+    ///
+    /// ```
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::statistics::Participant;
+    ///
+    /// assert_eq!(Participant::parse("Minimax lvl 10"), Participant::ai(AiType::Minimax, 10));
+    /// assert_eq!(Participant::parse("Human"), Participant::Human);
+    /// assert_eq!(Participant::parse("Alice"), Participant::Named("Alice".to_string()));
+    /// ```
+    pub fn parse(name: &str) -> Self {
+        if name == "Human" {
+            return Participant::Human;
+        }
+        let recursion_depth = match name.split_once(" lvl ") {
+            Some((_, depth)) => match depth.parse() {
+                Ok(depth) => depth,
+                Err(_) => return Participant::Named(name.to_string()),
+            },
+            None => 1,
+        };
+        (0..)
+            .map_while(|index| AiType::try_from(index).ok())
+            .map(|ai_type| Participant::ai(ai_type, recursion_depth))
+            .find(|participant| participant.to_string() == name)
+            .unwrap_or_else(|| Participant::Named(name.to_string()))
+    }
+}
+
+impl Display for Participant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Participant::Human => write!(f, "Human"),
+            Participant::Ai {
+                ai_type,
+                recursion_depth,
+            } => write!(f, "{}", ai_type.describe(*recursion_depth)),
+            Participant::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// the two participants of a pairing, in canonical order, and the rule variant they played
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Matchup {
+    pub participants: [Participant; 2],
+    /// games where white moves first are kept apart
+    pub white_first: bool,
+}
+
+impl Matchup {
+    /// the matchup of the participants in either order, and whether they were swapped into the
+    /// canonical one
+    pub fn new(participants: [Participant; 2], white_first: bool) -> (Self, bool) {
+        let [first, second] = participants;
+        let swapped = first > second;
+        let participants = if swapped {
+            [second, first]
+        } else {
+            [first, second]
+        };
+        (
+            Matchup {
+                participants,
+                white_first,
+            },
+            swapped,
         )
     }
 }
 
-#[derive(Default)]
+impl Display for Matchup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} vs {}", self.participants[0], self.participants[1])?;
+        if self.white_first {
+            write!(f, " (White first)")?;
+        }
+        Ok(())
+    }
+}
+
+/// the games of a matchup, from the perspective of its first participant
+#[derive(Serialize, Deserialize)]
+pub struct MatchupEntry {
+    pub matchup: Matchup,
+    pub statistic: Statistic,
+}
+
+/// a `Statistic` per matchup of single games, and per match or arena pairing under its name
+///
+/// ```
+/// use fia_othello::ai::AiType;
+/// use fia_othello::board::Player;
+/// use fia_othello::referee::Outcome;
+/// use fia_othello::referee::WinReason;
+/// use fia_othello::statistics::GameResult;
+/// use fia_othello::statistics::Participant;
+/// use fia_othello::statistics::Statistics;
+///
+/// let mut statistics = Statistics::default();
+/// let result = GameResult {
+///     outcome: Outcome::Won(Player::Black, WinReason::Disks),
+///     disc_counts: [40, 24],
+///     plies: 60,
+/// };
+/// let random = Participant::ai(AiType::Random, 1);
+/// statistics.add_game([Participant::Human, random.clone()], Player::Black, &result);
+/// statistics.add_game([random, Participant::Human], Player::Black, &result);
+///
+/// let entry = &statistics.matchups[0];
+/// assert_eq!(statistics.matchups.len(), 1);
+/// assert_eq!(entry.matchup.to_string(), "Human vs Random");
+/// assert_eq!(
+///     entry.statistic.to_string(),
+///     "50.0%, 0.0%, 50.0%, (2), +0.0 discs on average, 60.0 plies on average"
+/// );
+/// ```
+#[derive(Default, Serialize, Deserialize)]
 pub struct Statistics {
-    pub data: HashMap<String, Statistic>,
+    // empty in files from before the matchups were kept
+    #[serde(default)]
+    pub matchups: Vec<MatchupEntry>,
+    pub data: BTreeMap<String, Statistic>,
 }
 
 impl Statistics {
-    pub fn add_datum(&mut self, name: String, player: Player, outcome: &Outcome) {
-        let statistic = self.data.entry(name).or_default();
-        let (win_value, tie_value, lose_value) = match *outcome {
-            Outcome::Won(winning_player) => {
-                if player == winning_player {
-                    (1.0, 0.0, 0.0)
+    /// counts a game between the participants, black first, that started with `start_player`
+    pub fn add_game(
+        &mut self,
+        players: [Participant; 2],
+        start_player: Player,
+        result: &GameResult,
+    ) {
+        let (matchup, swapped) = Matchup::new(players, start_player == Player::White);
+        // the color of the matchup's first participant
+        let first_player = if swapped {
+            Player::White
+        } else {
+            Player::Black
+        };
+        self.entry(matchup).add(first_player, result);
+    }
+
+    /// adds the time each participant, black first, took for its moves in a game that started
+    /// with `start_player`
+    pub fn add_thinking_times(
+        &mut self,
+        players: [Participant; 2],
+        start_player: Player,
+        mut times: [ThinkingTime; 2],
+    ) {
+        let (matchup, swapped) = Matchup::new(players, start_player == Player::White);
+        if swapped {
+            times.swap(0, 1);
+        }
+        let statistic = self.entry(matchup);
+        for (time, added) in statistic.thinking_times.iter_mut().zip(times) {
+            time.merge(added);
+        }
+    }
+
+    /// the time every participant that had its moves timed took for them over all matchups, in
+    /// canonical order
+    pub fn thinking_times(&self) -> Vec<(Participant, ThinkingTime)> {
+        let mut times: BTreeMap<&Participant, ThinkingTime> = BTreeMap::new();
+        for entry in self.matchups.iter() {
+            for (participant, time) in entry
+                .matchup
+                .participants
+                .iter()
+                .zip(entry.statistic.thinking_times)
+            {
+                if time.moves > 0 {
+                    times.entry(participant).or_default().merge(time);
+                }
+            }
+        }
+        times
+            .into_iter()
+            .map(|(participant, time)| (participant.clone(), time))
+            .collect()
+    }
+
+    fn entry(&mut self, matchup: Matchup) -> &mut Statistic {
+        let index = match self
+            .matchups
+            .iter()
+            .position(|entry| entry.matchup == matchup)
+        {
+            Some(index) => index,
+            None => {
+                self.matchups.push(MatchupEntry {
+                    matchup,
+                    statistic: Statistic::default(),
+                });
+                self.matchups.len() - 1
+            }
+        };
+        &mut self.matchups[index].statistic
+    }
+
+    /// moves the "A vs B" entries of single games of files from before the matchups were kept
+    /// into matchups
+    ///
+    /// such an entry is from the perspective of the name sorting first as text, which is not
+    /// always the participant sorting first, "Minimax lvl 10" coming before "Minimax lvl 2"
+    pub fn migrate_entries(&mut self) {
+        let names: Vec<String> = self
+            .data
+            .keys()
+            .filter(|name| !name.starts_with("Match ") && !name.starts_with("Arena "))
+            .filter(|name| name.contains(" vs "))
+            .cloned()
+            .collect();
+        for name in names {
+            let (players, white_first) = match name.strip_suffix(" (White first)") {
+                Some(players) => (players, true),
+                None => (name.as_str(), false),
+            };
+            let Some((first, second)) = players.split_once(" vs ") else {
+                continue;
+            };
+            let (matchup, swapped) = Matchup::new(
+                [Participant::parse(first), Participant::parse(second)],
+                white_first,
+            );
+            let Some(statistic) = self.data.remove(&name) else {
+                continue;
+            };
+            let statistic = if swapped {
+                statistic.mirrored()
+            } else {
+                statistic
+            };
+            self.entry(matchup).merge(statistic);
+        }
+    }
+
+    /// turns the average disc differentials of files from before the sums were kept into sums
+    pub fn migrate_averages(&mut self) {
+        let statistics = self
+            .matchups
+            .iter_mut()
+            .map(|entry| &mut entry.statistic)
+            .chain(self.data.values_mut());
+        for statistic in statistics {
+            if let Some(average) = statistic.average_disc_differential.take() {
+                statistic.disc_differential_sum = average * statistic.count;
+                statistic.disc_differential_count = statistic.count;
+            }
+        }
+    }
+
+    /// a single entry summarizing a whole match, from the first configuration's perspective,
+    /// replacing the one of the same name so that a running match can be updated after every
+    /// game
+    pub fn add_match(&mut self, name: String, tally: &MatchTally) {
+        let count = tally.games_played as f64;
+        if count == 0.0 {
+            return;
+        }
+
+        self.data.insert(
+            name,
+            Statistic {
+                win_ratio: tally.first_wins as f64 / count,
+                tie_ratio: tally.draws as f64 / count,
+                lose_ratio: tally.second_wins as f64 / count,
+                count,
+                on_time_count: tally.on_time as f64,
+                // only humans resign, and matches are played by the AI
+                resignation_count: 0.0,
+                plies_sum: tally.plies as f64,
+                plies_count: count,
+                disc_differential_sum: tally.disc_differential as f64,
+                disc_differential_count: count,
+                average_disc_differential: None,
+                as_black: tally.first_by_color[Player::Black as usize],
+                as_white: tally.first_by_color[Player::White as usize],
+                // only the moves of the games on the board are timed, those of a match in its
+                // matchups
+                thinking_times: [ThinkingTime::default(); 2],
+                partial: !tally.is_finished(),
+                // the tally does not keep the order of its games
+                form: Form::default(),
+            },
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matchups.is_empty() && self.data.is_empty()
+    }
+
+    /// every entry under the name it is shown with, the matchups first
+    pub fn entries(&self) -> Vec<(String, &Statistic)> {
+        let matchups = self
+            .matchups
+            .iter()
+            .map(|entry| (entry.matchup.to_string(), &entry.statistic));
+        let summaries = self
+            .data
+            .iter()
+            .map(|(name, statistic)| (name.clone(), statistic));
+        matchups.chain(summaries).collect()
+    }
+
+    /// the entries whose name contains `filter`, whatever its case, in the order of `sort`;
+    /// entries that compare equal are ordered by name, so that the rows stay in place as new
+    /// results come in
+    pub fn rows(&self, sort: StatisticsSort, filter: &str) -> Vec<StatisticsRow<'_>> {
+        let filter = filter.trim().to_lowercase();
+        let matchups = self.matchups.iter().map(|entry| StatisticsRow {
+            name: entry.matchup.to_string(),
+            matchup: Some(&entry.matchup),
+            statistic: &entry.statistic,
+        });
+        let summaries = self.data.iter().map(|(name, statistic)| StatisticsRow {
+            name: name.clone(),
+            matchup: None,
+            statistic,
+        });
+        let mut rows: Vec<StatisticsRow> = matchups
+            .chain(summaries)
+            .filter(|row| row.name.to_lowercase().contains(&filter))
+            .collect();
+        rows.sort_by(|a, b| {
+            let order = sort.column.compare(a, b);
+            if sort.descending {
+                order.reverse()
+            } else {
+                order
+            }
+            .then_with(|| a.name.cmp(&b.name))
+        });
+        rows
+    }
+
+    /// a Markdown table of every entry, most played first, under `heading` and a line with the
+    /// crate version and `date`
+    pub fn to_markdown(&self, heading: &str, date: &str) -> String {
+        let mut markdown = format!(
+            "## {heading}\n\nFIA-Othello {}, {date}\n\n",
+            env!("CARGO_PKG_VERSION")
+        );
+        markdown.push_str(
+            "| Matchup | Games | W/T/L | Score [95% interval] | Avg disc diff | Form |\n\
+             | --- | ---: | ---: | ---: | ---: | --- |\n",
+        );
+        let mut entries = self.entries();
+        entries.sort_by(|(a_name, a), (b_name, b)| {
+            b.count.total_cmp(&a.count).then(a_name.cmp(b_name))
+        });
+        for (name, statistic) in entries {
+            let interval = wilson_interval(statistic.score() * statistic.count, statistic.count)
+                .map_or(String::new(), |(low, high)| {
+                    format!(" [{:.0}%, {:.0}%]", low * 100.0, high * 100.0)
+                });
+            let differential = statistic
+                .average_disc_differential()
+                .map_or("–".to_string(), |differential| {
+                    format!("{differential:+.1}")
+                });
+            let form = statistic
+                .form()
+                .map_or("–".to_string(), |form| form.to_string());
+            markdown.push_str(&format!(
+                "| {} | {:.0} | {:.0}/{:.0}/{:.0} | {:.0}%{interval} | {differential} | {form} |\n",
+                if statistic.partial {
+                    format!("{} (partial)", name.replace('|', "\\|"))
                 } else {
-                    (0.0, 0.0, 1.0)
+                    name.replace('|', "\\|")
+                },
+                statistic.count,
+                statistic.win_ratio * statistic.count,
+                statistic.tie_ratio * statistic.count,
+                statistic.lose_ratio * statistic.count,
+                statistic.score() * 100.0,
+            ));
+        }
+        markdown.push_str("\nThe score counts a tie as half a win.\n");
+        markdown
+    }
+
+    /// every participant of the matchups, in canonical order
+    pub fn participants(&self) -> Vec<Participant> {
+        let mut participants: Vec<Participant> = self
+            .matchups
+            .iter()
+            .flat_map(|entry| entry.matchup.participants.iter().cloned())
+            .collect();
+        participants.sort();
+        participants.dedup();
+        participants
+    }
+
+    /// forgets the games of the matchup
+    pub fn remove_matchup(&mut self, matchup: &Matchup) {
+        self.matchups.retain(|entry| entry.matchup != *matchup);
+    }
+
+    /// counts the games of `from` as games of `to`, merging the matchups that become the same
+    ///
+    /// the matchups keep their order, a merged one taking the place of the first of its parts
+    pub fn rename(&mut self, from: &Participant, to: &Participant) {
+        for entry in std::mem::take(&mut self.matchups) {
+            let participants = entry.matchup.participants.map(|participant| {
+                if participant == *from {
+                    to.clone()
+                } else {
+                    participant
+                }
+            });
+            let (matchup, swapped) = Matchup::new(participants, entry.matchup.white_first);
+            let statistic = if swapped {
+                entry.statistic.mirrored()
+            } else {
+                entry.statistic
+            };
+            self.entry(matchup).merge(statistic);
+        }
+    }
+
+    /// the maximum likelihood Elo ratings of every participant of the matchups and arenas, best
+    /// first, a tie counting as half a point for each
+    ///
+    /// ```
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Outcome;
+    /// use fia_othello::referee::WinReason;
+    /// use fia_othello::statistics::GameResult;
+    /// use fia_othello::statistics::Participant;
+    /// use fia_othello::statistics::Statistics;
+    ///
+    /// let mut statistics = Statistics::default();
+    /// let players = [Participant::ai(AiType::AlphaBeta, 3), Participant::ai(AiType::Random, 1)];
+    /// for (winner, disc_counts) in [(Player::Black, [33, 31]), (Player::White, [31, 33])] {
+    ///     let outcome = Outcome::Won(winner, WinReason::Disks);
+    ///     let result = GameResult { outcome, disc_counts, plies: 60 };
+    ///     statistics.add_game(players.clone(), Player::Black, &result);
+    /// }
+    ///
+    /// let ratings = statistics.ratings();
+    /// assert_eq!(ratings.len(), 2);
+    /// assert!(ratings.iter().all(|rating| rating.rating == 1500.0 && rating.provisional));
+    /// ```
+    pub fn ratings(&self) -> Vec<Rating> {
+        let mut participants: Vec<Participant> = Vec::new();
+        // (first, second, games, points of the first)
+        let mut matchups = Vec::new();
+        for (players, statistic) in self.pairings() {
+            let [first, second] = players.map(|participant| {
+                participants
+                    .iter()
+                    .position(|other| *other == participant)
+                    .unwrap_or_else(|| {
+                        participants.push(participant);
+                        participants.len() - 1
+                    })
+            });
+            let points = statistic.count * (statistic.win_ratio + statistic.tie_ratio / 2.0);
+            matchups.push((first, second, statistic.count, points));
+        }
+
+        let mut ratings = vec![MEAN_RATING; participants.len()];
+        for _ in 0..RATING_ITERATIONS {
+            let mut largest_step: f64 = 0.0;
+            for player in 0..participants.len() {
+                // a Newton step towards the rating whose expected score is the actual one
+                let (mut surplus, mut slope) = (0.0, 0.0);
+                for &(first, second, games, points) in matchups.iter() {
+                    let (opponent, points) = if first == player {
+                        (second, points)
+                    } else if second == player {
+                        (first, games - points)
+                    } else {
+                        continue;
+                    };
+                    let expected = expected_score(ratings[player], ratings[opponent]);
+                    surplus += points - games * expected;
+                    slope += games * expected * (1.0 - expected) * 10f64.ln() / 400.0;
+                }
+                if slope > 0.0 {
+                    let step = surplus / slope;
+                    ratings[player] = (ratings[player] + step).clamp(
+                        MEAN_RATING - MAX_RATING_OFFSET,
+                        MEAN_RATING + MAX_RATING_OFFSET,
+                    );
+                    largest_step = largest_step.max(step.abs());
                 }
             }
-            Outcome::Tie => (0.0, 1.0, 0.0),
+
+            let offset = MEAN_RATING - ratings.iter().sum::<f64>() / participants.len() as f64;
+            for rating in ratings.iter_mut() {
+                *rating += offset;
+            }
+            if largest_step < 1e-9 {
+                break;
+            }
+        }
+
+        let mut result: Vec<Rating> = participants
+            .iter()
+            .zip(ratings)
+            .enumerate()
+            .map(|(player, (participant, rating))| {
+                let games = matchups
+                    .iter()
+                    .filter(|(first, second, ..)| *first == player || *second == player)
+                    .map(|(_, _, games, _)| games)
+                    .sum();
+                Rating {
+                    name: participant.to_string(),
+                    rating,
+                    games,
+                    provisional: games < PROVISIONAL_GAMES,
+                }
+            })
+            .collect();
+        result.sort_by(|a, b| b.rating.total_cmp(&a.rating).then(a.name.cmp(&b.name)));
+        result
+    }
+
+    /// the results of every pair of participants of the matchups and arena pairings, the rule
+    /// variants together
+    ///
+    /// ```
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Outcome;
+    /// use fia_othello::referee::WinReason;
+    /// use fia_othello::statistics::GameResult;
+    /// use fia_othello::statistics::Participant;
+    /// use fia_othello::statistics::Statistics;
+    ///
+    /// let mut statistics = Statistics::default();
+    /// let outcome = Outcome::Won(Player::Black, WinReason::Disks);
+    /// let result = GameResult { outcome, disc_counts: [40, 24], plies: 60 };
+    /// let players = ["A", "B"].map(|name| Participant::Named(name.to_string()));
+    /// statistics.add_game(players, Player::Black, &result);
+    ///
+    /// let head_to_head = statistics.head_to_head();
+    /// assert_eq!(head_to_head.cells[0][1].unwrap().to_string(), "+1 =0 −0");
+    /// assert_eq!(head_to_head.cells[1][0].unwrap().to_string(), "+0 =0 −1");
+    /// assert_eq!(head_to_head.cells[0][0], None);
+    /// ```
+    pub fn head_to_head(&self) -> HeadToHead {
+        let mut participants: Vec<Participant> =
+            self.pairings().flat_map(|(players, _)| players).collect();
+        participants.sort();
+        participants.dedup();
+        let index_of: HashMap<&Participant, usize> = participants
+            .iter()
+            .enumerate()
+            .map(|(index, participant)| (participant, index))
+            .collect();
+
+        let count = participants.len();
+        let mut cells = vec![vec![None; count]; count];
+        for (players, statistic) in self.pairings() {
+            let [row, column] = [&players[0], &players[1]].map(|participant| index_of[participant]);
+            if row == column {
+                continue;
+            }
+            // the ratios are of whole games
+            let games = |ratio: f64| (ratio * statistic.count).round() as usize;
+            let cell = HeadToHeadCell {
+                wins: games(statistic.win_ratio),
+                ties: games(statistic.tie_ratio),
+                losses: games(statistic.lose_ratio),
+            };
+            for (row, column, cell) in [(row, column, cell), (column, row, cell.mirrored())] {
+                let total: &mut HeadToHeadCell = cells[row][column].get_or_insert_default();
+                total.wins += cell.wins;
+                total.ties += cell.ties;
+                total.losses += cell.losses;
+            }
+        }
+
+        HeadToHead {
+            participants,
+            cells,
+        }
+    }
+
+    // the participants of the matchups and of the arena pairings, with their games from the
+    // first participant's perspective
+    fn pairings(&self) -> impl Iterator<Item = ([Participant; 2], &Statistic)> {
+        let games = self
+            .matchups
+            .iter()
+            .map(|entry| (entry.matchup.participants.clone(), &entry.statistic));
+        let arenas = self
+            .data
+            .iter()
+            .filter_map(|(name, statistic)| Some((arena_pairing(name)?, statistic)));
+        games.chain(arenas)
+    }
+
+    /// the win ratio of the deeper search over the shallower one, for every pair of depths of
+    /// `ai_type` that played each other, by depths
+    ///
+    /// ```
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Outcome;
+    /// use fia_othello::referee::WinReason;
+    /// use fia_othello::statistics::GameResult;
+    /// use fia_othello::statistics::Participant;
+    /// use fia_othello::statistics::Statistics;
+    ///
+    /// let mut statistics = Statistics::default();
+    /// let players = [Participant::ai(AiType::Minimax, 2), Participant::ai(AiType::Minimax, 5)];
+    /// let outcome = Outcome::Won(Player::White, WinReason::Disks);
+    /// let result = GameResult { outcome, disc_counts: [20, 44], plies: 60 };
+    /// statistics.add_game(players, Player::Black, &result);
+    ///
+    /// let points = statistics.depth_points(AiType::Minimax);
+    /// assert_eq!(points[0].depths, [5, 2]);
+    /// assert_eq!(points[0].depth_difference(), 3);
+    /// assert_eq!(points[0].win_ratio, 1.0);
+    /// ```
+    pub fn depth_points(&self, ai_type: AiType) -> Vec<DepthPoint> {
+        let mut points: Vec<DepthPoint> = Vec::new();
+        for (players, statistic) in self.pairings() {
+            let [
+                Participant::Ai {
+                    ai_type: first_type,
+                    recursion_depth: first_depth,
+                },
+                Participant::Ai {
+                    ai_type: second_type,
+                    recursion_depth: second_depth,
+                },
+            ] = players
+            else {
+                continue;
+            };
+            if first_type != ai_type || second_type != ai_type || statistic.count == 0.0 {
+                continue;
+            }
+
+            let (depths, wins) = if first_depth >= second_depth {
+                (
+                    [first_depth, second_depth],
+                    statistic.win_ratio * statistic.count,
+                )
+            } else {
+                (
+                    [second_depth, first_depth],
+                    statistic.lose_ratio * statistic.count,
+                )
+            };
+            match points.iter_mut().find(|point| point.depths == depths) {
+                Some(point) => {
+                    let games = point.games + statistic.count;
+                    point.win_ratio = (point.win_ratio * point.games + wins) / games;
+                    point.games = games;
+                }
+                None => points.push(DepthPoint {
+                    depths,
+                    win_ratio: wins / statistic.count,
+                    games: statistic.count,
+                }),
+            }
+        }
+        points.sort_by_key(|point| point.depths);
+        points
+    }
+
+    /// the depth points of `ai_type` pooled by the difference of their depths, smallest first,
+    /// games of a depth against itself left out
+    ///
+    /// ```
+    /// use fia_othello::ai::AiType;
+    /// use fia_othello::board::Player;
+    /// use fia_othello::referee::Outcome;
+    /// use fia_othello::referee::WinReason;
+    /// use fia_othello::statistics::GameResult;
+    /// use fia_othello::statistics::Participant;
+    /// use fia_othello::statistics::Statistics;
+    ///
+    /// let mut statistics = Statistics::default();
+    /// for (shallower, deeper, winner) in [(1, 2, Player::White), (3, 4, Player::Black)] {
+    ///     let players = [
+    ///         Participant::ai(AiType::Minimax, shallower),
+    ///         Participant::ai(AiType::Minimax, deeper),
+    ///     ];
+    ///     let outcome = Outcome::Won(winner, WinReason::Disks);
+    ///     let result = GameResult { outcome, disc_counts: [32, 32], plies: 60 };
+    ///     statistics.add_game(players, Player::Black, &result);
+    /// }
+    ///
+    /// let gaps = statistics.depth_gaps(AiType::Minimax);
+    /// assert_eq!(gaps.len(), 1);
+    /// assert_eq!(gaps[0].depth_difference, 1);
+    /// assert_eq!(gaps[0].win_ratio, 0.5);
+    /// assert_eq!(gaps[0].depths, [[2, 1], [4, 3]]);
+    /// ```
+    pub fn depth_gaps(&self, ai_type: AiType) -> Vec<DepthGap> {
+        let mut gaps: Vec<DepthGap> = Vec::new();
+        for point in self.depth_points(ai_type) {
+            let depth_difference = point.depth_difference();
+            if depth_difference == 0 {
+                continue;
+            }
+            let wins = point.win_ratio * point.games;
+            match gaps
+                .iter_mut()
+                .find(|gap| gap.depth_difference == depth_difference)
+            {
+                Some(gap) => {
+                    let games = gap.games + point.games;
+                    gap.win_ratio = (gap.win_ratio * gap.games + wins) / games;
+                    gap.games = games;
+                    gap.depths.push(point.depths);
+                }
+                None => gaps.push(DepthGap {
+                    depth_difference,
+                    win_ratio: point.win_ratio,
+                    games: point.games,
+                    depths: vec![point.depths],
+                }),
+            }
+        }
+        gaps.sort_by_key(|gap| gap.depth_difference);
+        gaps
+    }
+}
+
+/// the games of one participant against another
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeadToHeadCell {
+    pub wins: usize,
+    pub ties: usize,
+    pub losses: usize,
+}
+
+impl HeadToHeadCell {
+    pub fn games(&self) -> usize {
+        self.wins + self.ties + self.losses
+    }
+
+    /// the share of the points taken, a tie counting as half a win
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.ties as f64 / 2.0) / self.games().max(1) as f64
+    }
+
+    fn mirrored(self) -> Self {
+        HeadToHeadCell {
+            wins: self.losses,
+            ties: self.ties,
+            losses: self.wins,
+        }
+    }
+}
+
+impl Display for HeadToHeadCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "+{} ={} −{}", self.wins, self.ties, self.losses)
+    }
+}
+
+/// every participant against every other one
+#[derive(Default)]
+pub struct HeadToHead {
+    /// in canonical order
+    pub participants: Vec<Participant>,
+    /// by row and column participant, from the row's perspective, None if the two never played
+    /// or on the diagonal
+    pub cells: Vec<Vec<Option<HeadToHeadCell>>>,
+}
+
+/// the games between two depths of the same AI type
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthPoint {
+    /// the deeper first
+    pub depths: [usize; 2],
+    /// of the deeper search
+    pub win_ratio: f64,
+    pub games: f64,
+}
+
+impl DepthPoint {
+    pub fn depth_difference(&self) -> usize {
+        self.depths[0] - self.depths[1]
+    }
+}
+
+/// the games that opened with a line, counted for the side that played its last move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpeningLine {
+    pub player: Player,
+    pub record: ColorRecord,
+}
+
+/// the first moves of games from the start position and how the games ended
+///
+/// openings that only differ by a symmetry of the start position are counted together, in the
+/// orientation of `canonical_opening`
+///
+/// ```
+/// use fia_othello::board::Player;
+/// use fia_othello::notation;
+/// use fia_othello::statistics::OpeningTree;
+///
+/// let mut openings = OpeningTree::new(2);
+/// let f5_d6 = notation::parse_transcript("F5D6").unwrap();
+/// let d3_c3 = notation::parse_transcript("D3C3").unwrap();
+/// assert!(openings.add_game(Player::Black, &f5_d6, Some(Player::Black)));
+/// assert!(openings.add_game(Player::Black, &d3_c3, Some(Player::White)));
+///
+/// let first_moves = openings.continuations(&[]);
+/// assert_eq!(first_moves.len(), 1);
+/// assert_eq!(first_moves[0].1.record.to_string(), "1-0-1");
+/// assert_eq!(openings.continuations(first_moves[0].0).len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct OpeningTree {
+    /// the length of the longest lines kept
+    pub plies: usize,
+    /// by their moves, so every line comes right after the one it continues
+    pub lines: BTreeMap<Vec<Move>, OpeningLine>,
+}
+
+impl OpeningTree {
+    pub fn new(plies: usize) -> Self {
+        OpeningTree {
+            plies,
+            lines: BTreeMap::new(),
+        }
+    }
+
+    /// counts the opening of a game started by `start_player` from the standard start position,
+    /// returns false without counting it if its moves are not legal there
+    pub fn add_game(
+        &mut self,
+        start_player: Player,
+        moves: &[Move],
+        winner: Option<Player>,
+    ) -> bool {
+        let moves = &moves[..moves.len().min(self.plies)];
+        let Ok(replay) = Referee::default().replay(&Board::default(), start_player, moves) else {
+            return false;
         };
+        let outcome = match winner {
+            Some(winner) => Outcome::Won(winner, WinReason::Disks),
+            None => Outcome::Tie,
+        };
+        // passes are next to impossible this early, but the side to move follows them
+        let players = replay.history.plies.iter().filter_map(|ply| match *ply {
+            Ply::Move { player, .. } => Some(player),
+            Ply::Pass { .. } => None,
+        });
+        let moves = canonical_opening(moves);
+        for (length, player) in (1..=moves.len()).zip(players) {
+            let line = self
+                .lines
+                .entry(moves[..length].to_vec())
+                .or_insert(OpeningLine {
+                    player,
+                    record: ColorRecord::default(),
+                });
+            line.record.record(player, &outcome);
+        }
+        true
+    }
 
-        let mut ratios = [
-            (&mut statistic.win_ratio, win_value),
-            (&mut statistic.tie_ratio, tie_value),
-            (&mut statistic.lose_ratio, lose_value),
+    /// the lines one move longer than `moves`, most played first
+    pub fn continuations(&self, moves: &[Move]) -> Vec<(&Vec<Move>, &OpeningLine)> {
+        let mut continuations: Vec<_> = self
+            .lines
+            .range(moves.to_vec()..)
+            .take_while(|(line, _)| line.starts_with(moves))
+            .filter(|(line, _)| line.len() == moves.len() + 1)
+            .collect();
+        continuations.sort_by_key(|(_, line)| std::cmp::Reverse(line.record.games()));
+        continuations
+    }
+}
+
+/// the moves in the orientation of the board whose transcript sorts last, which is the one that
+/// opens with F5, or F4 when white moves first
+///
+/// ```
+/// use fia_othello::notation;
+/// use fia_othello::statistics::canonical_opening;
+///
+/// let e6_f4 = notation::parse_transcript("E6F4").unwrap();
+/// let f5_d6 = notation::parse_transcript("F5D6").unwrap();
+/// assert_eq!(canonical_opening(&e6_f4), f5_d6);
+/// ```
+pub fn canonical_opening(moves: &[Move]) -> Vec<Move> {
+    Symmetry::ALL
+        .iter()
+        .map(|symmetry| {
+            moves
+                .iter()
+                .map(|&next_move| symmetry.apply(next_move))
+                .collect::<Vec<_>>()
+        })
+        // columns are lettered before the rows are numbered
+        .max_by_key(|line| {
+            line.iter()
+                .map(|&(row, col)| (col, row))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
+/// the games of every pair of depths of the same AI type that are the same distance apart
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthGap {
+    pub depth_difference: usize,
+    /// of the deeper search
+    pub win_ratio: f64,
+    pub games: f64,
+    /// the pairs of depths pooled, the deeper first
+    pub depths: Vec<[usize; 2]>,
+}
+
+impl DepthGap {
+    /// the gaps are sorted by their difference, so the first one found is the smallest
+    ///
+    /// ```
+    /// use fia_othello::statistics::DepthGap;
+    ///
+    /// let gap = |depth_difference, win_ratio, games| DepthGap {
+    ///     depth_difference,
+    ///     win_ratio,
+    ///     games,
+    ///     depths: Vec::new(),
+    /// };
+    /// // the first gap reaching 75% has too few games to tell
+    /// let gaps = [gap(1, 0.6, 40.0), gap(2, 0.8, 4.0), gap(3, 0.9, 30.0)];
+    /// let smallest = DepthGap::smallest_reaching(&gaps, 0.75, 10.0);
+    /// assert_eq!(smallest.map(|gap| gap.depth_difference), Some(3));
+    /// ```
+    pub fn smallest_reaching(
+        gaps: &[DepthGap],
+        win_ratio: f64,
+        min_games: f64,
+    ) -> Option<&DepthGap> {
+        gaps.iter()
+            .find(|gap| gap.games >= min_games && gap.win_ratio >= win_ratio)
+    }
+}
+
+// the two contestants of an entry such as "Arena 3: A vs B"
+// the games of a match are in the matchups of single games already, so its summary is left out
+fn arena_pairing(entry: &str) -> Option<[Participant; 2]> {
+    let (prefix, players) = entry.split_once(": ")?;
+    if !prefix.starts_with("Arena ") {
+        return None;
+    }
+    let (first, second) = players.split_once(" vs ")?;
+    Some([Participant::parse(first), Participant::parse(second)])
+}
+
+// the normal quantile of a two-sided 95% interval
+const Z_95: f64 = 1.959963984540054;
+
+/// the 95% Wilson score interval of the true share of successes after `successes` out of
+/// `trials`, None without trials
+///
+/// unlike the plain share it stays away from 0 and 1 for few trials: 3 out of 3 is somewhere
+/// between 44% and 100%
+///
+/// ```
+/// use fia_othello::statistics::wilson_interval;
+///
+/// let (low, high) = wilson_interval(3.0, 3.0).unwrap();
+/// assert!((low - 0.4385).abs() < 1e-4 && high == 1.0);
+/// assert_eq!(wilson_interval(0.0, 0.0), None);
+/// ```
+pub fn wilson_interval(successes: f64, trials: f64) -> Option<(f64, f64)> {
+    if trials <= 0.0 {
+        return None;
+    }
+    let share = successes / trials;
+    let z_squared = Z_95 * Z_95;
+    let denominator = 1.0 + z_squared / trials;
+    let center = (share + z_squared / (2.0 * trials)) / denominator;
+    let margin = Z_95
+        * (share * (1.0 - share) / trials + z_squared / (4.0 * trials * trials)).sqrt()
+        / denominator;
+    Some((
+        (center - margin).clamp(0.0, 1.0),
+        (center + margin).clamp(0.0, 1.0),
+    ))
+}
+
+// the share of the points the player is expected to take from the opponent
+/// `points` out of `games` as a share with its 95% Wilson interval, e.g. "62% [48%, 74%] over 50
+/// games", None without games
+///
+/// ```
+/// use fia_othello::statistics::score_summary;
+///
+/// assert_eq!(score_summary(31.0, 50.0).unwrap(), "62% [48%, 74%] over 50 games");
+/// assert_eq!(score_summary(0.0, 0.0), None);
+/// ```
+pub fn score_summary(points: f64, games: f64) -> Option<String> {
+    let (low, high) = wilson_interval(points, games)?;
+    Some(format!(
+        "{:.0}% [{:.0}%, {:.0}%] over {games:.0} games",
+        points / games * 100.0,
+        low * 100.0,
+        high * 100.0,
+    ))
+}
+
+fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a game of 60 plies, won by 40 discs to 24
+    fn result(outcome: Outcome) -> GameResult {
+        let disc_counts = match outcome {
+            Outcome::Won(Player::Black, _) => [40, 24],
+            Outcome::Won(Player::White, _) => [24, 40],
+            Outcome::Tie => [32, 32],
+        };
+        GameResult {
+            outcome,
+            disc_counts,
+            plies: 60,
+        }
+    }
+
+    fn named(name: &str) -> Participant {
+        Participant::Named(name.to_string())
+    }
+
+    // games the first participant played as black
+    fn add_games(
+        statistics: &mut Statistics,
+        players: [&str; 2],
+        wins: usize,
+        ties: usize,
+        losses: usize,
+    ) {
+        let outcomes = [
+            (wins, Outcome::Won(Player::Black, WinReason::Disks)),
+            (ties, Outcome::Tie),
+            (losses, Outcome::Won(Player::White, WinReason::Disks)),
+        ];
+        for (count, outcome) in outcomes {
+            for _ in 0..count {
+                statistics.add_game(players.map(named), Player::Black, &result(outcome));
+            }
+        }
+    }
+
+    fn tally(first_wins: usize, draws: usize, second_wins: usize) -> MatchTally {
+        let mut tally = MatchTally::new(first_wins + draws + second_wins);
+        tally.first_wins = first_wins;
+        tally.draws = draws;
+        tally.second_wins = second_wins;
+        tally.games_played = tally.total_games;
+        tally
+    }
+
+    fn rating_of(ratings: &[Rating], name: &str) -> f64 {
+        ratings
+            .iter()
+            .find(|rating| rating.name == name)
+            .unwrap()
+            .rating
+    }
+
+    #[test]
+    fn results_are_kept_by_color() {
+        let mut statistics = Statistics::default();
+        let outcome = Outcome::Won(Player::Black, WinReason::Disks);
+        statistics.add_game([named("A"), named("B")], Player::Black, &result(outcome));
+        statistics.add_game(
+            [named("A"), named("B")],
+            Player::Black,
+            &result(Outcome::Tie),
+        );
+        statistics.add_game([named("B"), named("A")], Player::Black, &result(outcome));
+
+        assert_eq!(statistics.matchups.len(), 1);
+        assert_eq!(
+            statistics.matchups[0]
+                .statistic
+                .color_breakdown()
+                .as_deref(),
+            Some("as Black: 1-1-0, as White: 0-0-1")
+        );
+    }
+
+    #[test]
+    fn match_results_are_kept_by_color() {
+        let mut tally = MatchTally::new(3);
+        // the first configuration has black, white and black again
+        for outcome in [Outcome::Won(Player::Black, WinReason::Disks); 3] {
+            tally.record(&outcome, 40, 24, 60);
+        }
+        let mut statistics = Statistics::default();
+        statistics.add_match("Match 1: A vs B".to_string(), &tally);
+
+        assert_eq!(
+            statistics.data["Match 1: A vs B"]
+                .color_breakdown()
+                .as_deref(),
+            Some("as Black: 2-0-0, as White: 0-0-1")
+        );
+    }
+
+    #[test]
+    fn entries_from_before_the_colors_were_kept_still_load() {
+        let statistics: Statistics = serde_json::from_str(
+            r#"{ "data": { "A vs B": {
+                "win_ratio": 0.5, "tie_ratio": 0.0, "lose_ratio": 0.5, "count": 2.0, "on_time_count": 0.0
+            } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(statistics.data["A vs B"].color_breakdown(), None);
+        assert_eq!(
+            statistics.data["A vs B"].to_string(),
+            "50.0%, 0.0%, 50.0%, (2)"
+        );
+    }
+
+    #[test]
+    fn three_wins_in_four_games_are_worth_191_points() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 3, 0, 1);
+
+        // 0.75 = 1 / (1 + 10^(-d/400)), so d = 400 log10(3)
+        let difference = 400.0 * 3f64.log10();
+        let ratings = statistics.ratings();
+        assert_eq!(ratings[0].name, "A");
+        assert!((ratings[0].rating - (MEAN_RATING + difference / 2.0)).abs() < 1e-6);
+        assert!((ratings[1].rating - (MEAN_RATING - difference / 2.0)).abs() < 1e-6);
+        assert_eq!(ratings[0].games, 4.0);
+        assert!(ratings[0].provisional);
+    }
+
+    #[test]
+    fn ties_count_as_half_points() {
+        let mut statistics = Statistics::default();
+        // 2 wins and 2 ties of 4 games score 0.75 as well
+        statistics.add_match("Arena 1: A vs B".to_string(), &tally(2, 2, 0));
+        statistics.add_match("Match 1: A vs B".to_string(), &tally(0, 0, 9));
+        for _ in 0..10 {
+            statistics.add_game(
+                [named("C"), named("D")],
+                Player::White,
+                &result(Outcome::Tie),
+            );
+        }
+
+        let ratings = statistics.ratings();
+        let difference = 400.0 * 3f64.log10();
+        assert!((rating_of(&ratings, "A") - rating_of(&ratings, "B") - difference).abs() < 1e-6);
+        assert!((rating_of(&ratings, "C") - rating_of(&ratings, "D")).abs() < 1e-6);
+        assert!(
+            !ratings
+                .iter()
+                .find(|rating| rating.name == "C")
+                .unwrap()
+                .provisional
+        );
+    }
+
+    #[test]
+    fn ratings_chain_through_common_opponents() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 3, 0, 1);
+        add_games(&mut statistics, ["B", "C"], 3, 0, 1);
+        // entries without two configurations are no matchups
+        statistics.add_match("Random".to_string(), &tally(1, 0, 0));
+
+        let ratings = statistics.ratings();
+        let difference = 400.0 * 3f64.log10();
+        let names: Vec<&str> = ratings.iter().map(|rating| rating.name.as_str()).collect();
+        assert_eq!(names, ["A", "B", "C"]);
+        assert!((rating_of(&ratings, "A") - (MEAN_RATING + difference)).abs() < 1e-6);
+        assert!((rating_of(&ratings, "B") - MEAN_RATING).abs() < 1e-6);
+        assert!((rating_of(&ratings, "C") - (MEAN_RATING - difference)).abs() < 1e-6);
+        assert_eq!(ratings[1].games, 8.0);
+    }
+
+    #[test]
+    fn a_perfect_score_stays_finite() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 5, 0, 0);
+
+        let ratings = statistics.ratings();
+        assert!(ratings[0].rating.is_finite());
+        assert!(ratings[0].rating - ratings[1].rating <= 2.0 * MAX_RATING_OFFSET);
+    }
+
+    #[test]
+    fn depths_are_ordered_as_numbers() {
+        let deep = Participant::ai(AiType::Minimax, 10);
+        let shallow = Participant::ai(AiType::Minimax, 2);
+        let mut statistics = Statistics::default();
+        let outcome = Outcome::Won(Player::Black, WinReason::Disks);
+        statistics.add_game(
+            [deep.clone(), shallow.clone()],
+            Player::Black,
+            &result(outcome),
+        );
+        statistics.add_game(
+            [shallow.clone(), deep.clone()],
+            Player::Black,
+            &result(outcome),
+        );
+
+        // one entry, from the perspective of the shallower search, whatever its color
+        assert_eq!(statistics.matchups.len(), 1);
+        let entry = &statistics.matchups[0];
+        assert_eq!(entry.matchup.participants, [shallow, deep]);
+        assert_eq!(entry.matchup.to_string(), "Minimax lvl 2 vs Minimax lvl 10");
+        assert_eq!(
+            entry.statistic.color_breakdown().as_deref(),
+            Some("as Black: 1-0-0, as White: 0-0-1")
+        );
+    }
+
+    #[test]
+    fn participants_are_told_apart_by_more_than_their_names() {
+        assert_ne!(named("Minimax lvl 2"), Participant::ai(AiType::Minimax, 2));
+        assert_eq!(
+            Participant::ai(AiType::Random, 5),
+            Participant::ai(AiType::Random, 1)
+        );
+        // a name with " vs " in it is no longer split
+        let mut statistics = Statistics::default();
+        statistics.add_game(
+            [named("Human vs Human"), Participant::Human],
+            Player::White,
+            &result(Outcome::Tie),
+        );
+        assert_eq!(
+            statistics.matchups[0].matchup.participants,
+            [Participant::Human, named("Human vs Human")]
+        );
+        assert!(statistics.matchups[0].matchup.white_first);
+    }
+
+    #[test]
+    fn old_entries_are_migrated_into_matchups() {
+        let mut statistics: Statistics = serde_json::from_str(
+            r#"{ "data": {
+                "Minimax lvl 10 vs Minimax lvl 2": {
+                    "win_ratio": 0.75, "tie_ratio": 0.0, "lose_ratio": 0.25, "count": 4.0,
+                    "on_time_count": 0.0, "as_black": { "wins": 2, "ties": 0, "losses": 0 },
+                    "as_white": { "wins": 1, "ties": 0, "losses": 1 }
+                },
+                "Human vs Random (White first)": {
+                    "win_ratio": 1.0, "tie_ratio": 0.0, "lose_ratio": 0.0, "count": 1.0, "on_time_count": 0.0
+                },
+                "Match 1: Random vs Random": {
+                    "win_ratio": 1.0, "tie_ratio": 0.0, "lose_ratio": 0.0, "count": 1.0, "on_time_count": 0.0
+                }
+            } }"#,
+        )
+        .unwrap();
+        statistics.migrate_entries();
+        // a second game of the same matchup lands in the migrated entry
+        statistics.add_game(
+            [
+                Participant::ai(AiType::Minimax, 2),
+                Participant::ai(AiType::Minimax, 10),
+            ],
+            Player::Black,
+            &result(Outcome::Won(Player::Black, WinReason::Disks)),
+        );
+
+        assert_eq!(statistics.matchups.len(), 2);
+        let keys: Vec<&str> = statistics.data.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["Match 1: Random vs Random"]);
+        let minimax = statistics
+            .matchups
+            .iter()
+            .find(|entry| !entry.matchup.white_first)
+            .unwrap();
+        assert_eq!(
+            minimax.matchup.to_string(),
+            "Minimax lvl 2 vs Minimax lvl 10"
+        );
+        // only the new game has a known length and final discs
+        assert_eq!(
+            minimax.statistic.to_string(),
+            "40.0%, 0.0%, 60.0%, (5), +16.0 discs on average, 60.0 plies on average"
+        );
+        assert_eq!(
+            minimax.statistic.color_breakdown().as_deref(),
+            Some("as Black: 2-0-1, as White: 0-0-2")
+        );
+        let human = statistics
+            .matchups
+            .iter()
+            .find(|entry| entry.matchup.white_first)
+            .unwrap();
+        assert_eq!(
+            human.matchup.participants,
+            [Participant::Human, Participant::ai(AiType::Random, 1)]
+        );
+    }
+
+    #[test]
+    fn lengths_and_differentials_are_averaged() {
+        let mut statistics = Statistics::default();
+        let players = [named("A"), named("B")];
+        let results = [
+            // A has black and wins by 20 after 60 plies
+            GameResult {
+                outcome: Outcome::Won(Player::Black, WinReason::Disks),
+                disc_counts: [42, 22],
+                plies: 60,
+            },
+            // A has white and loses by 18 after 23 plies, a pass included
+            GameResult {
+                outcome: Outcome::Won(Player::Black, WinReason::Disks),
+                disc_counts: [18, 0],
+                plies: 23,
+            },
+            // A has black and ties after 61 plies
+            GameResult {
+                outcome: Outcome::Tie,
+                disc_counts: [32, 32],
+                plies: 61,
+            },
         ];
+        statistics.add_game(players.clone(), Player::Black, &results[0]);
+        statistics.add_game([named("B"), named("A")], Player::Black, &results[1]);
+        statistics.add_game(players, Player::Black, &results[2]);
 
-        for (ratio, value) in ratios.iter_mut() {
-            let new_ratio = **ratio * statistic.count + *value;
-            **ratio = new_ratio / (statistic.count + 1.0);
+        let statistic = &statistics.matchups[0].statistic;
+        assert_eq!(statistic.average_plies(), Some(48.0));
+        assert_eq!(
+            statistic.average_disc_differential(),
+            Some((20.0 - 18.0) / 3.0)
+        );
+        assert_eq!(
+            statistic.to_string(),
+            "33.3%, 33.3%, 33.3%, (3), +0.7 discs on average, 48.0 plies on average"
+        );
+        assert_eq!(Statistic::default().average_plies(), None);
+    }
+
+    #[test]
+    fn average_differentials_of_old_files_become_sums() {
+        let mut statistics: Statistics = serde_json::from_str(
+            r#"{ "data": { "Match 1: A vs B": {
+                "win_ratio": 1.0, "tie_ratio": 0.0, "lose_ratio": 0.0, "count": 4.0,
+                "on_time_count": 0.0, "average_disc_differential": 12.5
+            } } }"#,
+        )
+        .unwrap();
+        statistics.migrate_averages();
+
+        let statistic = &statistics.data["Match 1: A vs B"];
+        assert_eq!(statistic.average_disc_differential(), Some(12.5));
+        assert_eq!(statistic.average_plies(), None);
+        assert!(
+            !serde_json::to_string(statistic)
+                .unwrap()
+                .contains("average_disc_differential")
+        );
+    }
+
+    #[test]
+    fn renaming_into_an_existing_matchup_merges_the_counters() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "C"], 2, 0, 0);
+        add_games(&mut statistics, ["B", "D"], 1, 0, 0);
+        add_games(&mut statistics, ["B", "C"], 0, 1, 1);
+
+        statistics.rename(&named("A"), &named("B"));
+
+        let names: Vec<String> = statistics
+            .matchups
+            .iter()
+            .map(|entry| entry.matchup.to_string())
+            .collect();
+        // the merged matchup stays where the first of its parts was
+        assert_eq!(names, ["B vs C", "B vs D"]);
+        let merged = &statistics.matchups[0].statistic;
+        assert_eq!(
+            merged.to_string(),
+            "50.0%, 25.0%, 25.0%, (4), +4.0 discs on average, 60.0 plies on average"
+        );
+        assert_eq!(
+            merged.color_breakdown().as_deref(),
+            Some("as Black: 2-1-1, as White: 0-0-0")
+        );
+        assert_eq!(
+            statistics.participants(),
+            [named("B"), named("C"), named("D")]
+        );
+    }
+
+    #[test]
+    fn renaming_past_the_opponent_mirrors_the_matchup() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 3, 0, 1);
+
+        statistics.rename(&named("A"), &Participant::parse("Minimax lvl 3"));
+        statistics.rename(&named("B"), &named("Zoe"));
+        statistics.rename(&named("Zoe"), &Participant::Human);
+
+        let entry = &statistics.matchups[0];
+        assert_eq!(entry.matchup.to_string(), "Human vs Minimax lvl 3");
+        assert_eq!(
+            entry.statistic.color_breakdown().as_deref(),
+            Some("as Black: 0-0-0, as White: 1-0-3")
+        );
+
+        statistics.remove_matchup(&entry.matchup.clone());
+        assert!(statistics.is_empty());
+    }
+
+    #[test]
+    fn depth_points_merge_the_matchups_of_the_same_depths() {
+        let mut statistics = Statistics::default();
+        let minimax = |depth| Participant::ai(AiType::Minimax, depth);
+        let outcome = Outcome::Won(Player::Black, WinReason::Disks);
+        // the deeper search wins with black and loses with black, once per variant
+        statistics.add_game([minimax(4), minimax(1)], Player::Black, &result(outcome));
+        statistics.add_game([minimax(1), minimax(4)], Player::White, &result(outcome));
+        // and every game of the arena
+        statistics.add_match(
+            "Arena 1: Minimax lvl 4 vs Minimax lvl 1".to_string(),
+            &tally(2, 0, 0),
+        );
+        // other types and names that only look like a depth are left out
+        statistics.add_game(
+            [minimax(2), Participant::ai(AiType::AlphaBeta, 3)],
+            Player::Black,
+            &result(outcome),
+        );
+        statistics.add_game(
+            [minimax(2), named("Minimax lvl two")],
+            Player::Black,
+            &result(outcome),
+        );
+        statistics.add_match(
+            "Arena 2: Minimax lvl 3 vs Alice".to_string(),
+            &tally(1, 0, 0),
+        );
+
+        let points = statistics.depth_points(AiType::Minimax);
+        assert_eq!(
+            points,
+            [DepthPoint {
+                depths: [4, 1],
+                win_ratio: 0.75,
+                games: 4.0,
+            }]
+        );
+        assert!(statistics.depth_points(AiType::Positional).is_empty());
+    }
+
+    #[test]
+    fn wilson_intervals_match_known_values() {
+        for (successes, trials, expected) in [
+            (0.0, 10.0, (0.0, 0.2775)),
+            (3.0, 3.0, (0.4385, 1.0)),
+            (5.0, 10.0, (0.2366, 0.7634)),
+            (50.0, 100.0, (0.4038, 0.5962)),
+            (31.0, 50.0, (0.4815, 0.7414)),
+        ] {
+            let (low, high) = wilson_interval(successes, trials).unwrap();
+            assert!(
+                (low - expected.0).abs() < 1e-4 && (high - expected.1).abs() < 1e-4,
+                "{successes} of {trials}: [{low}, {high}]"
+            );
+        }
+    }
+
+    #[test]
+    fn ties_count_as_half_a_win_in_the_interval() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 31, 0, 19);
+        add_games(&mut statistics, ["C", "D"], 1, 1, 1);
+
+        assert_eq!(
+            statistics.matchups[0].statistic.score_summary().as_deref(),
+            Some("62% [48%, 74%] over 50 games")
+        );
+        // 1.5 points of 3
+        assert_eq!(
+            statistics.matchups[1].statistic.score_summary().as_deref(),
+            Some("50% [13%, 87%] over 3 games")
+        );
+        assert_eq!(Statistic::default().score_summary(), None);
+    }
+
+    #[test]
+    fn the_head_to_head_matrix_adds_up_both_perspectives() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 2, 1, 0);
+        // the other variant and the other colors land in the same cell
+        for _ in 0..3 {
+            statistics.add_game(
+                [named("B"), named("A")],
+                Player::White,
+                &result(Outcome::Won(Player::Black, WinReason::Disks)),
+            );
+        }
+        add_games(&mut statistics, ["C", "C"], 1, 0, 0);
+        statistics.add_match("Arena 1: B vs C".to_string(), &tally(1, 0, 1));
+
+        let head_to_head = statistics.head_to_head();
+        assert_eq!(
+            head_to_head.participants,
+            [named("A"), named("B"), named("C")]
+        );
+        let cells = &head_to_head.cells;
+        assert_eq!(cells[0][1].unwrap().to_string(), "+2 =1 −3");
+        assert_eq!(cells[1][0].unwrap().to_string(), "+3 =1 −2");
+        assert_eq!(cells[1][2].unwrap().to_string(), "+1 =0 −1");
+        // a missing pairing and playing oneself
+        assert_eq!(cells[0][2], None);
+        assert_eq!(cells[2][2], None);
+        assert_eq!(cells[1][0].unwrap().score(), 3.5 / 6.0);
+    }
+
+    fn opening(transcript: &str) -> Vec<Move> {
+        crate::notation::parse_transcript(transcript).unwrap()
+    }
+
+    #[test]
+    fn mirrored_openings_are_counted_together() {
+        let mut openings = OpeningTree::new(3);
+        // the same line in all four orientations of the board
+        for transcript in ["F5D6C3", "E6F4C3", "D3C5F6", "C4E3F6"] {
+            assert!(openings.add_game(Player::Black, &opening(transcript), Some(Player::Black)));
+        }
+        assert!(openings.add_game(Player::Black, &opening("F5F6E6"), None));
+
+        let f5 = opening("F5");
+        let f5_d6 = opening("F5D6");
+        assert_eq!(openings.lines[&f5].record.to_string(), "4-1-0");
+        assert_eq!(openings.lines[&f5_d6].player, Player::White);
+        assert_eq!(openings.lines[&f5_d6].record.to_string(), "0-0-4");
+        assert_eq!(openings.lines[&opening("F5D6C3")].record.score(), 1.0);
+        assert_eq!(openings.lines.len(), 5);
+
+        let continuations: Vec<&Vec<Move>> = openings
+            .continuations(&f5)
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect();
+        assert_eq!(continuations, [&f5_d6, &opening("F5F6")]);
+    }
+
+    #[test]
+    fn openings_follow_the_start_player_and_reject_illegal_moves() {
+        let mut openings = OpeningTree::new(6);
+        assert!(openings.add_game(Player::White, &opening("E3"), Some(Player::White)));
+        assert!(openings.add_game(Player::White, &opening("C5"), Some(Player::Black)));
+        assert!(!openings.add_game(Player::Black, &opening("A1"), None));
+
+        let f4 = &openings.lines[&opening("F4")];
+        assert_eq!(f4.player, Player::White);
+        assert_eq!(f4.record.to_string(), "1-0-1");
+        assert_eq!(openings.lines.len(), 1);
+    }
+
+    #[test]
+    fn thinking_times_follow_the_participants() {
+        let mut statistics = Statistics::default();
+        let time = |seconds: &[f64]| {
+            let mut time = ThinkingTime::default();
+            for &seconds in seconds {
+                time.add(seconds);
+            }
+            time
+        };
+        // B plays black, so the times are swapped into the matchup's order
+        statistics.add_thinking_times(
+            [named("B"), named("A")],
+            Player::Black,
+            [time(&[2.0, 4.0]), ThinkingTime::default()],
+        );
+        statistics.add_thinking_times(
+            [named("C"), named("B")],
+            Player::Black,
+            [time(&[1.0]), time(&[6.0])],
+        );
+
+        let [a, b] = statistics.matchups[0].statistic.thinking_times();
+        assert_eq!(a.moves, 0);
+        assert_eq!(b.to_string(), "avg 3.0 s/move, max 4.0 s");
+        let names: Vec<String> = statistics
+            .thinking_times()
+            .iter()
+            .map(|(participant, time)| format!("{participant}: {time}"))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "B: avg 4.0 s/move, max 6.0 s",
+                "C: avg 1.0 s/move, max 1.0 s"
+            ]
+        );
+
+        // renaming mirrors the times along with the results
+        statistics.rename(&named("A"), &named("Y"));
+        let [b, y] = statistics.matchups[0].statistic.thinking_times();
+        assert_eq!((b.moves, y.moves), (2, 0));
+    }
+
+    #[test]
+    fn a_loss_ends_a_win_streak_and_a_tie_ends_both() {
+        let mut form = Form::default();
+        assert_eq!(form.streak(), None);
+        for _ in 0..3 {
+            form.record(GameOutcome::Win);
+        }
+        assert_eq!(form.streak(), Some("W3".to_string()));
+
+        form.record(GameOutcome::Loss);
+        assert_eq!(form.streak(), Some("L1".to_string()));
+        form.record(GameOutcome::Win);
+        form.record(GameOutcome::Tie);
+        assert_eq!(form.streak(), Some("T1".to_string()));
+        assert_eq!(form.longest_win_streak, 3);
+        assert_eq!(form.longest_loss_streak, 1);
+    }
+
+    #[test]
+    fn the_recent_record_rolls_over_after_twenty_games() {
+        let mut form = Form::default();
+        for _ in 0..FORM_GAMES {
+            form.record(GameOutcome::Loss);
+        }
+        for _ in 0..5 {
+            form.record(GameOutcome::Win);
+        }
+        assert_eq!(form.recent.len(), FORM_GAMES);
+        assert_eq!(
+            form.recent_record(),
+            ColorRecord {
+                wins: 5,
+                ties: 0,
+                losses: 15
+            }
+        );
+        // the streaks are not bound to the window
+        assert_eq!(form.longest_loss_streak, FORM_GAMES);
+    }
+
+    #[test]
+    fn the_form_of_a_matchup_is_kept_from_either_side_and_saved() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B"], 2, 0, 0);
+        // the same matchup with B as Black, whose win is A's loss
+        statistics.add_game(
+            ["B", "A"].map(named),
+            Player::Black,
+            &result(Outcome::Won(Player::Black, WinReason::Disks)),
+        );
+
+        let json = serde_json::to_string(&statistics).unwrap();
+        let statistics: Statistics = serde_json::from_str(&json).unwrap();
+        let form = statistics.matchups[0].statistic.form().unwrap();
+        assert_eq!(form.to_string(), "L1, longest win streak 2, last 3: 2-0-1");
+        assert_eq!(form.clone().mirrored().streak(), Some("W1".to_string()));
+    }
+
+    #[test]
+    fn the_markdown_report_lists_the_most_played_entries_first() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "B|C"], 2, 1, 1);
+        add_games(&mut statistics, ["A", "D"], 1, 0, 0);
+        statistics.add_match("Arena 1: A vs D".to_string(), &tally(3, 2, 1));
+
+        let expected = format!(
+            "## Othello statistics\n\
+             \n\
+             FIA-Othello {}, 2026-10-16 12:00\n\
+             \n\
+             | Matchup | Games | W/T/L | Score [95% interval] | Avg disc diff | Form |\n\
+             | --- | ---: | ---: | ---: | ---: | --- |\n\
+             | Arena 1: A vs D | 6 | 3/2/1 | 67% [30%, 90%] | +0.0 | – |\n\
+             | A vs B\\|C | 4 | 2/1/1 | 62% [22%, 91%] | +4.0 | L1, longest win streak 2, last 4: 2-1-1 |\n\
+             | A vs D | 1 | 1/0/0 | 100% [21%, 100%] | +16.0 | W1, longest win streak 1, last 1: 1-0-0 |\n\
+             \n\
+             The score counts a tie as half a win.\n",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert_eq!(
+            statistics.to_markdown("Othello statistics", "2026-10-16 12:00"),
+            expected
+        );
+    }
+
+    #[test]
+    fn rows_are_filtered_by_name_and_sorted_with_ties_in_name_order() {
+        let mut statistics = Statistics::default();
+        add_games(&mut statistics, ["A", "D"], 1, 0, 1);
+        add_games(&mut statistics, ["A", "B"], 3, 0, 1);
+        add_games(&mut statistics, ["C", "D"], 0, 1, 1);
+        statistics.add_match("Arena 1: b vs c".to_string(), &tally(1, 0, 0));
+        let names = |sort, filter| -> Vec<String> {
+            statistics
+                .rows(sort, filter)
+                .into_iter()
+                .map(|row| row.name)
+                .collect()
+        };
+
+        // A vs D and C vs D both have two games and stay in name order either way
+        let by_games = StatisticsSort::default();
+        assert_eq!(
+            names(by_games, ""),
+            ["A vs B", "A vs D", "C vs D", "Arena 1: b vs c"]
+        );
+        assert_eq!(
+            names(by_games.clicked(StatisticsColumn::Games), ""),
+            ["Arena 1: b vs c", "A vs D", "C vs D", "A vs B"]
+        );
+        let by_name = by_games.clicked(StatisticsColumn::Name);
+        assert_eq!(
+            names(by_name, ""),
+            ["A vs B", "A vs D", "Arena 1: b vs c", "C vs D"]
+        );
+        let by_wins = by_games.clicked(StatisticsColumn::Wins);
+        assert_eq!(
+            names(by_wins, ""),
+            ["Arena 1: b vs c", "A vs B", "A vs D", "C vs D"]
+        );
+
+        // whatever the case, and the summaries of matches are told from the matchups
+        let rows = statistics.rows(by_name, " B ");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].matchup.map(Matchup::to_string).unwrap(), "A vs B");
+        assert!(rows[1].matchup.is_none());
+        assert!(statistics.rows(by_name, "X").is_empty());
+    }
+
+    #[test]
+    fn a_match_summary_is_partial_until_its_last_game() {
+        let mut statistics = Statistics::default();
+        let mut tally = MatchTally::new(2);
+        tally.record(&Outcome::Tie, 32, 32, 60);
+        statistics.add_match("Match 1: A vs B".to_string(), &tally);
+        let statistic = &statistics.data["Match 1: A vs B"];
+        assert!(statistic.is_partial());
+        assert!(statistic.to_string().ends_with(", partial"));
+
+        tally.record(&Outcome::Tie, 32, 32, 60);
+        statistics.add_match("Match 1: A vs B".to_string(), &tally);
+        assert_eq!(statistics.data.len(), 1);
+        assert!(!statistics.data["Match 1: A vs B"].is_partial());
+        assert_eq!(statistics.data["Match 1: A vs B"].games(), 2.0);
+    }
+
+    #[test]
+    fn depth_gaps_pool_the_pairs_of_the_same_distance() {
+        let mut statistics = Statistics::default();
+        let minimax = |depth| Participant::ai(AiType::Minimax, depth);
+        let outcome = Outcome::Won(Player::Black, WinReason::Disks);
+        // the deeper search has black in every game
+        for _ in 0..3 {
+            statistics.add_game([minimax(3), minimax(1)], Player::Black, &result(outcome));
         }
-        statistic.count += 1.0;
+        statistics.add_game([minimax(2), minimax(5)], Player::Black, &result(outcome));
+        statistics.add_game([minimax(3), minimax(2)], Player::Black, &result(outcome));
+        statistics.add_game([minimax(2), minimax(2)], Player::Black, &result(outcome));
+
+        let gaps = statistics.depth_gaps(AiType::Minimax);
+        let summary: Vec<(usize, f64, f64)> = gaps
+            .iter()
+            .map(|gap| (gap.depth_difference, gap.win_ratio, gap.games))
+            .collect();
+        assert_eq!(summary, [(1, 1.0, 1.0), (2, 1.0, 3.0), (3, 0.0, 1.0)]);
+        assert_eq!(gaps[1].depths, [[3, 1]]);
+
+        let smallest = |min_games| {
+            DepthGap::smallest_reaching(&gaps, 0.75, min_games).map(|gap| gap.depth_difference)
+        };
+        assert_eq!(smallest(1.0), Some(1));
+        assert_eq!(smallest(2.0), Some(2));
+        assert_eq!(smallest(5.0), None);
     }
 }
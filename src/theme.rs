@@ -0,0 +1,285 @@
+use eframe::egui::Color32;
+use eframe::egui::ThemePreference;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::board::Player;
+
+// the built-in looks of the board, kept with the settings by name
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum BoardThemeName {
+    #[default]
+    Classic,
+    LightGreen,
+    Slate,
+    HighContrast,
+    Newspaper,
+}
+
+impl BoardThemeName {
+    pub const ALL: [BoardThemeName; 5] = [
+        BoardThemeName::Classic,
+        BoardThemeName::LightGreen,
+        BoardThemeName::Slate,
+        BoardThemeName::HighContrast,
+        BoardThemeName::Newspaper,
+    ];
+
+    // of its name in the strings of the interface
+    pub fn key(self) -> &'static str {
+        match self {
+            BoardThemeName::Classic => "theme-classic",
+            BoardThemeName::LightGreen => "theme-light-green",
+            BoardThemeName::Slate => "theme-slate",
+            BoardThemeName::HighContrast => "theme-high-contrast",
+            BoardThemeName::Newspaper => "theme-newspaper",
+        }
+    }
+
+    // the theme that suits a dark or a light side panel
+    pub fn default_for(dark_mode: bool) -> Self {
+        if dark_mode {
+            BoardThemeName::Classic
+        } else {
+            BoardThemeName::LightGreen
+        }
+    }
+
+    // the default of the old visuals gives way to the default of the new ones, a theme that was
+    // picked stays
+    pub fn follow_visuals(self, was_dark_mode: bool, dark_mode: bool) -> Self {
+        if self == BoardThemeName::default_for(was_dark_mode) {
+            BoardThemeName::default_for(dark_mode)
+        } else {
+            self
+        }
+    }
+
+    pub fn theme(self) -> BoardTheme {
+        match self {
+            BoardThemeName::Classic => BoardTheme {
+                board: Color32::DARK_GREEN,
+                grid: Color32::BLACK,
+                discs: [Color32::BLACK, Color32::WHITE],
+                disc_outline: Color32::TRANSPARENT,
+                valid_moves: [
+                    Color32::from_rgba_premultiplied(0, 100, 0, 40),
+                    Color32::from_rgba_premultiplied(100, 100, 100, 30),
+                ],
+                last_move: Color32::from_rgb(220, 40, 40),
+                hint: Color32::from_rgb(0, 200, 220),
+                solution: Color32::GOLD,
+                best_move: Color32::from_rgb(80, 160, 255),
+                hover: Color32::from_rgb(255, 255, 0),
+                winner: Color32::GOLD,
+                frontier: Color32::from_rgb(255, 140, 0),
+                annotation: Color32::from_rgb(230, 230, 230),
+                marks: Color32::from_rgb(255, 80, 160),
+                arrow_opacity: 0.8,
+            },
+            BoardThemeName::LightGreen => BoardTheme {
+                board: Color32::from_rgb(96, 168, 104),
+                grid: Color32::from_rgb(30, 80, 40),
+                discs: [Color32::BLACK, Color32::WHITE],
+                disc_outline: Color32::TRANSPARENT,
+                valid_moves: [
+                    Color32::from_rgba_premultiplied(0, 60, 0, 50),
+                    Color32::from_rgba_premultiplied(110, 110, 110, 40),
+                ],
+                last_move: Color32::from_rgb(200, 30, 30),
+                hint: Color32::from_rgb(0, 90, 200),
+                solution: Color32::GOLD,
+                best_move: Color32::from_rgb(40, 80, 220),
+                hover: Color32::from_rgb(255, 255, 0),
+                winner: Color32::GOLD,
+                frontier: Color32::from_rgb(230, 110, 0),
+                annotation: Color32::from_rgb(20, 50, 25),
+                marks: Color32::from_rgb(200, 0, 120),
+                arrow_opacity: 0.8,
+            },
+            BoardThemeName::Slate => BoardTheme {
+                board: Color32::from_rgb(70, 84, 96),
+                grid: Color32::from_rgb(40, 48, 56),
+                discs: [
+                    Color32::from_rgb(24, 24, 28),
+                    Color32::from_rgb(236, 236, 230),
+                ],
+                disc_outline: Color32::TRANSPARENT,
+                valid_moves: [
+                    Color32::from_rgba_premultiplied(20, 20, 30, 60),
+                    Color32::from_rgba_premultiplied(90, 90, 90, 40),
+                ],
+                last_move: Color32::from_rgb(240, 140, 60),
+                hint: Color32::from_rgb(120, 220, 200),
+                solution: Color32::GOLD,
+                best_move: Color32::from_rgb(130, 180, 255),
+                hover: Color32::from_rgb(250, 220, 90),
+                winner: Color32::GOLD,
+                frontier: Color32::from_rgb(230, 90, 200),
+                annotation: Color32::from_rgb(225, 230, 235),
+                marks: Color32::from_rgb(255, 120, 200),
+                arrow_opacity: 0.8,
+            },
+            BoardThemeName::HighContrast => BoardTheme {
+                board: Color32::from_rgb(0, 40, 140),
+                grid: Color32::WHITE,
+                discs: [Color32::BLACK, Color32::WHITE],
+                disc_outline: Color32::WHITE,
+                valid_moves: [
+                    Color32::from_rgba_premultiplied(120, 120, 0, 120),
+                    Color32::from_rgba_premultiplied(120, 120, 0, 120),
+                ],
+                last_move: Color32::from_rgb(255, 0, 255),
+                hint: Color32::from_rgb(0, 255, 255),
+                solution: Color32::from_rgb(255, 255, 0),
+                best_move: Color32::from_rgb(255, 128, 0),
+                hover: Color32::from_rgb(255, 255, 0),
+                winner: Color32::from_rgb(255, 0, 255),
+                frontier: Color32::from_rgb(0, 255, 0),
+                annotation: Color32::WHITE,
+                marks: Color32::RED,
+                arrow_opacity: 1.0,
+            },
+            BoardThemeName::Newspaper => BoardTheme {
+                board: Color32::from_rgb(245, 245, 240),
+                grid: Color32::BLACK,
+                discs: [Color32::BLACK, Color32::WHITE],
+                disc_outline: Color32::BLACK,
+                valid_moves: [
+                    Color32::from_rgba_premultiplied(0, 0, 0, 40),
+                    Color32::from_rgba_premultiplied(0, 0, 0, 40),
+                ],
+                last_move: Color32::from_rgb(90, 90, 90),
+                hint: Color32::from_rgb(60, 60, 60),
+                solution: Color32::from_rgb(30, 30, 30),
+                best_move: Color32::from_rgb(120, 120, 120),
+                hover: Color32::BLACK,
+                winner: Color32::from_rgb(128, 128, 128),
+                frontier: Color32::from_rgb(150, 150, 150),
+                annotation: Color32::BLACK,
+                marks: Color32::from_rgb(110, 110, 110),
+                arrow_opacity: 0.9,
+            },
+        }
+    }
+}
+
+// the look of the side panel and the dialogs
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Appearance {
+    // light or dark as the system prefers, following it when it changes
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    pub const ALL: [Appearance; 3] = [Appearance::System, Appearance::Light, Appearance::Dark];
+
+    // of its name in the strings of the interface
+    pub fn key(self) -> &'static str {
+        match self {
+            Appearance::System => "appearance-system",
+            Appearance::Light => "appearance-light",
+            Appearance::Dark => "appearance-dark",
+        }
+    }
+
+    pub fn preference(self) -> ThemePreference {
+        match self {
+            Appearance::System => ThemePreference::System,
+            Appearance::Light => ThemePreference::Light,
+            Appearance::Dark => ThemePreference::Dark,
+        }
+    }
+}
+
+// the colors the board is painted with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoardTheme {
+    pub board: Color32,
+    pub grid: Color32,
+    // black's and white's
+    pub discs: [Color32; 2],
+    // transparent for discs that stand out from the board on their own
+    pub disc_outline: Color32,
+    // the highlight of the valid moves on black's and on white's turn
+    pub valid_moves: [Color32; 2],
+    pub last_move: Color32,
+    pub hint: Color32,
+    // of a revealed puzzle
+    pub solution: Color32,
+    // the move the analysis preferred in a replay
+    pub best_move: Color32,
+    // the square under the pointer
+    pub hover: Color32,
+    // around the winner's share of the score bar
+    pub winner: Color32,
+    // the outline of the discs next to an empty square
+    pub frontier: Color32,
+    // the analyzed scores in the corners of the squares, but the best one's in `best_move`
+    pub annotation: Color32,
+    // the circles, crosses and letters left on the squares with right clicks
+    pub marks: Color32,
+    // of the arrows onto the hint and the preferred move, drawn in their marker's color
+    pub arrow_opacity: f32,
+}
+
+impl BoardTheme {
+    pub fn disc(&self, player: Player) -> Color32 {
+        self.discs[player as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the difference of the brightest and the darkest channel sums
+    fn contrast(a: Color32, b: Color32) -> i32 {
+        let sum = |color: Color32| color.r() as i32 + color.g() as i32 + color.b() as i32;
+        (sum(a) - sum(b)).abs()
+    }
+
+    #[test]
+    fn every_disc_stands_out_from_the_board_or_has_an_outline() {
+        for name in BoardThemeName::ALL {
+            let theme = name.theme();
+            for disc in theme.discs {
+                assert!(
+                    contrast(disc, theme.board) >= 100
+                        || contrast(theme.disc_outline, theme.board) >= 100,
+                    "{name:?}"
+                );
+            }
+            assert!(contrast(theme.discs[0], theme.discs[1]) > 300, "{name:?}");
+        }
+    }
+
+    #[test]
+    fn the_default_theme_follows_the_visuals_and_a_picked_one_stays() {
+        let default_dark = BoardThemeName::default_for(true);
+        let default_light = BoardThemeName::default_for(false);
+        assert_ne!(default_dark, default_light);
+        assert_eq!(default_dark.follow_visuals(true, false), default_light);
+        assert_eq!(default_light.follow_visuals(false, true), default_dark);
+        assert_eq!(default_dark.follow_visuals(true, true), default_dark);
+        assert_eq!(
+            BoardThemeName::Newspaper.follow_visuals(false, true),
+            BoardThemeName::Newspaper
+        );
+    }
+
+    #[test]
+    fn themes_are_saved_by_name() {
+        for name in BoardThemeName::ALL {
+            let json = serde_json::to_string(&name).unwrap();
+            assert_eq!(serde_json::from_str::<BoardThemeName>(&json).unwrap(), name);
+        }
+        assert_eq!(
+            serde_json::to_string(&BoardThemeName::HighContrast).unwrap(),
+            "\"HighContrast\""
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use crate::board::Player;
+use crate::referee::Outcome;
+use crate::referee::WinReason;
+use crate::statistics::ColorRecord;
+
+// the running score of a match between two configurations, "first" being the one that had
+// black in the first game
+pub struct MatchTally {
+    pub total_games: usize,
+    pub games_played: usize,
+    pub first_wins: usize,
+    pub draws: usize,
+    pub second_wins: usize,
+    pub on_time: usize,
+    // summed over all games, from the first configuration's perspective
+    pub disc_differential: i64,
+    // summed over all games, passes included
+    pub plies: usize,
+    // the results of the first configuration, by the color it had
+    pub first_by_color: [ColorRecord; 2],
+}
+
+impl MatchTally {
+    pub fn new(total_games: usize) -> Self {
+        MatchTally {
+            total_games,
+            games_played: 0,
+            first_wins: 0,
+            draws: 0,
+            second_wins: 0,
+            on_time: 0,
+            disc_differential: 0,
+            plies: 0,
+            first_by_color: [ColorRecord::default(); 2],
+        }
+    }
+
+    // colors alternate each game, starting with the first configuration as black
+    pub fn first_color(&self) -> Player {
+        if self.games_played.is_multiple_of(2) {
+            Player::Black
+        } else {
+            Player::White
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.games_played >= self.total_games
+    }
+
+    // call this once per game, before the colors of the next game are decided
+    pub fn record(
+        &mut self,
+        outcome: &Outcome,
+        black_count: usize,
+        white_count: usize,
+        plies: usize,
+    ) {
+        let first_color = self.first_color();
+        self.first_by_color[first_color as usize].record(first_color, outcome);
+        match *outcome {
+            Outcome::Won(player, _) if player == first_color => self.first_wins += 1,
+            Outcome::Won(..) => self.second_wins += 1,
+            Outcome::Tie => self.draws += 1,
+        }
+        if let Outcome::Won(_, WinReason::Time) = *outcome {
+            self.on_time += 1;
+        }
+
+        let differential = black_count as i64 - white_count as i64;
+        self.disc_differential += match first_color {
+            Player::Black => differential,
+            Player::White => -differential,
+        };
+        self.plies += plies;
+        self.games_played += 1;
+    }
+
+    // of the first configuration, a draw counting 1/2
+    pub fn first_points(&self) -> f64 {
+        self.first_wins as f64 + self.draws as f64 / 2.0
+    }
+
+    // how long the games left take at the pace of those played in `elapsed`, None before the
+    // first game is in
+    pub fn remaining_time(&self, elapsed: Duration) -> Option<Duration> {
+        remaining_time(self.games_played, self.total_games, elapsed)
+    }
+
+    pub fn average_disc_differential(&self) -> f64 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            self.disc_differential as f64 / self.games_played as f64
+        }
+    }
+}
+
+// how long the games left of `total_games` take at the pace of `games_played` in `elapsed`
+pub fn remaining_time(
+    games_played: usize,
+    total_games: usize,
+    elapsed: Duration,
+) -> Option<Duration> {
+    if games_played == 0 {
+        return None;
+    }
+    let games_left = total_games.saturating_sub(games_played) as u32;
+    Some(elapsed / games_played as u32 * games_left)
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::Path;
+
+use crate::board::Board;
+use crate::board::Move;
+
+// layout of the WThor (.wtb) game database format
+const HEADER_SIZE: usize = 16;
+const RECORD_SIZE: usize = 68;
+const MOVES_PER_RECORD: usize = 60;
+
+#[derive(Debug)]
+pub struct WthorGame {
+    pub tournament: u16,
+    pub black_player: u16,
+    pub white_player: u16,
+    // number of black discs at the end of the game
+    pub black_score: u8,
+    // black discs with perfect play from the position `depth` empties before the end
+    pub theoretical_score: u8,
+    pub moves: Vec<Move>,
+}
+
+#[derive(Debug)]
+pub struct WthorDatabase {
+    pub year: u16,
+    // number of empties from which the theoretical scores were computed
+    pub depth: u8,
+    pub games: Vec<WthorGame>,
+}
+
+#[derive(Debug)]
+pub enum WthorError {
+    Io(String),
+    MissingHeader { size: usize },
+    UnsupportedBoardSize(u8),
+    Truncated { expected_games: usize, size: usize },
+    InvalidMove { game: usize, ply: usize, byte: u8 },
+    // a disc count larger than the board
+    InvalidScore { game: usize, score: u8 },
+}
+
+impl std::fmt::Display for WthorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WthorError::Io(e) => write!(f, "cannot read file: {e}"),
+            WthorError::MissingHeader { size } => write!(
+                f,
+                "file is {size} bytes, too short for the {HEADER_SIZE}-byte WThor header"
+            ),
+            WthorError::UnsupportedBoardSize(size) => {
+                write!(
+                    f,
+                    "unsupported board size {size}, only 8x8 games are supported"
+                )
+            }
+            WthorError::Truncated {
+                expected_games,
+                size,
+            } => write!(
+                f,
+                "file is truncated, {expected_games} games need {} bytes but it has {size}",
+                HEADER_SIZE + expected_games * RECORD_SIZE
+            ),
+            WthorError::InvalidMove { game, ply, byte } => write!(
+                f,
+                "game {} has the invalid move byte {byte} at ply {}",
+                game + 1,
+                ply + 1
+            ),
+            WthorError::InvalidScore { game, score } => write!(
+                f,
+                "game {} has the score {score}, more discs than the {} squares of the board",
+                game + 1,
+                Board::SIZE * Board::SIZE
+            ),
+        }
+    }
+}
+
+pub fn read(path: &Path) -> Result<WthorDatabase, WthorError> {
+    let bytes = fs::read(path).map_err(|e| WthorError::Io(e.to_string()))?;
+    parse(&bytes)
+}
+
+pub fn parse(bytes: &[u8]) -> Result<WthorDatabase, WthorError> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(WthorError::MissingHeader { size: bytes.len() });
+    }
+
+    let game_count = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    let year = u16::from_le_bytes([bytes[10], bytes[11]]);
+    // 0 is used by older files to mean the regular 8x8 board
+    let board_size = bytes[12];
+    if board_size != 0 && board_size as usize != Board::SIZE {
+        return Err(WthorError::UnsupportedBoardSize(board_size));
+    }
+    let depth = bytes[14];
+
+    let records = &bytes[HEADER_SIZE..];
+    if records.len() < game_count * RECORD_SIZE {
+        return Err(WthorError::Truncated {
+            expected_games: game_count,
+            size: bytes.len(),
+        });
+    }
+
+    let games = records
+        .chunks_exact(RECORD_SIZE)
+        .take(game_count)
+        .enumerate()
+        .map(|(game, record)| parse_record(game, record))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(WthorDatabase { year, depth, games })
+}
+
+fn parse_record(game: usize, record: &[u8]) -> Result<WthorGame, WthorError> {
+    let mut moves = Vec::with_capacity(MOVES_PER_RECORD);
+
+    // each move is stored as 10 * row + column, both counted from 1, and 0 ends the game early
+    for (ply, &byte) in record[8..8 + MOVES_PER_RECORD].iter().enumerate() {
+        if byte == 0 {
+            break;
+        }
+
+        let (row, col) = ((byte / 10) as usize, (byte % 10) as usize);
+        if !(1..=Board::SIZE).contains(&row) || !(1..=Board::SIZE).contains(&col) {
+            return Err(WthorError::InvalidMove { game, ply, byte });
+        }
+        moves.push((row - 1, col - 1));
+    }
+
+    let (black_score, theoretical_score) = (record[6], record[7]);
+    for score in [black_score, theoretical_score] {
+        if score as usize > Board::SIZE * Board::SIZE {
+            return Err(WthorError::InvalidScore { game, score });
+        }
+    }
+
+    Ok(WthorGame {
+        tournament: u16::from_le_bytes([record[0], record[1]]),
+        black_player: u16::from_le_bytes([record[2], record[3]]),
+        white_player: u16::from_le_bytes([record[4], record[5]]),
+        black_score,
+        theoretical_score,
+        moves,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player;
+    use crate::referee::Referee;
+
+    // three games of 2024: a wipeout after nine moves, a full game with two passes and a full
+    // game without any
+    const SAMPLE: &[u8] = include_bytes!("../tests/fixtures/sample.wtb");
+
+    #[test]
+    fn the_header_and_the_records_are_read() {
+        let database = parse(SAMPLE).unwrap();
+        assert_eq!(database.year, 2024);
+        assert_eq!(database.depth, 4);
+        assert_eq!(database.games.len(), 3);
+
+        let wipeout = &database.games[0];
+        assert_eq!(wipeout.tournament, 7);
+        assert_eq!(wipeout.black_player, 100);
+        assert_eq!(wipeout.white_player, 200);
+        assert_eq!(wipeout.black_score, 13);
+        // the zeros after the last move are not moves
+        assert_eq!(
+            wipeout.moves,
+            [
+                (2, 3),
+                (2, 2),
+                (2, 1),
+                (1, 3),
+                (0, 4),
+                (5, 3),
+                (6, 3),
+                (2, 4),
+                (3, 5)
+            ]
+        );
+        assert_eq!(database.games[1].black_player, 101);
+        assert_eq!(database.games[2].moves.len(), MOVES_PER_RECORD);
+    }
+
+    #[test]
+    fn imported_games_replay_to_their_scores() {
+        for (index, game) in parse(SAMPLE).unwrap().games.iter().enumerate() {
+            let replay = Referee::default()
+                .replay(&Board::default(), Player::Black, &game.moves)
+                .unwrap();
+            assert_eq!(replay.next_player, None, "game {index}");
+            assert_eq!(
+                Referee::count_disks(&replay.board, Player::Black).0,
+                game.black_score as i32,
+                "game {index}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_file_cut_short_is_refused() {
+        assert!(matches!(
+            parse(&SAMPLE[..10]),
+            Err(WthorError::MissingHeader { size: 10 })
+        ));
+        // the last record lacks its last byte
+        let e = parse(&SAMPLE[..SAMPLE.len() - 1]).unwrap_err();
+        assert!(matches!(
+            e,
+            WthorError::Truncated {
+                expected_games: 3,
+                size: 219
+            }
+        ));
+        assert_eq!(
+            e.to_string(),
+            "file is truncated, 3 games need 220 bytes but it has 219"
+        );
+    }
+
+    #[test]
+    fn other_board_sizes_are_refused() {
+        let mut bytes = SAMPLE.to_vec();
+        bytes[12] = 10;
+        assert!(matches!(
+            parse(&bytes),
+            Err(WthorError::UnsupportedBoardSize(10))
+        ));
+        // older files leave the size out
+        bytes[12] = 0;
+        assert_eq!(parse(&bytes).unwrap().games.len(), 3);
+    }
+
+    #[test]
+    fn squares_off_the_board_are_refused() {
+        // the third move of the second game, in row 9
+        let mut bytes = SAMPLE.to_vec();
+        bytes[HEADER_SIZE + RECORD_SIZE + 8 + 2] = 91;
+        let e = parse(&bytes).unwrap_err();
+        assert!(matches!(
+            e,
+            WthorError::InvalidMove {
+                game: 1,
+                ply: 2,
+                byte: 91
+            }
+        ));
+        assert_eq!(
+            e.to_string(),
+            "game 2 has the invalid move byte 91 at ply 3"
+        );
+
+        // column 0
+        bytes[HEADER_SIZE + RECORD_SIZE + 8 + 2] = 30;
+        assert!(matches!(
+            parse(&bytes),
+            Err(WthorError::InvalidMove { byte: 30, .. })
+        ));
+    }
+
+    #[test]
+    fn scores_above_the_board_are_refused() {
+        // the final score of the third game
+        let mut bytes = SAMPLE.to_vec();
+        bytes[HEADER_SIZE + 2 * RECORD_SIZE + 6] = 200;
+        let e = parse(&bytes).unwrap_err();
+        assert!(matches!(
+            e,
+            WthorError::InvalidScore {
+                game: 2,
+                score: 200
+            }
+        ));
+        assert_eq!(
+            e.to_string(),
+            "game 3 has the score 200, more discs than the 64 squares of the board"
+        );
+
+        // a whole board is still a score
+        bytes[HEADER_SIZE + 2 * RECORD_SIZE + 6] = 64;
+        assert!(parse(&bytes).is_ok());
+
+        // the theoretical score as well
+        bytes[HEADER_SIZE + 7] = 65;
+        assert!(matches!(
+            parse(&bytes),
+            Err(WthorError::InvalidScore { game: 0, score: 65 })
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+
+// runs a whole session, the end of the input closes it
+fn run_session(script: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_FIA-Othello"))
+        .arg("--engine")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the binary should start");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn scripted_session() {
+    let transcript = run_session(
+        "boardsize 8\n\
+         clear_board\n\
+         play B f5\n\
+         play W f5\n\
+         1 frobnicate\n\
+         set_depth 2\n\
+         genmove W\n\
+         boardsize 10\n\
+         play B pass\n\
+         quit\n\
+         genmove B\n",
+    );
+
+    let replies: Vec<&str> = transcript.split_terminator("\n\n").collect();
+    assert_eq!(replies.len(), 10, "{transcript}");
+    assert_eq!(replies[0], "=");
+    assert_eq!(replies[1], "=");
+    assert_eq!(replies[2], "=");
+    assert_eq!(replies[3], "? illegal move");
+    assert_eq!(replies[4], "?1 unknown command");
+    assert_eq!(replies[5], "=");
+    // white's only replies to f5 are d6, f4 and f6
+    assert!(
+        ["= d6", "= f4", "= f6"].contains(&replies[6]),
+        "{}",
+        replies[6]
+    );
+    assert_eq!(replies[7], "? unacceptable size");
+    assert_eq!(replies[8], "? illegal move");
+    assert_eq!(replies[9], "=");
+    // nothing is answered after quit
+}
+
+#[test]
+fn genmove_passes_without_a_legal_move() {
+    // white has no valid move after these
+    let moves = ["d3", "c3", "b3", "d2", "e1", "d6", "d7", "e3", "f4"];
+    let mut script = "clear_board\n".to_string();
+    for (i, next_move) in moves.iter().enumerate() {
+        let color = if i % 2 == 0 { "B" } else { "W" };
+        script.push_str(&format!("play {color} {next_move}\n"));
+    }
+    script.push_str("genmove W\nplay W pass\n");
+
+    let transcript = run_session(&script);
+    let replies: Vec<&str> = transcript.split_terminator("\n\n").collect();
+    assert!(
+        replies[..=moves.len()].iter().all(|reply| *reply == "="),
+        "{transcript}"
+    );
+    assert_eq!(&replies[moves.len() + 1..], ["= pass", "="]);
+}
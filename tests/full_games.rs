@@ -0,0 +1,71 @@
+mod support;
+
+use fia_othello::board::Player;
+use support::GameRecord;
+use support::PlayerSpec;
+use support::assert_replays;
+use support::play_game;
+
+// plays `games` seeded games with alternating colors, `first` having black in the first one
+// returns the games with the color `first` played in each
+fn play_match(first: PlayerSpec, second: PlayerSpec, games: u64) -> Vec<(GameRecord, Player)> {
+    (0..games)
+        .map(|seed| {
+            if seed % 2 == 0 {
+                (play_game(first, second, seed), Player::Black)
+            } else {
+                (play_game(second, first, seed), Player::White)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn positional_beats_random() {
+    let games = play_match(PlayerSpec::Positional(3), PlayerSpec::Random, 20);
+
+    let wins = games
+        .iter()
+        .filter(|(record, color)| record.winner() == Some(*color))
+        .count();
+    assert!(wins >= 18, "Positional won only {wins} of 20 games");
+    for (record, _) in games.iter() {
+        assert_replays(record);
+    }
+}
+
+#[test]
+fn deeper_minimax_never_loses_to_depth_one() {
+    let games = play_match(PlayerSpec::Minimax(4), PlayerSpec::Minimax(1), 10);
+
+    for (record, color) in games.iter() {
+        assert_ne!(
+            record.winner(),
+            Some(color.opponent()),
+            "depth 4 lost as {color:?}"
+        );
+        assert_replays(record);
+    }
+}
+
+#[test]
+fn every_game_replays() {
+    // cheap games, so many of them, passes included
+    let pairings = [
+        (PlayerSpec::Random, PlayerSpec::Random),
+        (PlayerSpec::Random, PlayerSpec::AlphaBeta(2)),
+        (PlayerSpec::Positional(2), PlayerSpec::Minimax(2)),
+    ];
+    for (black, white) in pairings {
+        for seed in 0..20 {
+            assert_replays(&play_game(black, white, seed));
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_game() {
+    let first = play_game(PlayerSpec::Random, PlayerSpec::AlphaBeta(2), 42);
+    let second = play_game(PlayerSpec::Random, PlayerSpec::AlphaBeta(2), 42);
+    assert_eq!(first.moves, second.moves);
+}
@@ -0,0 +1,59 @@
+use std::process::Command;
+
+fn run_headless(args: &[&str]) -> serde_json::Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_FIA-Othello"))
+        .arg("--headless")
+        .args(args)
+        .output()
+        .expect("the binary should start");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    serde_json::from_slice(&output.stdout).expect("the report should be JSON")
+}
+
+#[test]
+fn tiny_match_is_played_to_the_end() {
+    let args = [
+        "--games",
+        "2",
+        "--first",
+        "random:1:7",
+        "--second",
+        "alphabeta:2:11",
+        "--json",
+    ];
+    let report = run_headless(&args);
+
+    let games = report["games"].as_array().unwrap();
+    assert_eq!(games.len(), 2);
+    // colors alternate
+    assert_eq!(games[0]["black"], "Random");
+    assert_eq!(games[1]["white"], "Random");
+    for game in games {
+        let discs = game["black_discs"].as_u64().unwrap() + game["white_discs"].as_u64().unwrap();
+        assert!(discs <= 64);
+        assert!(!game["transcript"].as_str().unwrap().is_empty());
+    }
+
+    let results = ["wins", "draws", "losses"].map(|key| report[key].as_u64().unwrap());
+    assert_eq!(results.iter().sum::<u64>(), 2);
+
+    // seeded engines replay the same games
+    let rerun = run_headless(&args);
+    for (game, rerun_game) in games.iter().zip(rerun["games"].as_array().unwrap()) {
+        assert_eq!(game["transcript"], rerun_game["transcript"]);
+    }
+}
+
+#[test]
+fn invalid_engine_is_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_FIA-Othello"))
+        .args(["--headless", "--first", "deepblue:3"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
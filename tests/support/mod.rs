@@ -0,0 +1,120 @@
+// plays whole games with the library alone, on the calling thread
+
+use fia_othello::ai::Agent;
+use fia_othello::ai::AiType;
+use fia_othello::ai::MoveRequest;
+use fia_othello::board::Board;
+use fia_othello::board::Move;
+use fia_othello::board::Player;
+use fia_othello::common::CellList;
+use fia_othello::history::History;
+use fia_othello::referee::Advance;
+use fia_othello::referee::Outcome;
+use fia_othello::referee::Referee;
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlayerSpec {
+    Random,
+    // the search depth of each
+    Minimax(usize),
+    AlphaBeta(usize),
+    Positional(usize),
+}
+
+impl PlayerSpec {
+    fn ai_type_and_depth(&self) -> (AiType, usize) {
+        match *self {
+            PlayerSpec::Random => (AiType::Random, 1),
+            PlayerSpec::Minimax(depth) => (AiType::Minimax, depth),
+            PlayerSpec::AlphaBeta(depth) => (AiType::AlphaBeta, depth),
+            PlayerSpec::Positional(depth) => (AiType::Positional, depth),
+        }
+    }
+}
+
+pub struct GameRecord {
+    pub moves: Vec<Move>,
+    // passes included
+    pub history: History,
+    pub board: Board,
+    pub outcome: Outcome,
+}
+
+impl GameRecord {
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome {
+            Outcome::Won(player, _) => Some(player),
+            Outcome::Tie => None,
+        }
+    }
+}
+
+// the same specs and seed always give the same game
+pub fn play_game(black: PlayerSpec, white: PlayerSpec, seed: u64) -> GameRecord {
+    let specs = [black, white];
+    let mut agents = [
+        Agent::with_seed(seed.wrapping_mul(2)),
+        Agent::with_seed(seed.wrapping_mul(2).wrapping_add(1)),
+    ];
+    for agent in agents.iter_mut() {
+        agent.log_searches = false;
+    }
+
+    let mut referee = Referee::default();
+    let mut board = Board::default();
+    let mut history = History::default();
+    let mut flip_cells = CellList::default();
+    let mut valid_moves = CellList::default();
+    let mut player = Player::Black;
+
+    loop {
+        let (ai_type, recursion_depth) = specs[player as usize].ai_type_and_depth();
+        let move_result = agents[player as usize].answer_move_request(MoveRequest {
+            board: board.clone(),
+            player,
+            algorithm_choice: ai_type,
+            recursion_depth,
+            time_budget: None,
+        });
+
+        let advance = referee
+            .play_move(
+                &mut board,
+                &mut history,
+                player,
+                move_result.next_move,
+                &mut flip_cells,
+                &mut valid_moves,
+            )
+            .unwrap_or_else(|| {
+                panic!(
+                    "{:?} played the illegal move {:?} as {player:?}",
+                    specs[player as usize], move_result.next_move
+                )
+            });
+
+        match advance {
+            Advance::Turn(next_player) => player = next_player,
+            Advance::Pass { .. } => {}
+            Advance::Over(outcome) => {
+                return GameRecord {
+                    moves: history.moves(),
+                    history,
+                    board,
+                    outcome,
+                };
+            }
+        }
+    }
+}
+
+// the referee must accept the game again, ending in the same position with the same passes
+pub fn assert_replays(record: &GameRecord) {
+    let replay = Referee::default()
+        .replay(&Board::default(), Player::Black, &record.moves)
+        .unwrap_or_else(|e| panic!("the game does not replay: {e}"));
+
+    assert_eq!(replay.board.grid, record.board.grid);
+    assert_eq!(replay.history.plies, record.history.plies);
+    assert_eq!(replay.next_player, None);
+}